@@ -1,10 +1,197 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{SampleFormat, Stream};
 
-use super::{AudioError, CaptureFormat, Result};
+use super::preprocess;
+use super::{AudioError, AudioSource, CaptureFormat, DeviceInfo, Result};
+use crate::config::schema::{AppConfig, CaptureSampleFormat};
+
+/// Количество попыток найти input-устройство по умолчанию.
+const DEFAULT_PROBE_ATTEMPTS: u32 = 3;
+
+/// Пауза между попытками поиска устройства по умолчанию (мс).
+const DEFAULT_PROBE_DELAY_MS: u64 = 500;
+
+/// Окно для расчета текущих уровней по каналам (мс).
+const LEVEL_WINDOW_MS: u32 = 100;
+
+/// Запас сверх `max_recording_duration_sec` для лимита буфера (секунды).
+///
+/// Таймаут безопасности срабатывает асинхронно, лимит не должен обрезать запись раньше него.
+const BUFFER_HEADROOM_SEC: u32 = 2;
+
+/// Лимит длительности записи по умолчанию (как в `AppConfig`).
+const DEFAULT_MAX_DURATION_SEC: u32 = 60;
+
+/// Максимальное число сэмплов в буфере для заданного формата и длительности.
+pub(crate) fn max_buffer_samples(sample_rate: u32, channels: u16, max_duration_sec: u32) -> usize {
+    sample_rate as usize
+        * channels.max(1) as usize
+        * (max_duration_sec as usize + BUFFER_HEADROOM_SEC as usize)
+}
+
+/// Добавляет сэмплы, не превышая `cap`. Возвращает `true`, если часть данных отброшена.
+fn append_capped(buf: &mut Vec<f32>, data: impl ExactSizeIterator<Item = f32>, cap: usize) -> bool {
+    let remaining = cap.saturating_sub(buf.len());
+    let truncated = data.len() > remaining;
+    buf.extend(data.take(remaining));
+    truncated
+}
+
+/// Переводит i16-сэмпл в f32 (-1.0..=1.0).
+fn i16_to_f32(sample: i16) -> f32 {
+    sample as f32 / i16::MAX as f32
+}
+
+/// Переводит u16-сэмпл (смещенный, середина 32768) в f32 (-1.0..1.0).
+fn u16_to_f32(sample: u16) -> f32 {
+    (sample as f32 - 32768.0) / 32768.0
+}
+
+/// RMS входного буфера cpal после конвертации в f32.
+///
+/// Считается по данным callback-а, а не по хвосту буфера записи: когда буфер
+/// заполнен (`append_capped` ничего не добавляет), уровень все равно виден.
+fn converted_rms<T: Copy>(data: &[T], convert: impl Fn(T) -> f32) -> f32 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let sum_sq: f32 = data
+        .iter()
+        .map(|&s| {
+            let sample = convert(s);
+            sample * sample
+        })
+        .sum();
+    (sum_sq / data.len() as f32).sqrt()
+}
+
+/// Формат cpal, соответствующий настройке конфига (`None` для `Auto`).
+fn preferred_cpal_format(preferred: CaptureSampleFormat) -> Option<SampleFormat> {
+    match preferred {
+        CaptureSampleFormat::Auto => None,
+        CaptureSampleFormat::F32 => Some(SampleFormat::F32),
+        CaptureSampleFormat::I16 => Some(SampleFormat::I16),
+        CaptureSampleFormat::U16 => Some(SampleFormat::U16),
+    }
+}
+
+/// Масштаб fixed-point представления уровня входного сигнала.
+const LEVEL_SCALE: f32 = 1_000_000.0;
+
+/// Переводит RMS в fixed-point для хранения в `AtomicU32`.
+fn level_to_fixed(level: f32) -> u32 {
+    (level.clamp(0.0, 1.0) * LEVEL_SCALE).round() as u32
+}
+
+/// Переводит fixed-point обратно в RMS.
+fn level_from_fixed(fixed: u32) -> f32 {
+    fixed as f32 / LEVEL_SCALE
+}
+
+/// Параметры повторного поиска устройства.
+///
+/// При отключении док-станции все входы пропадают на короткое время,
+/// пока ОС переключается на встроенный микрофон.
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceProbe {
+    pub attempts: u32,
+    pub delay: Duration,
+}
+
+impl Default for DeviceProbe {
+    fn default() -> Self {
+        Self {
+            attempts: DEFAULT_PROBE_ATTEMPTS,
+            delay: Duration::from_millis(DEFAULT_PROBE_DELAY_MS),
+        }
+    }
+}
+
+/// Опрашивает `list` до `attempts` раз с паузой `delay`, пока устройство не появится.
+///
+/// Возвращает `None`, если устройство так и не появилось.
+pub(crate) fn probe_for_device<D>(
+    mut list: impl FnMut() -> Option<D>,
+    probe: DeviceProbe,
+) -> Option<D> {
+    let attempts = probe.attempts.max(1);
+    for attempt in 1..=attempts {
+        if let Some(device) = list() {
+            return Some(device);
+        }
+        if attempt < attempts {
+            tracing::warn!(attempt, attempts, "no input device found, re-probing");
+            std::thread::sleep(probe.delay);
+        }
+    }
+    None
+}
+
+/// Находит input-устройство по умолчанию с повторными попытками.
+fn default_input_device(probe: DeviceProbe) -> Result<cpal::Device> {
+    let host = cpal::default_host();
+    probe_for_device(|| host.default_input_device(), probe).ok_or(AudioError::NoInputDevice)
+}
+
+/// Находит input-устройство по имени (как в `list_input_devices`).
+fn find_input_device(name: &str) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    let devices = host.input_devices().ok()?;
+    select_by_name(devices.map(|d| (device_name(&d), d)), name)
+}
+
+/// Выбирает устройство с точно совпадающим именем.
+pub(crate) fn select_by_name<D>(
+    devices: impl IntoIterator<Item = (String, D)>,
+    name: &str,
+) -> Option<D> {
+    devices
+        .into_iter()
+        .find(|(device_name, _)| device_name == name)
+        .map(|(_, device)| device)
+}
+
+/// Возвращает список доступных input-устройств.
+///
+/// Перечисление может занимать заметное время (особенно на Windows/ALSA),
+/// поэтому команды настроек кэшируют результат.
+pub fn list_input_devices() -> Result<Vec<DeviceInfo>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().map(|d| device_name(&d));
+
+    let devices = host
+        .input_devices()
+        .map_err(|e| AudioError::CaptureFailed(e.to_string()))?
+        .map(|device| {
+            let name = device_name(&device);
+            let is_default = default_name.as_deref() == Some(name.as_str());
+            DeviceInfo { name, is_default }
+        })
+        .collect();
+
+    Ok(devices)
+}
+
+/// Человекочитаемое имя устройства (или "unknown").
+fn device_name(device: &cpal::Device) -> String {
+    device
+        .description()
+        .map(|d| d.name().to_string())
+        .unwrap_or_else(|_| String::from("unknown"))
+}
+
+/// Результат записи: сэмплы, формат и признак обрезки по лимиту буфера.
+#[derive(Debug)]
+pub struct CapturedAudio {
+    pub samples: Vec<f32>,
+    pub format: CaptureFormat,
+    /// Запись превысила лимит буфера, хвост отброшен.
+    pub truncated: bool,
+}
 
 /// Захват аудио с микрофона через cpal.
 ///
@@ -16,29 +203,104 @@ pub struct AudioCapture {
     buffer: Arc<Mutex<Vec<f32>>>,
     format: Option<CaptureFormat>,
     is_recording: Arc<AtomicBool>,
+    probe: DeviceProbe,
+    /// Выбранное устройство; `None` - устройство по умолчанию.
+    device_name: Option<String>,
+    /// RMS последнего буфера из cpal callback (fixed-point, см. `LEVEL_SCALE`).
+    level: Arc<AtomicU32>,
+    /// Поток упал (устройство отключено) во время записи.
+    stream_failed: Arc<AtomicBool>,
+    /// Текст ошибки cpal, из-за которой упал поток.
+    stream_error: Arc<Mutex<Option<String>>>,
+    /// Лимит длительности записи; определяет размер буфера.
+    max_duration_sec: u32,
+    /// Буфер достиг лимита, новые данные отбрасываются.
+    truncated: Arc<AtomicBool>,
+    /// Предпочитаемый формат сэмплов.
+    sample_format: CaptureSampleFormat,
+    /// Запись приостановлена: поток открыт, данные не накапливаются.
+    paused: bool,
 }
 
 #[allow(dead_code)]
 impl AudioCapture {
     /// Создает AudioCapture с дефолтным input device.
     pub fn new() -> Result<Self> {
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or(AudioError::NoInputDevice)?;
-
-        let device_name = device
-            .description()
-            .map(|d| d.name().to_string())
-            .unwrap_or_else(|_| String::from("unknown"));
-        tracing::info!(device = device_name, "audio input device selected");
-
-        Ok(Self {
+        Self::with_probe(DeviceProbe::default())
+    }
+
+    /// Создает AudioCapture для устройства с заданным именем.
+    ///
+    /// Возвращает `NoInputDevice`, если устройства с таким именем нет.
+    pub fn new_with_device(name: &str) -> Result<Self> {
+        let device = find_input_device(name).ok_or(AudioError::NoInputDevice)?;
+        tracing::info!(device = device_name(&device), "audio input device selected");
+        Ok(Self::build(DeviceProbe::default(), Some(name.to_string())))
+    }
+
+    /// Создает AudioCapture с устройством и параметрами поиска из конфига.
+    ///
+    /// Если сохраненное устройство не найдено, используется устройство по умолчанию.
+    pub fn from_config(config: &AppConfig) -> Result<Self> {
+        let probe = DeviceProbe {
+            attempts: config.mic_probe_attempts,
+            delay: Duration::from_millis(u64::from(config.mic_probe_delay_ms)),
+        };
+
+        let mut capture = match config.input_device.as_deref().filter(|n| !n.is_empty()) {
+            Some(name) => {
+                let capture = Self::build(probe, Some(name.to_string()));
+                let device = capture.resolve_device()?;
+                tracing::info!(device = device_name(&device), "audio input device selected");
+                capture
+            }
+            None => Self::with_probe(probe)?,
+        };
+        capture.max_duration_sec = config.max_recording_duration_sec;
+        capture.sample_format = config.capture_sample_format;
+        Ok(capture)
+    }
+
+    fn with_probe(probe: DeviceProbe) -> Result<Self> {
+        let device = default_input_device(probe)?;
+
+        tracing::info!(device = device_name(&device), "audio input device selected");
+
+        Ok(Self::build(probe, None))
+    }
+
+    fn build(probe: DeviceProbe, device_name: Option<String>) -> Self {
+        Self {
             stream: None,
             buffer: Arc::new(Mutex::new(Vec::new())),
             format: None,
             is_recording: Arc::new(AtomicBool::new(false)),
-        })
+            probe,
+            device_name,
+            level: Arc::new(AtomicU32::new(0)),
+            stream_failed: Arc::new(AtomicBool::new(false)),
+            stream_error: Arc::new(Mutex::new(None)),
+            max_duration_sec: DEFAULT_MAX_DURATION_SEC,
+            truncated: Arc::new(AtomicBool::new(false)),
+            sample_format: CaptureSampleFormat::Auto,
+            paused: false,
+        }
+    }
+
+    /// Находит устройство заново (его могли отключить и подключить снова).
+    ///
+    /// Если выбранного устройства больше нет, используется устройство по умолчанию.
+    fn resolve_device(&self) -> Result<cpal::Device> {
+        if let Some(name) = self.device_name.as_deref() {
+            if let Some(device) = find_input_device(name) {
+                return Ok(device);
+            }
+            tracing::warn!(
+                device = name,
+                "selected input device not found, using default"
+            );
+        }
+        default_input_device(self.probe)
     }
 
     /// Начинает запись с микрофона.
@@ -46,30 +308,41 @@ impl AudioCapture {
     /// PCM-данные накапливаются в RAM-буфере как f32.
     /// Формат устройства (sample rate, channels) сохраняется.
     pub fn start_recording(&mut self) -> Result<()> {
-        if self.is_recording() {
+        if self.is_recording() || self.paused {
             return Err(AudioError::AlreadyRecording);
         }
 
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or(AudioError::NoInputDevice)?;
+        let device = self.resolve_device()?;
 
-        let config = device
-            .default_input_config()
-            .map_err(|e| AudioError::NoInputConfig(e.to_string()))?;
+        let config = self.select_input_config(&device)?;
 
         let device_sample_rate = config.sample_rate();
         let channels = config.channels();
         let sample_format = config.sample_format();
 
+        let cap = max_buffer_samples(device_sample_rate, channels, self.max_duration_sec);
         self.buffer.lock().expect("buffer mutex poisoned").clear();
+        self.truncated.store(false, Ordering::SeqCst);
 
         let buffer = Arc::clone(&self.buffer);
         let is_recording = Arc::clone(&self.is_recording);
+        let level = Arc::clone(&self.level);
+        level.store(0, Ordering::Relaxed);
+        let truncated = Arc::clone(&self.truncated);
 
-        let err_callback = |err: cpal::StreamError| {
-            tracing::error!(error = %err, "audio stream error");
+        self.stream_failed.store(false, Ordering::SeqCst);
+        self.stream_error
+            .lock()
+            .expect("stream error mutex poisoned")
+            .take();
+        let failed_flag = Arc::clone(&self.stream_failed);
+        let error_slot = Arc::clone(&self.stream_error);
+        let recording_flag = Arc::clone(&self.is_recording);
+        let err_callback = move |err: cpal::StreamError| {
+            tracing::error!(error = %err, "audio stream error, stopping capture");
+            *error_slot.lock().expect("stream error mutex poisoned") = Some(err.to_string());
+            failed_flag.store(true, Ordering::SeqCst);
+            recording_flag.store(false, Ordering::SeqCst);
         };
 
         let stream = match sample_format {
@@ -79,8 +352,12 @@ impl AudioCapture {
                         &config.into(),
                         move |data: &[f32], _: &cpal::InputCallbackInfo| {
                             if is_recording.load(Ordering::SeqCst) {
+                                let rms = preprocess::calculate_energy(data);
+                                level.store(level_to_fixed(rms), Ordering::Relaxed);
                                 if let Ok(mut buf) = buffer.lock() {
-                                    buf.extend_from_slice(data);
+                                    if append_capped(&mut buf, data.iter().copied(), cap) {
+                                        truncated.store(true, Ordering::Relaxed);
+                                    }
                                 }
                             }
                         },
@@ -96,8 +373,13 @@ impl AudioCapture {
                         &config.into(),
                         move |data: &[i16], _: &cpal::InputCallbackInfo| {
                             if is_recording.load(Ordering::SeqCst) {
+                                let rms = converted_rms(data, i16_to_f32);
+                                level.store(level_to_fixed(rms), Ordering::Relaxed);
                                 if let Ok(mut buf) = buffer.lock() {
-                                    buf.extend(data.iter().map(|&s| s as f32 / i16::MAX as f32));
+                                    let samples = data.iter().map(|&s| i16_to_f32(s));
+                                    if append_capped(&mut buf, samples, cap) {
+                                        truncated.store(true, Ordering::Relaxed);
+                                    }
                                 }
                             }
                         },
@@ -113,11 +395,13 @@ impl AudioCapture {
                         &config.into(),
                         move |data: &[u16], _: &cpal::InputCallbackInfo| {
                             if is_recording.load(Ordering::SeqCst) {
+                                let rms = converted_rms(data, u16_to_f32);
+                                level.store(level_to_fixed(rms), Ordering::Relaxed);
                                 if let Ok(mut buf) = buffer.lock() {
-                                    buf.extend(
-                                        data.iter()
-                                            .map(|&s| (s as f32 / u16::MAX as f32) * 2.0 - 1.0),
-                                    );
+                                    let samples = data.iter().map(|&s| u16_to_f32(s));
+                                    if append_capped(&mut buf, samples, cap) {
+                                        truncated.store(true, Ordering::Relaxed);
+                                    }
                                 }
                             }
                         },
@@ -158,19 +442,35 @@ impl AudioCapture {
     /// Останавливает запись и возвращает захваченный буфер + формат.
     ///
     /// После вызова stream уничтожается, буфер очищается.
-    pub fn stop_recording(&mut self) -> Result<(Vec<f32>, CaptureFormat)> {
+    /// Если поток упал во время записи, возвращает `DeviceDisconnected`.
+    pub fn stop_recording(&mut self) -> Result<CapturedAudio> {
         self.is_recording.store(false, Ordering::SeqCst);
+        self.paused = false;
 
         // Drop stream для остановки
         self.stream.take();
+        self.level.store(0, Ordering::Relaxed);
 
         let format = self.format.take().ok_or(AudioError::NotRecording)?;
 
+        if self.take_stream_error().is_some() {
+            self.buffer.lock().expect("buffer mutex poisoned").clear();
+            return Err(AudioError::DeviceDisconnected);
+        }
+
         let samples = {
             let mut buf = self.buffer.lock().expect("buffer mutex poisoned");
             std::mem::take(&mut *buf)
         };
 
+        let truncated = self.truncated.swap(false, Ordering::SeqCst);
+        if truncated {
+            tracing::warn!(
+                max_sec = self.max_duration_sec,
+                "recording buffer limit reached, audio truncated"
+            );
+        }
+
         tracing::info!(
             samples = samples.len(),
             sample_rate = format.sample_rate,
@@ -178,7 +478,145 @@ impl AudioCapture {
             "audio recording stopped"
         );
 
-        Ok((samples, format))
+        Ok(CapturedAudio {
+            samples,
+            format,
+            truncated,
+        })
+    }
+
+    /// Возвращает RMS каждого канала за последние `LEVEL_WINDOW_MS` мс записи.
+    ///
+    /// Помогает найти "мертвый" канал у стерео-микрофонов.
+    /// Пустой вектор, если запись не идет.
+    pub fn channel_levels(&self) -> Vec<f32> {
+        let Some(format) = self.format.as_ref() else {
+            return Vec::new();
+        };
+
+        let ch = format.channels.max(1) as usize;
+        let window_frames = (format.sample_rate * LEVEL_WINDOW_MS / 1000) as usize;
+        let buf = self.buffer.lock().expect("buffer mutex poisoned");
+        let end = buf.len() - buf.len() % ch;
+        let start = end.saturating_sub(window_frames * ch);
+
+        preprocess::channel_rms(&buf[start..end], format.channels)
+    }
+
+    /// Копия записанных сэмплов начиная с `offset` и формат записи.
+    ///
+    /// Для мониторинга идущей записи (авто-стоп); `None`, если запись не начата.
+    pub fn samples_since(&self, offset: usize) -> Option<(Vec<f32>, CaptureFormat)> {
+        let format = self.format.clone()?;
+        let buf = self.buffer.lock().expect("buffer mutex poisoned");
+        let start = offset.min(buf.len());
+        Some((buf[start..].to_vec(), format))
+    }
+
+    /// Текущий уровень входного сигнала (RMS последнего буфера, 0.0..=1.0).
+    ///
+    /// Обновляется в cpal callback без аллокаций; 0.0, если запись не идет.
+    pub fn current_level(&self) -> f32 {
+        level_from_fixed(self.level.load(Ordering::Relaxed))
+    }
+
+    /// Выбирает конфиг устройства с предпочитаемым форматом сэмплов.
+    ///
+    /// Частота берется как у конфига по умолчанию, если формат ее поддерживает,
+    /// иначе максимальная. Если формат недоступен - конфиг по умолчанию.
+    fn select_input_config(&self, device: &cpal::Device) -> Result<cpal::SupportedStreamConfig> {
+        let default = device
+            .default_input_config()
+            .map_err(|e| AudioError::NoInputConfig(e.to_string()))?;
+
+        let Some(preferred) = preferred_cpal_format(self.sample_format) else {
+            return Ok(default);
+        };
+        if default.sample_format() == preferred {
+            return Ok(default);
+        }
+
+        let matching = device
+            .supported_input_configs()
+            .ok()
+            .and_then(|mut ranges| {
+                ranges
+                    .find(|range| {
+                        range.sample_format() == preferred && range.channels() == default.channels()
+                    })
+                    .map(|range| {
+                        range
+                            .try_with_sample_rate(default.sample_rate())
+                            .unwrap_or_else(|| range.with_max_sample_rate())
+                    })
+            });
+
+        match matching {
+            Some(config) => Ok(config),
+            None => {
+                tracing::warn!(
+                    preferred = ?preferred,
+                    chosen = ?default.sample_format(),
+                    "preferred sample format not available, using device default"
+                );
+                Ok(default)
+            }
+        }
+    }
+
+    /// Приостанавливает накопление данных, не закрывая поток.
+    pub fn pause_recording(&mut self) -> Result<()> {
+        if self.format.is_none() || self.paused {
+            return Err(AudioError::NotRecording);
+        }
+        self.is_recording.store(false, Ordering::SeqCst);
+        self.level.store(0, Ordering::Relaxed);
+        self.paused = true;
+        tracing::info!("audio recording paused");
+        Ok(())
+    }
+
+    /// Продолжает запись в тот же буфер - итоговый клип остается непрерывным.
+    pub fn resume_recording(&mut self) -> Result<()> {
+        if !self.paused {
+            return Err(AudioError::NotRecording);
+        }
+        self.paused = false;
+        self.is_recording.store(true, Ordering::SeqCst);
+        tracing::info!("audio recording resumed");
+        Ok(())
+    }
+
+    /// Проверяет, приостановлена ли запись.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Проверяет, упал ли поток записи (например, микрофон отключен).
+    pub fn has_stream_failed(&self) -> bool {
+        self.stream_failed.load(Ordering::SeqCst)
+    }
+
+    /// Забирает ошибку упавшего потока (например, микрофон отключен во время записи).
+    ///
+    /// Возвращает текст ошибки cpal и сбрасывает флаг, так что о сбое
+    /// сообщается один раз. `None` - поток работает.
+    pub fn take_stream_error(&mut self) -> Option<String> {
+        if !self.stream_failed.swap(false, Ordering::SeqCst) {
+            return None;
+        }
+        let error = self
+            .stream_error
+            .lock()
+            .expect("stream error mutex poisoned")
+            .take();
+        Some(error.unwrap_or_else(|| "unknown stream error".to_string()))
+    }
+
+    /// Проверяет, заполнен ли буфер записи (`max_buffer_samples`): новые
+    /// сэмплы отбрасываются, запись пора остановить.
+    pub fn is_buffer_full(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed)
     }
 
     /// Проверяет, идет ли запись.
@@ -186,3 +624,288 @@ impl AudioCapture {
         self.is_recording.load(Ordering::SeqCst)
     }
 }
+
+impl AudioSource for AudioCapture {
+    fn start_recording(&mut self) -> Result<()> {
+        AudioCapture::start_recording(self)
+    }
+
+    fn stop_recording(&mut self) -> Result<(Vec<f32>, CaptureFormat)> {
+        AudioCapture::stop_recording(self).map(|captured| (captured.samples, captured.format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant_probe(attempts: u32) -> DeviceProbe {
+        DeviceProbe {
+            attempts,
+            delay: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn probe_should_return_device_when_it_appears_after_retries() {
+        // Given: устройство появляется только на третьем опросе
+        let mut calls = 0;
+        let lister = || {
+            calls += 1;
+            (calls >= 3).then_some("USB Microphone")
+        };
+
+        // When
+        let device = probe_for_device(lister, instant_probe(3));
+
+        // Then
+        assert_eq!(device, Some("USB Microphone"));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn probe_should_give_up_after_all_attempts() {
+        // Given
+        let mut calls = 0;
+        let lister = || {
+            calls += 1;
+            None::<&str>
+        };
+
+        // When
+        let device = probe_for_device(lister, instant_probe(3));
+
+        // Then
+        assert_eq!(device, None);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn probe_should_not_retry_when_device_present() {
+        // Given
+        let mut calls = 0;
+        let lister = || {
+            calls += 1;
+            Some("Built-in Microphone")
+        };
+
+        // When
+        let device = probe_for_device(lister, instant_probe(3));
+
+        // Then
+        assert_eq!(device, Some("Built-in Microphone"));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn stop_recording_should_report_disconnect_when_stream_failed() {
+        // Given: запись "шла", затем err_callback пометил поток как упавший
+        let mut capture = AudioCapture::build(instant_probe(1), None);
+        capture.format = Some(CaptureFormat {
+            sample_rate: 48000,
+            channels: 1,
+        });
+        capture
+            .buffer
+            .lock()
+            .unwrap()
+            .extend_from_slice(&[0.1; 480]);
+        capture.stream_failed.store(true, Ordering::SeqCst);
+
+        // When
+        let result = capture.stop_recording();
+
+        // Then
+        assert!(matches!(result, Err(AudioError::DeviceDisconnected)));
+        assert!(!capture.has_stream_failed());
+        assert!(capture.buffer.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn take_stream_error_should_report_failure_once() {
+        // Given: err_callback сохранил ошибку и поднял флаг
+        let mut capture = AudioCapture::build(instant_probe(1), None);
+        *capture.stream_error.lock().unwrap() = Some("device unplugged".to_string());
+        capture.stream_failed.store(true, Ordering::SeqCst);
+
+        // When
+        let first = capture.take_stream_error();
+        let second = capture.take_stream_error();
+
+        // Then
+        assert_eq!(first.as_deref(), Some("device unplugged"));
+        assert_eq!(second, None);
+        assert!(!capture.has_stream_failed());
+    }
+
+    fn started_capture() -> AudioCapture {
+        let mut capture = AudioCapture::build(instant_probe(1), None);
+        capture.format = Some(CaptureFormat {
+            sample_rate: 48000,
+            channels: 1,
+        });
+        capture.is_recording.store(true, Ordering::SeqCst);
+        capture
+    }
+
+    #[test]
+    fn pause_and_resume_should_keep_single_buffer() {
+        // Given: часть записи до паузы
+        let mut capture = started_capture();
+        capture
+            .buffer
+            .lock()
+            .unwrap()
+            .extend_from_slice(&[0.1; 480]);
+
+        // When
+        capture.pause_recording().expect("pause should succeed");
+        let paused = capture.is_paused() && !capture.is_recording();
+        capture.resume_recording().expect("resume should succeed");
+        capture
+            .buffer
+            .lock()
+            .unwrap()
+            .extend_from_slice(&[0.2; 480]);
+        let captured = capture.stop_recording().expect("stop should succeed");
+
+        // Then
+        assert!(paused);
+        assert_eq!(captured.samples.len(), 960);
+        assert_eq!(captured.samples[0], 0.1);
+        assert_eq!(captured.samples[959], 0.2);
+    }
+
+    #[test]
+    fn pause_should_fail_when_not_recording() {
+        // Given
+        let mut capture = AudioCapture::build(instant_probe(1), None);
+
+        // When / Then
+        assert!(matches!(
+            capture.pause_recording(),
+            Err(AudioError::NotRecording)
+        ));
+        assert!(matches!(
+            capture.resume_recording(),
+            Err(AudioError::NotRecording)
+        ));
+    }
+
+    #[test]
+    fn u16_to_f32_should_map_known_samples() {
+        assert_eq!(u16_to_f32(0), -1.0);
+        assert_eq!(u16_to_f32(32768), 0.0);
+        assert!((u16_to_f32(u16::MAX) - 1.0).abs() < 1e-4);
+        assert_eq!(u16_to_f32(16384), -0.5);
+        assert_eq!(u16_to_f32(49152), 0.5);
+    }
+
+    #[test]
+    fn i16_to_f32_should_map_known_samples() {
+        assert_eq!(i16_to_f32(0), 0.0);
+        assert_eq!(i16_to_f32(i16::MAX), 1.0);
+        assert!((i16_to_f32(i16::MIN) + 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn preferred_cpal_format_should_map_config_values() {
+        assert_eq!(preferred_cpal_format(CaptureSampleFormat::Auto), None);
+        assert_eq!(
+            preferred_cpal_format(CaptureSampleFormat::F32),
+            Some(SampleFormat::F32)
+        );
+        assert_eq!(
+            preferred_cpal_format(CaptureSampleFormat::I16),
+            Some(SampleFormat::I16)
+        );
+        assert_eq!(
+            preferred_cpal_format(CaptureSampleFormat::U16),
+            Some(SampleFormat::U16)
+        );
+    }
+
+    #[test]
+    fn max_buffer_samples_should_cover_full_duration_with_headroom() {
+        // Given / When
+        let cap = max_buffer_samples(48000, 2, 60);
+
+        // Then
+        assert!(cap >= 48000 * 2 * 60);
+        assert_eq!(cap, 48000 * 2 * (60 + BUFFER_HEADROOM_SEC as usize));
+    }
+
+    #[test]
+    fn converted_rms_should_match_f32_energy() {
+        // Given: полная шкала i16 и середина u16
+        let loud = [i16::MAX, i16::MIN + 1, i16::MAX, i16::MIN + 1];
+        let silent = [32768u16; 4];
+
+        // When / Then
+        assert!((converted_rms(&loud, i16_to_f32) - 1.0).abs() < 1e-6);
+        assert_eq!(converted_rms(&silent, u16_to_f32), 0.0);
+        assert_eq!(converted_rms::<i16>(&[], i16_to_f32), 0.0);
+    }
+
+    #[test]
+    fn append_capped_should_stop_at_limit_and_report_truncation() {
+        // Given
+        let mut buf = vec![0.0; 8];
+
+        // When
+        let first = append_capped(&mut buf, [0.5; 2].into_iter(), 10);
+        let second = append_capped(&mut buf, [0.5; 4].into_iter(), 10);
+
+        // Then
+        assert!(!first);
+        assert!(second);
+        assert_eq!(buf.len(), 10);
+    }
+
+    #[test]
+    fn level_fixed_point_should_round_trip() {
+        // Given
+        let level = 0.123_456;
+
+        // When
+        let restored = level_from_fixed(level_to_fixed(level));
+
+        // Then
+        assert!((restored - level).abs() < 1e-6);
+    }
+
+    #[test]
+    fn level_to_fixed_should_clamp_out_of_range_values() {
+        assert_eq!(level_to_fixed(-0.5), 0);
+        assert_eq!(level_from_fixed(level_to_fixed(3.0)), 1.0);
+    }
+
+    #[test]
+    fn select_by_name_should_pick_exact_match() {
+        // Given
+        let devices = vec![
+            ("Webcam Microphone".to_string(), 1),
+            ("USB Microphone".to_string(), 2),
+        ];
+
+        // When / Then
+        assert_eq!(select_by_name(devices.clone(), "USB Microphone"), Some(2));
+        assert_eq!(select_by_name(devices, "USB"), None);
+    }
+
+    #[test]
+    fn probe_should_try_at_least_once_when_attempts_zero() {
+        // Given
+        let mut calls = 0;
+        let lister = || {
+            calls += 1;
+            None::<&str>
+        };
+
+        // When
+        let _ = probe_for_device(lister, instant_probe(0));
+
+        // Then
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::Cursor;
+
+use ogg::PacketReader;
+use opus::{Channels, Decoder};
+
+use super::{AudioError, Result};
+
+/// Частота декодирования (совпадает с частотой `encode_ogg_opus`).
+const DECODE_SAMPLE_RATE: u32 = 16_000;
+
+/// Максимальная длина Opus-кадра: 120ms при 16kHz.
+const MAX_FRAME_SAMPLES: usize = 1920;
+
+/// Отсчетов granule position (48kHz) на один сэмпл 16kHz.
+const GRANULE_PER_SAMPLE: u64 = 48_000 / DECODE_SAMPLE_RATE as u64;
+
+/// Декодирует OGG/Opus в PCM mono 16kHz.
+///
+/// Обратная операция к `encode_ogg_opus`: pre-skip из OpusHead отбрасывается,
+/// длина обрезается по granule position последнего пакета (RFC 7845).
+/// Нужна офлайн-провайдерам STT, которые принимают те же байты, что и облачный API.
+#[cfg_attr(not(feature = "offline-whisper"), allow(dead_code))]
+pub fn decode_ogg_opus(data: &[u8]) -> Result<Vec<f32>> {
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut reader = PacketReader::new(Cursor::new(data));
+    let mut decoder = Decoder::new(DECODE_SAMPLE_RATE, Channels::Mono)
+        .map_err(|e| AudioError::DecodingFailed(e.to_string()))?;
+
+    let mut pre_skip: Option<usize> = None;
+    let mut packet_index = 0usize;
+    let mut last_granule = 0u64;
+    let mut samples = Vec::new();
+    let mut frame = [0.0f32; MAX_FRAME_SAMPLES];
+
+    while let Some(packet) = reader
+        .read_packet()
+        .map_err(|e| AudioError::DecodingFailed(format!("OGG read: {e}")))?
+    {
+        match packet_index {
+            0 => pre_skip = Some(parse_pre_skip(&packet.data)?),
+            // OpusTags - не нужны для декодирования
+            1 => {}
+            _ => {
+                let decoded = decoder
+                    .decode_float(&packet.data, &mut frame, false)
+                    .map_err(|e| AudioError::DecodingFailed(e.to_string()))?;
+                samples.extend_from_slice(&frame[..decoded]);
+                last_granule = packet.absgp_page();
+            }
+        }
+        packet_index += 1;
+    }
+
+    let pre_skip =
+        pre_skip.ok_or_else(|| AudioError::DecodingFailed("missing OpusHead".to_string()))?;
+    let skip = pre_skip.min(samples.len());
+    samples.drain(..skip);
+
+    let total = (last_granule / GRANULE_PER_SAMPLE) as usize;
+    samples.truncate(total.saturating_sub(skip));
+
+    Ok(samples)
+}
+
+/// Декодирует аудио, загружаемое на распознавание: WAV или OGG/Opus
+/// (формат определяется по сигнатуре, см. `audio_upload_format`).
+#[cfg_attr(not(feature = "offline-whisper"), allow(dead_code))]
+pub fn decode_audio(data: &[u8]) -> Result<Vec<f32>> {
+    if data.starts_with(b"RIFF") {
+        decode_wav(data)
+    } else {
+        decode_ogg_opus(data)
+    }
+}
+
+/// Декодирует WAV 16-bit PCM mono 16kHz (формат `encode_wav`).
+#[cfg_attr(not(feature = "offline-whisper"), allow(dead_code))]
+pub fn decode_wav(data: &[u8]) -> Result<Vec<f32>> {
+    let invalid = |reason: &str| AudioError::DecodingFailed(format!("WAV: {reason}"));
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(invalid("missing RIFF/WAVE header"));
+    }
+
+    let mut format_ok = false;
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let id = &data[offset..offset + 4];
+        let size = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let body = &data[offset + 8..(offset + 8 + size).min(data.len())];
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let format = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                if format != 1 || channels != 1 || bits != 16 {
+                    return Err(invalid("only 16-bit PCM mono is supported"));
+                }
+                if sample_rate != DECODE_SAMPLE_RATE {
+                    return Err(invalid(&format!(
+                        "expected {DECODE_SAMPLE_RATE} Hz, got {sample_rate} Hz"
+                    )));
+                }
+                format_ok = true;
+            }
+            b"data" if format_ok => {
+                return Ok(body
+                    .chunks_exact(2)
+                    .map(|b| f32::from(i16::from_le_bytes([b[0], b[1]])) / f32::from(i16::MAX))
+                    .collect());
+            }
+            _ => {}
+        }
+        // Чанки выравниваются по четной границе
+        offset += 8 + size + size % 2;
+    }
+    Err(invalid("missing fmt or data chunk"))
+}
+
+/// Читает pre-skip из заголовка OpusHead и переводит его в сэмплы 16kHz.
+fn parse_pre_skip(head: &[u8]) -> Result<usize> {
+    if head.len() < 19 || &head[..8] != b"OpusHead" {
+        return Err(AudioError::DecodingFailed(
+            "invalid OpusHead header".to_string(),
+        ));
+    }
+    let pre_skip_48k = u16::from_le_bytes([head[10], head[11]]);
+    Ok((u64::from(pre_skip_48k) / GRANULE_PER_SAMPLE) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encode::encode_ogg_opus;
+
+    fn generate_tone(duration_ms: u32) -> Vec<f32> {
+        let num_samples = (DECODE_SAMPLE_RATE * duration_ms / 1000) as usize;
+        (0..num_samples)
+            .map(|i| {
+                let t = i as f32 / DECODE_SAMPLE_RATE as f32;
+                0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn decode_should_roundtrip_encoded_tone() {
+        // Given
+        let tone = generate_tone(1000);
+        let encoded = encode_ogg_opus(&tone, DECODE_SAMPLE_RATE).unwrap();
+
+        // When
+        let decoded = decode_ogg_opus(&encoded).expect("decoding should succeed");
+
+        // Then: длина с точностью до кадра, громкость сохранена
+        assert!(decoded.len().abs_diff(tone.len()) <= 320);
+        assert!((rms(&decoded) - rms(&tone)).abs() < 0.1);
+    }
+
+    #[test]
+    fn decode_audio_should_roundtrip_wav() {
+        // Given
+        let tone = generate_tone(200);
+        let wav = crate::audio::encode::encode_wav(&tone, DECODE_SAMPLE_RATE);
+
+        // When
+        let decoded = decode_audio(&wav).expect("decoding should succeed");
+
+        // Then: точная длина, погрешность квантования 16 бит
+        assert_eq!(decoded.len(), tone.len());
+        assert!(decoded.iter().zip(&tone).all(|(a, b)| (a - b).abs() < 1e-3));
+    }
+
+    #[test]
+    fn decode_wav_should_reject_other_sample_rates() {
+        let wav = crate::audio::encode::encode_wav(&[0.0; 100], 44_100);
+        assert!(decode_wav(&wav).is_err());
+    }
+
+    #[test]
+    fn decode_should_return_empty_for_empty_input() {
+        assert!(decode_ogg_opus(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn decode_should_reject_non_ogg_data() {
+        assert!(decode_ogg_opus(b"definitely not an ogg stream").is_err());
+    }
+}
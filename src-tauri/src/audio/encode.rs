@@ -1,12 +1,24 @@
+use std::path::Path;
+
 use ogg::writing::{PacketWriteEndInfo, PacketWriter};
 use opus::{Application, Channels, Encoder};
 
 use super::{AudioError, Result};
+use crate::config::schema::{AppConfig, OpusApplication, UploadFormat, OPUS_BITRATE_RANGE_KBPS};
 
 /// Ожидаемая частота дискретизации (preprocess приводит к 16kHz).
 #[allow(dead_code)]
 const EXPECTED_SAMPLE_RATE: u32 = 16_000;
 
+/// Родная частота Opus: запись можно кодировать без понижения до 16kHz.
+const NATIVE_SAMPLE_RATE: u32 = 48_000;
+
+/// Частоты, принимаемые Opus-кодером.
+const SUPPORTED_SAMPLE_RATES: [u32; 2] = [EXPECTED_SAMPLE_RATE, NATIVE_SAMPLE_RATE];
+
+/// Кадров Opus в секунду (кадр 20ms).
+const FRAMES_PER_SECOND: u32 = 50;
+
 /// Размер кадра Opus: 20ms при 16 kHz = 320 samples.
 #[allow(dead_code)]
 const FRAME_SIZE: usize = 320;
@@ -27,28 +39,112 @@ const STREAM_SERIAL: u32 = 1;
 #[allow(dead_code)]
 const GRANULE_PER_FRAME: u64 = 960;
 
-/// Кодирует PCM mono 16kHz в OGG/Opus.
-///
-/// На входе ожидается mono 16kHz PCM после `preprocess()`.
-/// На выходе - валидный OGG/Opus файл, готовый для отправки в OpenAI API.
-/// Bitrate: 24 kbps (VoIP, достаточно для речи).
+/// Отсчетов granule position (48kHz) на один входной сэмпл 16kHz.
 #[allow(dead_code)]
+const GRANULE_PER_SAMPLE: u64 = NATIVE_SAMPLE_RATE as u64 / EXPECTED_SAMPLE_RATE as u64;
+
+/// Битрейт по умолчанию (кбит/с): достаточно для речи.
+const DEFAULT_BITRATE_KBPS: u32 = 24;
+
+/// Формат и параметры кодирования аудио для распознавания.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeConfig {
+    pub format: UploadFormat,
+    /// Битрейт Opus (кбит/с), в пределах `OPUS_BITRATE_RANGE_KBPS`
+    pub bitrate_kbps: u32,
+    pub application: OpusApplication,
+}
+
+impl Default for EncodeConfig {
+    fn default() -> Self {
+        Self {
+            format: UploadFormat::OggOpus,
+            bitrate_kbps: DEFAULT_BITRATE_KBPS,
+            application: OpusApplication::Voip,
+        }
+    }
+}
+
+impl EncodeConfig {
+    /// Параметры из конфига; битрейт приводится к допустимому для Opus диапазону.
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            format: config.audio_upload_format,
+            bitrate_kbps: config.opus_bitrate_kbps.clamp(
+                *OPUS_BITRATE_RANGE_KBPS.start(),
+                *OPUS_BITRATE_RANGE_KBPS.end(),
+            ),
+            application: config.opus_application,
+        }
+    }
+}
+
+/// Кодирует PCM mono в формат `encode.format`.
+///
+/// WAV принимает любую частоту дискретизации, OGG/Opus - только 16kHz и 48kHz.
+pub fn encode_audio(samples: &[f32], sample_rate: u32, encode: &EncodeConfig) -> Result<Vec<u8>> {
+    match encode.format {
+        UploadFormat::OggOpus => encode_ogg_opus_with(samples, sample_rate, encode),
+        UploadFormat::Wav => Ok(encode_wav(samples, sample_rate)),
+    }
+}
+
+/// Кодирует PCM mono 16kHz или 48kHz в OGG/Opus с параметрами по умолчанию.
+///
+/// См. [`encode_ogg_opus_with`].
+#[cfg(test)]
 pub fn encode_ogg_opus(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    encode_ogg_opus_with(samples, sample_rate, &EncodeConfig::default())
+}
+
+/// Кодирует PCM mono 16kHz или 48kHz в OGG/Opus.
+///
+/// На входе ожидается mono 16kHz PCM после `preprocess()` либо mono 48kHz
+/// без понижения частоты (родная частота Opus). Размер кадра (20ms) и шаг
+/// granule position зависят от входной частоты; granule всегда в 48kHz.
+/// На выходе - валидный OGG/Opus файл, готовый для отправки в OpenAI API.
+/// Битрейт и режим кодера - из `encode` (по умолчанию 24 kbps, VoIP); битрейт вне
+/// `OPUS_BITRATE_RANGE_KBPS` - `EncodingFailed`.
+///
+/// Granule position (RFC 7845) считает и pre-skip: длительность для плеера -
+/// `granule - PRE_SKIP`. Поэтому в конец добавляются фреймы тишины, пока
+/// закодированный поток не покроет `PRE_SKIP` + реальные сэмплы (иначе хвост
+/// записи остался бы в задержке кодера), а granule последнего пакета равна
+/// ровно реальной длине + `PRE_SKIP` - тишина дополнения в длительность не входит.
+pub fn encode_ogg_opus_with(
+    samples: &[f32],
+    sample_rate: u32,
+    encode: &EncodeConfig,
+) -> Result<Vec<u8>> {
     if samples.is_empty() {
         return Ok(Vec::new());
     }
 
-    if sample_rate != EXPECTED_SAMPLE_RATE {
+    if !SUPPORTED_SAMPLE_RATES.contains(&sample_rate) {
         return Err(AudioError::EncodingFailed(format!(
-            "expected {EXPECTED_SAMPLE_RATE} Hz, got {sample_rate} Hz"
+            "expected {EXPECTED_SAMPLE_RATE} or {NATIVE_SAMPLE_RATE} Hz, got {sample_rate} Hz"
         )));
     }
-
-    let mut encoder = Encoder::new(sample_rate, Channels::Mono, Application::Voip)
+    let frame_size = (sample_rate / FRAMES_PER_SECOND) as usize;
+    let granule_per_sample = u64::from(NATIVE_SAMPLE_RATE / sample_rate);
+
+    let application = match encode.application {
+        OpusApplication::Voip => Application::Voip,
+        OpusApplication::Audio => Application::Audio,
+    };
+    let mut encoder = Encoder::new(sample_rate, Channels::Mono, application)
         .map_err(|e| AudioError::EncodingFailed(e.to_string()))?;
 
+    let bitrate_kbps = encode.bitrate_kbps;
+    if !OPUS_BITRATE_RANGE_KBPS.contains(&bitrate_kbps) {
+        return Err(AudioError::EncodingFailed(format!(
+            "Opus bitrate {bitrate_kbps} kbps is outside {}..={} kbps",
+            OPUS_BITRATE_RANGE_KBPS.start(),
+            OPUS_BITRATE_RANGE_KBPS.end()
+        )));
+    }
     encoder
-        .set_bitrate(opus::Bitrate::Bits(24000))
+        .set_bitrate(opus::Bitrate::Bits(bitrate_kbps as i32 * 1000))
         .map_err(|e| AudioError::EncodingFailed(e.to_string()))?;
 
     let mut out = Vec::new();
@@ -68,22 +164,29 @@ pub fn encode_ogg_opus(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
             .map_err(|e| AudioError::EncodingFailed(format!("OGG tags write: {e}")))?;
 
         // Audio data packets (20ms frames)
-        let total_frames = samples.len().div_ceil(FRAME_SIZE);
+        let final_granule = samples.len() as u64 * granule_per_sample + u64::from(PRE_SKIP);
+        let total_frames = final_granule.div_ceil(GRANULE_PER_FRAME) as usize;
         let mut granule_pos: u64 = 0;
+        let mut frame = vec![0.0f32; frame_size];
 
-        for (i, chunk) in samples.chunks(FRAME_SIZE).enumerate() {
-            let mut frame = [0.0f32; FRAME_SIZE];
+        for i in 0..total_frames {
+            let start = (i * frame_size).min(samples.len());
+            let chunk = &samples[start..(start + frame_size).min(samples.len())];
+            frame.fill(0.0);
             frame[..chunk.len()].copy_from_slice(chunk);
 
             let encoded = encoder
                 .encode_vec_float(&frame, MAX_PACKET_SIZE)
                 .map_err(|e| AudioError::EncodingFailed(e.to_string()))?;
 
-            granule_pos += GRANULE_PER_FRAME;
-
-            let end_info = if i == total_frames - 1 {
+            let is_last = i == total_frames - 1;
+            let end_info = if is_last {
+                // Обрезка конца (RFC 7845): granule = реальная длина + pre-skip,
+                // а не дополненная
+                granule_pos = final_granule;
                 PacketWriteEndInfo::EndStream
             } else {
+                granule_pos += GRANULE_PER_FRAME;
                 PacketWriteEndInfo::NormalPacket
             };
 
@@ -106,6 +209,47 @@ pub fn encode_ogg_opus(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
     Ok(out)
 }
 
+/// Кодирует PCM mono в WAV (16-bit PCM, little-endian).
+///
+/// Частота дискретизации записывается в заголовок как есть, без ресемплинга.
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    const BITS_PER_SAMPLE: u16 = 16;
+    const CHANNELS: u16 = 1;
+
+    let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+    let byte_rate = sample_rate * u32::from(block_align);
+    let data_len = (samples.len() * usize::from(block_align)) as u32;
+
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&CHANNELS.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&byte_rate.to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    out
+}
+
+/// Сохраняет PCM mono в WAV-файл (16-bit PCM).
+pub fn save_wav(samples: &[f32], sample_rate: u32, path: &Path) -> Result<()> {
+    std::fs::write(path, encode_wav(samples, sample_rate))
+        .map_err(|e| AudioError::EncodingFailed(format!("WAV write {}: {e}", path.display())))
+}
+
 /// Формирует OpusHead header по RFC 7845.
 ///
 /// Структура (19 байт):
@@ -170,6 +314,24 @@ mod tests {
         assert_eq!(&result[..4], b"OggS");
     }
 
+    #[test]
+    fn encode_should_produce_ogg_stream_at_48khz() {
+        // Given: 1010 сэмплов 48kHz = 1 полный фрейм (960) + хвост 50
+        let tone = generate_tone(48000, 1000, 440.0, 0.5)[..1010].to_vec();
+
+        // When
+        let encoded = encode_ogg_opus(&tone, 48000).expect("48kHz encoding should succeed");
+
+        // Then: валидный поток, частота в OpusHead, granule без умножения
+        assert_eq!(&encoded[..4], b"OggS");
+        let head = build_opus_head(48000);
+        assert!(encoded.windows(head.len()).any(|w| w == head.as_slice()));
+        assert_eq!(
+            audio_granules(&encoded).last(),
+            Some(&(1010 + u64::from(PRE_SKIP)))
+        );
+    }
+
     #[test]
     fn encode_should_compress_significantly() {
         // Given: 1 second mono 16kHz (raw PCM = 64000 bytes)
@@ -212,6 +374,164 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    /// Возвращает granule position всех аудио-пакетов (после двух заголовков).
+    fn audio_granules(encoded: &[u8]) -> Vec<u64> {
+        let mut reader = ogg::PacketReader::new(std::io::Cursor::new(encoded));
+        let mut granules = Vec::new();
+        while let Some(packet) = reader.read_packet().expect("valid ogg stream") {
+            granules.push(packet.absgp_page());
+        }
+        granules.split_off(2)
+    }
+
+    #[test]
+    fn final_granule_should_match_real_sample_count_plus_pre_skip() {
+        // Given: 1010 сэмплов = 3 полных фрейма + хвост 50 сэмплов
+        let tone = generate_tone(16000, 1000, 440.0, 0.5)[..1010].to_vec();
+
+        // When
+        let encoded = encode_ogg_opus(&tone, 16000).expect("encoding should succeed");
+
+        // Then: 1010 * 3 + pre-skip, а не 4 * 960 (дополненная длина)
+        let granules = audio_granules(&encoded);
+        assert_eq!(granules.len(), 4);
+        assert_eq!(
+            *granules.last().unwrap(),
+            1010 * GRANULE_PER_SAMPLE + u64::from(PRE_SKIP)
+        );
+        assert_ne!(*granules.last().unwrap(), 4 * GRANULE_PER_FRAME);
+    }
+
+    #[test]
+    fn encode_should_add_frame_to_cover_pre_skip_when_no_tail() {
+        // Given: ровно 2 фрейма
+        let tone = generate_tone(16000, 40, 440.0, 0.5);
+        assert_eq!(tone.len(), 2 * FRAME_SIZE);
+
+        // When
+        let encoded = encode_ogg_opus(&tone, 16000).expect("encoding should succeed");
+
+        // Then: третий фрейм выводит из кодера задержку pre-skip
+        let granules = audio_granules(&encoded);
+        assert_eq!(granules, vec![960, 1920, 1920 + u64::from(PRE_SKIP)]);
+    }
+
+    #[test]
+    fn decoded_duration_should_match_input_sample_count() {
+        // Given
+        let tone = generate_tone(16000, 1000, 440.0, 0.5)[..12_345].to_vec();
+        let encoded = encode_ogg_opus(&tone, 16000).expect("encoding should succeed");
+
+        // When: декодируем поток и обрезаем по RFC 7845 (pre-skip и final granule)
+        let mut reader = ogg::PacketReader::new(std::io::Cursor::new(&encoded));
+        let mut decoder = opus::Decoder::new(16000, Channels::Mono).expect("decoder");
+        let mut buf = [0.0f32; FRAME_SIZE * 6];
+        let mut decoded_samples = 0u64;
+        let mut final_granule = 0u64;
+        let mut packet_index = 0;
+        while let Some(packet) = reader.read_packet().expect("valid ogg stream") {
+            packet_index += 1;
+            if packet_index <= 2 {
+                continue;
+            }
+            decoded_samples += decoder
+                .decode_float(&packet.data, &mut buf, false)
+                .expect("valid opus packet") as u64;
+            final_granule = packet.absgp_page();
+        }
+
+        // Then: поток покрывает всю запись, а длительность для плеера равна входу
+        assert!(decoded_samples * GRANULE_PER_SAMPLE >= final_granule);
+        let playable = (final_granule - u64::from(PRE_SKIP)) / GRANULE_PER_SAMPLE;
+        assert_eq!(playable, tone.len() as u64);
+    }
+
+    #[test]
+    fn higher_bitrate_should_produce_larger_file() {
+        // Given: шумоподобный сигнал, чтобы кодеру было что тратить на битрейт
+        let mut seed: u32 = 12345;
+        let noise: Vec<f32> = (0..16_000 * 2)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as f32 / 32768.0 - 1.0
+            })
+            .collect();
+        let high = EncodeConfig {
+            bitrate_kbps: 64,
+            application: OpusApplication::Audio,
+            ..EncodeConfig::default()
+        };
+
+        // When
+        let default_size = encode_ogg_opus(&noise, 16000).unwrap().len();
+        let high_size = encode_ogg_opus_with(&noise, 16000, &high).unwrap().len();
+
+        // Then
+        assert!(
+            high_size > default_size,
+            "64 kbps: {high_size} bytes, default: {default_size} bytes"
+        );
+    }
+
+    #[test]
+    fn encode_ogg_opus_with_should_reject_bitrate_out_of_range() {
+        // Given
+        let tone = generate_tone(16000, 100, 440.0, 0.5);
+        let too_high = EncodeConfig {
+            bitrate_kbps: 128,
+            ..EncodeConfig::default()
+        };
+
+        // When
+        let result = encode_ogg_opus_with(&tone, 16000, &too_high);
+
+        // Then
+        assert!(matches!(result, Err(AudioError::EncodingFailed(_))));
+    }
+
+    #[test]
+    fn encode_audio_should_write_wav_at_any_sample_rate() {
+        // Given: 44.1kHz отвергается Opus-кодером, но допустим для WAV
+        let tone = generate_tone(44_100, 100, 440.0, 0.5);
+        let wav = EncodeConfig {
+            format: UploadFormat::Wav,
+            ..EncodeConfig::default()
+        };
+
+        // When
+        let encoded = encode_audio(&tone, 44_100, &wav).expect("wav encoding should succeed");
+
+        // Then
+        assert_eq!(&encoded[..4], b"RIFF");
+        assert_eq!(&encoded[8..12], b"WAVE");
+        assert_eq!(&encoded[24..28], &44_100u32.to_le_bytes());
+        let data_len = u32::from_le_bytes(encoded[40..44].try_into().unwrap());
+        assert_eq!(data_len as usize, tone.len() * 2);
+        assert_eq!(encoded.len(), 44 + tone.len() * 2);
+    }
+
+    #[test]
+    fn encode_audio_should_keep_sample_rate_guard_for_opus() {
+        let tone = generate_tone(44_100, 100, 440.0, 0.5);
+        assert!(encode_audio(&tone, 44_100, &EncodeConfig::default()).is_err());
+    }
+
+    #[test]
+    fn encode_config_should_clamp_bitrate_from_config() {
+        // Given
+        let config = AppConfig {
+            opus_bitrate_kbps: 1,
+            ..AppConfig::default()
+        };
+
+        // When / Then
+        assert_eq!(EncodeConfig::from_config(&config).bitrate_kbps, 6);
+        assert_eq!(
+            EncodeConfig::from_config(&AppConfig::default()),
+            EncodeConfig::default()
+        );
+    }
+
     #[test]
     fn opus_head_should_have_correct_structure() {
         // Given / When
@@ -246,6 +566,50 @@ mod tests {
         // Then
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
-        assert!(err.contains("expected 16000 Hz"), "got: {err}");
+        assert!(err.contains("expected 16000 or 48000 Hz"), "got: {err}");
+    }
+
+    #[test]
+    fn encode_wav_should_write_pcm16_header_with_sample_rate() {
+        // Given
+        let tone = generate_tone(48000, 100, 440.0, 0.5);
+
+        // When
+        let wav = encode_wav(&tone, 48000);
+
+        // Then
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 1);
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 48000);
+        assert_eq!(u16::from_le_bytes([wav[34], wav[35]]), 16);
+        assert_eq!(
+            u32::from_le_bytes(wav[40..44].try_into().unwrap()) as usize,
+            tone.len() * 2
+        );
+        assert_eq!(wav.len(), 44 + tone.len() * 2);
+    }
+
+    #[test]
+    fn encode_wav_should_clamp_samples_out_of_range() {
+        // Given / When
+        let wav = encode_wav(&[2.0, -2.0], 16000);
+
+        // Then
+        assert_eq!(i16::from_le_bytes([wav[44], wav[45]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([wav[46], wav[47]]), -i16::MAX);
+    }
+
+    #[test]
+    fn save_wav_should_write_file() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clip.wav");
+
+        // When
+        save_wav(&[0.0; 160], 16000, &path).expect("save should succeed");
+
+        // Then
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 44 + 320);
     }
 }
@@ -1,4 +1,5 @@
 pub mod capture_cpal;
+pub mod decode;
 pub mod encode;
 pub mod preprocess;
 
@@ -10,11 +11,18 @@ pub struct CaptureFormat {
     pub channels: u16,
 }
 
+/// Описание input-устройства для UI настроек.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+}
+
 /// Ошибки аудио-модуля.
 #[derive(Debug, thiserror::Error)]
 #[allow(dead_code)]
 pub enum AudioError {
-    #[error("no audio input device found")]
+    #[error("no microphone found, please connect one")]
     NoInputDevice,
 
     #[error("failed to get default input config: {0}")]
@@ -31,7 +39,67 @@ pub enum AudioError {
 
     #[error("encoding failed: {0}")]
     EncodingFailed(String),
+
+    #[error("decoding failed: {0}")]
+    DecodingFailed(String),
+
+    #[error("microphone disconnected during recording")]
+    DeviceDisconnected,
 }
 
 #[allow(dead_code)]
 pub type Result<T> = std::result::Result<T, AudioError>;
+
+/// Проверяет, что запись короче `min_ms` (пустой буфер всегда слишком короткий).
+///
+/// Для interleaved-буфера `sample_rate` - сэмплы в секунду по всем каналам.
+pub fn is_too_short(samples: &[f32], sample_rate: u32, min_ms: u32) -> bool {
+    if samples.is_empty() || sample_rate == 0 {
+        return true;
+    }
+    (samples.len() as u64 * 1000) < u64::from(min_ms) * u64::from(sample_rate)
+}
+
+/// Источник аудио для записи (микрофон или заглушка в тестах).
+pub trait AudioSource {
+    /// Начинает запись.
+    fn start_recording(&mut self) -> Result<()>;
+
+    /// Останавливает запись и возвращает буфер + формат.
+    fn stop_recording(&mut self) -> Result<(Vec<f32>, CaptureFormat)>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_too_short_should_reject_below_minimum() {
+        // 299мс при 16kHz
+        assert!(is_too_short(&vec![0.0; 4784], 16_000, 300));
+    }
+
+    #[test]
+    fn is_too_short_should_accept_exact_minimum() {
+        assert!(!is_too_short(&vec![0.0; 4800], 16_000, 300));
+        assert!(!is_too_short(&vec![0.0; 4801], 16_000, 300));
+    }
+
+    #[test]
+    fn is_too_short_should_reject_one_sample_below_boundary() {
+        assert!(is_too_short(&vec![0.0; 4799], 16_000, 300));
+    }
+
+    #[test]
+    fn is_too_short_should_treat_empty_buffer_as_too_short() {
+        assert!(is_too_short(&[], 16_000, 300));
+        assert!(is_too_short(&[], 16_000, 0));
+    }
+
+    #[test]
+    fn is_too_short_should_account_for_interleaved_channels() {
+        // 300мс стерео 48kHz = 28800 сэмплов
+        assert!(!is_too_short(&vec![0.0; 28_800], 96_000, 300));
+        assert!(is_too_short(&vec![0.0; 14_400], 96_000, 300));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::config::schema::{AppConfig, ResampleQuality};
+
 /// Целевая частота дискретизации для STT.
 #[allow(dead_code)]
 pub const TARGET_SAMPLE_RATE: u32 = 16_000;
@@ -12,6 +14,79 @@ const ENERGY_FRAME_MS: u32 = 20;
 #[allow(dead_code)]
 const SILENCE_RMS_THRESHOLD: f32 = 0.005;
 
+/// Частота среза high-pass фильтра (Гц): убирает гул сети и удары по столу.
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+
+/// Целевой пик при нормализации громкости.
+const NORMALIZE_TARGET_PEAK: f32 = 0.9;
+
+/// Допуск, при котором пик считается уже достигнутым (нормализация не нужна).
+const NORMALIZE_TOLERANCE: f32 = 0.01;
+
+/// Пик ниже этого значения (-40 dBFS) считается тишиной: в записи только
+/// фоновый шум, и усиление до target сделало бы его громким для STT.
+const NORMALIZE_SILENCE_PEAK: f32 = 0.01;
+
+/// Число пересечений нуля sinc-ядра с каждой стороны (качество фильтра).
+const SINC_ZERO_CROSSINGS: f64 = 16.0;
+
+/// Запас частоты среза относительно Найквиста целевой частоты.
+const SINC_ROLLOFF: f64 = 0.95;
+
+/// Максимум фаз полифазного фильтра (знаменатель отношения частот).
+///
+/// Для всех распространенных частот устройств (8k-192k) фаз не больше 640.
+const SINC_MAX_PHASES: u64 = 1024;
+
+/// Алгоритм ресемплинга.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Resampler {
+    /// Windowed-sinc: без алиасинга при понижении частоты
+    Sinc,
+    /// Линейная интерполяция: быстро, но с алиасингом
+    #[default]
+    Linear,
+}
+
+/// Параметры препроцессинга.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreprocessOptions {
+    /// Применять high-pass фильтр до ресемплинга.
+    pub high_pass: bool,
+    /// Нормализовать пиковую громкость после ресемплинга.
+    pub normalize: bool,
+    /// Алгоритм ресемплинга в 16 kHz.
+    pub resampler: Resampler,
+    /// Порог RMS noise gate (`None` - выключен).
+    pub noise_gate_threshold: Option<f32>,
+}
+
+impl Default for PreprocessOptions {
+    fn default() -> Self {
+        Self {
+            high_pass: true,
+            normalize: false,
+            resampler: Resampler::default(),
+            noise_gate_threshold: None,
+        }
+    }
+}
+
+impl PreprocessOptions {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            high_pass: config.high_pass_filter,
+            normalize: config.normalize_audio,
+            resampler: match config.resample_quality {
+                ResampleQuality::Linear => Resampler::Linear,
+                ResampleQuality::Sinc => Resampler::Sinc,
+            },
+            noise_gate_threshold: (config.noise_gate_threshold > 0.0)
+                .then_some(config.noise_gate_threshold),
+        }
+    }
+}
+
 /// Конвертирует multi-channel аудио в mono.
 ///
 /// Если аудио уже mono (channels == 1), возвращает копию.
@@ -59,11 +134,228 @@ pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     output
 }
 
+/// Ресемплинг полифазным windowed-sinc фильтром (окно Блэкмана).
+///
+/// При понижении частоты срез фильтра сдвигается ниже нового Найквиста,
+/// поэтому высокие частоты (шипящие) не заворачиваются в речевую полосу.
+/// Длина выхода совпадает с `resample`. Для экзотических пар частот
+/// с числом фаз больше `SINC_MAX_PHASES` используется линейный ресемплинг.
+pub fn resample_hq(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() || from_rate == 0 || to_rate == 0 {
+        return samples.to_vec();
+    }
+
+    let g = gcd(u64::from(from_rate), u64::from(to_rate));
+    let step = u64::from(from_rate) / g; // входных сэмплов на `phases` выходных
+    let phases = u64::from(to_rate) / g;
+    if phases > SINC_MAX_PHASES {
+        tracing::debug!(
+            from_rate,
+            to_rate,
+            "too many sinc phases, using linear resample"
+        );
+        return resample(samples, from_rate, to_rate);
+    }
+
+    let table = SincTable::new(from_rate, to_rate, phases as usize);
+    let ratio = from_rate as f64 / to_rate as f64;
+    let output_len = ((samples.len() as f64) / ratio).ceil() as usize;
+    let last = samples.len() as i64 - 1;
+
+    (0..output_len as u64)
+        .map(|i| {
+            let pos = i * step;
+            let base = (pos / phases) as i64;
+            let weights = table.phase((pos % phases) as usize);
+            weights
+                .iter()
+                .enumerate()
+                .map(|(j, &w)| {
+                    let idx = (base + j as i64 - table.half).clamp(0, last) as usize;
+                    samples[idx] * w
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Таблица весов полифазного фильтра: `phases` наборов по `2 * half + 1` коэффициентов.
+struct SincTable {
+    half: i64,
+    taps: usize,
+    weights: Vec<f32>,
+}
+
+impl SincTable {
+    fn new(from_rate: u32, to_rate: u32, phases: usize) -> Self {
+        // Относительная частота среза (1.0 = Найквист входа)
+        let cutoff = (to_rate as f64 / from_rate as f64).min(1.0) * SINC_ROLLOFF;
+        let width = SINC_ZERO_CROSSINGS / cutoff;
+        let half = width.ceil() as i64;
+        let taps = (2 * half + 1) as usize;
+
+        let mut weights = Vec::with_capacity(phases * taps);
+        for phase in 0..phases {
+            let frac = phase as f64 / phases as f64;
+            let row: Vec<f64> = (0..taps)
+                .map(|j| {
+                    let d = (j as i64 - half) as f64 - frac;
+                    if d.abs() >= width {
+                        return 0.0;
+                    }
+                    let x = std::f64::consts::PI * d / width;
+                    let window = 0.42 + 0.5 * x.cos() + 0.08 * (2.0 * x).cos();
+                    cutoff * sinc(cutoff * d) * window
+                })
+                .collect();
+            // Нормализация: постоянный сигнал проходит без изменения уровня
+            let sum: f64 = row.iter().sum();
+            weights.extend(row.iter().map(|w| (w / sum) as f32));
+        }
+
+        Self {
+            half,
+            taps,
+            weights,
+        }
+    }
+
+    fn phase(&self, phase: usize) -> &[f32] {
+        &self.weights[phase * self.taps..(phase + 1) * self.taps]
+    }
+}
+
+/// Нормированная sinc-функция: `sin(pi x) / (pi x)`.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 /// Препроцессинг аудио: конвертация в mono + ресемплинг в 16 kHz.
 #[allow(dead_code)]
 pub fn preprocess(samples: &[f32], channels: u16, sample_rate: u32) -> Vec<f32> {
-    let mono = to_mono(samples, channels);
-    resample(&mono, sample_rate, TARGET_SAMPLE_RATE)
+    preprocess_with(
+        samples,
+        channels,
+        sample_rate,
+        &PreprocessOptions::default(),
+    )
+}
+
+/// Препроцессинг с параметрами:
+/// mono -> DC offset + high-pass -> 16 kHz -> noise gate -> нормализация.
+///
+/// Удаление DC offset, high-pass, noise gate и нормализация применяются по `options`
+/// (DC offset - вместе с high-pass).
+pub fn preprocess_with(
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    options: &PreprocessOptions,
+) -> Vec<f32> {
+    let mut mono = to_mono(samples, channels);
+    if options.high_pass {
+        remove_dc_offset(&mut mono);
+        mono = high_pass(&mono, sample_rate, HIGH_PASS_CUTOFF_HZ);
+    }
+    let mut resampled = match options.resampler {
+        Resampler::Sinc => resample_hq(&mono, sample_rate, TARGET_SAMPLE_RATE),
+        Resampler::Linear => resample(&mono, sample_rate, TARGET_SAMPLE_RATE),
+    };
+    if let Some(threshold) = options.noise_gate_threshold {
+        resampled = noise_gate(&resampled, TARGET_SAMPLE_RATE, threshold);
+    }
+    if options.normalize {
+        normalize_peak(&resampled, NORMALIZE_TARGET_PEAK)
+    } else {
+        resampled
+    }
+}
+
+/// Обнуляет кадры по `ENERGY_FRAME_MS` мс с RMS ниже `threshold`.
+///
+/// В отличие от `trim_silence` чистит и паузы внутри записи, чтобы STT
+/// не достраивал слова из фонового шума. Кадры с речью не меняются;
+/// неполный последний кадр обрабатывается так же.
+pub fn noise_gate(samples: &[f32], sample_rate: u32, threshold: f32) -> Vec<f32> {
+    let frame_size = (sample_rate * ENERGY_FRAME_MS / 1000) as usize;
+    if frame_size == 0 {
+        return samples.to_vec();
+    }
+
+    let mut output = samples.to_vec();
+    for frame in output.chunks_mut(frame_size) {
+        if calculate_energy(frame) < threshold {
+            frame.fill(0.0);
+        }
+    }
+    output
+}
+
+/// Масштабирует буфер так, чтобы максимальный по модулю сэмпл стал `target_peak`.
+///
+/// Тишина (пик ниже `NORMALIZE_SILENCE_PEAK`) и буфер, чей пик уже около цели,
+/// возвращаются без изменений.
+pub fn normalize_peak(samples: &[f32], target_peak: f32) -> Vec<f32> {
+    let peak = samples.iter().fold(0.0f32, |max, &s| max.max(s.abs()));
+    if peak < NORMALIZE_SILENCE_PEAK || (peak - target_peak).abs() <= NORMALIZE_TOLERANCE {
+        return samples.to_vec();
+    }
+
+    let gain = target_peak / peak;
+    samples.iter().map(|&s| s * gain).collect()
+}
+
+/// Вычитает среднее значение буфера (постоянное смещение микрофона).
+///
+/// Дополняет `high_pass`: фильтр первого порядка гасит смещение не сразу,
+/// а после вычитания среднего первые сэмплы не дают выброса.
+pub fn remove_dc_offset(samples: &mut [f32]) {
+    if samples.is_empty() {
+        return;
+    }
+    let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64;
+    let mean = mean as f32;
+    for sample in samples.iter_mut() {
+        *sample -= mean;
+    }
+}
+
+/// High-pass фильтр первого порядка (IIR).
+///
+/// `y[n] = a * (y[n-1] + x[n] - x[n-1])`, `a = RC / (RC + dt)`.
+/// Начальное состояние берется из первого сэмпла, поэтому постоянная
+/// составляющая удаляется без выброса в начале.
+pub fn high_pass(samples: &[f32], sample_rate: u32, cutoff_hz: f32) -> Vec<f32> {
+    if samples.is_empty() || sample_rate == 0 || cutoff_hz <= 0.0 {
+        return samples.to_vec();
+    }
+
+    let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff_hz);
+    let dt = 1.0 / sample_rate as f32;
+    let alpha = rc / (rc + dt);
+
+    let mut output = Vec::with_capacity(samples.len());
+    let mut prev_x = samples[0];
+    let mut prev_y = 0.0f32;
+    for &x in samples {
+        let y = alpha * (prev_y + x - prev_x);
+        output.push(y);
+        prev_x = x;
+        prev_y = y;
+    }
+    output
 }
 
 /// Вычисляет RMS энергию кадра.
@@ -76,6 +368,29 @@ pub fn calculate_energy(frame: &[f32]) -> f32 {
     (sum_sq / frame.len() as f32).sqrt()
 }
 
+/// Вычисляет RMS каждого канала interleaved-буфера.
+///
+/// Неполный последний фрейм игнорируется. Для пустого буфера
+/// возвращает нули по числу каналов.
+pub fn channel_rms(samples: &[f32], channels: u16) -> Vec<f32> {
+    let ch = channels.max(1) as usize;
+    let frames = samples.len() / ch;
+    if frames == 0 {
+        return vec![0.0; ch];
+    }
+
+    let mut sum_sq = vec![0.0f32; ch];
+    for frame in samples.chunks_exact(ch) {
+        for (acc, &s) in sum_sq.iter_mut().zip(frame) {
+            *acc += s * s;
+        }
+    }
+    sum_sq
+        .into_iter()
+        .map(|sum| (sum / frames as f32).sqrt())
+        .collect()
+}
+
 /// Обрезает тишину в начале аудио.
 ///
 /// Анализирует кадрами по `ENERGY_FRAME_MS` мс. Возвращает срез
@@ -275,6 +590,88 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    // --- resample_hq ---
+
+    #[test]
+    fn resample_hq_should_match_linear_output_length() {
+        for (len, from) in [(44100, 44100), (48000, 48000), (12345, 48000), (1000, 8000)] {
+            let samples = vec![0.0; len];
+            assert_eq!(
+                resample_hq(&samples, from, 16000).len(),
+                resample(&samples, from, 16000).len(),
+                "len={len} from={from}"
+            );
+        }
+    }
+
+    #[test]
+    fn resample_hq_should_return_same_when_rates_equal() {
+        let samples = vec![0.1, 0.2, 0.3];
+        assert_eq!(resample_hq(&samples, 16000, 16000), samples);
+    }
+
+    #[test]
+    fn resample_hq_should_handle_empty_input() {
+        assert!(resample_hq(&[], 48000, 16000).is_empty());
+    }
+
+    #[test]
+    fn resample_hq_should_preserve_dc_level() {
+        // Given
+        let samples = vec![0.5; 44100];
+
+        // When
+        let result = resample_hq(&samples, 44100, 16000);
+
+        // Then
+        assert!(result.iter().all(|&s| (s - 0.5).abs() < 1e-3));
+    }
+
+    #[test]
+    fn resample_hq_should_preserve_in_band_tone() {
+        // Given: 3 кГц ниже нового Найквиста (8 кГц)
+        let tone = generate_tone(48000, 500, 3000.0, 0.5);
+
+        // When
+        let result = resample_hq(&tone, 48000, 16000);
+
+        // Then
+        let ratio = calculate_energy(&result) / calculate_energy(&tone);
+        assert!((ratio - 1.0).abs() < 0.05, "ratio = {ratio}");
+    }
+
+    #[test]
+    fn resample_hq_should_suppress_aliasing_better_than_linear() {
+        // Given: 11 кГц выше нового Найквиста - должен быть отфильтрован
+        let tone = generate_tone(48000, 500, 11000.0, 0.5);
+
+        // When
+        let hq = calculate_energy(&resample_hq(&tone, 48000, 16000));
+        let linear = calculate_energy(&resample(&tone, 48000, 16000));
+
+        // Then
+        assert!(hq < 0.05 * calculate_energy(&tone), "hq = {hq}");
+        assert!(hq < linear / 5.0, "hq = {hq}, linear = {linear}");
+    }
+
+    /// Замер CPU-стоимости на 60-секундном клипе: `cargo test -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn resample_benchmark_60s_clip() {
+        let clip = generate_tone(48000, 60_000, 440.0, 0.5);
+
+        let start = std::time::Instant::now();
+        let linear = resample(&clip, 48000, 16000);
+        let linear_ms = start.elapsed().as_millis();
+
+        let start = std::time::Instant::now();
+        let hq = resample_hq(&clip, 48000, 16000);
+        let hq_ms = start.elapsed().as_millis();
+
+        eprintln!("60s 48kHz -> 16kHz: linear {linear_ms} ms, sinc {hq_ms} ms");
+        assert_eq!(linear.len(), hq.len());
+    }
+
     // --- preprocess ---
 
     #[test]
@@ -291,8 +688,235 @@ mod tests {
         assert_eq!(result.len(), expected_len);
     }
 
+    // --- high_pass ---
+
+    #[test]
+    fn high_pass_should_remove_dc_offset() {
+        // Given: тон 300 Гц со смещением 0.3
+        let signal: Vec<f32> = generate_tone(16000, 500, 300.0, 0.2)
+            .into_iter()
+            .map(|s| s + 0.3)
+            .collect();
+
+        // When
+        let filtered = high_pass(&signal, 16000, 80.0);
+
+        // Then: среднее по второй половине около нуля
+        let tail = &filtered[filtered.len() / 2..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 0.01, "mean = {mean}");
+    }
+
+    #[test]
+    fn high_pass_should_preserve_high_frequency_tone() {
+        // Given
+        let tone = generate_tone(16000, 500, 1000.0, 0.5);
+
+        // When
+        let filtered = high_pass(&tone, 16000, 80.0);
+
+        // Then
+        let ratio = calculate_energy(&filtered) / calculate_energy(&tone);
+        assert!(ratio > 0.95, "ratio = {ratio}");
+    }
+
+    #[test]
+    fn remove_dc_offset_should_make_constant_signal_zero_mean() {
+        // Given
+        let mut samples = vec![0.25; 16000];
+
+        // When
+        remove_dc_offset(&mut samples);
+
+        // Then
+        assert!(samples.iter().all(|&s| s.abs() < 1e-6));
+    }
+
+    #[test]
+    fn remove_dc_offset_should_handle_empty_input() {
+        let mut samples: Vec<f32> = Vec::new();
+        remove_dc_offset(&mut samples);
+        assert!(samples.is_empty());
+    }
+
+    #[test]
+    fn preprocess_with_should_remove_dc_and_keep_voice_tone() {
+        // Given: тон 440 Гц со смещением 0.2
+        let tone = generate_tone(16000, 500, 440.0, 0.3);
+        let biased: Vec<f32> = tone.iter().map(|&s| s + 0.2).collect();
+        let options = PreprocessOptions {
+            high_pass: true,
+            normalize: false,
+            resampler: Resampler::Sinc,
+            noise_gate_threshold: None,
+        };
+
+        // When
+        let result = preprocess_with(&biased, 1, 16000, &options);
+
+        // Then: среднее около нуля, энергия тона сохранена
+        let mean = result.iter().sum::<f32>() / result.len() as f32;
+        assert!(mean.abs() < 0.01, "mean = {mean}");
+        let ratio = calculate_energy(&result) / calculate_energy(&tone);
+        assert!((0.9..1.1).contains(&ratio), "ratio = {ratio}");
+    }
+
+    #[test]
+    fn high_pass_should_handle_empty_input() {
+        assert!(high_pass(&[], 16000, 80.0).is_empty());
+    }
+
+    #[test]
+    fn preprocess_with_should_skip_high_pass_when_disabled() {
+        // Given: постоянный сигнал 0.5 (high-pass обнулил бы его)
+        let samples = vec![0.5; 16000];
+        let options = PreprocessOptions {
+            high_pass: false,
+            normalize: false,
+            resampler: Resampler::Sinc,
+            noise_gate_threshold: None,
+        };
+
+        // When
+        let result = preprocess_with(&samples, 1, 16000, &options);
+
+        // Then
+        assert!(result.iter().all(|&s| (s - 0.5).abs() < 1e-6));
+    }
+
+    #[test]
+    fn preprocess_options_should_follow_resample_quality() {
+        // Given
+        let mut config = AppConfig::default();
+
+        // When / Then
+        assert_eq!(
+            PreprocessOptions::from_config(&config).resampler,
+            Resampler::Linear
+        );
+        config.resample_quality = ResampleQuality::Sinc;
+        assert_eq!(
+            PreprocessOptions::from_config(&config).resampler,
+            Resampler::Sinc
+        );
+    }
+
+    // --- noise_gate ---
+
+    #[test]
+    fn noise_gate_should_flatten_interior_pause_and_keep_tones() {
+        // Given: тон 200мс - шум 200мс - тон 200мс
+        let tone = generate_tone(16000, 200, 440.0, 0.5);
+        let noise = generate_tone(16000, 200, 3000.0, 0.002);
+        let signal = [tone.clone(), noise, tone.clone()].concat();
+
+        // When
+        let gated = noise_gate(&signal, 16000, SILENCE_RMS_THRESHOLD);
+
+        // Then
+        let third = tone.len();
+        assert_eq!(&gated[..third], &tone[..]);
+        assert!(gated[third..2 * third].iter().all(|&s| s == 0.0));
+        assert_eq!(&gated[2 * third..], &tone[..]);
+    }
+
+    #[test]
+    fn noise_gate_should_keep_length_and_handle_empty_input() {
+        assert_eq!(noise_gate(&[0.001; 1234], 16000, 0.005), vec![0.0; 1234]);
+        assert!(noise_gate(&[], 16000, 0.005).is_empty());
+    }
+
+    // --- normalize_peak ---
+
+    #[test]
+    fn normalize_peak_should_raise_quiet_sine_to_target() {
+        // Given: синус -20 dB (амплитуда 0.1)
+        let tone = generate_tone(16000, 100, 440.0, 0.1);
+
+        // When
+        let normalized = normalize_peak(&tone, 0.9);
+
+        // Then
+        let peak = normalized.iter().fold(0.0f32, |m, &s| m.max(s.abs()));
+        assert!((peak - 0.9).abs() < 1e-3, "peak = {peak}");
+    }
+
+    #[test]
+    fn normalize_peak_should_return_silence_unchanged() {
+        let silence = vec![0.0; 320];
+        assert_eq!(normalize_peak(&silence, 0.9), silence);
+    }
+
+    #[test]
+    fn normalize_peak_should_not_amplify_background_noise() {
+        // Given: только шум комнаты, пик -46 dB
+        let noise = generate_tone(16000, 100, 3000.0, 0.005);
+
+        // When / Then
+        assert_eq!(normalize_peak(&noise, 0.9), noise);
+    }
+
+    #[test]
+    fn normalize_peak_should_skip_buffer_near_target() {
+        let samples = vec![0.895, -0.5, 0.2];
+        assert_eq!(normalize_peak(&samples, 0.9), samples);
+    }
+
+    #[test]
+    fn preprocess_with_should_normalize_when_enabled() {
+        // Given
+        let tone = generate_tone(16000, 200, 1000.0, 0.1);
+        let options = PreprocessOptions {
+            high_pass: false,
+            normalize: true,
+            resampler: Resampler::Sinc,
+            noise_gate_threshold: None,
+        };
+
+        // When
+        let result = preprocess_with(&tone, 1, 16000, &options);
+
+        // Then
+        let peak = result.iter().fold(0.0f32, |m, &s| m.max(s.abs()));
+        assert!((peak - NORMALIZE_TARGET_PEAK).abs() < 1e-3);
+    }
+
     // --- calculate_energy ---
 
+    #[test]
+    fn channel_rms_should_report_silent_channel() {
+        // Given: левый канал - сигнал 0.5, правый - тишина
+        let samples: Vec<f32> = (0..100)
+            .flat_map(|i| [if i % 2 == 0 { 0.5 } else { -0.5 }, 0.0])
+            .collect();
+
+        // When
+        let levels = channel_rms(&samples, 2);
+
+        // Then
+        assert_eq!(levels.len(), 2);
+        assert!((levels[0] - 0.5).abs() < 1e-6);
+        assert_eq!(levels[1], 0.0);
+    }
+
+    #[test]
+    fn channel_rms_should_ignore_incomplete_trailing_frame() {
+        // Given: 2 полных стерео-фрейма + 1 лишний сэмпл
+        let samples = vec![0.3, 0.6, 0.3, 0.6, 1.0];
+
+        // When
+        let levels = channel_rms(&samples, 2);
+
+        // Then
+        assert!((levels[0] - 0.3).abs() < 1e-6);
+        assert!((levels[1] - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn channel_rms_should_return_zeros_for_empty_buffer() {
+        assert_eq!(channel_rms(&[], 2), vec![0.0, 0.0]);
+    }
+
     #[test]
     fn calculate_energy_should_return_zero_for_silence() {
         // Given
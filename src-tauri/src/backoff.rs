@@ -0,0 +1,119 @@
+//! Экспоненциальный backoff с jitter для повторных HTTP-запросов.
+
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Разброс задержки относительно экспоненциальной базы (±25%).
+const JITTER_FRACTION: f64 = 0.25;
+
+/// Запасной seed, если передан 0 (xorshift застревает на нулевом состоянии).
+const FALLBACK_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Источник jitter для backoff (xorshift64*, без внешних зависимостей).
+///
+/// Разносит повторные запросы параллельных чанков во времени, чтобы они
+/// не били в API одновременно. Для детерминированных тестов - `with_seed`.
+pub struct Jitter {
+    state: Mutex<u64>,
+}
+
+impl Jitter {
+    /// Генератор с фиксированным seed: одинаковый seed - одинаковые задержки.
+    pub fn with_seed(seed: u64) -> Self {
+        let seed = if seed == 0 { FALLBACK_SEED } else { seed };
+        Self {
+            state: Mutex::new(seed),
+        }
+    }
+
+    /// Генератор, засеянный текущим временем.
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(FALLBACK_SEED);
+        Self::with_seed(nanos)
+    }
+
+    /// Задержка перед повтором номер `attempt` (с нуля): `2^attempt` секунд ±25%,
+    /// не больше `max_sec`.
+    pub fn backoff(&self, attempt: u32, max_sec: u64) -> Duration {
+        let base = 1u64.checked_shl(attempt).unwrap_or(max_sec).min(max_sec) as f64;
+        let factor = 1.0 + JITTER_FRACTION * (2.0 * self.next_unit() - 1.0);
+        Duration::from_secs_f64((base * factor).min(max_sec as f64))
+    }
+
+    /// Следующее псевдослучайное число в диапазоне `[0, 1)`.
+    fn next_unit(&self) -> f64 {
+        let mut state = self.state.lock().expect("jitter mutex poisoned");
+        let mut x = *state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        *state = x;
+        (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_should_be_deterministic_for_same_seed() {
+        // Given
+        let a = Jitter::with_seed(42);
+        let b = Jitter::with_seed(42);
+
+        // When
+        let delays_a: Vec<Duration> = (0..5).map(|i| a.backoff(i, 16)).collect();
+        let delays_b: Vec<Duration> = (0..5).map(|i| b.backoff(i, 16)).collect();
+
+        // Then
+        assert_eq!(delays_a, delays_b);
+    }
+
+    #[test]
+    fn backoff_should_stay_within_25_percent_of_base() {
+        // Given
+        let jitter = Jitter::with_seed(7);
+
+        for attempt in 0..4 {
+            for _ in 0..100 {
+                // When
+                let delay = jitter.backoff(attempt, 16).as_secs_f64();
+
+                // Then
+                let base = (1u64 << attempt) as f64;
+                assert!(
+                    delay >= base * 0.75 && delay <= base * 1.25,
+                    "attempt {attempt}: {delay} outside ±25% of {base}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn backoff_should_not_exceed_max() {
+        // Given
+        let jitter = Jitter::with_seed(1);
+
+        // When / Then
+        for _ in 0..100 {
+            assert!(jitter.backoff(10, 16) <= Duration::from_secs(16));
+            assert!(jitter.backoff(70, 16) <= Duration::from_secs(16));
+        }
+    }
+
+    #[test]
+    fn with_seed_should_accept_zero() {
+        // Given
+        let jitter = Jitter::with_seed(0);
+
+        // When
+        let delays: Vec<Duration> = (0..3).map(|_| jitter.backoff(2, 16)).collect();
+
+        // Then: нулевое состояние не "застревает" на одном значении
+        assert!(delays.windows(2).any(|w| w[0] != w[1]));
+    }
+}
@@ -0,0 +1,210 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::audio::DeviceInfo;
+
+/// In-memory кэш одного значения с ограниченным временем жизни.
+///
+/// Используется для результатов дорогих перечислений (устройства, модели),
+/// которые запрашиваются при каждом открытии настроек.
+pub struct TtlCache<T: Clone> {
+    entry: Mutex<Option<(Instant, T)>>,
+}
+
+impl<T: Clone> TtlCache<T> {
+    pub fn new() -> Self {
+        Self {
+            entry: Mutex::new(None),
+        }
+    }
+
+    /// Возвращает закэшированное значение, если оно моложе `ttl`.
+    pub fn get(&self, ttl: Duration) -> Option<T> {
+        self.get_at(ttl, Instant::now())
+    }
+
+    /// Сохраняет значение с текущим временем.
+    pub fn store(&self, value: T) {
+        self.store_at(value, Instant::now());
+    }
+
+    /// Сбрасывает кэш (следующий запрос выполнит перечисление заново).
+    pub fn invalidate(&self) {
+        *self.entry.lock().expect("cache mutex poisoned") = None;
+    }
+
+    fn get_at(&self, ttl: Duration, now: Instant) -> Option<T> {
+        let entry = self.entry.lock().expect("cache mutex poisoned");
+        match entry.as_ref() {
+            Some((stored_at, value)) if now.saturating_duration_since(*stored_at) < ttl => {
+                Some(value.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn store_at(&self, value: T, now: Instant) {
+        *self.entry.lock().expect("cache mutex poisoned") = Some((now, value));
+    }
+
+    /// Возвращает значение из кэша или выполняет `load` и кэширует результат.
+    ///
+    /// `refresh = true` игнорирует кэш. Ошибки `load` не кэшируются.
+    pub fn get_or_load<E>(
+        &self,
+        ttl: Duration,
+        refresh: bool,
+        load: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.get_or_load_at(ttl, refresh, Instant::now(), load)
+    }
+
+    fn get_or_load_at<E>(
+        &self,
+        ttl: Duration,
+        refresh: bool,
+        now: Instant,
+        load: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E> {
+        if !refresh {
+            if let Some(value) = self.get_at(ttl, now) {
+                return Ok(value);
+            }
+        }
+
+        let value = load()?;
+        self.store_at(value.clone(), now);
+        Ok(value)
+    }
+}
+
+impl<T: Clone> Default for TtlCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Кэши перечислений для команд настроек (Tauri managed state).
+#[derive(Default)]
+pub struct EnumerationCache {
+    pub devices: TtlCache<Vec<DeviceInfo>>,
+    pub models: TtlCache<Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn cache_should_return_value_within_ttl() {
+        // Given
+        let cache = TtlCache::new();
+        let start = Instant::now();
+        cache.store_at(vec!["mic".to_string()], start);
+
+        // When
+        let value = cache.get_at(TTL, start + Duration::from_secs(10));
+
+        // Then
+        assert_eq!(value, Some(vec!["mic".to_string()]));
+    }
+
+    #[test]
+    fn cache_should_expire_after_ttl() {
+        // Given
+        let cache = TtlCache::new();
+        let start = Instant::now();
+        cache.store_at(vec!["mic".to_string()], start);
+
+        // When
+        let value = cache.get_at(TTL, start + TTL);
+
+        // Then
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn get_or_load_should_not_rescan_within_ttl() {
+        // Given
+        let cache = TtlCache::new();
+        let start = Instant::now();
+        let mut scans = 0;
+
+        // When
+        for offset in [0, 5, 29] {
+            let _ = cache.get_or_load_at(
+                TTL,
+                false,
+                start + Duration::from_secs(offset),
+                || -> Result<u32, ()> {
+                    scans += 1;
+                    Ok(scans)
+                },
+            );
+        }
+
+        // Then
+        assert_eq!(scans, 1);
+    }
+
+    #[test]
+    fn get_or_load_should_rescan_after_expiry() {
+        // Given
+        let cache = TtlCache::new();
+        let start = Instant::now();
+        let _ = cache.get_or_load_at(TTL, false, start, || Ok::<_, ()>(1));
+
+        // When
+        let value = cache.get_or_load_at(TTL, false, start + Duration::from_secs(31), || {
+            Ok::<_, ()>(2)
+        });
+
+        // Then
+        assert_eq!(value, Ok(2));
+    }
+
+    #[test]
+    fn get_or_load_should_rescan_when_refresh_forced() {
+        // Given
+        let cache = TtlCache::new();
+        let start = Instant::now();
+        let _ = cache.get_or_load_at(TTL, false, start, || Ok::<_, ()>(1));
+
+        // When
+        let value = cache.get_or_load_at(TTL, true, start, || Ok::<_, ()>(2));
+
+        // Then
+        assert_eq!(value, Ok(2));
+        assert_eq!(cache.get_at(TTL, start), Some(2));
+    }
+
+    #[test]
+    fn get_or_load_should_not_cache_errors() {
+        // Given
+        let cache: TtlCache<u32> = TtlCache::new();
+        let start = Instant::now();
+
+        // When
+        let first = cache.get_or_load_at(TTL, false, start, || Err("no devices"));
+        let second = cache.get_or_load_at(TTL, false, start, || Ok::<_, &str>(3));
+
+        // Then
+        assert_eq!(first, Err("no devices"));
+        assert_eq!(second, Ok(3));
+    }
+
+    #[test]
+    fn invalidate_should_drop_cached_value() {
+        // Given
+        let cache = TtlCache::new();
+        cache.store(1);
+
+        // When
+        cache.invalidate();
+
+        // Then
+        assert_eq!(cache.get(TTL), None);
+    }
+}
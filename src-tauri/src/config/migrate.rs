@@ -0,0 +1,221 @@
+//! Миграция конфига между версиями схемы (`config_version`).
+
+use serde_json::{Map, Value};
+use tracing::{info, warn};
+
+use crate::config::schema::{AppConfig, CURRENT_CONFIG_VERSION, DEFAULT_PROFILE};
+use crate::error::{AppError, Result};
+
+/// Шаг миграции: `MIGRATIONS[v]` переводит конфиг из версии `v` в `v + 1`.
+type Migration = fn(&mut Map<String, Value>);
+
+/// Пошаговые миграции, по одной на каждую версию до текущей.
+const MIGRATIONS: [Migration; CURRENT_CONFIG_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// Поля провайдера, которые в v2 переехали в профиль.
+const PROFILE_FIELDS: &[&str] = &["api_base_url", "stt_model", "enhance_model"];
+
+/// Версия 0 - конфиги до появления `config_version`: поля совпадают с v1.
+fn migrate_v0_to_v1(_fields: &mut Map<String, Value>) {}
+
+/// Версия 2 - профили провайдеров: плоские поля складываются в профиль `default`.
+///
+/// Плоские поля остаются: они отражают активный профиль.
+fn migrate_v1_to_v2(fields: &mut Map<String, Value>) {
+    if fields.contains_key("profiles") {
+        return;
+    }
+    let mut profile = Map::new();
+    profile.insert("name".to_string(), Value::from(DEFAULT_PROFILE));
+    for field in PROFILE_FIELDS {
+        if let Some(value) = fields.get(*field) {
+            profile.insert((*field).to_string(), value.clone());
+        }
+    }
+    fields.insert(
+        "profiles".to_string(),
+        Value::Array(vec![Value::Object(profile)]),
+    );
+    fields.insert("active_profile".to_string(), Value::from(DEFAULT_PROFILE));
+}
+
+/// Версия 3 - несколько хоткеев: `hotkey` становится списком `hotkeys` из одного элемента.
+fn migrate_v2_to_v3(fields: &mut Map<String, Value>) {
+    let Some(hotkey) = fields.remove("hotkey") else {
+        return;
+    };
+    if !fields.contains_key("hotkeys") {
+        fields.insert("hotkeys".to_string(), Value::Array(vec![hotkey]));
+    }
+}
+
+/// Версия схемы в JSON конфига; нет поля - версия 0.
+fn config_version(fields: &Map<String, Value>) -> u32 {
+    fields
+        .get("config_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| u32::try_from(v).unwrap_or(u32::MAX))
+}
+
+/// Приводит JSON конфига к текущей версии схемы и десериализует его.
+///
+/// - Применяет миграции от версии файла до `CURRENT_CONFIG_VERSION` и обновляет версию.
+///   Конфиг более новой версии не трогается (о нем предупредит `validate`).
+/// - Отсутствующие поля заполняются дефолтами.
+/// - Поле с неверным типом заменяется дефолтом, остальные поля сохраняются.
+///
+/// Ошибка - только если JSON не является объектом.
+pub fn migrate(value: Value) -> Result<AppConfig> {
+    let Value::Object(mut fields) = value else {
+        return Err(AppError::Config("config must be a JSON object".to_string()));
+    };
+
+    upgrade_fields(&mut fields);
+
+    match serde_json::from_value::<AppConfig>(Value::Object(fields.clone())) {
+        Ok(config) => Ok(config),
+        Err(e) => {
+            warn!("Config has invalid fields ({e}), replacing them with defaults");
+            Ok(merge_valid_fields(fields))
+        }
+    }
+}
+
+/// Применяет миграции от версии полей до `CURRENT_CONFIG_VERSION` и обновляет версию.
+///
+/// Поля более новой версии не трогаются.
+pub fn upgrade_fields(fields: &mut Map<String, Value>) {
+    let version = config_version(fields);
+    if version >= CURRENT_CONFIG_VERSION {
+        return;
+    }
+    for (from, step) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        step(fields);
+        info!("Config migrated from version {from} to {}", from + 1);
+    }
+    fields.insert(
+        "config_version".to_string(),
+        Value::from(CURRENT_CONFIG_VERSION),
+    );
+}
+
+/// Накладывает на дефолтный конфиг только те поля, которые по отдельности валидны.
+fn merge_valid_fields(fields: Map<String, Value>) -> AppConfig {
+    let Ok(Value::Object(mut merged)) = serde_json::to_value(AppConfig::default()) else {
+        unreachable!("AppConfig serializes to an object");
+    };
+
+    for (field, field_value) in fields {
+        let mut probe = merged.clone();
+        probe.insert(field.clone(), field_value);
+        if serde_json::from_value::<AppConfig>(Value::Object(probe.clone())).is_ok() {
+            merged = probe;
+        } else {
+            warn!("Config field {field:?} has invalid value, using default");
+        }
+    }
+
+    serde_json::from_value(Value::Object(merged)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::RecordingMode;
+
+    #[test]
+    fn migrate_should_upgrade_v0_config_and_keep_existing_fields() {
+        // Given: конфиг без config_version и без новых полей
+        let value = serde_json::json!({
+            "hotkey": "Alt+R",
+            "language": "ru",
+            "max_recording_duration_sec": 90,
+            "enhance_enabled": false
+        });
+
+        // When
+        let config = migrate(value).unwrap();
+
+        // Then
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.hotkeys, vec!["Alt+R"]);
+        assert_eq!(config.language, "ru");
+        assert_eq!(config.max_recording_duration_sec, 90);
+        assert!(!config.enhance_enabled);
+        // Отсутствующие поля - из дефолтов
+        let defaults = AppConfig::default();
+        assert_eq!(config.stt_model, defaults.stt_model);
+        assert_eq!(config.paste_segment_chars, defaults.paste_segment_chars);
+    }
+
+    #[test]
+    fn migrate_should_replace_only_invalid_fields_with_defaults() {
+        // Given
+        let value = serde_json::json!({
+            "config_version": 1,
+            "hotkey": "Alt+R",
+            "retry_count": "three",
+            "recording_mode": "hold"
+        });
+
+        // When
+        let config = migrate(value).unwrap();
+
+        // Then
+        assert_eq!(config.hotkeys, vec!["Alt+R"]);
+        assert_eq!(config.retry_count, AppConfig::default().retry_count);
+        assert_eq!(config.recording_mode, RecordingMode::default());
+    }
+
+    #[test]
+    fn migrate_should_fold_provider_fields_into_default_profile() {
+        // Given: конфиг v1 с плоскими полями провайдера
+        let value = serde_json::json!({
+            "config_version": 1,
+            "api_base_url": "https://proxy.example.com",
+            "stt_model": "whisper-1"
+        });
+
+        // When
+        let config = migrate(value).unwrap();
+
+        // Then
+        assert_eq!(config.active_profile, DEFAULT_PROFILE);
+        assert_eq!(config.profiles.len(), 1);
+        let profile = &config.profiles[0];
+        assert_eq!(profile.name, DEFAULT_PROFILE);
+        assert_eq!(profile.api_base_url, "https://proxy.example.com");
+        assert_eq!(profile.stt_model, "whisper-1");
+        assert_eq!(profile.enhance_model, AppConfig::default().enhance_model);
+    }
+
+    #[test]
+    fn migrate_should_turn_single_hotkey_into_list() {
+        // Given
+        let value = serde_json::json!({ "config_version": 2, "hotkey": "Alt+R" });
+
+        // When
+        let config = migrate(value).unwrap();
+
+        // Then
+        assert_eq!(config.hotkeys, vec!["Alt+R"]);
+    }
+
+    #[test]
+    fn migrate_should_keep_newer_version() {
+        // Given
+        let value = serde_json::json!({ "config_version": CURRENT_CONFIG_VERSION + 1 });
+
+        // When
+        let config = migrate(value).unwrap();
+
+        // Then
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION + 1);
+    }
+
+    #[test]
+    fn migrate_should_reject_non_object_json() {
+        assert!(migrate(serde_json::json!([1, 2, 3])).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod migrate;
 pub mod schema;
 pub mod secrets;
 pub mod storage;
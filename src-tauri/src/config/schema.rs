@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::enhance::EnhanceStyle;
+
 /// Режим записи: toggle (нажал-говоришь-нажал) или push-to-talk (удержание).
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -9,18 +11,245 @@ pub enum RecordingMode {
     PushToTalk,
 }
 
+/// Способ измерения тишины для авто-стопа.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SilenceClockMode {
+    /// Реальное время между кадрами
+    #[default]
+    WallClock,
+    /// Накопленная длительность аудио (кадры x 32мс), не зависит от задержек CPU
+    AudioDuration,
+}
+
+/// Формат чисел в итоговом тексте.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberFormat {
+    /// Оставить как есть
+    #[default]
+    Keep,
+    /// "twenty five" -> "25"
+    Digits,
+    /// "3" -> "three"
+    Words,
+}
+
+/// Способ вставки текста в активное окно.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteMethod {
+    /// Через clipboard + Ctrl+V/Cmd+V
+    #[default]
+    Clipboard,
+    /// Посимвольный ввод без clipboard
+    Type,
+    /// Clipboard, а при неудаче - посимвольный ввод
+    Auto,
+}
+
+/// Что отправить после успешной вставки текста.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteSuffix {
+    /// Ничего
+    #[default]
+    None,
+    /// Пробел, чтобы следующая вставка не слиплась с текстом
+    Space,
+    /// Enter (например, для пунктов списка)
+    Newline,
+}
+
+/// Режим кодера Opus.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpusApplication {
+    /// Оптимизирован для речи
+    #[default]
+    Voip,
+    /// Полоса и качество для музыки и шумного фона
+    Audio,
+}
+
+/// Алгоритм ресемплинга записи в 16 kHz.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResampleQuality {
+    /// Быстрая линейная интерполяция (с алиасингом)
+    #[default]
+    Linear,
+    /// Windowed-sinc: без алиасинга, лучше для шипящих
+    Sinc,
+}
+
+/// Формат аудио, отправляемого на распознавание.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadFormat {
+    /// OGG/Opus: в десятки раз меньше трафика
+    #[default]
+    OggOpus,
+    /// WAV 16-bit PCM для endpoint-ов, не принимающих Opus
+    Wav,
+}
+
+impl UploadFormat {
+    /// Имя файла в multipart-запросе.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::OggOpus => "audio.ogg",
+            Self::Wav => "audio.wav",
+        }
+    }
+
+    /// MIME-тип файла в multipart-запросе.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::OggOpus => "audio/ogg",
+            Self::Wav => "audio/wav",
+        }
+    }
+}
+
+/// Предпочитаемый формат сэмплов при захвате с микрофона.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSampleFormat {
+    /// Формат устройства по умолчанию
+    #[default]
+    Auto,
+    F32,
+    I16,
+    U16,
+}
+
+/// Провайдер распознавания речи.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SttProviderKind {
+    /// OpenAI API (облако)
+    #[default]
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// Локальная модель Whisper (`whisper_model_path`)
+    Offline,
+}
+
+/// API, через который выполняется улучшение текста.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnhanceApiFlavor {
+    /// OpenAI Responses API (`/v1/responses`)
+    #[default]
+    Responses,
+    /// Chat Completions (`/v1/chat/completions`) - для совместимых серверов без Responses
+    Chat,
+}
+
+/// Имя профиля провайдера, в который мигрируют плоские поля конфига v1.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Именованный профиль провайдера: endpoint и модели (API-ключ - в keychain).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderProfile {
+    /// Имя профиля (ключ для API-ключа в keychain)
+    pub name: String,
+
+    /// Base URL OpenAI-совместимого API
+    pub api_base_url: String,
+
+    /// Модель STT
+    pub stt_model: String,
+
+    /// Модель улучшения текста
+    pub enhance_model: String,
+}
+
+impl Default for ProviderProfile {
+    fn default() -> Self {
+        let defaults = AppConfig::default();
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            api_base_url: defaults.api_base_url,
+            stt_model: defaults.stt_model,
+            enhance_model: defaults.enhance_model,
+        }
+    }
+}
+
+/// Текущая версия схемы конфига.
+pub const CURRENT_CONFIG_VERSION: u32 = 3;
+
+/// Допустимые значения `language`.
+const LANGUAGES: &[&str] = &["auto", "ru", "en"];
+
+/// Допустимые значения `log_level`.
+pub const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+/// Допустимый диапазон `max_recording_duration_sec`.
+const MAX_RECORDING_RANGE: std::ops::RangeInclusive<u32> = 10..=120;
+
+/// Максимум повторных попыток HTTP-запросов (`retry_count`).
+const MAX_RETRY_COUNT: u32 = 10;
+
+/// Допустимый битрейт Opus (`opus_bitrate_kbps`), кбит/с.
+///
+/// Для mono 16 kHz выше 64 кбит/с качество уже не растет.
+pub const OPUS_BITRATE_RANGE_KBPS: std::ops::RangeInclusive<u32> = 6..=64;
+
+/// Серьезность проблемы в конфиге.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// Значение некорректно и не будет работать
+    Error,
+    /// Значение подозрительно или будет проигнорировано
+    Warning,
+}
+
+/// Одна найденная проблема конфига.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigIssue {
+    /// Имя поля (пустое для проблем всего файла)
+    pub field: String,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    pub fn error(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Error,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
 /// Основная структура конфигурации приложения.
 ///
 /// Хранится в JSON-файле в app config dir. Все дефолты - из ТЗ.
 /// API-ключ хранится отдельно в OS keychain (не здесь).
+/// Отсутствующие в файле поля заполняются дефолтами.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 #[allow(dead_code)]
 pub struct AppConfig {
     /// Версия схемы конфига (для будущих миграций)
     pub config_version: u32,
 
-    /// Глобальный хоткей записи
-    pub hotkey: String,
+    /// Глобальные хоткеи записи (любой из них запускает и останавливает диктовку)
+    pub hotkeys: Vec<String>,
 
     /// Режим записи
     pub recording_mode: RecordingMode,
@@ -75,13 +304,210 @@ pub struct AppConfig {
 
     /// Сохранять последний аудиофайл для отладки
     pub debug_save_audio: bool,
+
+    /// Время жизни кэша списков устройств и моделей (секунды)
+    pub enumeration_cache_ttl_sec: u32,
+
+    /// Количество попыток найти микрофон перед ошибкой
+    pub mic_probe_attempts: u32,
+
+    /// Пауза между попытками найти микрофон (миллисекунды)
+    pub mic_probe_delay_ms: u32,
+
+    /// URL webhook для отправки каждого результата (POST JSON), `None` - выключено
+    pub webhook_url: Option<String>,
+
+    /// Показывать результат с Accept/Discard вместо автоматической вставки
+    pub confirm_before_paste: bool,
+
+    /// Логировать вероятности речи VAD по кадрам (trace) и сводку за запись (debug)
+    pub vad_debug_probabilities: bool,
+
+    /// Как измерять длительность тишины для авто-стопа
+    pub vad_silence_clock: SilenceClockMode,
+
+    /// Приводить числа к цифрам или словам после улучшения (en/ru)
+    pub number_format: NumberFormat,
+
+    /// Через сколько минут простоя освобождать кэши устройств и моделей API,
+    /// загруженную модель Whisper и открытые потоки микрофона (0 - никогда)
+    pub idle_release_after_min: u32,
+
+    /// Размер части (в графемах) при посимвольном вводе текста
+    pub type_chunk_size: u32,
+
+    /// Пауза между частями при посимвольном вводе (мс)
+    pub type_chunk_delay_ms: u32,
+
+    /// Способ вставки текста
+    pub paste_method: PasteMethod,
+
+    /// Размер кэша результатов улучшения (записей, 0 - выключен)
+    pub enhance_cache_size: u32,
+
+    /// Время жизни записи в кэше улучшений (секунды)
+    pub enhance_cache_ttl_sec: u32,
+
+    /// PTT: короткое нажатие фиксирует запись до следующего нажатия
+    pub ptt_tap_to_lock: bool,
+
+    /// PTT: нажатие короче этого порога считается tap (миллисекунды)
+    pub ptt_tap_threshold_ms: u32,
+
+    /// Минимум свободного места на диске для сохранения отладочного аудио (МБ)
+    pub debug_audio_min_free_mb: u32,
+
+    /// Фразы, которые улучшение не должно менять (названия, команды)
+    pub protected_phrases: Vec<String>,
+
+    /// Имя выбранного микрофона, `None` - устройство по умолчанию
+    pub input_device: Option<String>,
+
+    /// Предпочитаемый формат сэмплов захвата (`auto` - формат устройства)
+    pub capture_sample_format: CaptureSampleFormat,
+
+    /// High-pass фильтр (80 Гц) перед ресемплингом: убирает гул и низкочастотный шум
+    pub high_pass_filter: bool,
+
+    /// Нормализация пиковой громкости перед кодированием (тихие записи распознаются хуже)
+    pub normalize_audio: bool,
+
+    /// Порог RMS noise gate: более тихие кадры обнуляются перед STT (0 - выключено)
+    pub noise_gate_threshold: f32,
+
+    /// Порог вероятности речи Silero VAD (0.0-1.0, ниже - чувствительнее к тихой речи)
+    pub vad_threshold: f32,
+
+    /// Сколько мс речи подряд нужно, чтобы сбросить таймер тишины
+    pub vad_speech_onset_ms: u32,
+
+    /// Сколько мс тишины после речи не запускают таймер авто-стопа
+    pub vad_hangover_ms: u32,
+
+    /// Порог RMS для запасного VAD, если модель Silero недоступна
+    pub vad_energy_threshold: f32,
+
+    /// Путь к модели whisper.cpp (ggml) для офлайн-распознавания
+    pub whisper_model_path: Option<String>,
+
+    /// Провайдер STT: облачный OpenAI или офлайн Whisper
+    pub stt_provider: SttProviderKind,
+
+    /// Подсказка для STT: словарь имен и терминов (пусто - без подсказки)
+    pub stt_prompt: String,
+
+    /// Распознавать чанки длинной записи по очереди, передавая хвост текста
+    /// предыдущего чанка в prompt (медленнее, но связнее на стыках)
+    pub stt_chain_chunk_prompts: bool,
+
+    /// Отправлять чанки в STT во время записи, не дожидаясь остановки (только OpenAI).
+    /// Чанки не проходят нормализацию и обрезку тишины; при ошибке запись
+    /// распознается целиком после остановки
+    pub stt_streaming: bool,
+
+    /// Температура декодирования STT (0.0..1.0, 0.0 - максимально детерминированно)
+    pub stt_temperature: f32,
+
+    /// Overlap между чанками длинной записи (секунды)
+    pub stt_chunk_overlap_sec: f32,
+
+    /// Минимальная длительность чанка (секунды): короче не режем
+    pub stt_min_chunk_sec: f32,
+
+    /// Начало зоны поиска тишины для разреза чанка (проценты от длины, 1..99)
+    pub stt_quiet_search_start_percent: u32,
+
+    /// Стиль улучшения текста: default, formal, casual, verbatim
+    pub enhance_style: EnhanceStyle,
+
+    /// Замены после улучшения (from -> to): целые слова, без учета регистра.
+    /// Работают и при выключенном enhance
+    pub glossary: Vec<(String, String)>,
+
+    /// API для улучшения: responses или chat (OpenAI-совместимые серверы)
+    pub enhance_api_flavor: EnhanceApiFlavor,
+
+    /// Потоковый ответ enhance (SSE): меньше задержка на длинном тексте
+    pub enhance_streaming: bool,
+
+    /// Минимальное отношение слов enhanced/raw, ниже - fallback к сырому тексту
+    pub enhance_min_word_ratio: f32,
+
+    /// Максимальное отношение слов enhanced/raw, выше - fallback к сырому тексту
+    pub enhance_max_word_ratio: f32,
+
+    /// Максимальная длина улучшенного текста (символов), длиннее - обрезается
+    pub enhance_max_chars: u32,
+
+    /// Утилита вставки на Linux/Wayland (wtype, ydotool или путь), если Ctrl+V
+    /// через enigo не сработал; пусто - автоопределение
+    pub wayland_paste_tool: String,
+
+    /// Сохранять и восстанавливать изображение в clipboard при вставке (дороже текста)
+    pub preserve_clipboard_images: bool,
+
+    /// Суффикс после вставки: none, space, newline
+    pub paste_suffix: PasteSuffix,
+
+    /// Размер сегмента (графем) при вставке длинного текста через clipboard; 0 - целиком
+    pub paste_segment_chars: u32,
+
+    /// Именованные профили провайдеров (endpoint, модели, свой API-ключ)
+    pub profiles: Vec<ProviderProfile>,
+
+    /// Имя активного профиля; его значения отражены в `api_base_url`,
+    /// `stt_model` и `enhance_model`
+    pub active_profile: String,
+
+    /// Хранить API-ключ в зашифрованном файле, если OS keychain недоступен.
+    /// Ключ файла выводится из идентификатора машины: это обфускация от
+    /// случайного просмотра и копирования, а не защита от процессов пользователя
+    pub secret_file_fallback: bool,
+
+    /// Сохранять вставленные тексты в history.json (только текст, без аудио)
+    pub keep_history: bool,
+
+    /// Максимум записей в истории, старые вытесняются
+    pub history_max_entries: u32,
+
+    /// Хоткей отмены обработки (любой режим записи); пусто - выключен
+    pub cancel_hotkey: String,
+
+    /// Сколько дней хранить файлы лога; 0 - не удалять
+    pub log_retention_days: u32,
+
+    /// Не улучшать текст короче стольких слов (короткие команды); 0 - всегда улучшать
+    pub enhance_min_words: u32,
+
+    /// Языки, для которых включено улучшение (коды или названия); пусто - все
+    pub enhance_languages: Vec<String>,
+
+    /// Общий лимит времени на повторы улучшения (секунды); по исчерпании - сырой текст.
+    /// 0 - без лимита
+    pub enhance_retry_budget_sec: u32,
+
+    /// Режим голосовых команд: "new line", "scratch that", "comma" выполняются, а не вставляются
+    pub command_mode: bool,
+
+    /// Битрейт кодирования Opus (кбит/с)
+    pub opus_bitrate_kbps: u32,
+
+    /// Режим кодера Opus: речь (voip) или audio
+    #[serde(alias = "opus_mode")]
+    pub opus_application: OpusApplication,
+
+    /// Формат аудио для распознавания: ogg_opus или wav
+    pub audio_upload_format: UploadFormat,
+
+    /// Качество ресемплинга в 16 kHz: linear (по умолчанию, быстрее) или sinc (без алиасинга)
+    pub resample_quality: ResampleQuality,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
-            config_version: 1,
-            hotkey: "Ctrl+Shift+S".to_string(),
+            config_version: CURRENT_CONFIG_VERSION,
+            hotkeys: vec!["Ctrl+Shift+S".to_string()],
             recording_mode: RecordingMode::default(),
             language: "auto".to_string(),
             stt_model: "gpt-4o-mini-transcribe".to_string(),
@@ -100,7 +526,372 @@ impl Default for AppConfig {
             retry_count: 3,
             log_level: "info".to_string(),
             debug_save_audio: false,
+            enumeration_cache_ttl_sec: 30,
+            mic_probe_attempts: 3,
+            mic_probe_delay_ms: 500,
+            webhook_url: None,
+            confirm_before_paste: false,
+            vad_debug_probabilities: false,
+            vad_silence_clock: SilenceClockMode::default(),
+            number_format: NumberFormat::default(),
+            idle_release_after_min: 30,
+            type_chunk_size: 32,
+            type_chunk_delay_ms: 20,
+            paste_method: PasteMethod::default(),
+            enhance_cache_size: 32,
+            enhance_cache_ttl_sec: 600,
+            ptt_tap_to_lock: false,
+            ptt_tap_threshold_ms: 250,
+            debug_audio_min_free_mb: 200,
+            protected_phrases: Vec::new(),
+            input_device: None,
+            capture_sample_format: CaptureSampleFormat::default(),
+            high_pass_filter: true,
+            normalize_audio: false,
+            noise_gate_threshold: 0.0,
+            vad_threshold: 0.5,
+            vad_speech_onset_ms: 64,
+            vad_hangover_ms: 200,
+            vad_energy_threshold: 0.01,
+            whisper_model_path: None,
+            stt_provider: SttProviderKind::default(),
+            stt_prompt: String::new(),
+            stt_chain_chunk_prompts: false,
+            stt_streaming: false,
+            stt_temperature: 0.0,
+            stt_chunk_overlap_sec: 1.5,
+            stt_min_chunk_sec: 5.0,
+            stt_quiet_search_start_percent: 70,
+            enhance_style: EnhanceStyle::default(),
+            glossary: Vec::new(),
+            enhance_api_flavor: EnhanceApiFlavor::default(),
+            enhance_streaming: false,
+            enhance_min_word_ratio: 0.3,
+            enhance_max_word_ratio: 1.5,
+            enhance_max_chars: 5000,
+            wayland_paste_tool: String::new(),
+            preserve_clipboard_images: false,
+            paste_suffix: PasteSuffix::default(),
+            paste_segment_chars: 2000,
+            profiles: Vec::new(),
+            active_profile: DEFAULT_PROFILE.to_string(),
+            secret_file_fallback: false,
+            keep_history: false,
+            history_max_entries: 200,
+            cancel_hotkey: String::new(),
+            log_retention_days: 14,
+            enhance_min_words: 0,
+            enhance_languages: Vec::new(),
+            enhance_retry_budget_sec: 20,
+            command_mode: false,
+            opus_bitrate_kbps: 24,
+            opus_application: OpusApplication::Voip,
+            audio_upload_format: UploadFormat::OggOpus,
+            resample_quality: ResampleQuality::Linear,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Активный профиль провайдера.
+    ///
+    /// Если профиля с именем `active_profile` нет - собирается из плоских полей.
+    pub fn active_provider(&self) -> ProviderProfile {
+        self.profiles
+            .iter()
+            .find(|p| p.name == self.active_profile)
+            .cloned()
+            .unwrap_or_else(|| ProviderProfile {
+                name: self.active_profile.clone(),
+                api_base_url: self.api_base_url.clone(),
+                stt_model: self.stt_model.clone(),
+                enhance_model: self.enhance_model.clone(),
+            })
+    }
+
+    /// Переносит значения активного профиля в плоские поля (после смены профиля).
+    pub fn select_active_profile(&mut self) {
+        let profile = self.active_provider();
+        self.api_base_url = profile.api_base_url;
+        self.stt_model = profile.stt_model;
+        self.enhance_model = profile.enhance_model;
+    }
+
+    /// Сохраняет плоские поля в активный профиль, создавая его при необходимости.
+    pub fn store_active_profile(&mut self) {
+        let profile = ProviderProfile {
+            name: self.active_profile.clone(),
+            api_base_url: self.api_base_url.clone(),
+            stt_model: self.stt_model.clone(),
+            enhance_model: self.enhance_model.clone(),
+        };
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Проверяет значения полей, не изменяя конфиг.
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        if self.config_version > CURRENT_CONFIG_VERSION {
+            issues.push(ConfigIssue::warning(
+                "config_version",
+                format!(
+                    "version {} is newer than supported {CURRENT_CONFIG_VERSION}",
+                    self.config_version
+                ),
+            ));
+        }
+        if self.hotkeys.is_empty() {
+            issues.push(ConfigIssue::error(
+                "hotkeys",
+                "at least one hotkey is required",
+            ));
+        }
+        if self.hotkeys.iter().any(|h| h.trim().is_empty()) {
+            issues.push(ConfigIssue::error("hotkeys", "hotkey must not be empty"));
+        }
+        if !LANGUAGES.contains(&self.language.as_str()) {
+            issues.push(ConfigIssue::error(
+                "language",
+                format!(
+                    "unknown language '{}', expected one of {LANGUAGES:?}",
+                    self.language
+                ),
+            ));
+        }
+        if self.stt_model.trim().is_empty() {
+            issues.push(ConfigIssue::error("stt_model", "model must not be empty"));
         }
+        if !(0.0..=1.0).contains(&self.stt_temperature) {
+            issues.push(ConfigIssue::warning(
+                "stt_temperature",
+                "must be within 0.0..=1.0, value will be clamped",
+            ));
+        }
+        if self.enhance_min_word_ratio >= self.enhance_max_word_ratio {
+            issues.push(ConfigIssue::warning(
+                "enhance_max_word_ratio",
+                "must be greater than enhance_min_word_ratio, defaults will be used",
+            ));
+        }
+        if self.enhance_enabled && self.enhance_model.trim().is_empty() {
+            issues.push(ConfigIssue::error(
+                "enhance_model",
+                "model must not be empty when enhance is enabled",
+            ));
+        }
+        if !self.vad_silence_threshold_sec.is_finite() || self.vad_silence_threshold_sec <= 0.0 {
+            issues.push(ConfigIssue::error(
+                "vad_silence_threshold_sec",
+                "must be a positive number of seconds",
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.vad_threshold) {
+            issues.push(ConfigIssue::warning(
+                "vad_threshold",
+                "must be within 0.0..=1.0, value will be clamped",
+            ));
+        }
+        if !MAX_RECORDING_RANGE.contains(&self.max_recording_duration_sec) {
+            issues.push(ConfigIssue::error(
+                "max_recording_duration_sec",
+                format!(
+                    "{} is outside {}..={}",
+                    self.max_recording_duration_sec,
+                    MAX_RECORDING_RANGE.start(),
+                    MAX_RECORDING_RANGE.end()
+                ),
+            ));
+        }
+        if u64::from(self.min_recording_duration_ms)
+            >= u64::from(self.max_recording_duration_sec) * 1000
+        {
+            issues.push(ConfigIssue::warning(
+                "min_recording_duration_ms",
+                "minimum is not shorter than maximum recording duration",
+            ));
+        }
+        if self.active_profile.trim().is_empty() {
+            issues.push(ConfigIssue::error(
+                "active_profile",
+                "profile name must not be empty",
+            ));
+        }
+        if !self.api_base_url.starts_with("https://") && !self.api_base_url.starts_with("http://") {
+            issues.push(ConfigIssue::error(
+                "api_base_url",
+                "must start with https:// or http://",
+            ));
+        }
+        for (field, value) in [
+            ("connect_timeout_sec", self.connect_timeout_sec),
+            ("read_timeout_stt_sec", self.read_timeout_stt_sec),
+            ("read_timeout_enhance_sec", self.read_timeout_enhance_sec),
+        ] {
+            if value == 0 {
+                issues.push(ConfigIssue::error(field, "timeout must be greater than 0"));
+            }
+        }
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            issues.push(ConfigIssue::warning(
+                "log_level",
+                format!(
+                    "unknown log level '{}', 'info' will be used",
+                    self.log_level
+                ),
+            ));
+        }
+        if let Some(url) = &self.webhook_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                issues.push(ConfigIssue::error(
+                    "webhook_url",
+                    "must start with https:// or http://",
+                ));
+            }
+        }
+        if self.type_chunk_size == 0 {
+            issues.push(ConfigIssue::warning(
+                "type_chunk_size",
+                "0 is treated as 1 grapheme per chunk",
+            ));
+        }
+
+        issues
+    }
+
+    /// Приводит значения вне допустимых диапазонов к ближайшим допустимым
+    /// (или к дефолтам, если ближайшего нет).
+    ///
+    /// Каждая корректировка логируется; возвращает имена исправленных полей.
+    pub fn validate_and_clamp(&mut self) -> Vec<&'static str> {
+        let defaults = Self::default();
+        let mut adjusted = Vec::new();
+        let mut note = |field: &'static str, old: String, new: String| {
+            tracing::warn!(field, "config value {old} is out of range, using {new}");
+            adjusted.push(field);
+        };
+
+        let duration = self
+            .max_recording_duration_sec
+            .clamp(*MAX_RECORDING_RANGE.start(), *MAX_RECORDING_RANGE.end());
+        if duration != self.max_recording_duration_sec {
+            note(
+                "max_recording_duration_sec",
+                self.max_recording_duration_sec.to_string(),
+                duration.to_string(),
+            );
+            self.max_recording_duration_sec = duration;
+        }
+
+        // 0 тоже недопустим: auto-stop сработал бы сразу после начала записи
+        if !self.vad_silence_threshold_sec.is_finite() || self.vad_silence_threshold_sec <= 0.0 {
+            note(
+                "vad_silence_threshold_sec",
+                self.vad_silence_threshold_sec.to_string(),
+                defaults.vad_silence_threshold_sec.to_string(),
+            );
+            self.vad_silence_threshold_sec = defaults.vad_silence_threshold_sec;
+        }
+
+        for (field, value, default) in [
+            (
+                "vad_threshold",
+                &mut self.vad_threshold,
+                defaults.vad_threshold,
+            ),
+            (
+                "stt_temperature",
+                &mut self.stt_temperature,
+                defaults.stt_temperature,
+            ),
+        ] {
+            let clamped = if value.is_finite() {
+                value.clamp(0.0, 1.0)
+            } else {
+                default
+            };
+            if clamped != *value {
+                note(field, value.to_string(), clamped.to_string());
+                *value = clamped;
+            }
+        }
+
+        let bitrate = self.opus_bitrate_kbps.clamp(
+            *OPUS_BITRATE_RANGE_KBPS.start(),
+            *OPUS_BITRATE_RANGE_KBPS.end(),
+        );
+        if bitrate != self.opus_bitrate_kbps {
+            note(
+                "opus_bitrate_kbps",
+                self.opus_bitrate_kbps.to_string(),
+                bitrate.to_string(),
+            );
+            self.opus_bitrate_kbps = bitrate;
+        }
+
+        if self.retry_count > MAX_RETRY_COUNT {
+            note(
+                "retry_count",
+                self.retry_count.to_string(),
+                MAX_RETRY_COUNT.to_string(),
+            );
+            self.retry_count = MAX_RETRY_COUNT;
+        }
+
+        for (field, value, default) in [
+            (
+                "connect_timeout_sec",
+                &mut self.connect_timeout_sec,
+                defaults.connect_timeout_sec,
+            ),
+            (
+                "read_timeout_stt_sec",
+                &mut self.read_timeout_stt_sec,
+                defaults.read_timeout_stt_sec,
+            ),
+            (
+                "read_timeout_enhance_sec",
+                &mut self.read_timeout_enhance_sec,
+                defaults.read_timeout_enhance_sec,
+            ),
+        ] {
+            if *value == 0 {
+                note(field, "0".to_string(), default.to_string());
+                *value = default;
+            }
+        }
+
+        if self.api_base_url.trim().is_empty() {
+            note(
+                "api_base_url",
+                format!("{:?}", self.api_base_url),
+                defaults.api_base_url.clone(),
+            );
+            self.api_base_url = defaults.api_base_url;
+        }
+
+        if self.active_profile.trim().is_empty() {
+            note(
+                "active_profile",
+                format!("{:?}", self.active_profile),
+                defaults.active_profile.clone(),
+            );
+            self.active_profile = defaults.active_profile;
+        }
+
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            note(
+                "log_level",
+                format!("{:?}", self.log_level),
+                defaults.log_level.clone(),
+            );
+            self.log_level = defaults.log_level;
+        }
+
+        adjusted
     }
 }
 
@@ -114,8 +905,8 @@ mod tests {
         let config = AppConfig::default();
 
         // Then
-        assert_eq!(config.config_version, 1);
-        assert_eq!(config.hotkey, "Ctrl+Shift+S");
+        assert_eq!(config.config_version, 3);
+        assert_eq!(config.hotkeys, vec!["Ctrl+Shift+S"]);
         assert_eq!(config.recording_mode, RecordingMode::Toggle);
         assert_eq!(config.language, "auto");
         assert_eq!(config.stt_model, "gpt-4o-mini-transcribe");
@@ -134,6 +925,284 @@ mod tests {
         assert_eq!(config.retry_count, 3);
         assert_eq!(config.log_level, "info");
         assert!(!config.debug_save_audio);
+        assert_eq!(config.enumeration_cache_ttl_sec, 30);
+        assert_eq!(config.mic_probe_attempts, 3);
+        assert_eq!(config.mic_probe_delay_ms, 500);
+        assert_eq!(config.webhook_url, None);
+        assert!(!config.confirm_before_paste);
+        assert!(!config.vad_debug_probabilities);
+        assert_eq!(config.vad_silence_clock, SilenceClockMode::WallClock);
+        assert_eq!(config.number_format, NumberFormat::Keep);
+        assert_eq!(config.idle_release_after_min, 30);
+        assert_eq!(config.type_chunk_size, 32);
+        assert_eq!(config.type_chunk_delay_ms, 20);
+        assert_eq!(config.paste_method, PasteMethod::Clipboard);
+        assert_eq!(config.enhance_cache_size, 32);
+        assert_eq!(config.enhance_cache_ttl_sec, 600);
+        assert!(!config.ptt_tap_to_lock);
+        assert_eq!(config.ptt_tap_threshold_ms, 250);
+        assert_eq!(config.debug_audio_min_free_mb, 200);
+        assert!(config.protected_phrases.is_empty());
+        assert_eq!(config.input_device, None);
+        assert_eq!(config.capture_sample_format, CaptureSampleFormat::Auto);
+        assert!(config.high_pass_filter);
+        assert!(!config.normalize_audio);
+        assert_eq!(config.noise_gate_threshold, 0.0);
+        assert!((config.vad_threshold - 0.5).abs() < f32::EPSILON);
+        assert_eq!(config.vad_speech_onset_ms, 64);
+        assert_eq!(config.vad_hangover_ms, 200);
+        assert!((config.vad_energy_threshold - 0.01).abs() < f32::EPSILON);
+        assert_eq!(config.whisper_model_path, None);
+        assert_eq!(config.stt_provider, SttProviderKind::OpenAi);
+        assert!(config.stt_prompt.is_empty());
+        assert!(!config.stt_chain_chunk_prompts);
+        assert!(!config.stt_streaming);
+        assert_eq!(config.stt_temperature, 0.0);
+        assert_eq!(config.stt_chunk_overlap_sec, 1.5);
+        assert_eq!(config.stt_min_chunk_sec, 5.0);
+        assert_eq!(config.stt_quiet_search_start_percent, 70);
+        assert_eq!(config.enhance_style, EnhanceStyle::Default);
+        assert!(config.glossary.is_empty());
+        assert_eq!(config.enhance_api_flavor, EnhanceApiFlavor::Responses);
+        assert!(!config.enhance_streaming);
+        assert_eq!(config.enhance_min_word_ratio, 0.3);
+        assert_eq!(config.enhance_max_word_ratio, 1.5);
+        assert_eq!(config.enhance_max_chars, 5000);
+        assert!(config.wayland_paste_tool.is_empty());
+        assert!(!config.preserve_clipboard_images);
+        assert_eq!(config.paste_suffix, PasteSuffix::None);
+        assert_eq!(config.paste_segment_chars, 2000);
+        assert!(config.profiles.is_empty());
+        assert_eq!(config.active_profile, "default");
+        assert!(!config.secret_file_fallback);
+        assert!(!config.keep_history);
+        assert_eq!(config.history_max_entries, 200);
+        assert_eq!(config.cancel_hotkey, "");
+        assert_eq!(config.log_retention_days, 14);
+        assert_eq!(config.enhance_min_words, 0);
+        assert!(config.enhance_languages.is_empty());
+        assert_eq!(config.enhance_retry_budget_sec, 20);
+        assert!(!config.command_mode);
+        assert_eq!(config.opus_bitrate_kbps, 24);
+        assert_eq!(config.opus_application, OpusApplication::Voip);
+        assert_eq!(config.audio_upload_format, UploadFormat::OggOpus);
+        assert_eq!(config.resample_quality, ResampleQuality::Linear);
+    }
+
+    #[test]
+    fn config_should_fill_missing_fields_with_defaults() {
+        // Given: конфиг старой версии без новых полей
+        let json = r#"{"hotkeys": ["Ctrl+Alt+D"], "language": "ru"}"#;
+
+        // When
+        let config: AppConfig = serde_json::from_str(json).expect("deserialize");
+
+        // Then
+        assert_eq!(config.hotkeys, vec!["Ctrl+Alt+D"]);
+        assert_eq!(config.language, "ru");
+        assert_eq!(config.enumeration_cache_ttl_sec, 30);
+    }
+
+    #[test]
+    fn config_should_accept_opus_mode_alias() {
+        // Given
+        let json = r#"{"opus_mode": "audio"}"#;
+
+        // When
+        let config: AppConfig = serde_json::from_str(json).expect("deserialize");
+
+        // Then
+        assert_eq!(config.opus_application, OpusApplication::Audio);
+    }
+
+    #[test]
+    fn default_config_should_have_no_issues() {
+        assert!(AppConfig::default().validate().is_empty());
+    }
+
+    #[test]
+    fn validate_should_report_invalid_values() {
+        // Given
+        let config = AppConfig {
+            language: "de".to_string(),
+            max_recording_duration_sec: 500,
+            api_base_url: "ftp://example.com".to_string(),
+            read_timeout_stt_sec: 0,
+            log_level: "verbose".to_string(),
+            vad_threshold: 1.5,
+            ..AppConfig::default()
+        };
+
+        // When
+        let issues = config.validate();
+
+        // Then
+        let fields: Vec<(&str, IssueSeverity)> = issues
+            .iter()
+            .map(|i| (i.field.as_str(), i.severity))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("language", IssueSeverity::Error),
+                ("vad_threshold", IssueSeverity::Warning),
+                ("max_recording_duration_sec", IssueSeverity::Error),
+                ("api_base_url", IssueSeverity::Error),
+                ("read_timeout_stt_sec", IssueSeverity::Error),
+                ("log_level", IssueSeverity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_and_clamp_should_keep_default_config_unchanged() {
+        // Given
+        let mut config = AppConfig::default();
+
+        // When
+        let adjusted = config.validate_and_clamp();
+
+        // Then
+        assert!(adjusted.is_empty());
+        assert_eq!(config, AppConfig::default());
+    }
+
+    #[test]
+    fn validate_and_clamp_should_clamp_max_recording_duration() {
+        // Given
+        let mut too_short = AppConfig {
+            max_recording_duration_sec: 0,
+            ..AppConfig::default()
+        };
+        let mut too_long = AppConfig {
+            max_recording_duration_sec: 500,
+            ..AppConfig::default()
+        };
+
+        // When
+        too_short.validate_and_clamp();
+        too_long.validate_and_clamp();
+
+        // Then
+        assert_eq!(too_short.max_recording_duration_sec, 10);
+        assert_eq!(too_long.max_recording_duration_sec, 120);
+    }
+
+    #[test]
+    fn validate_and_clamp_should_reset_invalid_silence_threshold() {
+        for value in [-1.0, 0.0, f32::NAN, f32::INFINITY] {
+            // Given
+            let mut config = AppConfig {
+                vad_silence_threshold_sec: value,
+                ..AppConfig::default()
+            };
+
+            // When
+            let adjusted = config.validate_and_clamp();
+
+            // Then
+            assert_eq!(adjusted, vec!["vad_silence_threshold_sec"], "value {value}");
+            assert_eq!(config.vad_silence_threshold_sec, 10.0);
+        }
+    }
+
+    #[test]
+    fn validate_and_clamp_should_clamp_probabilities() {
+        // Given
+        let mut config = AppConfig {
+            vad_threshold: 1.5,
+            stt_temperature: -0.5,
+            ..AppConfig::default()
+        };
+
+        // When
+        config.validate_and_clamp();
+
+        // Then
+        assert_eq!(config.vad_threshold, 1.0);
+        assert_eq!(config.stt_temperature, 0.0);
+    }
+
+    #[test]
+    fn validate_and_clamp_should_cap_retry_count() {
+        // Given
+        let mut config = AppConfig {
+            retry_count: 1000,
+            ..AppConfig::default()
+        };
+
+        // When
+        config.validate_and_clamp();
+
+        // Then
+        assert_eq!(config.retry_count, MAX_RETRY_COUNT);
+    }
+
+    #[test]
+    fn validate_and_clamp_should_clamp_opus_bitrate() {
+        // Given
+        let mut config = AppConfig {
+            opus_bitrate_kbps: 1000,
+            ..AppConfig::default()
+        };
+
+        // When
+        let adjusted = config.validate_and_clamp();
+
+        // Then
+        assert_eq!(adjusted, vec!["opus_bitrate_kbps"]);
+        assert_eq!(config.opus_bitrate_kbps, 64);
+    }
+
+    #[test]
+    fn validate_and_clamp_should_reset_zero_timeouts() {
+        // Given
+        let mut config = AppConfig {
+            connect_timeout_sec: 0,
+            read_timeout_stt_sec: 0,
+            read_timeout_enhance_sec: 0,
+            ..AppConfig::default()
+        };
+
+        // When
+        let adjusted = config.validate_and_clamp();
+
+        // Then
+        assert_eq!(adjusted.len(), 3);
+        assert_eq!(config.connect_timeout_sec, 5);
+        assert_eq!(config.read_timeout_stt_sec, 30);
+        assert_eq!(config.read_timeout_enhance_sec, 30);
+    }
+
+    #[test]
+    fn validate_and_clamp_should_reset_empty_api_base_url() {
+        // Given
+        let mut config = AppConfig {
+            api_base_url: "   ".to_string(),
+            ..AppConfig::default()
+        };
+
+        // When
+        config.validate_and_clamp();
+
+        // Then
+        assert_eq!(config.api_base_url, "https://api.openai.com");
+    }
+
+    #[test]
+    fn validate_and_clamp_should_reset_unknown_log_level() {
+        // Given
+        let mut config = AppConfig {
+            log_level: "verbose".to_string(),
+            ..AppConfig::default()
+        };
+
+        // When
+        let adjusted = config.validate_and_clamp();
+
+        // Then
+        assert_eq!(adjusted, vec!["log_level"]);
+        assert_eq!(config.log_level, "info");
     }
 
     #[test]
@@ -164,4 +1233,81 @@ mod tests {
         assert_eq!(serde_json::to_string(&toggle).unwrap(), "\"toggle\"");
         assert_eq!(serde_json::to_string(&ptt).unwrap(), "\"push_to_talk\"");
     }
+
+    #[test]
+    fn stt_provider_should_serialize_as_openai_and_offline() {
+        assert_eq!(
+            serde_json::to_string(&SttProviderKind::OpenAi).unwrap(),
+            "\"openai\""
+        );
+        assert_eq!(
+            serde_json::to_string(&SttProviderKind::Offline).unwrap(),
+            "\"offline\""
+        );
+    }
+
+    #[test]
+    fn active_provider_should_fall_back_to_flat_fields_without_profiles() {
+        // Given
+        let config = AppConfig {
+            api_base_url: "https://proxy.example.com".to_string(),
+            ..Default::default()
+        };
+
+        // When
+        let profile = config.active_provider();
+
+        // Then
+        assert_eq!(profile.name, DEFAULT_PROFILE);
+        assert_eq!(profile.api_base_url, "https://proxy.example.com");
+        assert_eq!(profile.stt_model, config.stt_model);
+    }
+
+    #[test]
+    fn select_active_profile_should_copy_profile_into_flat_fields() {
+        // Given
+        let mut config = AppConfig {
+            profiles: vec![
+                ProviderProfile::default(),
+                ProviderProfile {
+                    name: "groq".to_string(),
+                    api_base_url: "https://api.groq.com/openai".to_string(),
+                    stt_model: "whisper-large-v3".to_string(),
+                    enhance_model: "llama-3.3-70b".to_string(),
+                },
+            ],
+            active_profile: "groq".to_string(),
+            ..Default::default()
+        };
+
+        // When
+        config.select_active_profile();
+
+        // Then
+        assert_eq!(config.api_base_url, "https://api.groq.com/openai");
+        assert_eq!(config.stt_model, "whisper-large-v3");
+        assert_eq!(config.enhance_model, "llama-3.3-70b");
+    }
+
+    #[test]
+    fn store_active_profile_should_update_existing_or_add_new_profile() {
+        // Given
+        let mut config = AppConfig {
+            profiles: vec![ProviderProfile::default()],
+            stt_model: "whisper-1".to_string(),
+            ..Default::default()
+        };
+
+        // When
+        config.store_active_profile();
+        config.active_profile = "local".to_string();
+        config.api_base_url = "http://localhost:8080".to_string();
+        config.store_active_profile();
+
+        // Then
+        assert_eq!(config.profiles.len(), 2);
+        assert_eq!(config.profiles[0].stt_model, "whisper-1");
+        assert_eq!(config.profiles[1].name, "local");
+        assert_eq!(config.profiles[1].api_base_url, "http://localhost:8080");
+    }
 }
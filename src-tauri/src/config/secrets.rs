@@ -1,31 +1,110 @@
 #![allow(dead_code)]
 
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use sha2::{Digest, Sha256};
 use tracing::{info, warn};
 
+use crate::config::schema::{AppConfig, DEFAULT_PROFILE};
+use crate::config::storage::config_dir;
 use crate::error::{AppError, Result};
 
 /// Имя сервиса в OS keychain.
 const SERVICE_NAME: &str = "voicedictator";
 
-/// Имя пользователя (ключ) в OS keychain.
+/// Имя пользователя (ключ) в OS keychain для профиля `default`.
 const USERNAME: &str = "openai-api-key";
 
-/// Сохраняет API-ключ в OS keychain.
-pub fn store_api_key(key: &str) -> Result<()> {
-    let entry = keyring::Entry::new(SERVICE_NAME, USERNAME)
-        .map_err(|e| AppError::Config(format!("failed to create keyring entry: {}", e)))?;
-    entry
-        .set_password(key)
-        .map_err(|e| AppError::Config(format!("failed to store API key in keychain: {}", e)))?;
-    info!("API key stored in OS keychain");
-    Ok(())
-}
-
-/// Загружает API-ключ из OS keychain. Возвращает `None` если ключ не сохранен.
-pub fn load_api_key() -> Result<Option<String>> {
-    let entry = keyring::Entry::new(SERVICE_NAME, USERNAME)
-        .map_err(|e| AppError::Config(format!("failed to create keyring entry: {}", e)))?;
-    match entry.get_password() {
+/// Переменные окружения с API-ключом (в порядке приоритета), если keychain
+/// недоступен или пуст: headless CI, машины без keyring.
+const API_KEY_ENV_VARS: &[&str] = &["VOICEDICTATOR_API_KEY", "OPENAI_API_KEY"];
+
+/// Файл с зашифрованными API-ключами в `config_dir()`, если keychain недоступен.
+const SECRET_FILE_NAME: &str = "secret.enc";
+
+/// Соль для ключа шифрования файла: меняется при смене формата.
+const SECRET_FILE_SALT: &[u8] = b"voicedictator-secret-file-v1";
+
+/// Длина nonce AES-GCM (байт) в начале файла.
+const NONCE_LEN: usize = 12;
+
+/// Где искать API-ключ: профиль провайдера и разрешен ли файловый fallback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyScope {
+    /// Имя профиля провайдера
+    pub profile: String,
+
+    /// Хранить ключ в зашифрованном файле, если keychain недоступен
+    pub file_fallback: bool,
+}
+
+impl ApiKeyScope {
+    /// Активный профиль и `secret_file_fallback` из конфига.
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            profile: config.active_profile.clone(),
+            file_fallback: config.secret_file_fallback,
+        }
+    }
+}
+
+/// Имя пользователя в OS keychain для профиля провайдера.
+///
+/// Профиль `default` использует прежнее имя, чтобы сохраненный ключ не потерялся.
+fn keychain_username(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        USERNAME.to_string()
+    } else {
+        format!("{USERNAME}:{profile}")
+    }
+}
+
+/// Запись OS keychain с API-ключом профиля.
+fn keychain_entry(profile: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE_NAME, &keychain_username(profile))
+        .map_err(|e| AppError::Config(format!("failed to create keyring entry: {}", e)))
+}
+
+/// Сохраняет API-ключ профиля в OS keychain.
+///
+/// Если keychain недоступен и включен `file_fallback` - в зашифрованный файл.
+pub fn store_api_key(scope: &ApiKeyScope, key: &str) -> Result<()> {
+    let profile = scope.profile.as_str();
+    let stored = keychain_entry(profile).and_then(|entry| {
+        entry
+            .set_password(key)
+            .map_err(|e| AppError::Config(format!("failed to store API key in keychain: {}", e)))
+    });
+
+    match stored {
+        Ok(()) => {
+            info!("API key for profile {profile:?} stored in OS keychain");
+            Ok(())
+        }
+        Err(e) if scope.file_fallback => {
+            warn!("{e}, falling back to encrypted file");
+            let path = secret_file_path()?;
+            store_in_file(&path, &machine_key()?, profile, key)?;
+            info!("API key for profile {profile:?} stored in encrypted file {path:?}");
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Загружает API-ключ профиля. Возвращает `None` если ключ нигде не задан.
+///
+/// Приоритет: OS keychain (ключ профиля), затем зашифрованный файл (если
+/// включен `file_fallback`), затем переменные окружения `VOICEDICTATOR_API_KEY`
+/// и `OPENAI_API_KEY` (общие для всех профилей).
+/// Ключ из окружения в keychain не записывается.
+/// Ошибка keychain возвращается, только если ни в файле, ни в окружении ключа нет.
+pub fn load_api_key(scope: &ApiKeyScope) -> Result<Option<String>> {
+    let keychain = keychain_entry(&scope.profile).and_then(|entry| match entry.get_password() {
         Ok(key) => Ok(Some(key)),
         Err(keyring::Error::NoEntry) => Ok(None),
         Err(e) => {
@@ -35,13 +114,77 @@ pub fn load_api_key() -> Result<Option<String>> {
                 e
             )))
         }
+    });
+
+    if let Ok(Some(key)) = keychain {
+        return Ok(Some(key));
+    }
+    if let Some(key) = api_key_from_secret_file(scope) {
+        return Ok(Some(key));
+    }
+    match (keychain, api_key_from_env()) {
+        (_, Some(key)) => Ok(Some(key)),
+        (Err(e), None) => Err(e),
+        (Ok(_), None) => Ok(None),
     }
 }
 
-/// Удаляет API-ключ из OS keychain.
-pub fn delete_api_key() -> Result<()> {
-    let entry = keyring::Entry::new(SERVICE_NAME, USERNAME)
-        .map_err(|e| AppError::Config(format!("failed to create keyring entry: {}", e)))?;
+/// API-ключ профиля из зашифрованного файла, если fallback включен.
+fn api_key_from_secret_file(scope: &ApiKeyScope) -> Option<String> {
+    if !scope.file_fallback {
+        return None;
+    }
+    let path = secret_file_path().ok()?;
+    let key = match machine_key() {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Encrypted API key file is unavailable: {e}");
+            return None;
+        }
+    };
+    match load_from_file(&path, &key, &scope.profile) {
+        Ok(Some(key)) => {
+            info!("Using API key from encrypted file {path:?}");
+            Some(key)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Failed to read encrypted API key file: {e}");
+            None
+        }
+    }
+}
+
+/// API-ключ из переменных окружения `API_KEY_ENV_VARS`.
+fn api_key_from_env() -> Option<String> {
+    let key = api_key_from_lookup(|name| std::env::var(name).ok());
+    if key.is_some() {
+        info!("Using API key from environment variable");
+    }
+    key
+}
+
+/// Первое непустое значение из `API_KEY_ENV_VARS` (lookup - источник переменных).
+fn api_key_from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<String> {
+    API_KEY_ENV_VARS
+        .iter()
+        .filter_map(|name| lookup(name))
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+/// Удаляет API-ключ профиля из OS keychain и из зашифрованного файла.
+///
+/// Файл чистится независимо от `file_fallback`: ключ мог быть сохранен,
+/// пока fallback был включен. Без идентификатора машины файл не трогается:
+/// записать его в таком окружении было нельзя.
+pub fn delete_api_key(scope: &ApiKeyScope) -> Result<()> {
+    let path = secret_file_path()?;
+    if let Ok(key) = machine_key() {
+        remove_from_file(&path, &key, &scope.profile)?;
+    }
+
+    let entry = keychain_entry(&scope.profile)?;
     match entry.delete_credential() {
         Ok(()) => {
             info!("API key deleted from OS keychain");
@@ -58,9 +201,399 @@ pub fn delete_api_key() -> Result<()> {
     }
 }
 
-/// Проверяет наличие API-ключа в OS keychain.
-pub fn has_api_key() -> bool {
-    keyring::Entry::new(SERVICE_NAME, USERNAME)
+/// Проверяет наличие API-ключа профиля в OS keychain, зашифрованном файле
+/// или в переменных окружения.
+pub fn has_api_key(scope: &ApiKeyScope) -> bool {
+    let in_keychain = keychain_entry(&scope.profile)
         .map(|entry| entry.get_password().is_ok())
-        .unwrap_or(false)
+        .unwrap_or(false);
+    let in_file = scope.file_fallback
+        && secret_file_path()
+            .and_then(|path| load_from_file(&path, &machine_key()?, &scope.profile))
+            .is_ok_and(|key| key.is_some());
+    in_keychain || in_file || api_key_from_lookup(|name| std::env::var(name).ok()).is_some()
+}
+
+// --- Encrypted file fallback ---
+
+/// Путь к файлу с зашифрованными API-ключами.
+fn secret_file_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(SECRET_FILE_NAME))
+}
+
+/// Ключ шифрования файла: SHA-256 от соли и идентификатора машины.
+///
+/// Это обфускация, а не защита: ключ выводится из данных, доступных любому
+/// процессу пользователя. Она спасает от случайного чтения и копирования
+/// файла на другую машину. Без идентификатора машины - ошибка: ключ из
+/// угадываемых данных (имя хоста, пользователя) хуже, чем отказ от файла.
+fn machine_key() -> Result<[u8; 32]> {
+    let id = machine_id().ok_or_else(|| {
+        AppError::Config("machine id is unavailable, encrypted key file is disabled".to_string())
+    })?;
+    Ok(key_from_machine_id(&id))
+}
+
+/// SHA-256 от соли и идентификатора машины.
+fn key_from_machine_id(id: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(SECRET_FILE_SALT);
+    hasher.update(id.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Стабильный идентификатор машины, если платформа его предоставляет.
+fn machine_id() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    for path in ["/etc/machine-id", "/var/lib/dbus/machine-id"] {
+        if let Ok(id) = fs::read_to_string(path) {
+            if !id.trim().is_empty() {
+                return Some(id.trim().to_string());
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Some(id) =
+        command_output("ioreg", &["-rd1", "-c", "IOPlatformExpertDevice"]).and_then(|out| {
+            out.lines()
+                .find(|line| line.contains("IOPlatformUUID"))
+                .and_then(|line| line.split('"').nth(3))
+                .map(str::to_string)
+        })
+    {
+        return Some(id);
+    }
+
+    #[cfg(windows)]
+    if let Some(id) = command_output(
+        "reg",
+        &[
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ],
+    )
+    .and_then(|out| out.split_whitespace().last().map(str::to_string))
+    {
+        return Some(id);
+    }
+
+    warn!("Machine id is unavailable, encrypted API key file cannot be used");
+    None
+}
+
+/// Stdout команды, если она завершилась успешно.
+#[cfg(any(target_os = "macos", windows))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Шифрует данные AES-256-GCM: результат - nonce и шифротекст.
+fn encrypt(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| AppError::Config(format!("failed to encrypt secret file: {}", e)))?;
+    Ok([nonce.as_slice(), &ciphertext].concat())
+}
+
+/// Расшифровывает результат `encrypt`; неверный ключ или поврежденный файл - ошибка.
+fn decrypt(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < NONCE_LEN {
+        return Err(AppError::Config("secret file is truncated".to_string()));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            AppError::Config(
+                "failed to decrypt secret file (different machine or corrupted)".to_string(),
+            )
+        })
+}
+
+/// Ключи всех профилей из файла; нет файла - пустой набор.
+fn read_secret_file(path: &Path, key: &[u8; 32]) -> Result<BTreeMap<String, String>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read(path)
+        .map_err(|e| AppError::Config(format!("failed to read secret file {:?}: {}", path, e)))?;
+    let plaintext = decrypt(key, &data)?;
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| AppError::Config(format!("invalid secret file contents: {}", e)))
+}
+
+/// Записывает ключи профилей; пустой набор удаляет файл.
+fn write_secret_file(path: &Path, key: &[u8; 32], keys: &BTreeMap<String, String>) -> Result<()> {
+    if keys.is_empty() {
+        return match fs::remove_file(path) {
+            Ok(()) => {
+                info!("Encrypted API key file {:?} deleted", path);
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Config(format!(
+                "failed to delete secret file {:?}: {}",
+                path, e
+            ))),
+        };
+    }
+
+    let plaintext = serde_json::to_vec(keys)
+        .map_err(|e| AppError::Config(format!("failed to serialize secrets: {}", e)))?;
+    let data = encrypt(key, &plaintext)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            AppError::Config(format!("failed to create config dir {:?}: {}", parent, e))
+        })?;
+    }
+    write_private(path, &data)
+        .map_err(|e| AppError::Config(format!("failed to write secret file {:?}: {}", path, e)))
+}
+
+/// Атомарно записывает файл, доступный только владельцу (на Unix - 0600).
+///
+/// Как `storage::write_atomic`: запись во временный файл рядом и rename, чтобы
+/// сбой посреди записи не оставил обрезанный файл с ключами. Временный файл
+/// создается заново с правами 0600, а не наследует права оставшегося от сбоя.
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    match fs::remove_file(&tmp_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&tmp_path, path)
+}
+
+/// API-ключ профиля из файла.
+fn load_from_file(path: &Path, key: &[u8; 32], profile: &str) -> Result<Option<String>> {
+    Ok(read_secret_file(path, key)?.remove(profile))
+}
+
+/// Сохраняет API-ключ профиля в файл, не трогая ключи других профилей.
+fn store_in_file(path: &Path, key: &[u8; 32], profile: &str, api_key: &str) -> Result<()> {
+    let mut keys = read_secret_file(path, key)?;
+    keys.insert(profile.to_string(), api_key.to_string());
+    write_secret_file(path, key, &keys)
+}
+
+/// Удаляет API-ключ профиля из файла; файл без ключей удаляется целиком.
+///
+/// Нерасшифровываемый файл (другая машина) удаляется целиком.
+fn remove_from_file(path: &Path, key: &[u8; 32], profile: &str) -> Result<()> {
+    let mut keys = match read_secret_file(path, key) {
+        Ok(keys) => keys,
+        Err(e) => {
+            warn!("{e}, deleting secret file");
+            BTreeMap::new()
+        }
+    };
+    keys.remove(profile);
+    write_secret_file(path, key, &keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lookup<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn api_key_from_lookup_should_prefer_app_specific_variable() {
+        // Given
+        let vars = [
+            ("OPENAI_API_KEY", "sk-openai"),
+            ("VOICEDICTATOR_API_KEY", "sk-app"),
+        ];
+
+        // When / Then
+        assert_eq!(
+            api_key_from_lookup(lookup(&vars)).as_deref(),
+            Some("sk-app")
+        );
+    }
+
+    #[test]
+    fn api_key_from_lookup_should_skip_empty_values() {
+        // Given
+        let vars = [
+            ("VOICEDICTATOR_API_KEY", "  "),
+            ("OPENAI_API_KEY", "sk-openai"),
+        ];
+
+        // When / Then
+        assert_eq!(
+            api_key_from_lookup(lookup(&vars)).as_deref(),
+            Some("sk-openai")
+        );
+    }
+
+    #[test]
+    fn api_key_from_lookup_should_return_none_without_variables() {
+        assert_eq!(api_key_from_lookup(lookup(&[])), None);
+    }
+
+    #[test]
+    fn decrypt_should_restore_encrypted_data() {
+        // Given
+        let key = [7u8; 32];
+
+        // When
+        let data = encrypt(&key, b"sk-secret").unwrap();
+
+        // Then
+        assert!(!data.windows(9).any(|w| w == b"sk-secret"));
+        assert_eq!(decrypt(&key, &data).unwrap(), b"sk-secret");
+    }
+
+    #[test]
+    fn decrypt_should_fail_with_other_machine_key() {
+        // Given
+        let data = encrypt(&[7u8; 32], b"sk-secret").unwrap();
+
+        // When / Then
+        assert!(decrypt(&[8u8; 32], &data).is_err());
+        assert!(decrypt(&[7u8; 32], &data[..4]).is_err());
+    }
+
+    #[test]
+    fn secret_file_should_keep_keys_per_profile() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(SECRET_FILE_NAME);
+        let key = [1u8; 32];
+
+        // When
+        store_in_file(&path, &key, DEFAULT_PROFILE, "sk-default").unwrap();
+        store_in_file(&path, &key, "groq", "gsk-groq").unwrap();
+
+        // Then
+        assert_eq!(
+            load_from_file(&path, &key, DEFAULT_PROFILE)
+                .unwrap()
+                .as_deref(),
+            Some("sk-default")
+        );
+        assert_eq!(
+            load_from_file(&path, &key, "groq").unwrap().as_deref(),
+            Some("gsk-groq")
+        );
+        assert_eq!(load_from_file(&path, &key, "other").unwrap(), None);
+    }
+
+    #[test]
+    fn remove_from_file_should_delete_file_after_last_key() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(SECRET_FILE_NAME);
+        let key = [1u8; 32];
+        store_in_file(&path, &key, DEFAULT_PROFILE, "sk-default").unwrap();
+        store_in_file(&path, &key, "groq", "gsk-groq").unwrap();
+
+        // When
+        remove_from_file(&path, &key, "groq").unwrap();
+
+        // Then
+        assert!(path.exists());
+        assert_eq!(load_from_file(&path, &key, "groq").unwrap(), None);
+
+        // When
+        remove_from_file(&path, &key, DEFAULT_PROFILE).unwrap();
+
+        // Then
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn load_from_file_should_return_none_without_file() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+
+        // When / Then
+        assert_eq!(
+            load_from_file(&tmp.path().join(SECRET_FILE_NAME), &[1u8; 32], "x").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn write_private_should_replace_file_without_leaving_temp() {
+        // Given: старый файл и временный, оставшийся после сбоя
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(SECRET_FILE_NAME);
+        fs::write(&path, b"old").unwrap();
+        fs::write(tmp.path().join("secret.enc.tmp"), b"stale").unwrap();
+
+        // When
+        write_private(&path, b"new").unwrap();
+
+        // Then
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert!(!tmp.path().join("secret.enc.tmp").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_private_should_create_owner_only_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(SECRET_FILE_NAME);
+
+        // When
+        write_private(&path, b"data").unwrap();
+
+        // Then
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn key_from_machine_id_should_depend_on_id() {
+        assert_eq!(key_from_machine_id("a"), key_from_machine_id("a"));
+        assert_ne!(key_from_machine_id("a"), key_from_machine_id("b"));
+    }
+
+    #[test]
+    fn keychain_username_should_keep_legacy_name_for_default_profile() {
+        assert_eq!(keychain_username(DEFAULT_PROFILE), USERNAME);
+        assert_eq!(keychain_username("groq"), "openai-api-key:groq");
+    }
 }
@@ -1,11 +1,12 @@
 #![allow(dead_code)]
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tracing::{info, warn};
 
-use crate::config::schema::AppConfig;
+use crate::config::migrate::{migrate, upgrade_fields};
+use crate::config::schema::{AppConfig, ConfigIssue, CURRENT_CONFIG_VERSION};
 use crate::error::{AppError, Result};
 
 /// Имя файла конфигурации.
@@ -29,14 +30,18 @@ pub fn config_dir() -> Result<PathBuf> {
 }
 
 /// Возвращает полный путь к файлу конфигурации.
-fn config_file_path() -> Result<PathBuf> {
+pub fn config_file_path() -> Result<PathBuf> {
     Ok(config_dir()?.join(CONFIG_FILE_NAME))
 }
 
 /// Загружает конфиг из JSON-файла.
 ///
 /// - Если файл не существует - возвращает дефолтный конфиг и сохраняет его.
-/// - Если файл поврежден - логирует ошибку, создает бэкап, возвращает дефолтный.
+/// - Конфиг старой версии мигрируется (`migrate::migrate`) и сохраняется;
+///   отсутствующие и невалидные поля заменяются дефолтами.
+/// - Значения вне допустимых диапазонов приводятся к допустимым (`validate_and_clamp`).
+/// - Плоские поля провайдера заполняются из активного профиля (`select_active_profile`).
+/// - Если файл не является JSON-объектом - логирует ошибку, создает бэкап, возвращает дефолтный.
 pub fn load_config() -> Result<AppConfig> {
     let path = config_file_path()?;
 
@@ -50,9 +55,21 @@ pub fn load_config() -> Result<AppConfig> {
     let content = fs::read_to_string(&path)
         .map_err(|e| AppError::Config(format!("failed to read config file {:?}: {}", path, e)))?;
 
-    match serde_json::from_str::<AppConfig>(&content) {
-        Ok(config) => {
+    let parsed = serde_json::from_str::<serde_json::Value>(&content)
+        .map_err(AppError::from)
+        .and_then(|value| {
+            let stored_version = value.get("config_version").and_then(|v| v.as_u64());
+            migrate(value).map(|config| (config, stored_version))
+        });
+
+    match parsed {
+        Ok((mut config, stored_version)) => {
             info!("Config loaded from {:?}", path);
+            config.validate_and_clamp();
+            config.select_active_profile();
+            if stored_version != Some(u64::from(config.config_version)) {
+                save_config(&config)?;
+            }
             Ok(config)
         }
         Err(e) => {
@@ -77,38 +94,145 @@ pub fn load_config() -> Result<AppConfig> {
 /// (запись во временный файл + переименование).
 pub fn save_config(config: &AppConfig) -> Result<()> {
     let path = config_file_path()?;
-    let dir = config_dir()?;
-
-    fs::create_dir_all(&dir).map_err(|e| {
-        AppError::Config(format!(
-            "failed to create config directory {:?}: {}",
-            dir, e
-        ))
-    })?;
 
     let json = serde_json::to_string_pretty(config)
         .map_err(|e| AppError::Config(format!("failed to serialize config: {}", e)))?;
+    write_atomic(&path, &json)?;
 
-    // Атомарная запись: write to temp + rename
-    let tmp_path = dir.join("config.json.tmp");
-    fs::write(&tmp_path, &json).map_err(|e| {
-        AppError::Config(format!(
-            "failed to write temp config file {:?}: {}",
-            tmp_path, e
-        ))
-    })?;
+    info!("Config saved to {:?}", path);
+    Ok(())
+}
 
-    fs::rename(&tmp_path, &path).map_err(|e| {
-        AppError::Config(format!("failed to rename temp config to {:?}: {}", path, e))
+/// Атомарно записывает файл: запись во временный `<имя>.tmp` + переименование.
+///
+/// Создает родительский каталог, если его нет. При сбое на середине записи
+/// прежнее содержимое файла остается целым.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| {
+            AppError::Config(format!(
+                "failed to create config directory {:?}: {}",
+                dir, e
+            ))
+        })?;
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, contents).map_err(|e| {
+        AppError::Config(format!("failed to write temp file {:?}: {}", tmp_path, e))
     })?;
 
-    info!("Config saved to {:?}", path);
-    Ok(())
+    fs::rename(&tmp_path, path)
+        .map_err(|e| AppError::Config(format!("failed to rename temp file to {:?}: {}", path, e)))
+}
+
+/// Поля, которые никогда не попадают в экспорт и не принимаются при импорте.
+///
+/// API-ключ хранится в OS keychain, а не в `AppConfig`; список - защита на случай,
+/// если секрет окажется в JSON (старый или отредактированный вручную файл).
+const SECRET_FIELDS: &[&str] = &["api_key", "openai_api_key"];
+
+/// Сериализует конфиг в переносимый pretty JSON без секретов.
+pub fn export_config_json(config: &AppConfig) -> Result<String> {
+    let mut value = serde_json::to_value(config)?;
+    strip_secrets(&mut value);
+    serde_json::to_string_pretty(&value)
+        .map_err(|e| AppError::Config(format!("failed to serialize config: {}", e)))
+}
+
+/// Разбирает экспортированный JSON: миграция, отбрасывание секретов и
+/// `validate_and_clamp`.
+///
+/// Конфиг более новой версии, чем поддерживается, отклоняется.
+pub fn import_config_json(json: &str) -> Result<AppConfig> {
+    let mut value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| AppError::Config(format!("invalid config JSON: {}", e)))?;
+
+    let version = value.get("config_version").and_then(|v| v.as_u64());
+    if version.is_some_and(|v| v > u64::from(CURRENT_CONFIG_VERSION)) {
+        return Err(AppError::Config(format!(
+            "config version {} is newer than supported {CURRENT_CONFIG_VERSION}",
+            version.unwrap_or_default()
+        )));
+    }
+
+    strip_secrets(&mut value);
+    let mut config = migrate(value)?;
+    config.validate_and_clamp();
+    config.select_active_profile();
+    Ok(config)
+}
+
+/// Удаляет секретные поля из JSON конфига.
+fn strip_secrets(value: &mut serde_json::Value) {
+    if let Some(fields) = value.as_object_mut() {
+        for field in SECRET_FIELDS {
+            if fields.remove(*field).is_some() {
+                warn!("Secret field {field:?} dropped from config JSON");
+            }
+        }
+    }
+}
+
+/// Проверяет файл конфига и возвращает список проблем, ничего не изменяя.
+///
+/// Сообщает об ошибках JSON, неизвестных полях и неверных типах,
+/// а затем о значениях (`AppConfig::validate`). Отсутствующие поля
+/// проблемой не считаются - для них используются дефолты.
+/// Ошибка возвращается только если файл не удалось прочитать.
+pub fn validate_config_file(path: &Path) -> Result<Vec<ConfigIssue>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::Config(format!("failed to read config file {:?}: {}", path, e)))?;
+
+    let value: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => return Ok(vec![ConfigIssue::error("", format!("invalid JSON: {e}"))]),
+    };
+    let serde_json::Value::Object(mut fields) = value else {
+        return Ok(vec![ConfigIssue::error("", "config must be a JSON object")]);
+    };
+    // Поля старых версий (например, `hotkey`) проверяются после миграции
+    upgrade_fields(&mut fields);
+
+    let defaults = serde_json::to_value(AppConfig::default())?;
+    let known = defaults
+        .as_object()
+        .expect("AppConfig serializes to an object");
+    let mut issues = Vec::new();
+    let mut valid_fields = serde_json::Map::new();
+
+    for (field, field_value) in &fields {
+        if !known.contains_key(field) {
+            issues.push(ConfigIssue::warning(
+                field,
+                "unknown field, will be ignored",
+            ));
+            continue;
+        }
+
+        // Проверяем тип каждого поля отдельно, чтобы сообщить обо всех сразу
+        let mut probe = known.clone();
+        probe.insert(field.clone(), field_value.clone());
+        match serde_json::from_value::<AppConfig>(serde_json::Value::Object(probe)) {
+            Ok(_) => {
+                valid_fields.insert(field.clone(), field_value.clone());
+            }
+            Err(e) => issues.push(ConfigIssue::error(field, format!("invalid value: {e}"))),
+        }
+    }
+
+    let config: AppConfig = serde_json::from_value(serde_json::Value::Object(valid_fields))?;
+    issues.extend(config.validate());
+
+    Ok(issues)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::schema::IssueSeverity;
     use std::fs;
     use tempfile::TempDir;
 
@@ -137,8 +261,14 @@ mod tests {
         }
         let content = fs::read_to_string(&path)
             .map_err(|e| AppError::Config(format!("read error: {}", e)))?;
-        match serde_json::from_str::<AppConfig>(&content) {
-            Ok(config) => Ok(config),
+        let parsed = serde_json::from_str::<serde_json::Value>(&content)
+            .map_err(AppError::from)
+            .and_then(migrate);
+        match parsed {
+            Ok(mut config) => {
+                config.select_active_profile();
+                Ok(config)
+            }
             Err(_) => {
                 let backup = dir.join(CONFIG_BACKUP_NAME);
                 if let Err(e) = fs::copy(&path, &backup) {
@@ -151,6 +281,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn write_atomic_should_replace_file_without_leaving_temp() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested").join("data.json");
+
+        // When
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+
+        // Then
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!tmp.path().join("nested").join("data.json.tmp").exists());
+    }
+
     #[test]
     fn load_should_create_default_when_file_missing() {
         // Given
@@ -161,8 +306,8 @@ mod tests {
         let config = load_config_from(&dir).unwrap();
 
         // Then
-        assert_eq!(config.config_version, 1);
-        assert_eq!(config.hotkey, "Ctrl+Shift+S");
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.hotkeys, vec!["Ctrl+Shift+S"]);
         assert!(dir.join(CONFIG_FILE_NAME).exists());
     }
 
@@ -172,7 +317,7 @@ mod tests {
         let tmp = TempDir::new().unwrap();
         let dir = tmp.path().join("voicedictator");
         let config = AppConfig {
-            hotkey: "Alt+R".to_string(),
+            hotkeys: vec!["Alt+R".to_string()],
             language: "ru".to_string(),
             max_recording_duration_sec: 120,
             ..Default::default()
@@ -183,7 +328,7 @@ mod tests {
         let loaded = load_config_from(&dir).unwrap();
 
         // Then
-        assert_eq!(loaded.hotkey, "Alt+R");
+        assert_eq!(loaded.hotkeys, vec!["Alt+R"]);
         assert_eq!(loaded.language, "ru");
         assert_eq!(loaded.max_recording_duration_sec, 120);
     }
@@ -200,12 +345,117 @@ mod tests {
         let config = load_config_from(&dir).unwrap();
 
         // Then - должен вернуть дефолтный конфиг
-        assert_eq!(config.config_version, 1);
-        assert_eq!(config.hotkey, "Ctrl+Shift+S");
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.hotkeys, vec!["Ctrl+Shift+S"]);
         // Бэкап должен быть создан
         assert!(dir.join(CONFIG_BACKUP_NAME).exists());
     }
 
+    #[test]
+    fn load_should_migrate_v0_config_missing_newer_fields() {
+        // Given: конфиг ранней версии без config_version и новых полей
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("voicedictator");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(CONFIG_FILE_NAME),
+            r#"{ "hotkey": "Alt+R", "language": "en", "retry_count": 4 }"#,
+        )
+        .unwrap();
+
+        // When
+        let config = load_config_from(&dir).unwrap();
+
+        // Then: пользовательские значения сохранены, бэкап не нужен
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.hotkeys, vec!["Alt+R"]);
+        assert_eq!(config.language, "en");
+        assert_eq!(config.retry_count, 4);
+        assert_eq!(config.stt_model, AppConfig::default().stt_model);
+        assert!(!dir.join(CONFIG_BACKUP_NAME).exists());
+    }
+
+    #[test]
+    fn load_should_keep_valid_fields_when_one_field_has_wrong_type() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("voicedictator");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(CONFIG_FILE_NAME),
+            r#"{ "config_version": 1, "hotkey": "Alt+R", "retry_count": "three" }"#,
+        )
+        .unwrap();
+
+        // When
+        let config = load_config_from(&dir).unwrap();
+
+        // Then
+        assert_eq!(config.hotkeys, vec!["Alt+R"]);
+        assert_eq!(config.retry_count, AppConfig::default().retry_count);
+    }
+
+    #[test]
+    fn export_config_json_should_roundtrip_through_import() {
+        // Given
+        let config = AppConfig {
+            hotkeys: vec!["Alt+R".to_string()],
+            language: "ru".to_string(),
+            ..Default::default()
+        };
+
+        // When
+        let json = export_config_json(&config).unwrap();
+        let imported = import_config_json(&json).unwrap();
+
+        // Then
+        assert_eq!(imported, config);
+        assert!(!json.contains("api_key"));
+    }
+
+    #[test]
+    fn import_config_json_should_drop_secrets_and_clamp_values() {
+        // Given
+        let json = r#"{
+            "config_version": 1,
+            "api_key": "sk-secret",
+            "hotkey": "Alt+R",
+            "max_recording_duration_sec": 0
+        }"#;
+
+        // When
+        let config = import_config_json(json).unwrap();
+
+        // Then
+        assert_eq!(config.hotkeys, vec!["Alt+R"]);
+        assert_eq!(config.max_recording_duration_sec, 10);
+        assert!(!export_config_json(&config).unwrap().contains("sk-secret"));
+    }
+
+    #[test]
+    fn import_config_json_should_migrate_older_versions() {
+        // Given: конфиг без config_version
+        let json = r#"{ "hotkey": "Alt+R" }"#;
+
+        // When
+        let config = import_config_json(json).unwrap();
+
+        // Then
+        assert_eq!(config.config_version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.hotkeys, vec!["Alt+R"]);
+    }
+
+    #[test]
+    fn import_config_json_should_reject_newer_version_and_invalid_json() {
+        // Given
+        let newer = format!(r#"{{ "config_version": {} }}"#, CURRENT_CONFIG_VERSION + 1);
+
+        // When / Then
+        assert!(import_config_json(&newer).is_err());
+        assert!(import_config_json("not json").is_err());
+        assert!(import_config_json("[1, 2]").is_err());
+    }
+
     #[test]
     fn save_should_create_directory_if_not_exists() {
         // Given
@@ -236,4 +486,85 @@ mod tests {
         assert!(content.contains("  "));
         assert!(content.contains("\"config_version\""));
     }
+
+    #[test]
+    fn validate_config_file_should_report_all_problems() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        fs::write(
+            &path,
+            r#"{
+                "hotkey": "Alt+R",
+                "language": "de",
+                "retry_count": "three",
+                "recording_mode": "hold",
+                "max_recording_duration_sec": 5,
+                "theme": "dark"
+            }"#,
+        )
+        .unwrap();
+
+        // When
+        let issues = validate_config_file(&path).unwrap();
+
+        // Then
+        let mut fields: Vec<(&str, IssueSeverity)> = issues
+            .iter()
+            .map(|i| (i.field.as_str(), i.severity))
+            .collect();
+        fields.sort_by_key(|(f, _)| *f);
+        assert_eq!(
+            fields,
+            vec![
+                ("language", IssueSeverity::Error),
+                ("max_recording_duration_sec", IssueSeverity::Error),
+                ("recording_mode", IssueSeverity::Error),
+                ("retry_count", IssueSeverity::Error),
+                ("theme", IssueSeverity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_config_file_should_not_modify_file() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        let original = r#"{"language": "xx"}"#;
+        fs::write(&path, original).unwrap();
+
+        // When
+        let _ = validate_config_file(&path).unwrap();
+
+        // Then
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn validate_config_file_should_report_invalid_json() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, "{ invalid json !!!").unwrap();
+
+        // When
+        let issues = validate_config_file(&path).unwrap();
+
+        // Then
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert!(issues[0].message.starts_with("invalid JSON"));
+    }
+
+    #[test]
+    fn validate_config_file_should_accept_partial_valid_config() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, r#"{"hotkey": "Alt+R", "language": "ru"}"#).unwrap();
+
+        // When / Then
+        assert!(validate_config_file(&path).unwrap().is_empty());
+    }
 }
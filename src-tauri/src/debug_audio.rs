@@ -0,0 +1,406 @@
+//! Сохранение отладочного аудио (`debug_save_audio`) и проверки перед ним.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::audio::{encode, preprocess, CaptureFormat};
+use crate::config::schema::{AppConfig, UploadFormat};
+use crate::config::storage;
+
+/// Каталог отладочных записей внутри `config_dir()`.
+const DEBUG_DIR_NAME: &str = "debug";
+
+/// Префикс имен файлов записей: `recording_<unix ms>_raw.wav` / `_16k.ogg` (`_16k.wav`).
+const RECORDING_PREFIX: &str = "recording_";
+
+/// Суффикс записи в формате устройства (mono, до ресемплинга).
+const RAW_SUFFIX: &str = "_raw.wav";
+
+/// Суффикс записи после препроцессинга (то, что уходит в STT) для формата отправки.
+fn processed_suffix(format: UploadFormat) -> &'static str {
+    match format {
+        UploadFormat::OggOpus => "_16k.ogg",
+        UploadFormat::Wav => "_16k.wav",
+    }
+}
+
+/// Сколько последних записей хранить; старые удаляются.
+const KEEP_RECORDINGS: usize = 10;
+
+/// Байт в мегабайте.
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Общий флаг отключения сохранения на время сессии.
+static SAVE_GATE: DebugSaveGate = DebugSaveGate::new();
+
+/// Достаточно ли свободного места для сохранения.
+///
+/// Если свободное место неизвестно (`None`), сохранение разрешено.
+pub fn has_enough_space(free_bytes: Option<u64>, min_free_mb: u32) -> bool {
+    match free_bytes {
+        Some(free) => free >= u64::from(min_free_mb) * BYTES_PER_MB,
+        None => true,
+    }
+}
+
+/// Отключает сохранение при нехватке места с однократным предупреждением.
+///
+/// После первого срабатывания сохранение выключено до перезапуска,
+/// чтобы не засорять лог ошибками записи на заполненный диск.
+pub struct DebugSaveGate {
+    disabled: AtomicBool,
+}
+
+impl DebugSaveGate {
+    pub const fn new() -> Self {
+        Self {
+            disabled: AtomicBool::new(false),
+        }
+    }
+
+    /// Возвращает `true`, если сохранять можно.
+    pub fn allow(&self, free_bytes: Option<u64>, min_free_mb: u32) -> bool {
+        if self.disabled.load(Ordering::SeqCst) {
+            return false;
+        }
+        if has_enough_space(free_bytes, min_free_mb) {
+            return true;
+        }
+
+        if !self.disabled.swap(true, Ordering::SeqCst) {
+            tracing::warn!(
+                free_mb = free_bytes.unwrap_or(0) / BYTES_PER_MB,
+                min_free_mb,
+                "low disk space, debug audio saving disabled for this session"
+            );
+        }
+        false
+    }
+}
+
+/// Проверяет место в `dir` перед записью отладочного аудио.
+pub fn can_save_debug_audio(dir: &Path, min_free_mb: u32) -> bool {
+    SAVE_GATE.allow(free_space_bytes(dir), min_free_mb)
+}
+
+/// Сохраняет запись в `config_dir()/debug/`, если включен `debug_save_audio`.
+///
+/// Пишутся два файла с меткой времени: `_raw.wav` - mono с частотой устройства
+/// (до ресемплинга, частота видна в заголовке WAV) и `_16k.ogg` (`_16k.wav`) -
+/// после препроцессинга, закодированная с `audio_upload_format` и параметрами
+/// Opus из конфига, как уходит в STT. Хранятся последние `KEEP_RECORDINGS`
+/// записей. Ошибки только логируются. Кодирование и запись блокирующие -
+/// вызывать вне async-контекста (`spawn_blocking`).
+pub fn save_debug_recording(samples: &[f32], format: &CaptureFormat, config: &AppConfig) {
+    if !config.debug_save_audio {
+        return;
+    }
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    match storage::config_dir() {
+        Ok(dir) => {
+            let dir = dir.join(DEBUG_DIR_NAME);
+            if save_recording_to(&dir, stamp, samples, format, config).is_some() {
+                prune_recordings(&dir, KEEP_RECORDINGS);
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "debug audio: config dir unavailable"),
+    }
+}
+
+/// Сохраняет запись с меткой `stamp` в `dir`. Возвращает путь к raw-файлу, если он записан.
+fn save_recording_to(
+    dir: &Path,
+    stamp: u128,
+    samples: &[f32],
+    format: &CaptureFormat,
+    config: &AppConfig,
+) -> Option<PathBuf> {
+    if !config.debug_save_audio || !can_save_debug_audio(dir, config.debug_audio_min_free_mb) {
+        return None;
+    }
+
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        tracing::warn!(error = %e, "debug audio: failed to create directory");
+        return None;
+    }
+
+    let mono = preprocess::to_mono(samples, format.channels);
+    let path = dir.join(format!("{RECORDING_PREFIX}{stamp}{RAW_SUFFIX}"));
+    if let Err(e) = encode::save_wav(&mono, format.sample_rate, &path) {
+        tracing::warn!(error = %e, "debug audio: failed to save recording");
+        return None;
+    }
+    tracing::info!(
+        path = %path.display(),
+        sample_rate = format.sample_rate,
+        channels = format.channels,
+        "debug audio saved"
+    );
+
+    let processed = preprocess::preprocess_with(
+        samples,
+        format.channels,
+        format.sample_rate,
+        &preprocess::PreprocessOptions::from_config(config),
+    );
+    let encode_config = encode::EncodeConfig::from_config(config);
+    let processed_path = dir.join(format!(
+        "{RECORDING_PREFIX}{stamp}{}",
+        processed_suffix(encode_config.format)
+    ));
+    match encode::encode_audio(&processed, preprocess::TARGET_SAMPLE_RATE, &encode_config)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| std::fs::write(&processed_path, bytes).map_err(|e| e.to_string()))
+    {
+        Ok(()) => tracing::info!(path = %processed_path.display(), "debug audio saved"),
+        Err(e) => tracing::warn!(error = %e, "debug audio: failed to save processed recording"),
+    }
+    Some(path)
+}
+
+/// Удаляет файлы записей, кроме последних `keep` (не меньше одной, по метке времени в имени).
+fn prune_recordings(dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(u128, PathBuf)> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let stamp = name
+                .strip_prefix(RECORDING_PREFIX)?
+                .split('_')
+                .next()?
+                .parse()
+                .ok()?;
+            Some((stamp, path))
+        })
+        .collect();
+
+    let mut stamps: Vec<u128> = files.iter().map(|(stamp, _)| *stamp).collect();
+    stamps.sort_unstable_by(|a, b| b.cmp(a));
+    stamps.dedup();
+    let Some(&oldest_kept) = stamps.get(keep.max(1) - 1) else {
+        return;
+    };
+
+    files.retain(|(stamp, _)| *stamp < oldest_kept);
+    for (_, path) in files {
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!(error = %e, path = %path.display(), "debug audio: failed to remove old file");
+        }
+    }
+}
+
+/// Свободное место (байт) на томе, содержащем `path` или его ближайшего
+/// существующего предка.
+#[cfg(unix)]
+pub fn free_space_bytes(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+
+    // SAFETY: c_path - валидная C-строка, stat инициализируется statvfs при rc == 0
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if rc != 0 {
+        return None;
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Свободное место (байт), доступное пользователю на томе, содержащем `path`
+/// или его ближайшего существующего предка.
+#[cfg(windows)]
+pub fn free_space_bytes(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let wide: Vec<u16> = existing
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut available: u64 = 0;
+    // SAFETY: wide - нуль-терминированная UTF-16 строка, available живет до конца вызова
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+/// На остальных платформах свободное место не проверяется.
+#[cfg(not(any(unix, windows)))]
+pub fn free_space_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = BYTES_PER_MB;
+
+    #[test]
+    fn has_enough_space_should_skip_save_below_threshold() {
+        assert!(!has_enough_space(Some(99 * MB), 100));
+    }
+
+    #[test]
+    fn has_enough_space_should_allow_save_at_or_above_threshold() {
+        assert!(has_enough_space(Some(100 * MB), 100));
+        assert!(has_enough_space(Some(5_000 * MB), 100));
+    }
+
+    #[test]
+    fn has_enough_space_should_allow_save_when_unknown() {
+        assert!(has_enough_space(None, 100));
+    }
+
+    #[test]
+    fn gate_should_stay_disabled_after_low_space() {
+        // Given
+        let gate = DebugSaveGate::new();
+
+        // When
+        let low = gate.allow(Some(10 * MB), 100);
+        let recovered = gate.allow(Some(10_000 * MB), 100);
+
+        // Then: после первого срабатывания сохранение выключено до перезапуска
+        assert!(!low);
+        assert!(!recovered);
+    }
+
+    #[test]
+    fn gate_should_allow_when_space_sufficient() {
+        let gate = DebugSaveGate::new();
+        assert!(gate.allow(Some(10_000 * MB), 100));
+        assert!(gate.allow(Some(10_000 * MB), 100));
+    }
+
+    fn stereo_format() -> CaptureFormat {
+        CaptureFormat {
+            sample_rate: 44100,
+            channels: 2,
+        }
+    }
+
+    #[test]
+    fn save_recording_should_not_write_when_flag_off() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let debug_dir = dir.path().join("debug");
+        let config = AppConfig::default();
+
+        // When
+        let saved = save_recording_to(&debug_dir, 1, &[0.1; 882], &stereo_format(), &config);
+
+        // Then
+        assert_eq!(saved, None);
+        assert!(!debug_dir.exists());
+    }
+
+    #[test]
+    fn save_recording_should_write_mono_wav_at_device_rate() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let debug_dir = dir.path().join("debug");
+        let config = AppConfig {
+            debug_save_audio: true,
+            debug_audio_min_free_mb: 0,
+            ..AppConfig::default()
+        };
+
+        // When: 441 стерео-фрейм
+        let saved = save_recording_to(&debug_dir, 42, &[0.1; 882], &stereo_format(), &config);
+
+        // Then
+        let path = saved.expect("file should be written");
+        assert_eq!(path, debug_dir.join("recording_42_raw.wav"));
+        let wav = std::fs::read(&path).unwrap();
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 44100);
+        assert_eq!(wav.len(), 44 + 441 * 2);
+        let ogg = std::fs::read(debug_dir.join("recording_42_16k.ogg")).unwrap();
+        assert_eq!(&ogg[..4], b"OggS");
+    }
+
+    #[test]
+    fn save_recording_should_encode_processed_audio_with_upload_format() {
+        // Given: отправка в WAV
+        let dir = tempfile::tempdir().unwrap();
+        let config = AppConfig {
+            debug_save_audio: true,
+            debug_audio_min_free_mb: 0,
+            audio_upload_format: UploadFormat::Wav,
+            ..AppConfig::default()
+        };
+
+        // When
+        save_recording_to(dir.path(), 7, &[0.1; 882], &stereo_format(), &config);
+
+        // Then: обработанная запись - WAV 16kHz, OGG не пишется
+        let wav = std::fs::read(dir.path().join("recording_7_16k.wav")).unwrap();
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 16000);
+        assert!(!dir.path().join("recording_7_16k.ogg").exists());
+    }
+
+    #[test]
+    fn prune_recordings_should_keep_newest_pairs() {
+        // Given: 12 записей по два файла и посторонний файл
+        let dir = tempfile::tempdir().unwrap();
+        for stamp in 1..=12 {
+            for suffix in [RAW_SUFFIX, processed_suffix(UploadFormat::OggOpus)] {
+                std::fs::write(
+                    dir.path()
+                        .join(format!("{RECORDING_PREFIX}{stamp}{suffix}")),
+                    b"x",
+                )
+                .unwrap();
+            }
+        }
+        std::fs::write(dir.path().join("notes.txt"), b"x").unwrap();
+
+        // When
+        prune_recordings(dir.path(), 10);
+
+        // Then: удалены две самые старые записи
+        let mut names: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 21);
+        assert!(names.contains(&"notes.txt".to_string()));
+        assert!(!names.contains(&"recording_1_raw.wav".to_string()));
+        assert!(!names.contains(&"recording_2_16k.ogg".to_string()));
+        assert!(names.contains(&"recording_3_raw.wav".to_string()));
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn free_space_should_be_known_for_temp_dir() {
+        // Given: несуществующий подкаталог - берется ближайший предок
+        let dir = std::env::temp_dir()
+            .join("voicedictator-missing")
+            .join("debug");
+
+        // When / Then
+        assert!(free_space_bytes(&dir).is_some());
+    }
+}
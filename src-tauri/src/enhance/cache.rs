@@ -0,0 +1,227 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Общий кэш улучшений (enhancer создается заново для каждой диктовки).
+static SHARED_CACHE: LazyLock<Arc<EnhanceCache>> =
+    LazyLock::new(|| Arc::new(EnhanceCache::new(0, Duration::ZERO)));
+
+/// Возвращает общий кэш с лимитами из конфига.
+pub fn shared_cache(capacity: usize, ttl: Duration) -> Arc<EnhanceCache> {
+    let cache = Arc::clone(&SHARED_CACHE);
+    cache.set_limits(capacity, ttl);
+    cache
+}
+
+/// Ключ кэша: результат зависит от всех входов запроса.
+///
+/// `base_url` входит в ключ, чтобы смена провайдера не отдавала чужие результаты.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnhanceCacheKey {
+    pub base_url: String,
+    pub raw: String,
+    pub model: String,
+    pub prompt: String,
+    pub language: Option<String>,
+}
+
+/// LRU-кэш улучшенных текстов с ограниченным временем жизни.
+///
+/// Повторное улучшение того же текста (retry, дубли) не тратит запрос к API.
+pub struct EnhanceCache {
+    inner: Mutex<CacheInner>,
+}
+
+struct CacheInner {
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<EnhanceCacheKey, (Instant, String)>,
+    /// Порядок использования: в начале - давно не использованные.
+    order: VecDeque<EnhanceCacheKey>,
+}
+
+impl EnhanceCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            inner: Mutex::new(CacheInner {
+                capacity,
+                ttl,
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Обновляет лимиты; лишние записи вытесняются сразу.
+    pub fn set_limits(&self, capacity: usize, ttl: Duration) {
+        let mut inner = self.inner.lock().expect("enhance cache mutex poisoned");
+        inner.capacity = capacity;
+        inner.ttl = ttl;
+        inner.evict_overflow();
+    }
+
+    /// Возвращает закэшированный результат, если он не истек.
+    pub fn get(&self, key: &EnhanceCacheKey) -> Option<String> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Сохраняет результат улучшения.
+    pub fn insert(&self, key: EnhanceCacheKey, enhanced: String) {
+        self.insert_at(key, enhanced, Instant::now());
+    }
+
+    fn get_at(&self, key: &EnhanceCacheKey, now: Instant) -> Option<String> {
+        let mut inner = self.inner.lock().expect("enhance cache mutex poisoned");
+        let ttl = inner.ttl;
+        let (stored_at, value) = inner.entries.get(key)?.clone();
+
+        if now.saturating_duration_since(stored_at) >= ttl {
+            inner.remove(key);
+            return None;
+        }
+
+        inner.touch(key);
+        Some(value)
+    }
+
+    fn insert_at(&self, key: EnhanceCacheKey, enhanced: String, now: Instant) {
+        let mut inner = self.inner.lock().expect("enhance cache mutex poisoned");
+        if inner.capacity == 0 {
+            return;
+        }
+
+        inner.remove(&key);
+        inner.order.push_back(key.clone());
+        inner.entries.insert(key, (now, enhanced));
+        inner.evict_overflow();
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .expect("enhance cache mutex poisoned")
+            .entries
+            .len()
+    }
+}
+
+impl CacheInner {
+    fn touch(&mut self, key: &EnhanceCacheKey) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(k) = self.order.remove(pos) {
+                self.order.push_back(k);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &EnhanceCacheKey) {
+        if self.entries.remove(key).is_some() {
+            self.order.retain(|k| k != key);
+        }
+    }
+
+    fn evict_overflow(&mut self) {
+        while self.entries.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(600);
+
+    fn key(raw: &str) -> EnhanceCacheKey {
+        EnhanceCacheKey {
+            base_url: "https://api.openai.com".to_string(),
+            raw: raw.to_string(),
+            model: "gpt-5-mini".to_string(),
+            prompt: "prompt".to_string(),
+            language: None,
+        }
+    }
+
+    #[test]
+    fn cache_should_return_stored_value() {
+        // Given
+        let cache = EnhanceCache::new(4, TTL);
+        cache.insert(key("hello"), "Hello.".to_string());
+
+        // When / Then
+        assert_eq!(cache.get(&key("hello")), Some("Hello.".to_string()));
+    }
+
+    #[test]
+    fn cache_should_miss_when_any_key_component_differs() {
+        // Given
+        let cache = EnhanceCache::new(4, TTL);
+        cache.insert(key("hello"), "Hello.".to_string());
+
+        // When / Then
+        let mut other_model = key("hello");
+        other_model.model = "gpt-5".to_string();
+        let mut other_prompt = key("hello");
+        other_prompt.prompt = "other".to_string();
+        let mut other_language = key("hello");
+        other_language.language = Some("ru".to_string());
+
+        assert_eq!(cache.get(&key("hello!")), None);
+        assert_eq!(cache.get(&other_model), None);
+        assert_eq!(cache.get(&other_prompt), None);
+        assert_eq!(cache.get(&other_language), None);
+    }
+
+    #[test]
+    fn cache_should_expire_entries_after_ttl() {
+        // Given
+        let cache = EnhanceCache::new(4, TTL);
+        let start = Instant::now();
+        cache.insert_at(key("hello"), "Hello.".to_string(), start);
+
+        // When
+        let before = cache.get_at(&key("hello"), start + TTL - Duration::from_secs(1));
+        let after = cache.get_at(&key("hello"), start + TTL);
+
+        // Then
+        assert_eq!(before, Some("Hello.".to_string()));
+        assert_eq!(after, None);
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn cache_should_evict_least_recently_used() {
+        // Given
+        let cache = EnhanceCache::new(2, TTL);
+        cache.insert(key("a"), "A".to_string());
+        cache.insert(key("b"), "B".to_string());
+
+        // When: "a" использован недавно, "b" вытесняется
+        assert!(cache.get(&key("a")).is_some());
+        cache.insert(key("c"), "C".to_string());
+
+        // Then
+        assert_eq!(cache.get(&key("b")), None);
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("c")).is_some());
+    }
+
+    #[test]
+    fn cache_should_be_disabled_with_zero_capacity() {
+        // Given
+        let cache = EnhanceCache::new(0, TTL);
+
+        // When
+        cache.insert(key("hello"), "Hello.".to_string());
+
+        // Then
+        assert_eq!(cache.get(&key("hello")), None);
+    }
+}
@@ -0,0 +1,163 @@
+//! Детерминированные замены по глоссарию (`glossary`) после улучшения текста.
+//!
+//! Исправляет имена и термины, которые STT и LLM стабильно пишут неверно.
+
+use super::protect::is_boundary;
+
+/// Заменяет вхождения `from` на `to` целыми словами, без учета регистра.
+///
+/// Регистр найденного фрагмента переносится на замену: "ACME" -> верхний регистр,
+/// "Acme" -> заглавная первая буква, иначе `to` вставляется как есть.
+/// Длинные фразы обрабатываются первыми; пары с пустым `from` игнорируются.
+pub fn apply_glossary(text: &str, glossary: &[(String, String)]) -> String {
+    let mut entries: Vec<(&str, &str)> = glossary
+        .iter()
+        .map(|(from, to)| (from.trim(), to.trim()))
+        .filter(|(from, _)| !from.is_empty())
+        .collect();
+    if entries.is_empty() {
+        return text.to_string();
+    }
+    entries.sort_by_key(|(from, _)| std::cmp::Reverse(from.chars().count()));
+
+    let mut result = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let rest = &text[pos..];
+        let found = entries.iter().find_map(|(from, to)| {
+            let len = match_ignore_case(rest, from)?;
+            is_boundary(text, pos, len).then_some((len, *to))
+        });
+
+        match found {
+            Some((len, to)) => {
+                result.push_str(&transfer_case(&rest[..len], to));
+                pos += len;
+            }
+            None => {
+                let c = rest.chars().next().expect("pos is within text");
+                result.push(c);
+                pos += c.len_utf8();
+            }
+        }
+    }
+
+    result
+}
+
+/// Длина в байтах префикса `text`, совпадающего с `pattern` без учета регистра.
+fn match_ignore_case(text: &str, pattern: &str) -> Option<usize> {
+    let mut text_chars = text.char_indices();
+    for p in pattern.chars() {
+        let (_, t) = text_chars.next()?;
+        if !t.to_lowercase().eq(p.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(text_chars.next().map_or(text.len(), |(i, _)| i))
+}
+
+/// Переносит регистр найденного фрагмента на замену.
+fn transfer_case(matched: &str, replacement: &str) -> String {
+    let letters: Vec<char> = matched.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return replacement.to_uppercase();
+    }
+
+    let starts_upper = matched.chars().next().is_some_and(char::is_uppercase);
+    let mut chars = replacement.chars();
+    match chars.next() {
+        Some(first) if starts_upper && first.is_lowercase() => {
+            first.to_uppercase().chain(chars).collect()
+        }
+        _ => replacement.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn apply_glossary_should_replace_whole_words() {
+        // Given
+        let entries = glossary(&[("acme", "ACME Corp")]);
+
+        // When
+        let result = apply_glossary("I work at acme, not acmeville", &entries);
+
+        // Then
+        assert_eq!(result, "I work at ACME Corp, not acmeville");
+    }
+
+    #[test]
+    fn apply_glossary_should_not_replace_inside_larger_words() {
+        // Given
+        let entries = glossary(&[("cat", "Kat")]);
+
+        // When
+        let result = apply_glossary("concatenate cats scat cat", &entries);
+
+        // Then
+        assert_eq!(result, "concatenate cats scat Kat");
+    }
+
+    #[test]
+    fn apply_glossary_should_preserve_case_of_match() {
+        // Given
+        let entries = glossary(&[("neighborstan", "neighborStan")]);
+
+        // When
+        let result = apply_glossary("neighborstan. Neighborstan! NEIGHBORSTAN?", &entries);
+
+        // Then
+        assert_eq!(result, "neighborStan. NeighborStan! NEIGHBORSTAN?");
+    }
+
+    #[test]
+    fn apply_glossary_should_handle_cyrillic_entries() {
+        // Given
+        let entries = glossary(&[("нейборстан", "НейборСтан"), ("кубер", "Kubernetes")]);
+
+        // When
+        let result = apply_glossary(
+            "Нейборстан деплоит в кубер, а не в куберне. НЕЙБОРСТАН доволен",
+            &entries,
+        );
+
+        // Then
+        assert_eq!(
+            result,
+            "НейборСтан деплоит в Kubernetes, а не в куберне. НЕЙБОРСТАН доволен"
+        );
+    }
+
+    #[test]
+    fn apply_glossary_should_prefer_longer_phrases() {
+        // Given
+        let entries = glossary(&[("acme", "Acme"), ("acme cloud", "AcmeCloud")]);
+
+        // When
+        let result = apply_glossary("deploy to acme cloud via acme", &entries);
+
+        // Then
+        assert_eq!(result, "deploy to AcmeCloud via Acme");
+    }
+
+    #[test]
+    fn apply_glossary_should_ignore_empty_entries() {
+        // Given
+        let entries = glossary(&[("", "x"), ("  ", "y")]);
+
+        // When / Then
+        assert_eq!(apply_glossary("some text", &entries), "some text");
+    }
+}
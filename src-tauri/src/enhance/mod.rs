@@ -1,7 +1,14 @@
+pub mod cache;
+pub mod glossary;
+pub mod numbers;
 pub mod openai_responses;
+pub mod protect;
 
+pub use self::glossary::apply_glossary;
 pub use self::openai_responses::OpenAiEnhancer;
 
+use serde::{Deserialize, Serialize};
+
 /// Ошибки модуля улучшения текста.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum EnhanceError {
@@ -37,6 +44,85 @@ pub trait EnhanceProvider: Send + Sync {
     ) -> impl std::future::Future<Output = Result<String>> + Send;
 }
 
+/// Стиль улучшения текста (`enhance_style`): определяет системный промпт.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnhanceStyle {
+    /// Пунктуация, грамматика, регистр - без изменения формулировок
+    #[default]
+    Default,
+    /// Деловой стиль: разговорные слова и слова-паразиты заменяются нейтральными
+    Formal,
+    /// Разговорный стиль: неформальные формулировки сохраняются
+    Casual,
+    /// Почти дословно: только явные опечатки и пробелы
+    Verbatim,
+}
+
+impl EnhanceStyle {
+    /// Допустимый диапазон отношения слов enhanced/raw для стиля.
+    ///
+    /// Для `Verbatim` диапазон не уже 0.2..2.0: исправление пробелов может склеивать
+    /// или разбивать слова, а содержательных правок модель не делает.
+    fn word_ratio_bounds(self, validation: &EnhanceValidationConfig) -> (f64, f64) {
+        let (min, max) = (validation.min_word_ratio, validation.max_word_ratio);
+        match self {
+            Self::Verbatim => (
+                min.min(VERBATIM_MIN_WORD_RATIO),
+                max.max(VERBATIM_MAX_WORD_RATIO),
+            ),
+            _ => (min, max),
+        }
+    }
+}
+
+/// Пороги проверки результата улучшения (защита от галлюцинаций).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnhanceValidationConfig {
+    /// Минимальное отношение слов enhanced/raw.
+    pub min_word_ratio: f64,
+    /// Максимальное отношение слов enhanced/raw.
+    pub max_word_ratio: f64,
+    /// Максимальная длина улучшенного текста (символов), длиннее - обрезается.
+    pub max_enhanced_chars: usize,
+}
+
+impl Default for EnhanceValidationConfig {
+    fn default() -> Self {
+        Self {
+            min_word_ratio: MIN_WORD_RATIO,
+            max_word_ratio: MAX_WORD_RATIO,
+            max_enhanced_chars: MAX_ENHANCED_CHARS,
+        }
+    }
+}
+
+impl EnhanceValidationConfig {
+    /// Пороги из конфига; некорректные значения заменяются значениями по умолчанию.
+    ///
+    /// Если диапазон отношений пуст (`min >= max`), используются обе границы по умолчанию.
+    pub fn from_config(config: &crate::config::schema::AppConfig) -> Self {
+        let defaults = Self::default();
+        let min = f64::from(config.enhance_min_word_ratio);
+        let max = f64::from(config.enhance_max_word_ratio);
+        let (min_word_ratio, max_word_ratio) =
+            if min.is_finite() && max.is_finite() && min >= 0.0 && min < max {
+                (min, max)
+            } else {
+                (defaults.min_word_ratio, defaults.max_word_ratio)
+            };
+        let max_enhanced_chars = match config.enhance_max_chars {
+            0 => defaults.max_enhanced_chars,
+            chars => chars as usize,
+        };
+        Self {
+            min_word_ratio,
+            max_word_ratio,
+            max_enhanced_chars,
+        }
+    }
+}
+
 /// Результат валидации улучшенного текста.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValidationResult {
@@ -46,20 +132,36 @@ pub enum ValidationResult {
     Fallback(String),
 }
 
-/// Максимальная допустимая длина улучшенного текста (символов).
+/// Максимальная допустимая длина улучшенного текста по умолчанию (символов).
 const MAX_ENHANCED_CHARS: usize = 5000;
 
-/// Минимальное отношение слов enhanced/raw (30%).
+/// Минимальное отношение слов enhanced/raw по умолчанию (30%).
 const MIN_WORD_RATIO: f64 = 0.3;
 
-/// Максимальное отношение слов enhanced/raw (150%).
+/// Максимальное отношение слов enhanced/raw по умолчанию (150%).
 const MAX_WORD_RATIO: f64 = 1.5;
 
+/// Минимальное отношение слов enhanced/raw для стиля `Verbatim` (20%).
+const VERBATIM_MIN_WORD_RATIO: f64 = 0.2;
+
+/// Максимальное отношение слов enhanced/raw для стиля `Verbatim` (200%).
+const VERBATIM_MAX_WORD_RATIO: f64 = 2.0;
+
 /// Проверяет результат улучшения текста на адекватность.
 ///
 /// Защита от галлюцинаций LLM: если модель выдала пустой,
 /// слишком короткий или слишком длинный текст, возвращаем исходный.
-pub fn validate_enhancement(raw: &str, enhanced: &str) -> ValidationResult {
+/// Пороги задает `validation`; границы отношения числа слов зависят еще и от `style`.
+///
+/// Если в исходном тексте есть переводы строк, структура абзацев сохраняется:
+/// обрезаются только пробелы по краям, а переводы строк в начале и в конце
+/// берутся из исходного текста.
+pub fn validate_enhancement(
+    raw: &str,
+    enhanced: &str,
+    style: EnhanceStyle,
+    validation: &EnhanceValidationConfig,
+) -> ValidationResult {
     if raw.trim().is_empty() {
         return ValidationResult::Fallback(raw.to_string());
     }
@@ -77,8 +179,9 @@ pub fn validate_enhancement(raw: &str, enhanced: &str) -> ValidationResult {
     // Для очень коротких текстов (1-2 слова) пропускаем проверку ratio
     if raw_words > 2 {
         let ratio = enhanced_words as f64 / raw_words as f64;
+        let (min_ratio, max_ratio) = style.word_ratio_bounds(validation);
 
-        if ratio < MIN_WORD_RATIO {
+        if ratio < min_ratio {
             tracing::warn!(
                 "Enhancement too short ({enhanced_words} vs {raw_words} words, ratio {ratio:.2}), \
                  probable loss of content, falling back to raw"
@@ -86,7 +189,7 @@ pub fn validate_enhancement(raw: &str, enhanced: &str) -> ValidationResult {
             return ValidationResult::Fallback(raw.to_string());
         }
 
-        if ratio > MAX_WORD_RATIO {
+        if ratio > max_ratio {
             tracing::warn!(
                 "Enhancement too long ({enhanced_words} vs {raw_words} words, ratio {ratio:.2}), \
                  probable hallucination, falling back to raw"
@@ -95,16 +198,97 @@ pub fn validate_enhancement(raw: &str, enhanced: &str) -> ValidationResult {
         }
     }
 
-    if enhanced_trimmed.len() > MAX_ENHANCED_CHARS {
-        let truncated: String = enhanced_trimmed.chars().take(MAX_ENHANCED_CHARS).collect();
+    let max_chars = validation.max_enhanced_chars;
+    if enhanced_trimmed.len() > max_chars {
+        let truncated: String = enhanced_trimmed.chars().take(max_chars).collect();
         tracing::warn!(
-            "Enhancement exceeds {MAX_ENHANCED_CHARS} chars ({}), truncating",
+            "Enhancement exceeds {max_chars} chars ({}), truncating",
             enhanced_trimmed.len()
         );
-        return ValidationResult::Ok(truncated);
+        return ValidationResult::Ok(with_outer_newlines(raw, &truncated));
     }
 
-    ValidationResult::Ok(enhanced_trimmed.to_string())
+    ValidationResult::Ok(with_outer_newlines(raw, enhanced_trimmed))
+}
+
+/// Названия языков, которые STT возвращает вместо кодов (verbose_json).
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("en", "english"),
+    ("ru", "russian"),
+    ("uk", "ukrainian"),
+    ("de", "german"),
+    ("fr", "french"),
+    ("es", "spanish"),
+];
+
+/// Нужно ли вызывать enhance для распознанного текста.
+///
+/// Пропускает API-вызов, если enhance выключен, слов меньше `enhance_min_words`
+/// или `language` не входит в `enhance_languages`. Пустой список - любой язык;
+/// неизвестный язык (auto без определения) улучшение не блокирует.
+pub fn should_enhance(
+    text: &str,
+    language: Option<&str>,
+    config: &crate::config::schema::AppConfig,
+) -> bool {
+    if !config.enhance_enabled {
+        return false;
+    }
+    if count_words(text) < config.enhance_min_words as usize {
+        tracing::debug!(
+            min_words = config.enhance_min_words,
+            "text too short, enhance skipped"
+        );
+        return false;
+    }
+    match language {
+        Some(lang) if !config.enhance_languages.is_empty() => {
+            let allowed = config
+                .enhance_languages
+                .iter()
+                .any(|allowed| same_language(allowed, lang));
+            if !allowed {
+                tracing::debug!(
+                    language = lang,
+                    "language not in enhance_languages, enhance skipped"
+                );
+            }
+            allowed
+        }
+        _ => true,
+    }
+}
+
+/// Совпадают ли языки с учетом кода и названия ("en" и "English").
+fn same_language(a: &str, b: &str) -> bool {
+    let normalize = |lang: &str| {
+        let lang = lang.trim().to_lowercase();
+        LANGUAGE_NAMES
+            .iter()
+            .find(|(_, name)| *name == lang)
+            .map(|(code, _)| code.to_string())
+            .unwrap_or(lang)
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Переносит переводы строк из начала и конца `raw` на обрезанный `text`.
+///
+/// Для однострочного `raw` возвращает `text` без изменений.
+fn with_outer_newlines(raw: &str, text: &str) -> String {
+    if !raw.contains('\n') {
+        return text.to_string();
+    }
+    let is_newline = |c: char| c == '\n' || c == '\r';
+    let leading: String = raw[..raw.len() - raw.trim_start().len()]
+        .chars()
+        .filter(|&c| is_newline(c))
+        .collect();
+    let trailing: String = raw[raw.trim_end().len()..]
+        .chars()
+        .filter(|&c| is_newline(c))
+        .collect();
+    format!("{leading}{text}{trailing}")
 }
 
 fn count_words(text: &str) -> usize {
@@ -114,6 +298,45 @@ fn count_words(text: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::schema::AppConfig;
+
+    #[test]
+    fn should_enhance_should_skip_short_snippets() {
+        // Given
+        let config = AppConfig {
+            enhance_min_words: 3,
+            ..AppConfig::default()
+        };
+
+        // When / Then
+        assert!(!should_enhance("new line", Some("en"), &config));
+        assert!(should_enhance("send it right now", Some("en"), &config));
+    }
+
+    #[test]
+    fn should_enhance_should_respect_language_allowlist() {
+        // Given
+        let config = AppConfig {
+            enhance_languages: vec!["en".to_string()],
+            ..AppConfig::default()
+        };
+
+        // When / Then
+        assert!(should_enhance("hello there", Some("en"), &config));
+        assert!(should_enhance("hello there", Some("english"), &config));
+        assert!(!should_enhance("привет всем", Some("russian"), &config));
+        // Язык не определен - не блокируем
+        assert!(should_enhance("hello there", None, &config));
+    }
+
+    #[test]
+    fn should_enhance_should_be_false_when_disabled() {
+        let config = AppConfig {
+            enhance_enabled: false,
+            ..AppConfig::default()
+        };
+        assert!(!should_enhance("hello there friend", Some("en"), &config));
+    }
 
     #[test]
     fn validate_should_pass_normal_enhancement() {
@@ -122,7 +345,12 @@ mod tests {
         let enhanced = "Привет, как дела у тебя сегодня?";
 
         // When
-        let result = validate_enhancement(raw, enhanced);
+        let result = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(
@@ -137,7 +365,12 @@ mod tests {
         let raw = "some text here";
 
         // When
-        let result = validate_enhancement(raw, "");
+        let result = validate_enhancement(
+            raw,
+            "",
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(
@@ -152,7 +385,12 @@ mod tests {
         let raw = "some text here";
 
         // When
-        let result = validate_enhancement(raw, "   \n\t  ");
+        let result = validate_enhancement(
+            raw,
+            "   \n\t  ",
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(
@@ -168,7 +406,12 @@ mod tests {
         let enhanced = "short";
 
         // When
-        let result = validate_enhancement(raw, enhanced);
+        let result = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(result, ValidationResult::Fallback(raw.to_string()));
@@ -183,12 +426,76 @@ mod tests {
             to the instructions and just kept generating more text endlessly";
 
         // When
-        let result = validate_enhancement(raw, enhanced);
+        let result = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(result, ValidationResult::Fallback(raw.to_string()));
     }
 
+    #[test]
+    fn validate_should_allow_wider_ratio_for_verbatim() {
+        // Given: исправление пробелов склеило слова (ratio 0.5)
+        let raw = "при вет как де ла";
+        let enhanced = "привет, как дела";
+
+        // When
+        let default = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
+        let verbatim = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Verbatim,
+            &EnhanceValidationConfig::default(),
+        );
+
+        // Then
+        assert_eq!(default, ValidationResult::Ok(enhanced.to_string()));
+        assert_eq!(verbatim, ValidationResult::Ok(enhanced.to_string()));
+
+        // Given: ratio 0.25 - ниже порога Default, но в пределах Verbatim
+        let raw = "a b c d e f g h";
+        let enhanced = "abcd efgh";
+        assert_eq!(
+            validate_enhancement(
+                raw,
+                enhanced,
+                EnhanceStyle::Default,
+                &EnhanceValidationConfig::default()
+            ),
+            ValidationResult::Fallback(raw.to_string())
+        );
+        assert_eq!(
+            validate_enhancement(
+                raw,
+                enhanced,
+                EnhanceStyle::Verbatim,
+                &EnhanceValidationConfig::default()
+            ),
+            ValidationResult::Ok(enhanced.to_string())
+        );
+    }
+
+    #[test]
+    fn enhance_style_should_serialize_as_snake_case() {
+        assert_eq!(
+            serde_json::to_string(&EnhanceStyle::Verbatim).unwrap(),
+            "\"verbatim\""
+        );
+        assert_eq!(
+            serde_json::from_str::<EnhanceStyle>("\"formal\"").unwrap(),
+            EnhanceStyle::Formal
+        );
+    }
+
     #[test]
     fn validate_should_truncate_very_long_text() {
         // Given
@@ -196,7 +503,12 @@ mod tests {
         let enhanced = "b ".repeat(3000);
 
         // When
-        let result = validate_enhancement(&raw, &enhanced);
+        let result = validate_enhancement(
+            &raw,
+            &enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         match result {
@@ -212,7 +524,12 @@ mod tests {
         let enhanced = "OK.";
 
         // When
-        let result = validate_enhancement(raw, enhanced);
+        let result = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(result, ValidationResult::Ok("OK.".to_string()));
@@ -221,8 +538,18 @@ mod tests {
     #[test]
     fn validate_should_fallback_on_empty_raw() {
         // Given: empty raw text should always fallback, even if enhanced is non-empty
-        let result_empty = validate_enhancement("", "some enhanced text");
-        let result_whitespace = validate_enhancement("   \n\t  ", "some enhanced text");
+        let result_empty = validate_enhancement(
+            "",
+            "some enhanced text",
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
+        let result_whitespace = validate_enhancement(
+            "   \n\t  ",
+            "some enhanced text",
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(result_empty, ValidationResult::Fallback("".to_string()));
@@ -239,7 +566,12 @@ mod tests {
         let enhanced = "  Hello world, test check.  ";
 
         // When
-        let result = validate_enhancement(raw, enhanced);
+        let result = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
 
         // Then
         assert_eq!(
@@ -248,6 +580,181 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_should_accept_ratio_within_raised_max() {
+        // Given: ratio 1.75 - выше порога по умолчанию, но в пределах 2.0
+        let raw = "one two three four";
+        let enhanced = "one, two, three and four of them";
+        let validation = EnhanceValidationConfig {
+            max_word_ratio: 2.0,
+            ..Default::default()
+        };
+
+        // When
+        let default = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
+        let raised = validate_enhancement(raw, enhanced, EnhanceStyle::Default, &validation);
+
+        // Then
+        assert_eq!(default, ValidationResult::Fallback(raw.to_string()));
+        assert_eq!(raised, ValidationResult::Ok(enhanced.to_string()));
+    }
+
+    #[test]
+    fn validate_should_fallback_below_raised_min() {
+        // Given: ratio 0.5 - в пределах порога по умолчанию, но ниже 0.6
+        let raw = "one two three four";
+        let enhanced = "one two";
+        let validation = EnhanceValidationConfig {
+            min_word_ratio: 0.6,
+            ..Default::default()
+        };
+
+        // When
+        let result = validate_enhancement(raw, enhanced, EnhanceStyle::Default, &validation);
+
+        // Then
+        assert_eq!(result, ValidationResult::Fallback(raw.to_string()));
+    }
+
+    #[test]
+    fn validate_should_truncate_at_configured_max_chars() {
+        // Given
+        let raw = "a b c d e";
+        let enhanced = "a b c d e";
+        let validation = EnhanceValidationConfig {
+            max_enhanced_chars: 5,
+            ..Default::default()
+        };
+
+        // When
+        let result = validate_enhancement(raw, enhanced, EnhanceStyle::Default, &validation);
+
+        // Then
+        assert_eq!(result, ValidationResult::Ok("a b c".to_string()));
+    }
+
+    #[test]
+    fn validate_should_keep_long_text_with_raised_max_chars() {
+        // Given
+        let raw = "word ".repeat(1500);
+        let enhanced = "word ".repeat(1500);
+        let validation = EnhanceValidationConfig {
+            max_enhanced_chars: 10_000,
+            ..Default::default()
+        };
+
+        // When
+        let result = validate_enhancement(&raw, &enhanced, EnhanceStyle::Default, &validation);
+
+        // Then
+        assert_eq!(result, ValidationResult::Ok(enhanced.trim().to_string()));
+    }
+
+    #[test]
+    fn verbatim_should_not_narrow_configured_ratio_bounds() {
+        // Given
+        let validation = EnhanceValidationConfig {
+            min_word_ratio: 0.1,
+            max_word_ratio: 3.0,
+            ..Default::default()
+        };
+
+        // When / Then
+        assert_eq!(
+            EnhanceStyle::Verbatim.word_ratio_bounds(&validation),
+            (0.1, 3.0)
+        );
+        assert_eq!(
+            EnhanceStyle::Verbatim.word_ratio_bounds(&EnhanceValidationConfig::default()),
+            (VERBATIM_MIN_WORD_RATIO, VERBATIM_MAX_WORD_RATIO)
+        );
+    }
+
+    #[test]
+    fn validation_config_from_config_should_use_config_values() {
+        // Given
+        let config = crate::config::schema::AppConfig {
+            enhance_min_word_ratio: 0.5,
+            enhance_max_word_ratio: 2.5,
+            enhance_max_chars: 20_000,
+            ..Default::default()
+        };
+
+        // When
+        let validation = EnhanceValidationConfig::from_config(&config);
+
+        // Then
+        assert_eq!(validation.min_word_ratio, 0.5);
+        assert_eq!(validation.max_word_ratio, 2.5);
+        assert_eq!(validation.max_enhanced_chars, 20_000);
+    }
+
+    #[test]
+    fn validation_config_from_config_should_replace_invalid_values() {
+        // Given: пустой диапазон и нулевой лимит
+        let config = crate::config::schema::AppConfig {
+            enhance_min_word_ratio: 2.0,
+            enhance_max_word_ratio: 1.0,
+            enhance_max_chars: 0,
+            ..Default::default()
+        };
+
+        // When
+        let validation = EnhanceValidationConfig::from_config(&config);
+
+        // Then
+        assert_eq!(validation, EnhanceValidationConfig::default());
+    }
+
+    #[test]
+    fn validate_should_preserve_paragraph_breaks() {
+        // Given
+        let raw = "первый абзац про планы\n\nвторой абзац про итоги\n";
+        let enhanced = "  Первый абзац про планы.\n\nВторой абзац про итоги.  ";
+
+        // When
+        let result = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
+
+        // Then: абзацы и завершающий перевод строки сохранены
+        assert_eq!(
+            result,
+            ValidationResult::Ok(
+                "Первый абзац про планы.\n\nВторой абзац про итоги.\n".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn validate_should_restore_leading_newlines_from_raw() {
+        // Given
+        let raw = "\nsecond line of the note\nthird line here";
+        let enhanced = "Second line of the note.\nThird line here.";
+
+        // When
+        let result = validate_enhancement(
+            raw,
+            enhanced,
+            EnhanceStyle::Default,
+            &EnhanceValidationConfig::default(),
+        );
+
+        // Then
+        assert_eq!(
+            result,
+            ValidationResult::Ok("\nSecond line of the note.\nThird line here.".to_string())
+        );
+    }
+
     #[test]
     fn count_words_should_handle_various_whitespace() {
         assert_eq!(count_words("hello world"), 2);
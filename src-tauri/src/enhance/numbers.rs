@@ -0,0 +1,614 @@
+//! Детерминированная нормализация чисел: "twenty five" <-> "25".
+//!
+//! Поддерживаются базовые количественные числительные английского
+//! и русского языков в диапазоне 0..=999 999 (именительный падеж).
+
+use crate::config::schema::NumberFormat;
+
+/// Максимальное число, которое переводится в слова.
+const MAX_WORDS_VALUE: u64 = 999_999;
+
+/// Знаки, допустимые перед числом при переводе в слова.
+const WORDS_PREFIX_CHARS: &[char] = &['(', '"', '\'', '«'];
+
+/// Знаки, допустимые после числа при переводе в слова.
+const WORDS_SUFFIX_CHARS: &[char] = &['.', ',', '!', '?', ';', ':', ')', '"', '\'', '»'];
+
+/// Язык для записи чисел словами.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberLanguage {
+    English,
+    Russian,
+}
+
+impl NumberLanguage {
+    /// Определяет язык по настройке; для "auto" - по наличию кириллицы в тексте.
+    pub fn resolve(language: &str, text: &str) -> Self {
+        match language {
+            "ru" => Self::Russian,
+            "en" => Self::English,
+            _ if text.chars().any(is_cyrillic) => Self::Russian,
+            _ => Self::English,
+        }
+    }
+}
+
+/// Применяет выбранный в конфиге формат чисел к тексту.
+pub fn normalize_numbers(text: &str, format: NumberFormat, language: &str) -> String {
+    match format {
+        NumberFormat::Keep => text.to_string(),
+        NumberFormat::Digits => to_digits(text),
+        NumberFormat::Words => to_words(text, NumberLanguage::resolve(language, text)),
+    }
+}
+
+/// Заменяет числительные словами на цифры: "twenty five" -> "25".
+///
+/// Одиночные "one"/"один" не заменяются: чаще всего это местоимение
+/// ("the blue one"), а не число.
+pub fn to_digits(text: &str) -> String {
+    let tokens = tokenize(text);
+    let mut out = String::with_capacity(text.len());
+    let mut copied_up_to = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let (end, parser) = parse_run(text, &tokens, i);
+        if end == i || is_lone_one(&parser, end - i) {
+            i += 1;
+            continue;
+        }
+
+        let first = split_affixes(&text[tokens[i].0..tokens[i].1]);
+        let last = split_affixes(&text[tokens[end - 1].0..tokens[end - 1].1]);
+        out.push_str(&text[copied_up_to..tokens[i].0]);
+        out.push_str(first.0);
+        out.push_str(&parser.value().to_string());
+        out.push_str(last.2);
+        copied_up_to = tokens[end - 1].1;
+        i = end;
+    }
+
+    out.push_str(&text[copied_up_to..]);
+    out
+}
+
+/// Заменяет целые числа цифрами на слова: "3" -> "three".
+///
+/// Не трогает дроби, время, номера с ведущими нулями и числа больше 999 999.
+pub fn to_words(text: &str, language: NumberLanguage) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut copied_up_to = 0;
+
+    for (start, end) in tokenize(text) {
+        let (prefix, core, suffix) = split_affixes(&text[start..end]);
+        let Some(value) = parse_plain_integer(core) else {
+            continue;
+        };
+        if !prefix.chars().all(|c| WORDS_PREFIX_CHARS.contains(&c))
+            || !suffix.chars().all(|c| WORDS_SUFFIX_CHARS.contains(&c))
+        {
+            continue;
+        }
+
+        let words = match language {
+            NumberLanguage::English => english_words(value),
+            NumberLanguage::Russian => russian_words(value),
+        };
+        out.push_str(&text[copied_up_to..start]);
+        out.push_str(prefix);
+        out.push_str(&words);
+        out.push_str(suffix);
+        copied_up_to = end;
+    }
+
+    out.push_str(&text[copied_up_to..]);
+    out
+}
+
+// --- Разбор текста ---
+
+/// Границы слов (по пробельным символам) в байтах.
+fn tokenize(text: &str) -> Vec<(usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (idx, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                tokens.push((s, idx));
+                start = None;
+            }
+            (false, None) => start = Some(idx),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, text.len()));
+    }
+
+    tokens
+}
+
+/// Делит слово на (ведущая пунктуация, ядро, завершающая пунктуация).
+fn split_affixes(token: &str) -> (&str, &str, &str) {
+    let core_start = token
+        .char_indices()
+        .find(|(_, c)| c.is_alphanumeric())
+        .map(|(i, _)| i)
+        .unwrap_or(token.len());
+    let core_end = token
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_alphanumeric())
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(core_start);
+
+    (
+        &token[..core_start],
+        &token[core_start..core_end],
+        &token[core_end..],
+    )
+}
+
+/// Находит максимальную цепочку числительных, начиная с токена `start`.
+///
+/// Цепочка прерывается пунктуацией между словами.
+fn parse_run(text: &str, tokens: &[(usize, usize)], start: usize) -> (usize, CardinalParser) {
+    let mut parser = CardinalParser::default();
+    let mut end = start;
+
+    while end < tokens.len() {
+        let (prefix, core, suffix) = split_affixes(&text[tokens[end].0..tokens[end].1]);
+        if core.is_empty() || (end > start && !prefix.is_empty()) {
+            break;
+        }
+
+        let mut candidate = parser.clone();
+        let accepted = core
+            .split('-')
+            .all(|word| candidate.accept(&word.to_lowercase()));
+        if !accepted {
+            break;
+        }
+
+        parser = candidate;
+        end += 1;
+        if !suffix.is_empty() {
+            break;
+        }
+    }
+
+    (end, parser)
+}
+
+fn is_lone_one(parser: &CardinalParser, run_len: usize) -> bool {
+    run_len == 1 && parser.words == 1 && parser.value() == 1
+}
+
+fn parse_plain_integer(core: &str) -> Option<u64> {
+    if core.is_empty() || !core.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if core.len() > 1 && core.starts_with('0') {
+        return None;
+    }
+    core.parse::<u64>().ok().filter(|&v| v <= MAX_WORDS_VALUE)
+}
+
+fn is_cyrillic(c: char) -> bool {
+    ('\u{0400}'..='\u{04FF}').contains(&c)
+}
+
+// --- Слова -> число ---
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Zero,
+    /// 1..9
+    Unit,
+    /// 10..19
+    Teen,
+    /// 20, 30 .. 90
+    Ten,
+    /// Английское "hundred" (множитель).
+    Hundred,
+    /// Русские "сто".."девятьсот" (готовое значение).
+    Hundreds,
+    Thousand,
+}
+
+/// Пошаговый разборщик количественного числительного.
+#[derive(Debug, Clone, Default)]
+struct CardinalParser {
+    total: u64,
+    current: u64,
+    last: Option<Kind>,
+    seen_thousand: bool,
+    words: usize,
+}
+
+impl CardinalParser {
+    /// Принимает очередное слово, если оно грамматически продолжает число.
+    fn accept(&mut self, word: &str) -> bool {
+        let Some((kind, value)) = lookup_word(word) else {
+            return false;
+        };
+
+        let allowed = match (self.last, kind) {
+            (_, Kind::Thousand) if self.seen_thousand => false,
+            (None, Kind::Hundred) => false,
+            (None, Kind::Thousand) => word == "тысяча",
+            (None, _) => true,
+            (Some(Kind::Zero), _) => false,
+            (Some(Kind::Unit), Kind::Hundred) => self.current < 10,
+            (
+                Some(Kind::Unit | Kind::Teen | Kind::Ten | Kind::Hundred | Kind::Hundreds),
+                Kind::Thousand,
+            ) => true,
+            (Some(Kind::Ten), Kind::Unit) => true,
+            (Some(Kind::Hundred | Kind::Hundreds), Kind::Unit | Kind::Teen | Kind::Ten) => true,
+            (Some(Kind::Thousand), Kind::Unit | Kind::Teen | Kind::Ten | Kind::Hundreds) => true,
+            _ => false,
+        };
+        if !allowed {
+            return false;
+        }
+
+        match kind {
+            Kind::Hundred => self.current *= 100,
+            Kind::Thousand => {
+                self.total += self.current.max(1) * 1000;
+                self.current = 0;
+                self.seen_thousand = true;
+            }
+            _ => self.current += value,
+        }
+        self.last = Some(kind);
+        self.words += 1;
+        true
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+}
+
+fn lookup_word(word: &str) -> Option<(Kind, u64)> {
+    let entry = match word {
+        "zero" | "ноль" => (Kind::Zero, 0),
+        "one" | "один" | "одна" | "одно" => (Kind::Unit, 1),
+        "two" | "два" | "две" => (Kind::Unit, 2),
+        "three" | "три" => (Kind::Unit, 3),
+        "four" | "четыре" => (Kind::Unit, 4),
+        "five" | "пять" => (Kind::Unit, 5),
+        "six" | "шесть" => (Kind::Unit, 6),
+        "seven" | "семь" => (Kind::Unit, 7),
+        "eight" | "восемь" => (Kind::Unit, 8),
+        "nine" | "девять" => (Kind::Unit, 9),
+        "ten" | "десять" => (Kind::Teen, 10),
+        "eleven" | "одиннадцать" => (Kind::Teen, 11),
+        "twelve" | "двенадцать" => (Kind::Teen, 12),
+        "thirteen" | "тринадцать" => (Kind::Teen, 13),
+        "fourteen" | "четырнадцать" => (Kind::Teen, 14),
+        "fifteen" | "пятнадцать" => (Kind::Teen, 15),
+        "sixteen" | "шестнадцать" => (Kind::Teen, 16),
+        "seventeen" | "семнадцать" => (Kind::Teen, 17),
+        "eighteen" | "восемнадцать" => (Kind::Teen, 18),
+        "nineteen" | "девятнадцать" => (Kind::Teen, 19),
+        "twenty" | "двадцать" => (Kind::Ten, 20),
+        "thirty" | "тридцать" => (Kind::Ten, 30),
+        "forty" | "сорок" => (Kind::Ten, 40),
+        "fifty" | "пятьдесят" => (Kind::Ten, 50),
+        "sixty" | "шестьдесят" => (Kind::Ten, 60),
+        "seventy" | "семьдесят" => (Kind::Ten, 70),
+        "eighty" | "восемьдесят" => (Kind::Ten, 80),
+        "ninety" | "девяносто" => (Kind::Ten, 90),
+        "hundred" => (Kind::Hundred, 100),
+        "сто" => (Kind::Hundreds, 100),
+        "двести" => (Kind::Hundreds, 200),
+        "триста" => (Kind::Hundreds, 300),
+        "четыреста" => (Kind::Hundreds, 400),
+        "пятьсот" => (Kind::Hundreds, 500),
+        "шестьсот" => (Kind::Hundreds, 600),
+        "семьсот" => (Kind::Hundreds, 700),
+        "восемьсот" => (Kind::Hundreds, 800),
+        "девятьсот" => (Kind::Hundreds, 900),
+        "thousand" | "тысяча" | "тысячи" | "тысяч" => (Kind::Thousand, 1000),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+// --- Число -> слова ---
+
+const EN_BELOW_TWENTY: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const EN_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const RU_BELOW_TWENTY: [&str; 20] = [
+    "ноль",
+    "один",
+    "два",
+    "три",
+    "четыре",
+    "пять",
+    "шесть",
+    "семь",
+    "восемь",
+    "девять",
+    "десять",
+    "одиннадцать",
+    "двенадцать",
+    "тринадцать",
+    "четырнадцать",
+    "пятнадцать",
+    "шестнадцать",
+    "семнадцать",
+    "восемнадцать",
+    "девятнадцать",
+];
+
+const RU_TENS: [&str; 10] = [
+    "",
+    "",
+    "двадцать",
+    "тридцать",
+    "сорок",
+    "пятьдесят",
+    "шестьдесят",
+    "семьдесят",
+    "восемьдесят",
+    "девяносто",
+];
+
+const RU_HUNDREDS: [&str; 10] = [
+    "",
+    "сто",
+    "двести",
+    "триста",
+    "четыреста",
+    "пятьсот",
+    "шестьсот",
+    "семьсот",
+    "восемьсот",
+    "девятьсот",
+];
+
+fn english_words(value: u64) -> String {
+    if value == 0 {
+        return EN_BELOW_TWENTY[0].to_string();
+    }
+
+    let mut parts = Vec::new();
+    let thousands = value / 1000;
+    let rest = value % 1000;
+    if thousands > 0 {
+        parts.push(format!("{} thousand", english_below_thousand(thousands)));
+    }
+    if rest > 0 {
+        parts.push(english_below_thousand(rest));
+    }
+    parts.join(" ")
+}
+
+fn english_below_thousand(value: u64) -> String {
+    let mut parts = Vec::new();
+    let hundreds = value / 100;
+    let rest = (value % 100) as usize;
+
+    if hundreds > 0 {
+        parts.push(format!("{} hundred", EN_BELOW_TWENTY[hundreds as usize]));
+    }
+    if rest >= 20 {
+        let unit = rest % 10;
+        if unit > 0 {
+            parts.push(format!("{}-{}", EN_TENS[rest / 10], EN_BELOW_TWENTY[unit]));
+        } else {
+            parts.push(EN_TENS[rest / 10].to_string());
+        }
+    } else if rest > 0 {
+        parts.push(EN_BELOW_TWENTY[rest].to_string());
+    }
+    parts.join(" ")
+}
+
+fn russian_words(value: u64) -> String {
+    if value == 0 {
+        return RU_BELOW_TWENTY[0].to_string();
+    }
+
+    let mut parts = Vec::new();
+    let thousands = value / 1000;
+    let rest = value % 1000;
+    if thousands == 1 {
+        parts.push("тысяча".to_string());
+    } else if thousands > 1 {
+        parts.push(format!(
+            "{} {}",
+            russian_below_thousand(thousands, true),
+            russian_thousand_form(thousands)
+        ));
+    }
+    if rest > 0 {
+        parts.push(russian_below_thousand(rest, false));
+    }
+    parts.join(" ")
+}
+
+/// `feminine` - женский род для единиц ("одна тысяча", "две тысячи").
+fn russian_below_thousand(value: u64, feminine: bool) -> String {
+    let mut parts = Vec::new();
+    let hundreds = (value / 100) as usize;
+    let rest = (value % 100) as usize;
+
+    if hundreds > 0 {
+        parts.push(RU_HUNDREDS[hundreds].to_string());
+    }
+    let unit = if rest >= 20 {
+        parts.push(RU_TENS[rest / 10].to_string());
+        rest % 10
+    } else {
+        rest
+    };
+    if unit > 0 {
+        let word = match (unit, feminine) {
+            (1, true) => "одна",
+            (2, true) => "две",
+            _ => RU_BELOW_TWENTY[unit],
+        };
+        parts.push(word.to_string());
+    }
+    parts.join(" ")
+}
+
+fn russian_thousand_form(thousands: u64) -> &'static str {
+    let last_two = thousands % 100;
+    if (11..=14).contains(&last_two) {
+        return "тысяч";
+    }
+    match thousands % 10 {
+        1 => "тысяча",
+        2..=4 => "тысячи",
+        _ => "тысяч",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_digits_should_convert_english_compound() {
+        assert_eq!(to_digits("I need twenty five apples"), "I need 25 apples");
+    }
+
+    #[test]
+    fn to_digits_should_convert_hyphenated_and_hundreds() {
+        assert_eq!(
+            to_digits("about one hundred twenty-three people"),
+            "about 123 people"
+        );
+        assert_eq!(to_digits("two thousand five hundred"), "2500");
+    }
+
+    #[test]
+    fn to_digits_should_convert_russian_cardinals() {
+        assert_eq!(
+            to_digits("купи двадцать пять яблок и сто двадцать три груши"),
+            "купи 25 яблок и 123 груши"
+        );
+        assert_eq!(to_digits("две тысячи двадцать четыре"), "2024");
+        assert_eq!(to_digits("тысяча сто"), "1100");
+    }
+
+    #[test]
+    fn to_digits_should_keep_surrounding_punctuation() {
+        assert_eq!(to_digits("Total: twenty five."), "Total: 25.");
+        assert_eq!(to_digits("(forty two)"), "(42)");
+    }
+
+    #[test]
+    fn to_digits_should_not_join_numbers_across_punctuation() {
+        assert_eq!(to_digits("twenty, five"), "20, 5");
+    }
+
+    #[test]
+    fn to_digits_should_split_digit_sequences_that_are_not_one_number() {
+        // "five twenty" - две разные величины, а не 25
+        assert_eq!(to_digits("five twenty"), "5 20");
+    }
+
+    #[test]
+    fn to_digits_should_keep_lone_one_and_embedded_words() {
+        assert_eq!(to_digits("the blue one"), "the blue one");
+        assert_eq!(to_digits("someone often"), "someone often");
+    }
+
+    #[test]
+    fn to_words_should_convert_english() {
+        assert_eq!(
+            to_words("buy 3 apples", NumberLanguage::English),
+            "buy three apples"
+        );
+        assert_eq!(to_words("25.", NumberLanguage::English), "twenty-five.");
+        assert_eq!(
+            to_words("1205", NumberLanguage::English),
+            "one thousand two hundred five"
+        );
+    }
+
+    #[test]
+    fn to_words_should_convert_russian_with_thousand_forms() {
+        assert_eq!(to_words("3 яблока", NumberLanguage::Russian), "три яблока");
+        assert_eq!(
+            to_words("2024", NumberLanguage::Russian),
+            "две тысячи двадцать четыре"
+        );
+        assert_eq!(to_words("1000", NumberLanguage::Russian), "тысяча");
+        assert_eq!(
+            to_words("21000", NumberLanguage::Russian),
+            "двадцать одна тысяча"
+        );
+        assert_eq!(
+            to_words("11000", NumberLanguage::Russian),
+            "одиннадцать тысяч"
+        );
+    }
+
+    #[test]
+    fn to_words_should_skip_non_plain_integers() {
+        let text = "at 10:30 pay $5, version 3.5, code 007, 5% or 3rd, 1000000";
+        assert_eq!(to_words(text, NumberLanguage::English), text);
+    }
+
+    #[test]
+    fn words_and_digits_should_roundtrip() {
+        for value in [0, 7, 13, 40, 99, 100, 101, 999, 1001, 45_678, 999_999] {
+            let en = english_words(value);
+            let ru = russian_words(value);
+            assert_eq!(to_digits(&en), value.to_string(), "en: {en}");
+            assert_eq!(to_digits(&ru), value.to_string(), "ru: {ru}");
+        }
+    }
+
+    #[test]
+    fn normalize_numbers_should_respect_format() {
+        assert_eq!(
+            normalize_numbers("twenty five", NumberFormat::Keep, "auto"),
+            "twenty five"
+        );
+        assert_eq!(
+            normalize_numbers("twenty five", NumberFormat::Digits, "auto"),
+            "25"
+        );
+        assert_eq!(normalize_numbers("3", NumberFormat::Words, "en"), "three");
+        assert_eq!(
+            normalize_numbers("купи 3 яблока", NumberFormat::Words, "auto"),
+            "купи три яблока"
+        );
+    }
+}
@@ -1,10 +1,18 @@
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use reqwest::header;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 
-use super::{validate_enhancement, EnhanceError, EnhanceProvider, Result, ValidationResult};
+use super::cache::{self, EnhanceCache, EnhanceCacheKey};
+use super::{
+    validate_enhancement, EnhanceError, EnhanceProvider, EnhanceStyle, EnhanceValidationConfig,
+    Result, ValidationResult,
+};
+use crate::backoff::Jitter;
+use crate::config::schema::EnhanceApiFlavor;
+use crate::redact;
 
 const USER_AGENT: &str = "VoiceDictator/0.1.0";
 
@@ -22,6 +30,10 @@ const REASONING_EFFORT: &str = "minimal";
 /// "low" - сжатый ответ без пояснений.
 const TEXT_VERBOSITY: &str = "low";
 
+/// Минимальная доля слов исходного текста, при которой оборванный поток
+/// считается достаточно полным, чтобы вернуть полученную часть.
+const MIN_PARTIAL_STREAM_RATIO: f64 = 0.8;
+
 const SYSTEM_PROMPT: &str = "\
 You are a text post-processor. Fix punctuation, grammar, and normalize \
 spacing/capitalization in the following dictated text. Do NOT change meaning, \
@@ -34,10 +46,58 @@ Fix punctuation, grammar, and normalize spacing/capitalization. \
 Do NOT change meaning, do NOT add facts, do NOT rephrase, \
 do NOT shorten or expand. Return only the corrected text, nothing else.";
 
+/// Добавляется к промпту, если в исходном тексте есть переводы строк.
+const PRESERVE_PARAGRAPHS_PROMPT: &str = " \
+The text contains line breaks: preserve every paragraph break and line break \
+exactly where it is, do NOT merge paragraphs.";
+
+/// `EnhanceStyle::Formal`: деловой тон, без разговорных слов и слов-паразитов.
+const FORMAL_PROMPT: &str = "\
+You are a text post-processor. Fix punctuation, grammar, and normalize \
+spacing/capitalization in the following dictated text, and render it in a \
+formal, professional register: replace colloquialisms with neutral wording \
+and drop filler words. Do NOT change meaning, do NOT add facts. \
+Return only the corrected text, nothing else.";
+
+const FORMAL_PROMPT_WITH_LANG: &str = "\
+You are a text post-processor. The text is dictated in {lang}. \
+Fix punctuation, grammar, and normalize spacing/capitalization, and render it \
+in a formal, professional register: replace colloquialisms with neutral wording \
+and drop filler words. Do NOT change meaning, do NOT add facts. \
+Return only the corrected text, nothing else.";
+
+/// `EnhanceStyle::Casual`: разговорный тон, неформальные слова сохраняются.
+const CASUAL_PROMPT: &str = "\
+You are a text post-processor. Fix punctuation and obvious grammar mistakes \
+in the following dictated text, keeping its casual, conversational tone: \
+preserve informal wording, slang and contractions. Do NOT change meaning, \
+do NOT add facts, do NOT make it formal. Return only the corrected text, nothing else.";
+
+const CASUAL_PROMPT_WITH_LANG: &str = "\
+You are a text post-processor. The text is dictated in {lang}. \
+Fix punctuation and obvious grammar mistakes, keeping its casual, \
+conversational tone: preserve informal wording, slang and contractions. \
+Do NOT change meaning, do NOT add facts, do NOT make it formal. \
+Return only the corrected text, nothing else.";
+
+/// `EnhanceStyle::Verbatim`: только явные опечатки и пробелы.
+const VERBATIM_PROMPT: &str = "\
+You are a text post-processor. Reproduce the following dictated text verbatim, \
+fixing only obvious typos and spacing. Do NOT change wording, word order, \
+grammar or punctuation style, do NOT remove filler words. \
+Return only the text, nothing else.";
+
+const VERBATIM_PROMPT_WITH_LANG: &str = "\
+You are a text post-processor. The text is dictated in {lang}. \
+Reproduce it verbatim, fixing only obvious typos and spacing. \
+Do NOT change wording, word order, grammar or punctuation style, \
+do NOT remove filler words. Return only the text, nothing else.";
+
 /// Клиент улучшения текста через OpenAI Responses API.
 ///
 /// Выполняет `POST /v1/responses` с системным промптом для пост-обработки текста.
-/// Поддерживает retry с exponential backoff и обработку rate limiting (429).
+/// Для серверов без Responses API (`EnhanceApiFlavor::Chat`) - `POST /v1/chat/completions`.
+/// Поддерживает retry с exponential backoff (с jitter ±25%) и обработку rate limiting (429).
 pub struct OpenAiEnhancer {
     client: reqwest::Client,
     base_url: String,
@@ -45,6 +105,13 @@ pub struct OpenAiEnhancer {
     model: String,
     retry_count: u32,
     read_timeout: Duration,
+    cache: Option<Arc<EnhanceCache>>,
+    style: EnhanceStyle,
+    flavor: EnhanceApiFlavor,
+    streaming: bool,
+    validation: EnhanceValidationConfig,
+    jitter: Jitter,
+    retry_budget: Option<Duration>,
 }
 
 #[derive(Serialize)]
@@ -54,6 +121,8 @@ struct ResponsesRequest {
     input: String,
     reasoning: ReasoningParam,
     text: TextParam,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Serialize)]
@@ -73,6 +142,48 @@ struct ResponsesResponse {
     usage: Option<UsageInfo>,
 }
 
+#[derive(Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatChoiceMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatChoiceMessage {
+    /// Текст ответа; `null` у ответов без текста (например, tool calls).
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    prompt_tokens: u32,
+    #[serde(default)]
+    completion_tokens: u32,
+}
+
 #[derive(Deserialize)]
 struct UsageInfo {
     #[serde(default)]
@@ -142,33 +253,159 @@ impl OpenAiEnhancer {
             model: model.to_string(),
             retry_count,
             read_timeout,
+            cache: None,
+            style: EnhanceStyle::default(),
+            flavor: EnhanceApiFlavor::default(),
+            streaming: false,
+            validation: EnhanceValidationConfig::default(),
+            jitter: Jitter::from_time(),
+            retry_budget: None,
         })
     }
 
+    /// Включает кэширование результатов улучшения.
+    pub fn with_cache(mut self, cache: Arc<EnhanceCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Задает стиль улучшения (системный промпт и границы валидации).
+    pub fn with_style(mut self, style: EnhanceStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Задает API запроса: Responses или Chat Completions.
+    pub fn with_api_flavor(mut self, flavor: EnhanceApiFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    /// Задает пороги проверки результата улучшения.
+    pub fn with_validation(mut self, validation: EnhanceValidationConfig) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Включает потоковый (SSE) режим ответа.
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Ограничивает общее время повторов: если очередное ожидание (backoff
+    /// или Retry-After) выходит за лимит, сразу возвращается сырой текст.
+    pub fn with_retry_budget(mut self, budget: Option<Duration>) -> Self {
+        self.retry_budget = budget;
+        self
+    }
+
     /// Создает клиент из AppConfig и API-ключа.
+    ///
+    /// Endpoint и модель берутся из активного профиля провайдера.
+    /// Стиль берется из `enhance_style`, API - из `enhance_api_flavor`,
+    /// потоковый режим - из `enhance_streaming`, пороги проверки - из `enhance_*_ratio`
+    /// и `enhance_max_chars`, лимит времени повторов - из `enhance_retry_budget_sec`.
+    /// При `enhance_cache_size > 0` использует общий кэш результатов.
     pub fn from_config(config: &crate::config::schema::AppConfig, api_key: &str) -> Result<Self> {
-        Self::new(
-            &config.api_base_url,
+        let profile = config.active_provider();
+        let enhancer = Self::new(
+            &profile.api_base_url,
             api_key,
-            &config.enhance_model,
+            &profile.enhance_model,
             Duration::from_secs(config.connect_timeout_sec as u64),
             Duration::from_secs(config.read_timeout_enhance_sec as u64),
             config.retry_count,
-        )
+        )?
+        .with_style(config.enhance_style)
+        .with_api_flavor(config.enhance_api_flavor)
+        .with_streaming(config.enhance_streaming)
+        .with_validation(EnhanceValidationConfig::from_config(config))
+        .with_retry_budget(
+            (config.enhance_retry_budget_sec > 0)
+                .then(|| Duration::from_secs(u64::from(config.enhance_retry_budget_sec))),
+        );
+
+        if config.enhance_cache_size == 0 {
+            return Ok(enhancer);
+        }
+        Ok(enhancer.with_cache(cache::shared_cache(
+            config.enhance_cache_size as usize,
+            Duration::from_secs(u64::from(config.enhance_cache_ttl_sec)),
+        )))
     }
 
     /// Улучшение текста с retry и rate limiting.
     async fn do_enhance(&self, raw_text: &str, language: Option<&str>) -> Result<String> {
-        let url = format!("{}/v1/responses", self.base_url);
-        let instructions = build_instructions(language);
+        self.enhance_with_retry(raw_text, language, false).await
+    }
+
+    /// Потоковый вариант `do_enhance`: ответ читается как SSE по мере генерации.
+    ///
+    /// Валидация выполняется по собранному целиком тексту.
+    async fn do_enhance_streaming(&self, raw_text: &str, language: Option<&str>) -> Result<String> {
+        self.enhance_with_retry(raw_text, language, true).await
+    }
+
+    /// Общий цикл retry, rate limiting, кэша и валидации для обоих режимов.
+    async fn enhance_with_retry(
+        &self,
+        raw_text: &str,
+        language: Option<&str>,
+        stream: bool,
+    ) -> Result<String> {
+        let url = match self.flavor {
+            EnhanceApiFlavor::Responses => format!("{}/v1/responses", self.base_url),
+            EnhanceApiFlavor::Chat => format!("{}/v1/chat/completions", self.base_url),
+        };
+        let mut instructions = build_instructions(language, self.style);
+        if raw_text.contains('\n') {
+            instructions.push_str(PRESERVE_PARAGRAPHS_PROMPT);
+        }
+        let cache_key = EnhanceCacheKey {
+            base_url: self.base_url.clone(),
+            raw: raw_text.to_string(),
+            model: self.model.clone(),
+            prompt: instructions.clone(),
+            language: language.map(str::to_string),
+        };
+        if let Some(cached) = self.cache.as_ref().and_then(|c| c.get(&cache_key)) {
+            tracing::info!("Enhance cache hit, skipping API request");
+            return Ok(cached);
+        }
+
         let mut retries_left = self.retry_count;
         let mut rate_limit_retries: u32 = 0;
+        let deadline = self.retry_budget.map(|budget| Instant::now() + budget);
 
         loop {
-            match self.send_request(&url, &instructions, raw_text).await {
+            let result = match (stream, self.flavor) {
+                (true, _) => {
+                    self.send_streaming_request(&url, &instructions, raw_text)
+                        .await
+                }
+                (false, EnhanceApiFlavor::Responses) => {
+                    self.send_request(&url, &instructions, raw_text).await
+                }
+                (false, EnhanceApiFlavor::Chat) => {
+                    self.send_chat_request(&url, &instructions, raw_text).await
+                }
+            };
+            match result {
                 Ok(enhanced) => {
-                    return match validate_enhancement(raw_text, &enhanced) {
-                        ValidationResult::Ok(text) | ValidationResult::Fallback(text) => Ok(text),
+                    return match validate_enhancement(
+                        raw_text,
+                        &enhanced,
+                        self.style,
+                        &self.validation,
+                    ) {
+                        ValidationResult::Ok(text) => {
+                            if let Some(cache) = &self.cache {
+                                cache.insert(cache_key, text.clone());
+                            }
+                            Ok(text)
+                        }
+                        ValidationResult::Fallback(text) => Ok(text),
                     };
                 }
                 Err(EnhanceError::RateLimited { retry_after_sec }) => {
@@ -177,11 +414,19 @@ impl OpenAiEnhancer {
                         tracing::warn!("Enhance rate limit retries exhausted, returning raw text");
                         return Ok(raw_text.to_string());
                     }
+                    let wait = Duration::from_secs(retry_after_sec);
+                    if exceeds_deadline(deadline, wait) {
+                        tracing::warn!(
+                            "Enhance retry budget exhausted (rate limited for {retry_after_sec}s), \
+                             returning raw text"
+                        );
+                        return Ok(raw_text.to_string());
+                    }
                     tracing::warn!(
                         "API rate limited, waiting {retry_after_sec}s \
                          (attempt {rate_limit_retries}/{MAX_RATE_LIMIT_RETRIES})"
                     );
-                    tokio::time::sleep(Duration::from_secs(retry_after_sec)).await;
+                    tokio::time::sleep(wait).await;
                     continue;
                 }
                 Err(e) if !Self::is_retryable(&e) => {
@@ -201,16 +446,18 @@ impl OpenAiEnhancer {
                         return Ok(raw_text.to_string());
                     }
                     let attempt = self.retry_count - retries_left;
-                    let backoff_sec = 1u64
-                        .checked_shl(attempt)
-                        .unwrap_or(MAX_BACKOFF_SEC)
-                        .min(MAX_BACKOFF_SEC);
+                    let backoff = self.jitter.backoff(attempt, MAX_BACKOFF_SEC);
+                    if exceeds_deadline(deadline, backoff) {
+                        tracing::warn!("Enhance retry budget exhausted: {e}, returning raw text");
+                        return Ok(raw_text.to_string());
+                    }
                     tracing::warn!(
-                        "Enhance request failed (retry {}/{}), backoff {backoff_sec}s: {e}",
+                        "Enhance request failed (retry {}/{}), backoff {:.2}s: {e}",
                         attempt + 1,
-                        self.retry_count
+                        self.retry_count,
+                        backoff.as_secs_f32()
                     );
-                    tokio::time::sleep(Duration::from_secs(backoff_sec)).await;
+                    tokio::time::sleep(backoff).await;
                     retries_left -= 1;
                 }
             }
@@ -232,63 +479,10 @@ impl OpenAiEnhancer {
 
     /// Одиночный HTTP-запрос к Responses API.
     async fn send_request(&self, url: &str, instructions: &str, input: &str) -> Result<String> {
-        let body = ResponsesRequest {
-            model: self.model.clone(),
-            instructions: instructions.to_string(),
-            input: input.to_string(),
-            reasoning: ReasoningParam {
-                effort: REASONING_EFFORT.to_string(),
-            },
-            text: TextParam {
-                verbosity: TEXT_VERBOSITY.to_string(),
-            },
-        };
-
-        let response = self
-            .client
-            .post(url)
-            .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
-            .header(header::CONTENT_TYPE, "application/json")
-            .timeout(self.read_timeout)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    EnhanceError::Timeout
-                } else {
-                    EnhanceError::Network(e.to_string())
-                }
-            })?;
-
+        let body = self.responses_body(instructions, input, false);
+        let response = self.post_json(url, &body).await?;
         let status = response.status();
-
-        // Обработка статусов дублирует stt/openai.rs - осознанное решение:
-        // модули используют разные Error-типы и могут разойтись по логике.
-        if status == StatusCode::UNAUTHORIZED {
-            return Err(EnhanceError::AuthFailed);
-        }
-
-        if status == StatusCode::TOO_MANY_REQUESTS {
-            let retry_after = response
-                .headers()
-                .get(header::RETRY_AFTER)
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse::<u64>().ok())
-                .unwrap_or(5)
-                .clamp(1, 60);
-            return Err(EnhanceError::RateLimited {
-                retry_after_sec: retry_after,
-            });
-        }
-
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            return Err(EnhanceError::ApiError {
-                status: status.as_u16(),
-                message: body,
-            });
-        }
+        let response = self.check_status(response).await?;
 
         let body_text = response
             .text()
@@ -362,123 +556,489 @@ impl OpenAiEnhancer {
 
         extract_output_text(&resp)
     }
-}
 
-impl EnhanceProvider for OpenAiEnhancer {
-    async fn enhance(&self, raw_text: &str, language: Option<&str>) -> Result<String> {
-        self.do_enhance(raw_text, language).await
-    }
-}
+    /// Одиночный HTTP-запрос к Chat Completions API.
+    async fn send_chat_request(
+        &self,
+        url: &str,
+        instructions: &str,
+        input: &str,
+    ) -> Result<String> {
+        let body = self.chat_body(instructions, input, false);
 
-/// Формирует системный промпт с учетом языка.
-fn build_instructions(language: Option<&str>) -> String {
-    match language {
-        Some(lang) if lang != "auto" => SYSTEM_PROMPT_WITH_LANG.replace("{lang}", lang),
-        _ => SYSTEM_PROMPT.to_string(),
-    }
-}
+        let response = self.post_json(url, &body).await?;
+        let status = response.status();
+        let response = self.check_status(response).await?;
 
-/// Извлекает текст из ответа Responses API.
-///
-/// Берёт только items с `type == "message"` и content-блоки с `type == "output_text"`,
-/// чтобы не включать reasoning-traces и служебные элементы в финальный текст.
-fn extract_output_text(resp: &ResponsesResponse) -> Result<String> {
-    let text: String = resp
-        .output
-        .iter()
-        .filter(|item| item.r#type.is_empty() || item.r#type == "message")
-        .flat_map(|item| item.content.iter())
-        .filter(|block| block.r#type.is_empty() || block.r#type == "output_text")
-        .map(|block| block.text.as_str())
-        .collect();
+        let body_text = response
+            .text()
+            .await
+            .map_err(|e| EnhanceError::InvalidResponse(e.to_string()))?;
 
-    if text.trim().is_empty() {
-        return Err(EnhanceError::InvalidResponse(
-            "empty output text in response".to_string(),
-        ));
-    }
+        tracing::debug!(status = %status, "enhance chat API response received");
 
-    Ok(text)
-}
+        let resp: ChatResponse = serde_json::from_str(&body_text)
+            .map_err(|e| EnhanceError::InvalidResponse(e.to_string()))?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        if let Some(usage) = &resp.usage {
+            tracing::info!(
+                input_tokens = usage.prompt_tokens,
+                output_tokens = usage.completion_tokens,
+                "enhance API usage"
+            );
+        }
 
-    #[test]
-    fn client_should_be_created_with_valid_params() {
-        let client = OpenAiEnhancer::new(
-            "https://api.openai.com",
-            "test-key",
-            "gpt-5-mini",
-            Duration::from_secs(5),
-            Duration::from_secs(15),
-            3,
-        );
-        assert!(client.is_ok());
+        let text = resp
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .unwrap_or_default();
+        if text.trim().is_empty() {
+            return Err(EnhanceError::InvalidResponse(
+                "empty message content in chat response".to_string(),
+            ));
+        }
+        Ok(text)
     }
 
-    #[test]
-    fn client_should_trim_trailing_slash_from_base_url() {
-        // Given / When
-        let client = OpenAiEnhancer::new(
-            "https://api.openai.com/",
-            "test-key",
-            "gpt-5-mini",
-            Duration::from_secs(5),
-            Duration::from_secs(15),
-            3,
-        )
-        .unwrap();
+    /// Одиночный потоковый запрос: `"stream": true`, ответ читается как SSE.
+    ///
+    /// Обрыв потока до получения текста - retryable `Network`; если текст уже
+    /// пришел и покрывает большую часть входа, возвращается полученная часть.
+    async fn send_streaming_request(
+        &self,
+        url: &str,
+        instructions: &str,
+        input: &str,
+    ) -> Result<String> {
+        let response = match self.flavor {
+            EnhanceApiFlavor::Responses => {
+                let body = self.responses_body(instructions, input, true);
+                self.post_json(url, &body).await?
+            }
+            EnhanceApiFlavor::Chat => {
+                let body = self.chat_body(instructions, input, true);
+                self.post_json(url, &body).await?
+            }
+        };
+        let mut response = self.check_status(response).await?;
 
-        // Then
-        assert_eq!(client.base_url, "https://api.openai.com");
-    }
+        let mut parser = SseParser::default();
+        let mut text = String::new();
+        let mut completed = false;
+        let mut interruption = None;
 
-    #[test]
-    fn client_should_store_model_from_constructor() {
-        // Given / When
-        let client = OpenAiEnhancer::new(
-            "https://api.openai.com",
-            "key",
-            "my-custom-model",
-            Duration::from_secs(5),
-            Duration::from_secs(15),
-            2,
-        )
-        .unwrap();
+        while !completed {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    interruption = Some(e);
+                    break;
+                }
+            };
+            for data in parser.push(&chunk) {
+                match parse_stream_event(self.flavor, &data)? {
+                    StreamEvent::Delta(delta) => text.push_str(&delta),
+                    StreamEvent::Done => completed = true,
+                    StreamEvent::Ignored => {}
+                }
+            }
+        }
 
-        // Then
-        assert_eq!(client.model, "my-custom-model");
-    }
+        if completed {
+            tracing::debug!(chars = text.len(), "enhance stream completed");
+            if text.trim().is_empty() {
+                return Err(EnhanceError::InvalidResponse(
+                    "empty output text in stream".to_string(),
+                ));
+            }
+            return Ok(text);
+        }
 
-    #[test]
-    fn from_config_should_use_config_values() {
-        // Given
-        let config = crate::config::schema::AppConfig {
-            api_base_url: "https://custom.api.com".to_string(),
-            enhance_model: "custom-enhance".to_string(),
-            connect_timeout_sec: 10,
-            read_timeout_enhance_sec: 30,
-            retry_count: 5,
-            ..Default::default()
-        };
+        if is_substantial_partial(&text, input) {
+            tracing::warn!(
+                chars = text.len(),
+                "enhance stream interrupted, using partial text"
+            );
+            return Ok(text);
+        }
 
-        // When
-        let client = OpenAiEnhancer::from_config(&config, "api-key-123").unwrap();
+        match interruption {
+            Some(e) if e.is_timeout() && text.is_empty() => Err(EnhanceError::Timeout),
+            Some(e) => Err(EnhanceError::Network(format!("stream interrupted: {e}"))),
+            None => Err(EnhanceError::Network(
+                "stream ended before completion".to_string(),
+            )),
+        }
+    }
 
-        // Then
-        assert_eq!(client.base_url, "https://custom.api.com");
-        assert_eq!(client.model, "custom-enhance");
-        assert_eq!(client.retry_count, 5);
-        assert_eq!(client.read_timeout, Duration::from_secs(30));
+    /// Тело запроса к Responses API.
+    fn responses_body(&self, instructions: &str, input: &str, stream: bool) -> ResponsesRequest {
+        ResponsesRequest {
+            model: self.model.clone(),
+            instructions: instructions.to_string(),
+            input: input.to_string(),
+            reasoning: ReasoningParam {
+                effort: REASONING_EFFORT.to_string(),
+            },
+            text: TextParam {
+                verbosity: TEXT_VERBOSITY.to_string(),
+            },
+            stream,
+        }
     }
 
-    #[test]
-    fn is_retryable_should_return_true_for_network_error() {
-        assert!(OpenAiEnhancer::is_retryable(&EnhanceError::Network(
-            "err".into()
-        )));
+    /// Тело запроса к Chat Completions API: системный промпт + текст пользователя.
+    fn chat_body(&self, instructions: &str, input: &str, stream: bool) -> ChatRequest {
+        ChatRequest {
+            model: self.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: instructions.to_string(),
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: input.to_string(),
+                },
+            ],
+            stream,
+        }
+    }
+
+    /// POST JSON-тела с авторизацией и таймаутом ответа.
+    async fn post_json<T: Serialize>(&self, url: &str, body: &T) -> Result<reqwest::Response> {
+        self.client
+            .post(url)
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
+            .header(header::CONTENT_TYPE, "application/json")
+            .timeout(self.read_timeout)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    EnhanceError::Timeout
+                } else {
+                    EnhanceError::Network(e.to_string())
+                }
+            })
+    }
+
+    /// Преобразует неуспешный HTTP-статус в `EnhanceError`.
+    /// Тело ответа попадает в ошибку без API-ключа и с ограничением длины.
+    async fn check_status(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        let status = response.status();
+
+        // Обработка статусов дублирует stt/openai.rs - осознанное решение:
+        // модули используют разные Error-типы и могут разойтись по логике.
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(EnhanceError::AuthFailed);
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(5)
+                .clamp(1, 60);
+            return Err(EnhanceError::RateLimited {
+                retry_after_sec: retry_after,
+            });
+        }
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(EnhanceError::ApiError {
+                status: status.as_u16(),
+                message: redact::api_error_message(&body, &self.api_key),
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+impl EnhanceProvider for OpenAiEnhancer {
+    async fn enhance(&self, raw_text: &str, language: Option<&str>) -> Result<String> {
+        if self.streaming {
+            self.do_enhance_streaming(raw_text, language).await
+        } else {
+            self.do_enhance(raw_text, language).await
+        }
+    }
+}
+
+/// Закончится ли ожидание `wait` после `deadline` (`None` - лимита нет).
+fn exceeds_deadline(deadline: Option<Instant>, wait: Duration) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() + wait > deadline)
+}
+
+/// Формирует системный промпт с учетом стиля и языка.
+fn build_instructions(language: Option<&str>, style: EnhanceStyle) -> String {
+    let (prompt, prompt_with_lang) = match style {
+        EnhanceStyle::Default => (SYSTEM_PROMPT, SYSTEM_PROMPT_WITH_LANG),
+        EnhanceStyle::Formal => (FORMAL_PROMPT, FORMAL_PROMPT_WITH_LANG),
+        EnhanceStyle::Casual => (CASUAL_PROMPT, CASUAL_PROMPT_WITH_LANG),
+        EnhanceStyle::Verbatim => (VERBATIM_PROMPT, VERBATIM_PROMPT_WITH_LANG),
+    };
+    match language {
+        Some(lang) if lang != "auto" => prompt_with_lang.replace("{lang}", lang),
+        _ => prompt.to_string(),
+    }
+}
+
+/// Событие потокового ответа, значимое для сборки текста.
+#[derive(Debug, PartialEq)]
+enum StreamEvent {
+    /// Очередной фрагмент текста.
+    Delta(String),
+    /// Сервер сообщил о завершении ответа.
+    Done,
+    /// Служебное событие без текста.
+    Ignored,
+}
+
+/// Событие потока Responses API (`response.output_text.delta` и др.).
+#[derive(Deserialize)]
+struct ResponsesStreamEvent {
+    #[serde(default)]
+    r#type: String,
+    #[serde(default)]
+    delta: String,
+}
+
+/// Чанк потока Chat Completions API.
+#[derive(Deserialize)]
+struct ChatStreamChunk {
+    #[serde(default)]
+    choices: Vec<ChatStreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatStreamChoice {
+    #[serde(default)]
+    delta: ChatStreamDelta,
+}
+
+#[derive(Default, Deserialize)]
+struct ChatStreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// Инкрементальный разбор SSE: копит байты до конца строки и отдает
+/// поле `data` каждого завершенного (пустой строкой) события.
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    data: String,
+}
+
+impl SseParser {
+    /// Добавляет очередной кусок потока и возвращает `data` завершенных событий.
+    ///
+    /// Строки декодируются только целиком, поэтому UTF-8 символ, разрезанный
+    /// между чанками, не портится.
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(std::mem::take(&mut self.data));
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value.strip_prefix(' ').unwrap_or(value));
+            }
+        }
+
+        events
+    }
+}
+
+/// Разбирает `data` одного SSE-события с учетом API.
+fn parse_stream_event(flavor: EnhanceApiFlavor, data: &str) -> Result<StreamEvent> {
+    match flavor {
+        EnhanceApiFlavor::Responses => {
+            let event: ResponsesStreamEvent = serde_json::from_str(data)
+                .map_err(|e| EnhanceError::InvalidResponse(e.to_string()))?;
+            match event.r#type.as_str() {
+                "response.output_text.delta" => Ok(StreamEvent::Delta(event.delta)),
+                "response.completed" => Ok(StreamEvent::Done),
+                "response.failed" | "response.incomplete" | "error" => Err(
+                    EnhanceError::InvalidResponse(format!("stream event: {}", event.r#type)),
+                ),
+                _ => Ok(StreamEvent::Ignored),
+            }
+        }
+        EnhanceApiFlavor::Chat => {
+            if data.trim() == "[DONE]" {
+                return Ok(StreamEvent::Done);
+            }
+            let chunk: ChatStreamChunk = serde_json::from_str(data)
+                .map_err(|e| EnhanceError::InvalidResponse(e.to_string()))?;
+            let delta: String = chunk
+                .choices
+                .into_iter()
+                .filter_map(|choice| choice.delta.content)
+                .collect();
+            if delta.is_empty() {
+                Ok(StreamEvent::Ignored)
+            } else {
+                Ok(StreamEvent::Delta(delta))
+            }
+        }
+    }
+}
+
+/// Достаточно ли текста пришло до обрыва потока, чтобы им воспользоваться.
+fn is_substantial_partial(partial: &str, input: &str) -> bool {
+    let received = partial.split_whitespace().count();
+    let expected = input.split_whitespace().count();
+    received > 0 && received as f64 >= expected as f64 * MIN_PARTIAL_STREAM_RATIO
+}
+
+/// Извлекает текст из ответа Responses API.
+///
+/// Берёт только items с `type == "message"` и content-блоки с `type == "output_text"`,
+/// чтобы не включать reasoning-traces и служебные элементы в финальный текст.
+fn extract_output_text(resp: &ResponsesResponse) -> Result<String> {
+    let text: String = resp
+        .output
+        .iter()
+        .filter(|item| item.r#type.is_empty() || item.r#type == "message")
+        .flat_map(|item| item.content.iter())
+        .filter(|block| block.r#type.is_empty() || block.r#type == "output_text")
+        .map(|block| block.text.as_str())
+        .collect();
+
+    if text.trim().is_empty() {
+        return Err(EnhanceError::InvalidResponse(
+            "empty output text in response".to_string(),
+        ));
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_should_be_created_with_valid_params() {
+        let client = OpenAiEnhancer::new(
+            "https://api.openai.com",
+            "test-key",
+            "gpt-5-mini",
+            Duration::from_secs(5),
+            Duration::from_secs(15),
+            3,
+        );
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn client_should_trim_trailing_slash_from_base_url() {
+        // Given / When
+        let client = OpenAiEnhancer::new(
+            "https://api.openai.com/",
+            "test-key",
+            "gpt-5-mini",
+            Duration::from_secs(5),
+            Duration::from_secs(15),
+            3,
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(client.base_url, "https://api.openai.com");
+    }
+
+    #[test]
+    fn client_should_store_model_from_constructor() {
+        // Given / When
+        let client = OpenAiEnhancer::new(
+            "https://api.openai.com",
+            "key",
+            "my-custom-model",
+            Duration::from_secs(5),
+            Duration::from_secs(15),
+            2,
+        )
+        .unwrap();
+
+        // Then
+        assert_eq!(client.model, "my-custom-model");
+    }
+
+    #[test]
+    fn from_config_should_use_config_values() {
+        // Given
+        let config = crate::config::schema::AppConfig {
+            api_base_url: "https://custom.api.com".to_string(),
+            enhance_model: "custom-enhance".to_string(),
+            connect_timeout_sec: 10,
+            read_timeout_enhance_sec: 30,
+            retry_count: 5,
+            ..Default::default()
+        };
+
+        // When
+        let client = OpenAiEnhancer::from_config(&config, "api-key-123").unwrap();
+
+        // Then
+        assert_eq!(client.base_url, "https://custom.api.com");
+        assert_eq!(client.model, "custom-enhance");
+        assert_eq!(client.retry_count, 5);
+        assert_eq!(client.read_timeout, Duration::from_secs(30));
+        assert!(client.cache.is_some());
+    }
+
+    #[test]
+    fn from_config_should_disable_cache_for_zero_size() {
+        // Given
+        let config = crate::config::schema::AppConfig {
+            enhance_cache_size: 0,
+            ..Default::default()
+        };
+
+        // When
+        let client = OpenAiEnhancer::from_config(&config, "api-key-123").unwrap();
+
+        // Then
+        assert!(client.cache.is_none());
+    }
+
+    #[test]
+    fn exceeds_deadline_should_compare_wait_with_remaining_budget() {
+        // Given
+        let deadline = Some(Instant::now() + Duration::from_secs(5));
+
+        // When / Then
+        assert!(!exceeds_deadline(deadline, Duration::from_secs(1)));
+        assert!(exceeds_deadline(deadline, Duration::from_secs(10)));
+        assert!(!exceeds_deadline(None, Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn is_retryable_should_return_true_for_network_error() {
+        assert!(OpenAiEnhancer::is_retryable(&EnhanceError::Network(
+            "err".into()
+        )));
     }
 
     #[test]
@@ -522,60 +1082,242 @@ mod tests {
 
     #[test]
     fn build_instructions_should_return_default_prompt_for_none() {
-        let result = build_instructions(None);
+        let result = build_instructions(None, EnhanceStyle::Default);
         assert_eq!(result, SYSTEM_PROMPT);
     }
 
     #[test]
     fn build_instructions_should_return_default_prompt_for_auto() {
-        let result = build_instructions(Some("auto"));
+        let result = build_instructions(Some("auto"), EnhanceStyle::Default);
         assert_eq!(result, SYSTEM_PROMPT);
     }
 
     #[test]
     fn build_instructions_should_include_language() {
-        let result = build_instructions(Some("ru"));
+        let result = build_instructions(Some("ru"), EnhanceStyle::Default);
         assert!(result.contains("ru"));
         assert!(result.contains("text post-processor"));
     }
 
     #[test]
-    fn extract_output_text_should_get_text_from_valid_response() {
+    fn build_instructions_should_pick_prompt_per_style() {
+        assert_eq!(
+            build_instructions(None, EnhanceStyle::Formal),
+            FORMAL_PROMPT
+        );
+        assert_eq!(
+            build_instructions(None, EnhanceStyle::Casual),
+            CASUAL_PROMPT
+        );
+        assert_eq!(
+            build_instructions(Some("auto"), EnhanceStyle::Verbatim),
+            VERBATIM_PROMPT
+        );
+    }
+
+    #[test]
+    fn build_instructions_should_include_language_per_style() {
+        for (style, marker) in [
+            (EnhanceStyle::Default, "Fix punctuation, grammar"),
+            (EnhanceStyle::Formal, "formal, professional register"),
+            (EnhanceStyle::Casual, "casual"),
+            (EnhanceStyle::Verbatim, "verbatim"),
+        ] {
+            let result = build_instructions(Some("en"), style);
+            assert!(
+                result.contains("The text is dictated in en."),
+                "{style:?}: {result}"
+            );
+            assert!(result.contains(marker), "{style:?}: {result}");
+            assert!(!result.contains("{lang}"), "{style:?}: {result}");
+        }
+    }
+
+    #[test]
+    fn from_config_should_use_enhance_style() {
         // Given
-        let resp = ResponsesResponse {
-            output: vec![OutputItem {
-                content: vec![ContentBlock {
-                    text: "Hello, world!".to_string(),
-                    ..Default::default()
-                }],
-                ..Default::default()
-            }],
-            usage: None,
+        let config = crate::config::schema::AppConfig {
+            enhance_style: EnhanceStyle::Verbatim,
+            enhance_cache_size: 0,
+            ..Default::default()
         };
 
         // When
-        let result = extract_output_text(&resp);
+        let enhancer = OpenAiEnhancer::from_config(&config, "key").unwrap();
 
         // Then
-        assert_eq!(result.unwrap(), "Hello, world!");
+        assert_eq!(enhancer.style, EnhanceStyle::Verbatim);
     }
 
     #[test]
-    fn extract_output_text_should_join_multiple_blocks() {
+    fn from_config_should_use_enhance_api_flavor() {
         // Given
-        let resp = ResponsesResponse {
-            output: vec![OutputItem {
-                content: vec![
-                    ContentBlock {
-                        text: "Hello, ".to_string(),
-                        ..Default::default()
-                    },
-                    ContentBlock {
-                        text: "world!".to_string(),
-                        ..Default::default()
-                    },
-                ],
-                ..Default::default()
+        let config = crate::config::schema::AppConfig {
+            enhance_api_flavor: EnhanceApiFlavor::Chat,
+            enhance_cache_size: 0,
+            ..Default::default()
+        };
+
+        // When
+        let enhancer = OpenAiEnhancer::from_config(&config, "key").unwrap();
+
+        // Then
+        assert_eq!(enhancer.flavor, EnhanceApiFlavor::Chat);
+    }
+
+    #[test]
+    fn from_config_should_use_enhance_streaming() {
+        // Given
+        let config = crate::config::schema::AppConfig {
+            enhance_streaming: true,
+            enhance_cache_size: 0,
+            ..Default::default()
+        };
+
+        // When
+        let enhancer = OpenAiEnhancer::from_config(&config, "key").unwrap();
+
+        // Then
+        assert!(enhancer.streaming);
+    }
+
+    #[test]
+    fn sse_parser_should_assemble_events_split_across_chunks() {
+        // Given
+        let mut parser = SseParser::default();
+
+        // When
+        let mut events = parser.push(b"event: response.output_text.delta\ndata: {\"a\"");
+        events.extend(parser.push(b":1}\n\ndata: second\r\n\r\n"));
+
+        // Then
+        assert_eq!(events, vec!["{\"a\":1}".to_string(), "second".to_string()]);
+    }
+
+    #[test]
+    fn sse_parser_should_not_break_multibyte_chars_split_between_chunks() {
+        // Given
+        let mut parser = SseParser::default();
+        let bytes = "data: привет\n\n".as_bytes();
+
+        // When: разрез посреди кириллической буквы
+        let mut events = parser.push(&bytes[..8]);
+        events.extend(parser.push(&bytes[8..]));
+
+        // Then
+        assert_eq!(events, vec!["привет".to_string()]);
+    }
+
+    #[test]
+    fn sse_parser_should_ignore_comments_and_incomplete_events() {
+        // Given
+        let mut parser = SseParser::default();
+
+        // When
+        let events = parser.push(b": keep-alive\n\ndata: pending");
+
+        // Then
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn parse_stream_event_should_handle_responses_events() {
+        // Given
+        let delta = r#"{"type":"response.output_text.delta","delta":"Hi"}"#;
+        let done = r#"{"type":"response.completed","response":{}}"#;
+        let other = r#"{"type":"response.created"}"#;
+        let failed = r#"{"type":"response.failed"}"#;
+
+        // When / Then
+        let flavor = EnhanceApiFlavor::Responses;
+        assert_eq!(
+            parse_stream_event(flavor, delta).unwrap(),
+            StreamEvent::Delta("Hi".to_string())
+        );
+        assert_eq!(parse_stream_event(flavor, done).unwrap(), StreamEvent::Done);
+        assert_eq!(
+            parse_stream_event(flavor, other).unwrap(),
+            StreamEvent::Ignored
+        );
+        assert!(matches!(
+            parse_stream_event(flavor, failed),
+            Err(EnhanceError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn parse_stream_event_should_handle_chat_chunks() {
+        // Given
+        let delta = r#"{"choices":[{"delta":{"content":"Hi"}}]}"#;
+        let role_only = r#"{"choices":[{"delta":{"role":"assistant"}}]}"#;
+
+        // When / Then
+        let flavor = EnhanceApiFlavor::Chat;
+        assert_eq!(
+            parse_stream_event(flavor, delta).unwrap(),
+            StreamEvent::Delta("Hi".to_string())
+        );
+        assert_eq!(
+            parse_stream_event(flavor, role_only).unwrap(),
+            StreamEvent::Ignored
+        );
+        assert_eq!(
+            parse_stream_event(flavor, "[DONE]").unwrap(),
+            StreamEvent::Done
+        );
+        assert!(parse_stream_event(flavor, "not json").is_err());
+    }
+
+    #[test]
+    fn is_substantial_partial_should_require_most_of_input() {
+        assert!(is_substantial_partial(
+            "one two three four",
+            "one two three four five"
+        ));
+        assert!(!is_substantial_partial(
+            "one two",
+            "one two three four five"
+        ));
+        assert!(!is_substantial_partial("", ""));
+    }
+
+    #[test]
+    fn extract_output_text_should_get_text_from_valid_response() {
+        // Given
+        let resp = ResponsesResponse {
+            output: vec![OutputItem {
+                content: vec![ContentBlock {
+                    text: "Hello, world!".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            usage: None,
+        };
+
+        // When
+        let result = extract_output_text(&resp);
+
+        // Then
+        assert_eq!(result.unwrap(), "Hello, world!");
+    }
+
+    #[test]
+    fn extract_output_text_should_join_multiple_blocks() {
+        // Given
+        let resp = ResponsesResponse {
+            output: vec![OutputItem {
+                content: vec![
+                    ContentBlock {
+                        text: "Hello, ".to_string(),
+                        ..Default::default()
+                    },
+                    ContentBlock {
+                        text: "world!".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
             }],
             usage: None,
         };
@@ -726,7 +1468,7 @@ mod tests {
 #[cfg(test)]
 mod integration_tests {
     use super::*;
-    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::matchers::{body_json, body_string_contains, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn make_responses_json(text: &str) -> serde_json::Value {
@@ -742,103 +1484,507 @@ mod integration_tests {
         })
     }
 
-    async fn create_test_client(base_url: &str) -> OpenAiEnhancer {
-        OpenAiEnhancer::new(
-            base_url,
+    fn make_chat_json(text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "chatcmpl-test",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": text },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 5 }
+        })
+    }
+
+    async fn create_chat_test_client(base_url: &str) -> OpenAiEnhancer {
+        create_test_client(base_url)
+            .await
+            .with_api_flavor(EnhanceApiFlavor::Chat)
+    }
+
+    async fn create_test_client(base_url: &str) -> OpenAiEnhancer {
+        OpenAiEnhancer::new(
+            base_url,
+            "test-api-key",
+            "gpt-5-mini",
+            Duration::from_secs(5),
+            Duration::from_secs(10),
+            2,
+        )
+        .unwrap()
+    }
+
+    fn test_cache() -> Arc<EnhanceCache> {
+        Arc::new(EnhanceCache::new(8, Duration::from_secs(600)))
+    }
+
+    #[tokio::test]
+    async fn enhance_should_use_cache_for_identical_input() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("Hello, world!")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri())
+            .await
+            .with_cache(test_cache());
+
+        // When
+        let first = client.do_enhance("hello world", Some("en")).await.unwrap();
+        let second = client.do_enhance("hello world", Some("en")).await.unwrap();
+
+        // Then: второй вызов без запроса к API (expect(1))
+        assert_eq!(first, "Hello, world!");
+        assert_eq!(second, "Hello, world!");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_miss_cache_for_different_input() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("Hello, world!")),
+            )
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri())
+            .await
+            .with_cache(test_cache());
+
+        // When: другой текст и другой язык - разные ключи
+        client.do_enhance("hello world", None).await.unwrap();
+        client.do_enhance("hello there world", None).await.unwrap();
+        client.do_enhance("hello world", Some("en")).await.unwrap();
+
+        // Then: три запроса к API (проверяется expect(3))
+    }
+
+    #[tokio::test]
+    async fn enhance_should_not_cache_raw_fallback() {
+        // Given: API недоступен (500), retry отключены
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = OpenAiEnhancer::new(
+            &server.uri(),
+            "test-api-key",
+            "gpt-5-mini",
+            Duration::from_secs(5),
+            Duration::from_secs(10),
+            0,
+        )
+        .unwrap()
+        .with_cache(test_cache());
+
+        // When
+        let first = client.do_enhance("hello world", None).await.unwrap();
+        let second = client.do_enhance("hello world", None).await.unwrap();
+
+        // Then: fallback к raw не кэшируется, второй вызов снова идет в API
+        assert_eq!(first, "hello world");
+        assert_eq!(second, "hello world");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_return_improved_text() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("Hello, world!")),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("hello world", None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "Hello, world!");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_fallback_on_empty_response() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_responses_json("")))
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When: пустой output → InvalidResponse (non-retryable) → fallback
+        let result = client.do_enhance("hello world", None).await;
+
+        // Then: возвращается исходный текст
+        assert_eq!(result.unwrap(), "hello world");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_fallback_on_hallucination() {
+        // Given: модель возвращает значительно больше текста, чем во входе
+        let server = MockServer::start().await;
+        let hallucinated = "this is a very long hallucinated response that has way too many \
+            words compared to the original input text and should be detected as a hallucination \
+            by our validation logic because the ratio exceeds the maximum allowed threshold";
+
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json(hallucinated)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("hello world test", None).await;
+
+        // Then: fallback к исходному тексту — обнаружена галлюцинация
+        assert_eq!(result.unwrap(), "hello world test");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_retry_on_server_error() {
+        // Given: первый запрос → 500, второй → 200
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("error"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("Recovered text.")),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("recovered text", None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "Recovered text.");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_return_raw_on_api_failure() {
+        // Given: always 401
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("my original text", None).await;
+
+        // Then: non-retryable ошибка → fallback к исходному тексту
+        assert_eq!(result.unwrap(), "my original text");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_return_raw_after_exhausting_retries() {
+        // Given: always 500
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("server error"))
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("my text here", None).await;
+
+        // Then: retry исчерпаны → fallback к исходному тексту
+        assert_eq!(result.unwrap(), "my text here");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_handle_rate_limiting() {
+        // Given: first -> 429, second -> 200
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(429).append_header("Retry-After", "1"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("After rate limit.")),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("after rate limit", None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "After rate limit.");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_return_raw_text_when_retry_budget_exhausted() {
+        // Given: сервер всегда отвечает 429, лимит повторов 2 с
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(429).append_header("Retry-After", "1"))
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri())
+            .await
+            .with_retry_budget(Some(Duration::from_secs(2)));
+
+        // When
+        let started = Instant::now();
+        let result = client.do_enhance("budget text", None).await;
+
+        // Then: fallback раньше, чем MAX_RATE_LIMIT_RETRIES ожиданий по 1 с
+        assert_eq!(result.unwrap(), "budget text");
+        let elapsed = started.elapsed();
+        assert!(elapsed < Duration::from_secs(4), "took {elapsed:?}");
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.len() < MAX_RATE_LIMIT_RETRIES as usize + 1);
+    }
+
+    #[tokio::test]
+    async fn enhance_should_not_retry_on_400() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("bad request"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("some text", None).await;
+
+        // Then: non-retryable, возвращается исходный текст
+        assert_eq!(result.unwrap(), "some text");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_send_correct_request_body() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .and(header("authorization", "Bearer test-api-key"))
+            .and(header("content-type", "application/json"))
+            .and(body_json(serde_json::json!({
+                "model": "gpt-5-mini",
+                "instructions": SYSTEM_PROMPT,
+                "input": "test text",
+                "reasoning": { "effort": REASONING_EFFORT },
+                "text": { "verbosity": TEXT_VERBOSITY }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("Test text.")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("test text", None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "Test text.");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_handle_invalid_json_response() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_enhance("original text here", None).await;
+
+        // Then: InvalidResponse is non-retryable -> fallback
+        assert_eq!(result.unwrap(), "original text here");
+    }
+
+    #[tokio::test]
+    async fn enhance_should_timeout_on_slow_response() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(make_responses_json("too late"))
+                    .set_delay(Duration::from_secs(10)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OpenAiEnhancer::new(
+            &server.uri(),
             "test-api-key",
             "gpt-5-mini",
             Duration::from_secs(5),
-            Duration::from_secs(10),
-            2,
+            Duration::from_millis(200),
+            0, // без retry
         )
-        .unwrap()
+        .unwrap();
+
+        // When
+        let result = client.do_enhance("my text", None).await;
+
+        // Then: timeout → fallback к исходному тексту
+        assert_eq!(result.unwrap(), "my text");
     }
 
     #[tokio::test]
-    async fn enhance_should_return_improved_text() {
-        // Given
+    async fn send_request_should_return_invalid_response_for_unparseable_json_body() {
+        // Given: сервер возвращает 200, но с невалидным JSON, который нельзя
+        // распарсить как ResponsesResponse. Ошибка должна быть InvalidResponse, не Network.
         let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/responses"))
-            .respond_with(
-                ResponseTemplate::new(200).set_body_json(make_responses_json("Hello, world!")),
-            )
+            .respond_with(ResponseTemplate::new(200).set_body_string("{not valid json!}"))
             .mount(&server)
             .await;
 
         let client = create_test_client(&server.uri()).await;
+        let instructions = build_instructions(None, EnhanceStyle::Default);
 
         // When
-        let result = client.do_enhance("hello world", None).await;
+        let result = client
+            .send_request(
+                &format!("{}/v1/responses", server.uri()),
+                &instructions,
+                "test input",
+            )
+            .await;
 
-        // Then
-        assert_eq!(result.unwrap(), "Hello, world!");
+        // Then: тип ошибки должен быть InvalidResponse для однозначной диагностики
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, EnhanceError::InvalidResponse(_)),
+            "ожидался InvalidResponse, получено: {err:?}"
+        );
     }
 
     #[tokio::test]
-    async fn enhance_should_fallback_on_empty_response() {
+    async fn chat_flavor_should_return_improved_text() {
         // Given
         let server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(make_responses_json("")))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_chat_json("Hello, world!")))
+            .expect(1)
             .mount(&server)
             .await;
 
-        let client = create_test_client(&server.uri()).await;
+        let client = create_chat_test_client(&server.uri()).await;
 
-        // When: пустой output → InvalidResponse (non-retryable) → fallback
+        // When
         let result = client.do_enhance("hello world", None).await;
 
-        // Then: возвращается исходный текст
-        assert_eq!(result.unwrap(), "hello world");
+        // Then
+        assert_eq!(result.unwrap(), "Hello, world!");
     }
 
     #[tokio::test]
-    async fn enhance_should_fallback_on_hallucination() {
-        // Given: модель возвращает значительно больше текста, чем во входе
+    async fn chat_flavor_should_send_system_and_user_messages() {
+        // Given
         let server = MockServer::start().await;
-        let hallucinated = "this is a very long hallucinated response that has way too many \
-            words compared to the original input text and should be detected as a hallucination \
-            by our validation logic because the ratio exceeds the maximum allowed threshold";
-
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
-            .respond_with(
-                ResponseTemplate::new(200).set_body_json(make_responses_json(hallucinated)),
-            )
+            .and(path("/v1/chat/completions"))
+            .and(header("authorization", "Bearer test-api-key"))
+            .and(body_json(serde_json::json!({
+                "model": "gpt-5-mini",
+                "messages": [
+                    { "role": "system", "content": SYSTEM_PROMPT },
+                    { "role": "user", "content": "test text" }
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_chat_json("Test text.")))
+            .expect(1)
             .mount(&server)
             .await;
 
-        let client = create_test_client(&server.uri()).await;
+        let client = create_chat_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("hello world test", None).await;
+        let result = client.do_enhance("test text", None).await;
 
-        // Then: fallback к исходному тексту — обнаружена галлюцинация
-        assert_eq!(result.unwrap(), "hello world test");
+        // Then
+        assert_eq!(result.unwrap(), "Test text.");
     }
 
     #[tokio::test]
-    async fn enhance_should_retry_on_server_error() {
+    async fn chat_flavor_should_retry_on_server_error() {
         // Given: первый запрос → 500, второй → 200
         let server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
+            .and(path("/v1/chat/completions"))
             .respond_with(ResponseTemplate::new(500).set_body_string("error"))
             .up_to_n_times(1)
             .mount(&server)
             .await;
 
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
+            .and(path("/v1/chat/completions"))
             .respond_with(
-                ResponseTemplate::new(200).set_body_json(make_responses_json("Recovered text.")),
+                ResponseTemplate::new(200).set_body_json(make_chat_json("Recovered text.")),
             )
             .mount(&server)
             .await;
 
-        let client = create_test_client(&server.uri()).await;
+        let client = create_chat_test_client(&server.uri()).await;
 
         // When
         let result = client.do_enhance("recovered text", None).await;
@@ -848,109 +1994,163 @@ mod integration_tests {
     }
 
     #[tokio::test]
-    async fn enhance_should_return_raw_on_api_failure() {
-        // Given: always 401
+    async fn chat_flavor_should_not_retry_on_400() {
+        // Given
         let server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
-            .respond_with(ResponseTemplate::new(401))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("bad request"))
+            .expect(1)
             .mount(&server)
             .await;
 
-        let client = create_test_client(&server.uri()).await;
+        let client = create_chat_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("my original text", None).await;
+        let result = client.do_enhance("some text", None).await;
 
-        // Then: non-retryable ошибка → fallback к исходному тексту
-        assert_eq!(result.unwrap(), "my original text");
+        // Then: non-retryable, возвращается исходный текст
+        assert_eq!(result.unwrap(), "some text");
     }
 
     #[tokio::test]
-    async fn enhance_should_return_raw_after_exhausting_retries() {
-        // Given: always 500
+    async fn send_chat_request_should_reject_empty_content() {
+        // Given: content = null (например, ответ без текста)
         let server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
-            .respond_with(ResponseTemplate::new(500).set_body_string("server error"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "role": "assistant", "content": null } }]
+            })))
             .mount(&server)
             .await;
 
-        let client = create_test_client(&server.uri()).await;
+        let client = create_chat_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("my text here", None).await;
+        let result = client
+            .send_chat_request(
+                &format!("{}/v1/chat/completions", server.uri()),
+                SYSTEM_PROMPT,
+                "test input",
+            )
+            .await;
 
-        // Then: retry исчерпаны → fallback к исходному тексту
-        assert_eq!(result.unwrap(), "my text here");
+        // Then
+        assert!(matches!(result, Err(EnhanceError::InvalidResponse(_))));
     }
 
     #[tokio::test]
-    async fn enhance_should_handle_rate_limiting() {
-        // Given: first -> 429, second -> 200
+    async fn send_chat_request_should_return_invalid_response_for_unparseable_json_body() {
+        // Given
         let server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
-            .respond_with(ResponseTemplate::new(429).append_header("Retry-After", "1"))
-            .up_to_n_times(1)
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{not valid json!}"))
             .mount(&server)
             .await;
 
+        let client = create_chat_test_client(&server.uri()).await;
+
+        // When
+        let result = client
+            .send_chat_request(
+                &format!("{}/v1/chat/completions", server.uri()),
+                SYSTEM_PROMPT,
+                "test input",
+            )
+            .await;
+
+        // Then
+        assert!(matches!(result, Err(EnhanceError::InvalidResponse(_))));
+    }
+
+    /// SSE-тело потока Responses API из фрагментов текста.
+    fn make_responses_sse(deltas: &[&str], completed: bool) -> String {
+        let mut body =
+            String::from("event: response.created\ndata: {\"type\":\"response.created\"}\n\n");
+        for delta in deltas {
+            let event = serde_json::json!({
+                "type": "response.output_text.delta",
+                "delta": delta
+            });
+            body.push_str(&format!(
+                "event: response.output_text.delta\ndata: {event}\n\n"
+            ));
+        }
+        if completed {
+            body.push_str("event: response.completed\ndata: {\"type\":\"response.completed\"}\n\n");
+        }
+        body
+    }
+
+    fn sse_response(body: String) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")
+    }
+
+    #[tokio::test]
+    async fn streaming_should_assemble_text_from_deltas() {
+        // Given
+        let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/responses"))
-            .respond_with(
-                ResponseTemplate::new(200).set_body_json(make_responses_json("After rate limit.")),
-            )
+            .and(BodyJsonField("stream"))
+            .respond_with(sse_response(make_responses_sse(
+                &["Hello", ", ", "world!"],
+                true,
+            )))
+            .expect(1)
             .mount(&server)
             .await;
 
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("after rate limit", None).await;
+        let result = client.do_enhance_streaming("hello world", None).await;
 
         // Then
-        assert_eq!(result.unwrap(), "After rate limit.");
+        assert_eq!(result.unwrap(), "Hello, world!");
     }
 
     #[tokio::test]
-    async fn enhance_should_not_retry_on_400() {
+    async fn streaming_should_assemble_chat_deltas() {
         // Given
+        let body = [
+            r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#,
+            r#"data: {"choices":[{"delta":{"content":"Hello, "}}]}"#,
+            r#"data: {"choices":[{"delta":{"content":"world!"}}]}"#,
+            "data: [DONE]",
+        ]
+        .join("\n\n")
+            + "\n\n";
         let server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/responses"))
-            .respond_with(ResponseTemplate::new(400).set_body_string("bad request"))
+            .and(path("/v1/chat/completions"))
+            .and(BodyJsonField("stream"))
+            .respond_with(sse_response(body))
             .expect(1)
             .mount(&server)
             .await;
 
-        let client = create_test_client(&server.uri()).await;
+        let client = create_chat_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("some text", None).await;
+        let result = client.do_enhance_streaming("hello world", None).await;
 
-        // Then: non-retryable, возвращается исходный текст
-        assert_eq!(result.unwrap(), "some text");
+        // Then
+        assert_eq!(result.unwrap(), "Hello, world!");
     }
 
     #[tokio::test]
-    async fn enhance_should_send_correct_request_body() {
-        // Given
+    async fn streaming_should_return_substantial_partial_text_when_stream_breaks() {
+        // Given: поток оборвался без response.completed, но текст почти полный
         let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/responses"))
-            .and(header("authorization", "Bearer test-api-key"))
-            .and(header("content-type", "application/json"))
-            .and(body_json(serde_json::json!({
-                "model": "gpt-5-mini",
-                "instructions": SYSTEM_PROMPT,
-                "input": "test text",
-                "reasoning": { "effort": REASONING_EFFORT },
-                "text": { "verbosity": TEXT_VERBOSITY }
-            })))
-            .respond_with(
-                ResponseTemplate::new(200).set_body_json(make_responses_json("Test text.")),
-            )
+            .respond_with(sse_response(make_responses_sse(
+                &["One two ", "three four", " five"],
+                false,
+            )))
             .expect(1)
             .mount(&server)
             .await;
@@ -958,90 +2158,130 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("test text", None).await;
+        let result = client
+            .send_streaming_request(
+                &format!("{}/v1/responses", server.uri()),
+                SYSTEM_PROMPT,
+                "one two three four five six",
+            )
+            .await;
 
         // Then
-        assert_eq!(result.unwrap(), "Test text.");
+        assert_eq!(result.unwrap(), "One two three four five");
     }
 
     #[tokio::test]
-    async fn enhance_should_handle_invalid_json_response() {
-        // Given
+    async fn streaming_should_retry_when_stream_breaks_before_text() {
+        // Given: первый поток обрывается без текста, второй - полный
         let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/responses"))
-            .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+            .respond_with(sse_response(make_responses_sse(&[], false)))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(sse_response(make_responses_sse(&["Recovered text."], true)))
             .mount(&server)
             .await;
 
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("original text here", None).await;
+        let result = client.do_enhance_streaming("recovered text", None).await;
 
-        // Then: InvalidResponse is non-retryable -> fallback
-        assert_eq!(result.unwrap(), "original text here");
+        // Then
+        assert_eq!(result.unwrap(), "Recovered text.");
     }
 
     #[tokio::test]
-    async fn enhance_should_timeout_on_slow_response() {
+    async fn streaming_should_report_network_error_for_short_partial_text() {
         // Given
         let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/responses"))
-            .respond_with(
-                ResponseTemplate::new(200)
-                    .set_body_json(make_responses_json("too late"))
-                    .set_delay(Duration::from_secs(10)),
+            .respond_with(sse_response(make_responses_sse(&["One"], false)))
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client
+            .send_streaming_request(
+                &format!("{}/v1/responses", server.uri()),
+                SYSTEM_PROMPT,
+                "one two three four five six",
             )
+            .await;
+
+        // Then: обрыв retryable
+        let err = result.unwrap_err();
+        assert!(matches!(err, EnhanceError::Network(_)), "got {err:?}");
+    }
+
+    #[tokio::test]
+    async fn streaming_should_fallback_to_raw_on_hallucinated_stream() {
+        // Given: собранный текст не проходит validate_enhancement
+        let long = "This is a very long hallucinated answer that the model invented \
+                    out of thin air and that has nothing to do with the input at all";
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(sse_response(make_responses_sse(&[long], true)))
             .mount(&server)
             .await;
 
-        let client = OpenAiEnhancer::new(
-            &server.uri(),
-            "test-api-key",
-            "gpt-5-mini",
-            Duration::from_secs(5),
-            Duration::from_millis(200),
-            0, // без retry
-        )
-        .unwrap();
+        let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_enhance("my text", None).await;
+        let result = client.do_enhance_streaming("short text", None).await;
 
-        // Then: timeout → fallback к исходному тексту
-        assert_eq!(result.unwrap(), "my text");
+        // Then
+        assert_eq!(result.unwrap(), "short text");
+    }
+
+    /// Проверяет, что JSON-тело запроса содержит `"<field>": true`.
+    struct BodyJsonField(&'static str);
+
+    impl wiremock::Match for BodyJsonField {
+        fn matches(&self, request: &wiremock::Request) -> bool {
+            serde_json::from_slice::<serde_json::Value>(&request.body)
+                .ok()
+                .and_then(|body| body.get(self.0).and_then(serde_json::Value::as_bool))
+                .unwrap_or(false)
+        }
     }
 
     #[tokio::test]
-    async fn send_request_should_return_invalid_response_for_unparseable_json_body() {
-        // Given: сервер возвращает 200, но с невалидным JSON, который нельзя
-        // распарсить как ResponsesResponse. Ошибка должна быть InvalidResponse, не Network.
+    async fn enhance_should_ask_to_preserve_paragraphs_for_multiline_text() {
+        // Given
         let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/responses"))
-            .respond_with(ResponseTemplate::new(200).set_body_string("{not valid json!}"))
+            .and(body_string_contains("preserve every paragraph break"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json(
+                    "First paragraph here.\n\nSecond paragraph here.",
+                )),
+            )
+            .expect(1)
             .mount(&server)
             .await;
 
         let client = create_test_client(&server.uri()).await;
-        let instructions = build_instructions(None);
 
         // When
         let result = client
-            .send_request(
-                &format!("{}/v1/responses", server.uri()),
-                &instructions,
-                "test input",
-            )
+            .do_enhance("first paragraph here\n\nsecond paragraph here", None)
             .await;
 
-        // Then: тип ошибки должен быть InvalidResponse для однозначной диагностики
-        let err = result.unwrap_err();
-        assert!(
-            matches!(err, EnhanceError::InvalidResponse(_)),
-            "ожидался InvalidResponse, получено: {err:?}"
+        // Then
+        assert_eq!(
+            result.unwrap(),
+            "First paragraph here.\n\nSecond paragraph here."
         );
     }
 }
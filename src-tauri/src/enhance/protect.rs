@@ -0,0 +1,159 @@
+//! Защита фраз от изменения при улучшении текста (`protected_phrases`).
+//!
+//! Перед отправкой в LLM фразы заменяются плейсхолдерами `⟦P0⟧`, `⟦P1⟧`...,
+//! после улучшения плейсхолдеры заменяются обратно исходным текстом.
+
+/// Текст с замаскированными фразами.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskedText {
+    /// Текст с плейсхолдерами вместо фраз.
+    pub text: String,
+    /// Исходные фрагменты; индекс соответствует номеру плейсхолдера.
+    pub originals: Vec<String>,
+}
+
+/// Формирует плейсхолдер для фрагмента с номером `index`.
+fn placeholder(index: usize) -> String {
+    format!("⟦P{index}⟧")
+}
+
+/// Заменяет вхождения защищенных фраз плейсхолдерами.
+///
+/// Сравнение точное (с учетом регистра), фраза должна стоять на границе слов.
+/// Длинные фразы обрабатываются первыми, чтобы "Acme Cloud" не разбивалась на "Acme".
+pub fn mask_phrases(text: &str, phrases: &[String]) -> MaskedText {
+    let mut sorted: Vec<&str> = phrases
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    sorted.sort_by_key(|p| std::cmp::Reverse(p.len()));
+    sorted.dedup();
+
+    let mut originals = Vec::new();
+    let mut masked = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let rest = &text[pos..];
+        let found = sorted
+            .iter()
+            .find(|phrase| rest.starts_with(**phrase) && is_boundary(text, pos, phrase.len()));
+
+        match found {
+            Some(phrase) => {
+                masked.push_str(&placeholder(originals.len()));
+                originals.push(phrase.to_string());
+                pos += phrase.len();
+            }
+            None => {
+                let c = rest.chars().next().expect("pos is within text");
+                masked.push(c);
+                pos += c.len_utf8();
+            }
+        }
+    }
+
+    MaskedText {
+        text: masked,
+        originals,
+    }
+}
+
+/// Восстанавливает исходные фразы вместо плейсхолдеров.
+///
+/// Возвращает `None`, если LLM потеряла или продублировала плейсхолдер -
+/// тогда нельзя гарантировать сохранность фраз.
+pub fn unmask_phrases(enhanced: &str, originals: &[String]) -> Option<String> {
+    let mut result = enhanced.to_string();
+    for (index, original) in originals.iter().enumerate() {
+        let marker = placeholder(index);
+        if result.matches(&marker).count() != 1 {
+            tracing::warn!(index, "protected phrase placeholder lost or duplicated");
+            return None;
+        }
+        result = result.replacen(&marker, original, 1);
+    }
+    Some(result)
+}
+
+/// Проверяет, что фрагмент `text[start..start + len]` не является частью слова.
+pub(super) fn is_boundary(text: &str, start: usize, len: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[start + len..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phrases(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn mask_should_replace_phrases_with_placeholders() {
+        // Given
+        let text = "deploy with kubectl apply -f then ping AcmeCloud";
+
+        // When
+        let masked = mask_phrases(text, &phrases(&["kubectl apply -f", "AcmeCloud"]));
+
+        // Then
+        assert_eq!(masked.text, "deploy with ⟦P0⟧ then ping ⟦P1⟧");
+        assert_eq!(
+            masked.originals,
+            phrases(&["kubectl apply -f", "AcmeCloud"])
+        );
+    }
+
+    #[test]
+    fn mask_should_prefer_longest_phrase() {
+        // Given / When
+        let masked = mask_phrases("use Acme Cloud now", &phrases(&["Acme", "Acme Cloud"]));
+
+        // Then
+        assert_eq!(masked.text, "use ⟦P0⟧ now");
+        assert_eq!(masked.originals, phrases(&["Acme Cloud"]));
+    }
+
+    #[test]
+    fn mask_should_respect_word_boundaries() {
+        // Given / When
+        let masked = mask_phrases("Acmeish and Acme", &phrases(&["Acme"]));
+
+        // Then
+        assert_eq!(masked.text, "Acmeish and ⟦P0⟧");
+    }
+
+    #[test]
+    fn mask_should_keep_text_without_phrases() {
+        let masked = mask_phrases("привет мир", &phrases(&["", "  "]));
+        assert_eq!(masked.text, "привет мир");
+        assert!(masked.originals.is_empty());
+    }
+
+    #[test]
+    fn unmask_should_restore_phrases_byte_for_byte() {
+        // Given
+        let text = "ping AcmeCloud and iOS";
+        let masked = mask_phrases(text, &phrases(&["AcmeCloud", "iOS"]));
+
+        // When: LLM почистила текст вокруг плейсхолдеров
+        let restored = unmask_phrases("Ping ⟦P0⟧ and ⟦P1⟧.", &masked.originals);
+
+        // Then
+        assert_eq!(restored.as_deref(), Some("Ping AcmeCloud and iOS."));
+    }
+
+    #[test]
+    fn unmask_should_fail_when_placeholder_lost() {
+        assert_eq!(unmask_phrases("Ping.", &phrases(&["AcmeCloud"])), None);
+    }
+
+    #[test]
+    fn unmask_should_fail_when_placeholder_duplicated() {
+        assert_eq!(unmask_phrases("⟦P0⟧ ⟦P0⟧", &phrases(&["AcmeCloud"])), None);
+    }
+}
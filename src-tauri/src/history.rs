@@ -0,0 +1,170 @@
+//! История распознанных текстов (`keep_history`): JSON-файл с ограниченным
+//! числом записей. Хранится только текст, аудио никогда не сохраняется.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::schema::AppConfig;
+use crate::config::storage::{config_dir, write_atomic};
+use crate::error::{AppError, Result};
+
+/// Имя файла истории в `config_dir()`.
+const HISTORY_FILE_NAME: &str = "history.json";
+
+/// Одна запись истории.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Время вставки (Unix, секунды)
+    pub timestamp: u64,
+    /// Вставленный текст
+    pub text: String,
+}
+
+/// Путь к файлу истории.
+pub fn history_file_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join(HISTORY_FILE_NAME))
+}
+
+/// Добавляет текст в историю, если `keep_history` включен.
+///
+/// Ошибки записи только логируются: история не должна мешать вставке.
+pub fn record(config: &AppConfig, text: &str) {
+    if !config.keep_history {
+        return;
+    }
+    let entry = HistoryEntry {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        text: text.to_string(),
+    };
+    let result = history_file_path()
+        .and_then(|path| append_entry(&path, entry, config.history_max_entries as usize));
+    if let Err(e) = result {
+        warn!("Failed to save transcription history: {e}");
+    }
+}
+
+/// Загружает историю (старые записи - первыми).
+pub fn load_history() -> Result<Vec<HistoryEntry>> {
+    read_entries(&history_file_path()?)
+}
+
+/// Удаляет файл истории.
+pub fn clear_history() -> Result<()> {
+    clear_file(&history_file_path()?)
+}
+
+/// Записи из файла; нет файла - пустая история.
+///
+/// Поврежденный файл не блокирует запись новой истории: он считается пустым.
+fn read_entries(path: &Path) -> Result<Vec<HistoryEntry>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(AppError::Config(format!(
+                "failed to read history file {:?}: {}",
+                path, e
+            )))
+        }
+    };
+    match serde_json::from_str(&content) {
+        Ok(entries) => Ok(entries),
+        Err(e) => {
+            warn!("History file {:?} is corrupted ({e}), starting over", path);
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Добавляет запись, оставляя не более `max_entries` последних (кольцевой буфер).
+fn append_entry(path: &Path, entry: HistoryEntry, max_entries: usize) -> Result<()> {
+    let mut entries = read_entries(path)?;
+    entries.push(entry);
+    let excess = entries.len().saturating_sub(max_entries.max(1));
+    entries.drain(..excess);
+
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|e| AppError::Config(format!("failed to serialize history: {}", e)))?;
+    write_atomic(path, &json)
+}
+
+/// Удаляет файл истории; отсутствующий файл - не ошибка.
+fn clear_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => {
+            info!("Transcription history cleared");
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(AppError::Config(format!(
+            "failed to delete history file {:?}: {}",
+            path, e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(timestamp: u64, text: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn append_entry_should_keep_only_last_entries() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(HISTORY_FILE_NAME);
+
+        // When
+        for i in 0..5 {
+            append_entry(&path, entry(i, &format!("text {i}")), 3).unwrap();
+        }
+
+        // Then
+        let entries = read_entries(&path).unwrap();
+        assert_eq!(
+            entries,
+            vec![entry(2, "text 2"), entry(3, "text 3"), entry(4, "text 4")]
+        );
+    }
+
+    #[test]
+    fn read_entries_should_return_empty_when_file_missing_or_corrupted() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(HISTORY_FILE_NAME);
+
+        // When / Then
+        assert!(read_entries(&path).unwrap().is_empty());
+        fs::write(&path, "not json").unwrap();
+        assert!(read_entries(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn clear_file_should_remove_history_and_ignore_missing_file() {
+        // Given
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(HISTORY_FILE_NAME);
+        append_entry(&path, entry(1, "hello"), 10).unwrap();
+
+        // When
+        clear_file(&path).unwrap();
+
+        // Then
+        assert!(!path.exists());
+        assert!(clear_file(&path).is_ok());
+    }
+}
@@ -1,26 +1,189 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use tauri::{AppHandle, Manager, Runtime};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-use crate::config::schema::RecordingMode;
+use crate::config::schema::{AppConfig, RecordingMode};
+use crate::error::{AppError, Result};
 use crate::state::{AppEvent, AppState, SharedAppState};
+use crate::tray;
+
+/// Логика "PTT с фиксацией": короткое нажатие (tap) фиксирует запись,
+/// следующее нажатие ее останавливает. Удержание работает как обычный PTT.
+#[derive(Debug, Default)]
+pub struct PttLatch {
+    pressed_at: Option<Instant>,
+    locked: bool,
+}
+
+impl PttLatch {
+    /// Обрабатывает нажатие клавиши.
+    ///
+    /// `is_recording` - идет ли запись сейчас (фиксация сбрасывается,
+    /// если запись завершилась другим способом, например по таймауту).
+    pub fn on_press(&mut self, now: Instant, is_recording: bool) -> Option<AppEvent> {
+        if self.locked && is_recording {
+            // Повторный tap останавливает зафиксированную запись;
+            // следующий Released игнорируется
+            self.locked = false;
+            self.pressed_at = None;
+            return Some(AppEvent::HotkeyUp);
+        }
+
+        self.locked = false;
+        self.pressed_at = Some(now);
+        Some(AppEvent::HotkeyDown)
+    }
+
+    /// Обрабатывает отпускание клавиши.
+    ///
+    /// Нажатие короче `tap_threshold` фиксирует запись (событие не отправляется),
+    /// более долгое удержание останавливает запись как обычный PTT.
+    pub fn on_release(&mut self, now: Instant, tap_threshold: Duration) -> Option<AppEvent> {
+        let pressed_at = self.pressed_at.take()?;
+
+        if now.saturating_duration_since(pressed_at) < tap_threshold {
+            self.locked = true;
+            return None;
+        }
+        Some(AppEvent::HotkeyUp)
+    }
+
+    /// Зафиксирована ли запись.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+}
+
+/// Состояние фиксации PTT (Tauri managed state).
+pub struct PttLatchState(pub Mutex<PttLatch>);
+
+impl PttLatchState {
+    pub fn new() -> Self {
+        Self(Mutex::new(PttLatch::default()))
+    }
+}
+
+/// Парсит строку хоткея из конфига.
+fn parse_hotkey(hotkey_str: &str) -> Result<Shortcut> {
+    hotkey_str
+        .parse()
+        .map_err(|e| AppError::Hotkey(format!("invalid hotkey \"{}\": {}", hotkey_str, e)))
+}
+
+/// Превращает ошибку регистрации от global-shortcut в понятное сообщение.
+///
+/// Плагин отдает только текст ошибки ОС: занятая комбинация выглядит как
+/// "already registered" (Windows), "failed/unable to register" (macOS, X11)
+/// или "already grabbed"/"in use" (X11). Остальное - прочий сбой регистрации.
+fn registration_error(hotkey_str: &str, error: &str) -> AppError {
+    let lower = error.to_lowercase();
+    let in_use = [
+        "already",
+        "in use",
+        "taken",
+        "failed to register",
+        "unable to register",
+    ]
+    .iter()
+    .any(|marker| lower.contains(marker));
+    if in_use {
+        AppError::Hotkey(format!(
+            "{} is already in use by another application. Choose a different combination",
+            hotkey_str
+        ))
+    } else {
+        AppError::Hotkey(format!(
+            "failed to register hotkey \"{}\": {}",
+            hotkey_str, error
+        ))
+    }
+}
 
 /// Регистрирует глобальный хоткей из строки конфига.
 ///
-/// При ошибке парсинга или регистрации возвращает описание проблемы.
+/// Различает неверный синтаксис и комбинацию, занятую другим приложением.
 /// Приложение продолжит работать через tray-меню (fallback).
-pub fn register_hotkey<R: Runtime>(app: &AppHandle<R>, hotkey_str: &str) -> Result<(), String> {
-    let shortcut: Shortcut = hotkey_str
-        .parse()
-        .map_err(|e| format!("invalid hotkey \"{}\": {}", hotkey_str, e))?;
+pub fn register_hotkey<R: Runtime>(app: &AppHandle<R>, hotkey_str: &str) -> Result<()> {
+    let shortcut = parse_hotkey(hotkey_str)?;
 
     app.global_shortcut()
         .register(shortcut)
-        .map_err(|e| format!("failed to register hotkey \"{}\": {}", hotkey_str, e))?;
+        .map_err(|e| registration_error(hotkey_str, &e.to_string()))?;
 
     tracing::info!(hotkey = %hotkey_str, "global hotkey registered");
     Ok(())
 }
 
+/// Проверяет хоткеи записи и отмены перед регистрацией.
+///
+/// - Нужен хотя бы один хоткей записи, каждый должен парситься.
+/// - Хоткеи записи не повторяются, хоткей отмены не совпадает ни с одним из них.
+///
+/// Пустой `cancel_hotkey` - отмена хоткеем выключена. Сравниваются
+/// распарсенные комбинации, поэтому порядок и регистр модификаторов не важны.
+/// Ошибка называет хоткей, который не прошел проверку.
+pub fn check_hotkeys(hotkeys: &[String], cancel_hotkey: &str) -> Result<()> {
+    if hotkeys.is_empty() {
+        return Err(AppError::Hotkey(
+            "at least one recording hotkey is required".to_string(),
+        ));
+    }
+
+    let mut parsed: Vec<(Shortcut, &str)> = Vec::with_capacity(hotkeys.len());
+    for hotkey in hotkeys {
+        let shortcut = parse_hotkey(hotkey)?;
+        if let Some((_, first)) = parsed.iter().find(|(s, _)| *s == shortcut) {
+            return Err(AppError::Hotkey(format!(
+                "hotkey \"{}\" duplicates \"{}\"",
+                hotkey, first
+            )));
+        }
+        parsed.push((shortcut, hotkey));
+    }
+
+    if cancel_hotkey.trim().is_empty() {
+        return Ok(());
+    }
+    let cancel = parse_hotkey(cancel_hotkey)?;
+    if let Some((_, hotkey)) = parsed.iter().find(|(s, _)| *s == cancel) {
+        return Err(AppError::Hotkey(format!(
+            "cancel hotkey \"{}\" must differ from recording hotkey \"{}\"",
+            cancel_hotkey, hotkey
+        )));
+    }
+    Ok(())
+}
+
+/// Регистрирует все хоткеи записи и, если задан, хоткей отмены (`cancel_hotkey`).
+pub fn register_hotkeys<R: Runtime>(
+    app: &AppHandle<R>,
+    hotkeys: &[String],
+    cancel_hotkey_str: &str,
+) -> Result<()> {
+    check_hotkeys(hotkeys, cancel_hotkey_str)?;
+    for hotkey in hotkeys {
+        register_hotkey(app, hotkey)?;
+    }
+    if !cancel_hotkey_str.trim().is_empty() {
+        register_hotkey(app, cancel_hotkey_str)?;
+    }
+    Ok(())
+}
+
+/// Совпадает ли сработавший хоткей с `cancel_hotkey` из конфига.
+fn is_cancel_hotkey<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut) -> bool {
+    let cancel_hotkey = app
+        .state::<Mutex<AppConfig>>()
+        .lock()
+        .expect("config mutex poisoned")
+        .cancel_hotkey
+        .clone();
+    !cancel_hotkey.trim().is_empty()
+        && parse_hotkey(&cancel_hotkey).is_ok_and(|cancel| cancel == *shortcut)
+}
+
 /// Обработчик события глобального хоткея.
 ///
 /// Определяет AppEvent в зависимости от режима записи (Toggle/PTT)
@@ -28,9 +191,18 @@ pub fn register_hotkey<R: Runtime>(app: &AppHandle<R>, hotkey_str: &str) -> Resu
 /// global-shortcut при каждом срабатывании зарегистрированного хоткея.
 pub fn on_shortcut_event<R: Runtime>(
     app: &AppHandle<R>,
-    _shortcut: &Shortcut,
+    shortcut: &Shortcut,
     event: tauri_plugin_global_shortcut::ShortcutEvent,
 ) {
+    // Хоткей отмены: сразу прерывает обработку в любом режиме записи
+    if is_cancel_hotkey(app, shortcut) {
+        if matches!(event.state, ShortcutState::Pressed) {
+            tracing::debug!("cancel hotkey pressed");
+            crate::dispatch_and_update(app, AppEvent::Cancel);
+        }
+        return;
+    }
+
     let shared = app.state::<SharedAppState>();
     let mode = shared.recording_mode();
     let current = shared.current_state();
@@ -50,10 +222,220 @@ pub fn on_shortcut_event<R: Runtime>(
 
         (RecordingMode::Toggle, ShortcutState::Pressed) => AppEvent::HotkeyPressed,
         (RecordingMode::Toggle, ShortcutState::Released) => return,
-        (RecordingMode::PushToTalk, ShortcutState::Pressed) => AppEvent::HotkeyDown,
-        (RecordingMode::PushToTalk, ShortcutState::Released) => AppEvent::HotkeyUp,
+        (RecordingMode::PushToTalk, state) => match ptt_event(app, state, current) {
+            Some(event) => event,
+            None => return,
+        },
     };
 
     tracing::debug!(mode = ?mode, event = ?app_event, "hotkey event dispatched");
     crate::dispatch_and_update(app, app_event);
 }
+
+/// Определяет событие PTT с учетом фиксации по tap (`ptt_tap_to_lock`).
+fn ptt_event<R: Runtime>(
+    app: &AppHandle<R>,
+    state: ShortcutState,
+    current: AppState,
+) -> Option<AppEvent> {
+    let (tap_to_lock, threshold) = {
+        let config = app
+            .state::<Mutex<AppConfig>>()
+            .lock()
+            .expect("config mutex poisoned");
+        (
+            config.ptt_tap_to_lock,
+            Duration::from_millis(u64::from(config.ptt_tap_threshold_ms)),
+        )
+    };
+
+    if !tap_to_lock {
+        return Some(match state {
+            ShortcutState::Pressed => AppEvent::HotkeyDown,
+            ShortcutState::Released => AppEvent::HotkeyUp,
+        });
+    }
+
+    let latch_state = app.state::<PttLatchState>();
+    let mut latch = latch_state.0.lock().expect("ptt latch mutex poisoned");
+    let now = Instant::now();
+    match state {
+        ShortcutState::Pressed => latch.on_press(
+            now,
+            matches!(current, AppState::Recording | AppState::Paused),
+        ),
+        ShortcutState::Released => {
+            let event = latch.on_release(now, threshold);
+            if latch.is_locked() {
+                tracing::info!("PTT recording locked by tap");
+                tray::show_recording_locked(app);
+            }
+            event
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: Duration = Duration::from_millis(250);
+
+    fn hotkeys(list: &[&str]) -> Vec<String> {
+        list.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn check_hotkeys_should_allow_several_hotkeys_and_empty_cancel_hotkey() {
+        assert!(check_hotkeys(&hotkeys(&["Ctrl+Shift+S"]), "").is_ok());
+        assert!(check_hotkeys(&hotkeys(&["Ctrl+Shift+S", "F13"]), "Ctrl+Shift+X").is_ok());
+    }
+
+    #[test]
+    fn check_hotkeys_should_reject_cancel_hotkey_equal_to_recording_one() {
+        // Given: та же комбинация с другим порядком модификаторов
+        let result = check_hotkeys(&hotkeys(&["F13", "Ctrl+Shift+S"]), "Shift+Ctrl+S");
+
+        // Then
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("must differ"), "{err}");
+        assert!(err.contains("Ctrl+Shift+S"), "{err}");
+    }
+
+    #[test]
+    fn check_hotkeys_should_reject_duplicated_hotkey() {
+        // When
+        let err = check_hotkeys(&hotkeys(&["Ctrl+Shift+S", "F13", "shift+ctrl+s"]), "")
+            .unwrap_err()
+            .to_string();
+
+        // Then
+        assert!(err.contains("shift+ctrl+s"), "{err}");
+    }
+
+    #[test]
+    fn check_hotkeys_should_name_unparseable_hotkey() {
+        // When
+        let err = check_hotkeys(&hotkeys(&["Ctrl+Shift+S", "Ctrl+NotAKey"]), "")
+            .unwrap_err()
+            .to_string();
+
+        // Then
+        assert!(err.contains("invalid hotkey \"Ctrl+NotAKey\""), "{err}");
+    }
+
+    #[test]
+    fn registration_error_should_report_hotkey_in_use() {
+        // Given: тексты ошибок global-hotkey на разных ОС
+        for os_error in [
+            "HotKey already registered: HotKey { mods: CONTROL | SHIFT, key: KeyS, id: 1 }",
+            "Failed to register hotkey: Unable to register accelerator",
+        ] {
+            // When
+            let err = registration_error("Ctrl+Shift+S", os_error);
+
+            // Then
+            assert!(matches!(err, AppError::Hotkey(_)));
+            assert_eq!(
+                err.to_string(),
+                "Hotkey error: Ctrl+Shift+S is already in use by another application. \
+                 Choose a different combination"
+            );
+        }
+    }
+
+    #[test]
+    fn registration_error_should_keep_unknown_error_text() {
+        // When
+        let err = registration_error("F13", "event loop closed").to_string();
+
+        // Then
+        assert!(err.contains("failed to register hotkey \"F13\""), "{err}");
+        assert!(err.contains("event loop closed"), "{err}");
+    }
+
+    #[test]
+    fn check_hotkeys_should_reject_empty_list_and_invalid_cancel_hotkey() {
+        assert!(check_hotkeys(&[], "").is_err());
+        assert!(check_hotkeys(&hotkeys(&["Ctrl+Shift+S"]), "Ctrl+NotAKey").is_err());
+    }
+
+    #[test]
+    fn hold_should_stop_recording_on_release() {
+        // Given
+        let mut latch = PttLatch::default();
+        let start = Instant::now();
+
+        // When
+        let down = latch.on_press(start, false);
+        let up = latch.on_release(start + Duration::from_millis(800), THRESHOLD);
+
+        // Then
+        assert_eq!(down, Some(AppEvent::HotkeyDown));
+        assert_eq!(up, Some(AppEvent::HotkeyUp));
+        assert!(!latch.is_locked());
+    }
+
+    #[test]
+    fn tap_should_lock_recording() {
+        // Given
+        let mut latch = PttLatch::default();
+        let start = Instant::now();
+
+        // When
+        latch.on_press(start, false);
+        let up = latch.on_release(start + Duration::from_millis(100), THRESHOLD);
+
+        // Then: запись продолжается
+        assert_eq!(up, None);
+        assert!(latch.is_locked());
+    }
+
+    #[test]
+    fn next_tap_should_stop_locked_recording() {
+        // Given: запись зафиксирована
+        let mut latch = PttLatch::default();
+        let start = Instant::now();
+        latch.on_press(start, false);
+        latch.on_release(start + Duration::from_millis(100), THRESHOLD);
+
+        // When
+        let stop = latch.on_press(start + Duration::from_secs(30), true);
+        let release = latch.on_release(start + Duration::from_millis(30_100), THRESHOLD);
+
+        // Then
+        assert_eq!(stop, Some(AppEvent::HotkeyUp));
+        assert_eq!(release, None);
+        assert!(!latch.is_locked());
+    }
+
+    #[test]
+    fn press_after_recording_ended_elsewhere_should_start_new_recording() {
+        // Given: запись была зафиксирована, но остановлена по таймауту
+        let mut latch = PttLatch::default();
+        let start = Instant::now();
+        latch.on_press(start, false);
+        latch.on_release(start + Duration::from_millis(100), THRESHOLD);
+
+        // When
+        let event = latch.on_press(start + Duration::from_secs(120), false);
+
+        // Then
+        assert_eq!(event, Some(AppEvent::HotkeyDown));
+        assert!(!latch.is_locked());
+    }
+
+    #[test]
+    fn release_exactly_at_threshold_should_count_as_hold() {
+        // Given
+        let mut latch = PttLatch::default();
+        let start = Instant::now();
+        latch.on_press(start, false);
+
+        // When
+        let up = latch.on_release(start + THRESHOLD, THRESHOLD);
+
+        // Then
+        assert_eq!(up, Some(AppEvent::HotkeyUp));
+    }
+}
@@ -0,0 +1,187 @@
+//! Освобождение ресурсов после долгого простоя (`idle_release_after_min`).
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::time::Instant;
+
+use crate::cache::EnumerationCache;
+use crate::config::schema::AppConfig;
+use crate::state::{AppState, SharedAppState};
+use crate::stt::ModelCache;
+
+/// Период проверки простоя.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Порог простоя из конфига; `None`, если освобождение выключено (0).
+pub fn release_threshold(config: &AppConfig) -> Option<Duration> {
+    (config.idle_release_after_min > 0)
+        .then(|| Duration::from_secs(u64::from(config.idle_release_after_min) * 60))
+}
+
+/// Отслеживает время последней активности и решает, когда освобождать ресурсы.
+///
+/// Освобождение происходит один раз за период простоя; любая активность
+/// сбрасывает таймер.
+pub struct IdleTimer {
+    inner: Mutex<IdleInner>,
+}
+
+struct IdleInner {
+    last_activity: Instant,
+    released: bool,
+}
+
+impl IdleTimer {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(IdleInner {
+                last_activity: Instant::now(),
+                released: false,
+            }),
+        }
+    }
+
+    /// Отмечает активность пользователя (начало записи и т.п.).
+    pub fn touch(&self) {
+        let mut inner = self.inner.lock().expect("idle mutex poisoned");
+        inner.last_activity = Instant::now();
+        inner.released = false;
+    }
+
+    /// Возвращает `true`, если пора освободить ресурсы.
+    ///
+    /// Не-Idle состояние считается активностью.
+    pub fn should_release(&self, is_idle: bool, threshold: Option<Duration>) -> bool {
+        if !is_idle {
+            self.touch();
+            return false;
+        }
+
+        let mut inner = self.inner.lock().expect("idle mutex poisoned");
+        match threshold {
+            Some(threshold) if !inner.released && inner.last_activity.elapsed() >= threshold => {
+                inner.released = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for IdleTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Запускает фоновую проверку простоя.
+pub fn spawn_idle_watcher<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let is_idle = app.state::<SharedAppState>().current_state() == AppState::Idle;
+            let threshold = release_threshold(
+                &app.state::<Mutex<AppConfig>>()
+                    .lock()
+                    .expect("config mutex poisoned"),
+            );
+
+            if app.state::<IdleTimer>().should_release(is_idle, threshold) {
+                release_resources(&app);
+            }
+        }
+    });
+}
+
+/// Освобождает ресурсы простоя: кэши перечислений, загруженную модель Whisper
+/// и открытые потоки микрофона. Все пересоздается лениво при следующем использовании.
+fn release_resources<R: Runtime>(app: &AppHandle<R>) {
+    let cache = app.state::<EnumerationCache>();
+    cache.devices.invalidate();
+    cache.models.invalidate();
+
+    let model_released = app.state::<ModelCache>().release();
+    crate::pipeline::release_audio_streams(app);
+
+    tracing::info!(
+        model_released,
+        "idle threshold reached, cached resources released"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: Option<Duration> = Some(Duration::from_secs(10 * 60));
+
+    #[tokio::test(start_paused = true)]
+    async fn should_not_release_before_threshold() {
+        // Given
+        let timer = IdleTimer::new();
+
+        // When
+        tokio::time::advance(Duration::from_secs(9 * 60)).await;
+
+        // Then
+        assert!(!timer.should_release(true, THRESHOLD));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_release_once_after_threshold() {
+        // Given
+        let timer = IdleTimer::new();
+
+        // When
+        tokio::time::advance(Duration::from_secs(10 * 60)).await;
+
+        // Then
+        assert!(timer.should_release(true, THRESHOLD));
+        assert!(!timer.should_release(true, THRESHOLD));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn activity_should_restart_idle_period() {
+        // Given
+        let timer = IdleTimer::new();
+        tokio::time::advance(Duration::from_secs(8 * 60)).await;
+
+        // When: запись (не Idle) сбрасывает таймер
+        assert!(!timer.should_release(false, THRESHOLD));
+        tokio::time::advance(Duration::from_secs(8 * 60)).await;
+
+        // Then
+        assert!(!timer.should_release(true, THRESHOLD));
+        tokio::time::advance(Duration::from_secs(2 * 60)).await;
+        assert!(timer.should_release(true, THRESHOLD));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn should_never_release_when_disabled() {
+        // Given
+        let timer = IdleTimer::new();
+
+        // When
+        tokio::time::advance(Duration::from_secs(24 * 60 * 60)).await;
+
+        // Then
+        assert!(!timer.should_release(true, None));
+    }
+
+    #[test]
+    fn release_threshold_should_be_none_for_zero() {
+        // Given
+        let mut config = AppConfig::default();
+        config.idle_release_after_min = 0;
+
+        // When / Then
+        assert_eq!(release_threshold(&config), None);
+
+        config.idle_release_after_min = 15;
+        assert_eq!(release_threshold(&config), Some(Duration::from_secs(900)));
+    }
+}
@@ -1,27 +1,39 @@
 mod audio;
+mod backoff;
+mod cache;
 mod config;
+mod debug_audio;
 mod enhance;
 mod error;
+mod history;
 mod hotkey;
+mod idle;
 mod logging;
+mod metrics;
 mod notifications;
 mod paste;
 mod pipeline;
+mod redact;
 mod state;
 mod stt;
 mod tray;
 #[allow(dead_code, unused_imports)]
 mod vad;
+mod webhook;
 
 use std::sync::Mutex;
 use std::time::Duration;
 
-use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-use crate::config::schema::AppConfig;
-use crate::pipeline::{PipelineState, ResultText};
-use crate::state::{AppEvent, AppState, SharedAppState};
+use crate::audio::DeviceInfo;
+use crate::cache::EnumerationCache;
+use crate::config::schema::{AppConfig, ConfigIssue, SttProviderKind};
+use crate::config::secrets::ApiKeyScope;
+use crate::idle::IdleTimer;
+use crate::pipeline::{PendingPaste, PipelineState, ResultText};
+use crate::state::{AppEvent, AppState, SharedAppState, StateChange};
 
 // --- Tauri commands ---
 
@@ -33,16 +45,177 @@ fn get_config(config: tauri::State<'_, Mutex<AppConfig>>) -> Result<AppConfig, S
 }
 
 /// Сохраняет обновленный конфиг (файл + in-memory state).
+///
+/// Возвращает сохраненный конфиг: при смене `active_profile` поля провайдера
+/// берутся из выбранного профиля.
 #[tauri::command]
 fn save_config(
     updated_config: AppConfig,
     config_state: tauri::State<'_, Mutex<AppConfig>>,
     shared_state: tauri::State<'_, SharedAppState>,
-) -> Result<(), String> {
+    cache: tauri::State<'_, EnumerationCache>,
+) -> Result<AppConfig, String> {
+    apply_config(updated_config, &config_state, &shared_state, &cache)
+}
+
+/// Возвращает текущий конфиг как переносимый JSON (без API-ключа).
+#[tauri::command]
+fn export_config(config: tauri::State<'_, Mutex<AppConfig>>) -> Result<String, String> {
+    let cfg = config.lock().expect("config mutex poisoned").clone();
+    config::storage::export_config_json(&cfg).map_err(|e| e.to_string())
+}
+
+/// Импортирует конфиг из JSON: миграция, коррекция значений, сохранение.
+///
+/// API-ключ не импортируется - он остается в OS keychain.
+#[tauri::command]
+fn import_config(
+    json: String,
+    config_state: tauri::State<'_, Mutex<AppConfig>>,
+    shared_state: tauri::State<'_, SharedAppState>,
+    cache: tauri::State<'_, EnumerationCache>,
+) -> Result<AppConfig, String> {
+    let imported = config::storage::import_config_json(&json).map_err(|e| e.to_string())?;
+    apply_config(imported, &config_state, &shared_state, &cache)
+}
+
+/// Сохраняет конфиг в файл и обновляет in-memory state.
+fn apply_config(
+    mut updated_config: AppConfig,
+    config_state: &Mutex<AppConfig>,
+    shared_state: &SharedAppState,
+    cache: &EnumerationCache,
+) -> Result<AppConfig, String> {
+    hotkey::check_hotkeys(&updated_config.hotkeys, &updated_config.cancel_hotkey)
+        .map_err(|e| e.to_string())?;
+
+    let mut current = config_state.lock().expect("config mutex poisoned");
+    let profile_changed = current.active_profile != updated_config.active_profile;
+    if profile_changed {
+        updated_config.select_active_profile();
+    } else {
+        updated_config.store_active_profile();
+    }
+
     config::storage::save_config(&updated_config).map_err(|e| e.to_string())?;
     shared_state.set_recording_mode(updated_config.recording_mode.clone());
-    *config_state.lock().expect("config mutex poisoned") = updated_config;
-    Ok(())
+    // Список моделей зависит от endpoint и ключа профиля
+    if profile_changed || current.api_base_url != updated_config.api_base_url {
+        cache.models.invalidate();
+    }
+    if current.log_level != updated_config.log_level {
+        logging::set_log_level(&updated_config.log_level);
+    }
+    *current = updated_config.clone();
+    Ok(updated_config)
+}
+
+/// Где хранится API-ключ активного профиля.
+fn api_key_scope(config: &Mutex<AppConfig>) -> ApiKeyScope {
+    ApiKeyScope::from_config(&config.lock().expect("config mutex poisoned"))
+}
+
+/// Нужен ли API-ключ текущей конфигурации и отсутствует ли он
+/// (офлайн STT без улучшения текста работает без ключа).
+fn api_key_missing(config: &Mutex<AppConfig>) -> bool {
+    let config = config.lock().expect("config mutex poisoned");
+    pipeline::api_key_missing(&config, || {
+        config::secrets::has_api_key(&ApiKeyScope::from_config(&config))
+    })
+}
+
+/// Возвращает тайминги последней сессии диктовки (STT, enhance, вставка).
+#[tauri::command]
+fn get_last_metrics(
+    shared_state: tauri::State<'_, SharedAppState>,
+) -> Result<metrics::SessionMetrics, String> {
+    shared_state
+        .last_metrics()
+        .ok_or_else(|| "No dictation sessions recorded yet".to_string())
+}
+
+/// Возвращает сохраненную историю вставленных текстов (старые - первыми).
+#[tauri::command]
+fn get_history() -> Result<Vec<history::HistoryEntry>, String> {
+    history::load_history().map_err(|e| e.to_string())
+}
+
+/// Удаляет историю вставленных текстов.
+#[tauri::command]
+fn clear_history() -> Result<(), String> {
+    history::clear_history().map_err(|e| e.to_string())
+}
+
+/// Проверяет файл конфига (по умолчанию - текущий config.json) без загрузки.
+#[tauri::command]
+fn validate_config_file(path: Option<String>) -> Result<Vec<ConfigIssue>, String> {
+    let path = match path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => config::storage::config_file_path().map_err(|e| e.to_string())?,
+    };
+    config::storage::validate_config_file(&path).map_err(|e| e.to_string())
+}
+
+/// Записывает клип `duration_ms` и возвращает распознанный текст без вставки.
+///
+/// Для автоматизации и smoke-тестов: не требует хоткея и не меняет состояние UI.
+#[tauri::command]
+async fn dictate_once(
+    duration_ms: u64,
+    config: tauri::State<'_, Mutex<AppConfig>>,
+    models: tauri::State<'_, stt::ModelCache>,
+) -> Result<String, String> {
+    let config = config.lock().expect("config mutex poisoned").clone();
+    pipeline::dictate_once(config, Duration::from_millis(duration_ms), &models).await
+}
+
+/// Возвращает текущее состояние приложения (для индикатора статуса в UI).
+#[tauri::command]
+fn get_current_state(shared_state: tauri::State<'_, SharedAppState>) -> AppState {
+    shared_state.current_state()
+}
+
+/// Начинает запись из UI (как пункт трея "Start Recording").
+#[tauri::command]
+fn start_recording_cmd(app: AppHandle) -> AppState {
+    start_dictation(&app)
+}
+
+/// Останавливает запись из UI (как пункт трея "Stop Recording").
+#[tauri::command]
+fn stop_recording_cmd(app: AppHandle) -> AppState {
+    stop_dictation(&app)
+}
+
+/// Запускает проверку микрофона: события `audio-level` для VU-метра настроек.
+///
+/// Доступна только вне диктовки.
+#[tauri::command]
+fn start_mic_test(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<SharedAppState>().current_state();
+    if state != AppState::Idle {
+        return Err("Microphone test is not available during dictation".to_string());
+    }
+    pipeline::start_mic_test(&app).map_err(|e| e.to_string())
+}
+
+/// Останавливает проверку микрофона.
+#[tauri::command]
+fn stop_mic_test(app: AppHandle) {
+    pipeline::stop_mic_test(&app);
+}
+
+/// Самопроверка микрофона и STT: запись ~1с и распознавание без вставки.
+///
+/// Ошибки этапов возвращаются в результате, а не как `Err`, чтобы UI
+/// показал, какой шаг не прошел.
+#[tauri::command]
+async fn run_self_test(
+    config: tauri::State<'_, Mutex<AppConfig>>,
+    models: tauri::State<'_, stt::ModelCache>,
+) -> Result<pipeline::SelfTestResult, String> {
+    let config = config.lock().expect("config mutex poisoned").clone();
+    Ok(pipeline::run_self_test(config, &models).await)
 }
 
 /// Сбрасывает конфиг в дефолтные значения, возвращает новый конфиг.
@@ -54,20 +227,45 @@ fn reset_config(
     let defaults = AppConfig::default();
     config::storage::save_config(&defaults).map_err(|e| e.to_string())?;
     shared_state.set_recording_mode(defaults.recording_mode.clone());
+    logging::set_log_level(&defaults.log_level);
     *config_state.lock().expect("config mutex poisoned") = defaults.clone();
     Ok(defaults)
 }
 
-/// Проверяет наличие API-ключа в OS keychain.
+/// Проверяет наличие API-ключа активного профиля в OS keychain.
 #[tauri::command]
-fn get_has_api_key() -> bool {
-    config::secrets::has_api_key()
+fn get_has_api_key(config: tauri::State<'_, Mutex<AppConfig>>) -> bool {
+    config::secrets::has_api_key(&api_key_scope(&config))
 }
 
-/// Сохраняет API-ключ в OS keychain.
+/// Сохраняет API-ключ активного профиля в OS keychain.
+///
+/// Список моделей зависит от ключа, поэтому его кэш сбрасывается.
 #[tauri::command]
-fn save_api_key(key: String) -> Result<(), String> {
-    config::secrets::store_api_key(&key).map_err(|e| e.to_string())
+fn save_api_key(
+    key: String,
+    config: tauri::State<'_, Mutex<AppConfig>>,
+    cache: tauri::State<'_, EnumerationCache>,
+) -> Result<(), String> {
+    config::secrets::store_api_key(&api_key_scope(&config), &key).map_err(|e| e.to_string())?;
+    cache.models.invalidate();
+    Ok(())
+}
+
+/// Удаляет API-ключ активного профиля из OS keychain и зашифрованного файла.
+///
+/// Отсутствующий ключ - не ошибка. Наличие ключа проверяется при каждом
+/// старте записи, поэтому следующий хоткей снова откроет онбординг.
+#[tauri::command]
+fn delete_api_key(
+    app: AppHandle,
+    config: tauri::State<'_, Mutex<AppConfig>>,
+    cache: tauri::State<'_, EnumerationCache>,
+) -> Result<(), String> {
+    config::secrets::delete_api_key(&api_key_scope(&config)).map_err(|e| e.to_string())?;
+    cache.models.invalidate();
+    tray::update_tray(&app, app.state::<SharedAppState>().current_state());
+    Ok(())
 }
 
 /// Проверяет валидность API-ключа запросом к OpenAI API.
@@ -79,6 +277,195 @@ async fn validate_api_key(
     key: String,
     config: tauri::State<'_, Mutex<AppConfig>>,
 ) -> Result<bool, String> {
+    let base_url = checked_api_base_url(&config)?;
+
+    let response = models_request(&base_url, &key)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    match response.status().as_u16() {
+        200..=299 => Ok(true),
+        401 => Ok(false),
+        code => Err(format!("Unexpected API response: {}", code)),
+    }
+}
+
+/// Результат проверки подключения и настроенных моделей (`validate_setup`).
+#[derive(Debug, Clone, serde::Serialize)]
+struct SetupValidation {
+    /// Endpoint ответил на GET /v1/models
+    connectivity_ok: bool,
+    /// Ключ не отклонён (не 401)
+    key_valid: bool,
+    /// Endpoint вернул список моделей и модели сверены с ним
+    models_verified: bool,
+    /// Настроенные модели, которых нет в списке endpoint-а
+    unknown_models: Vec<String>,
+    /// Описание ошибки, если проверка не прошла
+    error: Option<String>,
+}
+
+impl SetupValidation {
+    fn failed(connectivity_ok: bool, error: String) -> Self {
+        Self {
+            connectivity_ok,
+            key_valid: false,
+            models_verified: false,
+            unknown_models: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+/// Проверяет подключение, ключ и наличие `stt_model` / `enhance_model` на endpoint-е.
+///
+/// `stt_model` проверяется только для облачного STT, `enhance_model` - только
+/// при включенном улучшении.
+///
+/// `key` - ключ для проверки до сохранения; без него берётся сохранённый.
+/// Если endpoint не отдаёт список моделей (404/405 или ответ не в формате
+/// OpenAI), результат - "подключение есть, модели не проверены".
+#[tauri::command]
+async fn validate_setup(
+    key: Option<String>,
+    config: tauri::State<'_, Mutex<AppConfig>>,
+) -> Result<SetupValidation, String> {
+    let base_url = checked_api_base_url(&config)?;
+    let key = match key.filter(|k| !k.trim().is_empty()) {
+        Some(key) => key,
+        None => config::secrets::load_api_key(&api_key_scope(&config))
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "API key is not set".to_string())?,
+    };
+    let wanted = wanted_models(&config.lock().expect("config mutex poisoned"));
+    Ok(check_setup(&base_url, &key, &wanted).await)
+}
+
+/// Модели, которые должны быть на endpoint-е при текущих настройках.
+fn wanted_models(config: &AppConfig) -> Vec<String> {
+    let mut wanted = Vec::new();
+    if config.stt_provider == SttProviderKind::OpenAi {
+        wanted.push(config.stt_model.clone());
+    }
+    if config.enhance_enabled {
+        wanted.push(config.enhance_model.clone());
+    }
+    wanted
+}
+
+/// Запрашивает GET /v1/models и сверяет ответ с `wanted` (см. `validate_setup`).
+async fn check_setup(base_url: &str, key: &str, wanted: &[String]) -> SetupValidation {
+    let response = match models_request(base_url, key).send().await {
+        Ok(response) => response,
+        Err(e) => return SetupValidation::failed(false, format!("Network error: {}", e)),
+    };
+
+    match response.status().as_u16() {
+        200..=299 => {}
+        401 => return SetupValidation::failed(true, "API key was rejected".to_string()),
+        404 | 405 => return models_unverified(),
+        code => return SetupValidation::failed(true, format!("Unexpected API response: {}", code)),
+    }
+
+    let available = match response.bytes().await {
+        Ok(body) => parse_model_ids(&body).ok(),
+        Err(_) => None,
+    };
+    let Some(available) = available.filter(|ids| !ids.is_empty()) else {
+        return models_unverified();
+    };
+
+    let unknown_models = unknown_models(&available, wanted);
+    let error = (!unknown_models.is_empty()).then(|| {
+        format!(
+            "Models not available on this endpoint: {}",
+            unknown_models.join(", ")
+        )
+    });
+    SetupValidation {
+        connectivity_ok: true,
+        key_valid: true,
+        models_verified: true,
+        unknown_models,
+        error,
+    }
+}
+
+/// Endpoint доступен и принял ключ, но список моделей не отдаёт.
+fn models_unverified() -> SetupValidation {
+    SetupValidation {
+        connectivity_ok: true,
+        key_valid: true,
+        models_verified: false,
+        unknown_models: Vec::new(),
+        error: None,
+    }
+}
+
+/// Модели из `wanted`, которых нет среди `available` (без дубликатов).
+fn unknown_models(available: &[String], wanted: &[String]) -> Vec<String> {
+    let mut unknown: Vec<String> = Vec::new();
+    for model in wanted {
+        if !available.contains(model) && !unknown.contains(model) {
+            unknown.push(model.clone());
+        }
+    }
+    unknown
+}
+
+/// Возвращает список input-устройств (кэшируется на `enumeration_cache_ttl_sec`).
+///
+/// `refresh = true` принудительно перечисляет устройства заново.
+#[tauri::command]
+fn get_audio_devices(
+    refresh: Option<bool>,
+    cache: tauri::State<'_, EnumerationCache>,
+    config: tauri::State<'_, Mutex<AppConfig>>,
+) -> Result<Vec<DeviceInfo>, String> {
+    let ttl = enumeration_cache_ttl(&config);
+    cache
+        .devices
+        .get_or_load(ttl, refresh.unwrap_or(false), || {
+            audio::capture_cpal::list_input_devices()
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Возвращает идентификаторы моделей из GET /v1/models (кэшируется как устройства).
+#[tauri::command]
+async fn list_models(
+    refresh: Option<bool>,
+    cache: tauri::State<'_, EnumerationCache>,
+    config: tauri::State<'_, Mutex<AppConfig>>,
+) -> Result<Vec<String>, String> {
+    let ttl = enumeration_cache_ttl(&config);
+    if !refresh.unwrap_or(false) {
+        if let Some(models) = cache.models.get(ttl) {
+            return Ok(models);
+        }
+    }
+
+    let base_url = checked_api_base_url(&config)?;
+    let api_key = config::secrets::load_api_key(&api_key_scope(&config))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "API key is not set".to_string())?;
+
+    let models = fetch_model_ids(&base_url, &api_key).await?;
+    cache.models.store(models.clone());
+    Ok(models)
+}
+
+fn enumeration_cache_ttl(config: &Mutex<AppConfig>) -> Duration {
+    let ttl_sec = config
+        .lock()
+        .expect("config mutex poisoned")
+        .enumeration_cache_ttl_sec;
+    Duration::from_secs(u64::from(ttl_sec))
+}
+
+/// Возвращает base URL из конфига после SSRF-проверки.
+fn checked_api_base_url(config: &Mutex<AppConfig>) -> Result<String, String> {
     let base_url = config
         .lock()
         .expect("config mutex poisoned")
@@ -97,21 +484,53 @@ async fn validate_api_key(
         );
     }
 
+    Ok(base_url.to_string())
+}
+
+fn models_request(base_url: &str, key: &str) -> reqwest::RequestBuilder {
     let url = format!("{}/v1/models", base_url);
-    let client = reqwest::Client::new();
-    let response = client
+    reqwest::Client::new()
         .get(&url)
         .header("Authorization", format!("Bearer {}", key))
         .timeout(Duration::from_secs(10))
+}
+
+/// Разбирает ответ GET /v1/models (`{"data": [{"id": ...}]}`) в отсортированные id.
+fn parse_model_ids(body: &[u8]) -> Result<Vec<String>, serde_json::Error> {
+    #[derive(serde::Deserialize)]
+    struct ModelsResponse {
+        data: Vec<ModelEntry>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ModelEntry {
+        id: String,
+    }
+
+    let body: ModelsResponse = serde_json::from_slice(body)?;
+    let mut ids: Vec<String> = body.data.into_iter().map(|m| m.id).collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// Запрашивает GET /v1/models и возвращает отсортированные id моделей.
+async fn fetch_model_ids(base_url: &str, key: &str) -> Result<Vec<String>, String> {
+    let response = models_request(base_url, key)
         .send()
         .await
         .map_err(|e| format!("Network error: {}", e))?;
 
-    match response.status().as_u16() {
-        200..=299 => Ok(true),
-        401 => Ok(false),
-        code => Err(format!("Unexpected API response: {}", code)),
+    let status = response.status().as_u16();
+    if !(200..=299).contains(&status) {
+        return Err(format!("Unexpected API response: {}", status));
     }
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    parse_model_ids(&body).map_err(|e| format!("Invalid models response: {}", e))
 }
 
 /// Возвращает текст из последнего результата pipeline (для Result window).
@@ -120,6 +539,24 @@ fn get_result_text(result: tauri::State<'_, ResultText>) -> Option<String> {
     result.0.lock().expect("result mutex poisoned").clone()
 }
 
+/// Есть ли текст, ожидающий подтверждения перед вставкой.
+#[tauri::command]
+fn has_pending_paste(pending: tauri::State<'_, PendingPaste>) -> bool {
+    pending.is_pending()
+}
+
+/// Вставляет подтвержденный пользователем текст (Accept).
+#[tauri::command]
+fn accept_pending_paste(app: AppHandle) -> Result<(), String> {
+    pipeline::accept_pending_paste(&app)
+}
+
+/// Отбрасывает неподтвержденный текст (Discard), clipboard не меняется.
+#[tauri::command]
+fn discard_pending_paste(app: AppHandle) {
+    pipeline::discard_pending_paste(&app);
+}
+
 /// Копирует текст в системный буфер обмена через нативный API (arboard).
 #[tauri::command]
 fn copy_to_clipboard(text: String) -> Result<(), String> {
@@ -131,13 +568,30 @@ fn copy_to_clipboard(text: String) -> Result<(), String> {
     Ok(())
 }
 
-/// Перерегистрирует глобальный хоткей (unregister all + register new).
+/// Перерегистрирует глобальные хоткеи записи и отмены (unregister all + register new).
+///
+/// Без `cancel_hotkey_str` хоткей отмены берется из текущего конфига.
+/// Неверные и совпадающие хоткеи отклоняются до снятия старых регистраций.
 #[tauri::command]
-fn update_hotkey(app: AppHandle, hotkey_str: String) -> Result<(), String> {
+fn update_hotkey(
+    app: AppHandle,
+    hotkeys: Vec<String>,
+    cancel_hotkey_str: Option<String>,
+    config: tauri::State<'_, Mutex<AppConfig>>,
+) -> Result<(), String> {
+    let cancel_hotkey_str = cancel_hotkey_str.unwrap_or_else(|| {
+        config
+            .lock()
+            .expect("config mutex poisoned")
+            .cancel_hotkey
+            .clone()
+    });
+    hotkey::check_hotkeys(&hotkeys, &cancel_hotkey_str).map_err(|e| e.to_string())?;
+
     app.global_shortcut()
         .unregister_all()
         .map_err(|e| format!("Failed to unregister hotkeys: {}", e))?;
-    hotkey::register_hotkey(&app, &hotkey_str)
+    hotkey::register_hotkeys(&app, &hotkeys, &cancel_hotkey_str).map_err(|e| e.to_string())
 }
 
 // --- Settings window ---
@@ -178,30 +632,64 @@ fn open_settings_window_inner<R: Runtime>(app: &AppHandle<R>, url: WebviewUrl) {
 
 /// Применяет событие к state machine, обновляет tray и отправляет уведомление.
 ///
-/// Перед началом записи проверяет наличие API-ключа. Если ключ не задан,
-/// открывает настройки и показывает уведомление.
+/// Перед началом записи проверяет наличие API-ключа (если он нужен для
+/// текущих настроек). Если ключ не задан, открывает настройки и показывает уведомление.
 pub(crate) fn dispatch_and_update<R: Runtime>(app: &AppHandle<R>, event: AppEvent) {
     if matches!(event, AppEvent::HotkeyPressed | AppEvent::HotkeyDown) {
         let shared = app.state::<SharedAppState>();
-        if shared.current_state() == AppState::Idle && !config::secrets::has_api_key() {
+        if shared.current_state() == AppState::Idle
+            && api_key_missing(&app.state::<Mutex<AppConfig>>())
+        {
             notifications::notify_error(app, "Set API key in Settings first");
             open_settings_window(app);
             return;
         }
     }
 
+    // Переход и старт/стоп захвата выполняются под одной блокировкой:
+    // одновременные хоткей и трей не запустят две записи
     let shared = app.state::<SharedAppState>();
-    let (old, new) = shared.dispatch_with_old(&event);
+    shared.dispatch_serialized(&event, |old, new| {
+        tray::update_tray(app, new);
+        emit_state_changed(app, old, new);
+        notifications::notify_state_change(app, old, new, None);
 
-    if old == new {
-        return;
+        // Pipeline side effects triggered by state transitions
+        handle_transition_side_effects(app, old, new);
+    });
+}
+
+/// Начинает запись событием текущего режима (трей, кнопки UI).
+///
+/// Работает только из Idle: в Toggle то же событие остановило бы запись.
+/// Проверка API-ключа - в `dispatch_and_update`. Возвращает новое состояние.
+pub(crate) fn start_dictation<R: Runtime>(app: &AppHandle<R>) -> AppState {
+    let shared = app.state::<SharedAppState>();
+    if shared.current_state() == AppState::Idle {
+        dispatch_and_update(app, state::start_event(&shared.recording_mode()));
     }
+    shared.current_state()
+}
 
-    tray::update_tray(app, new);
-    notifications::notify_state_change(app, old, new);
+/// Останавливает запись (или паузу) событием текущего режима.
+///
+/// В остальных состояниях ничего не делает. Возвращает новое состояние.
+pub(crate) fn stop_dictation<R: Runtime>(app: &AppHandle<R>) -> AppState {
+    let shared = app.state::<SharedAppState>();
+    if matches!(
+        shared.current_state(),
+        AppState::Recording | AppState::Paused
+    ) {
+        dispatch_and_update(app, state::stop_event(&shared.recording_mode()));
+    }
+    shared.current_state()
+}
 
-    // Pipeline side effects triggered by state transitions
-    handle_transition_side_effects(app, old, new);
+/// Сообщает окнам о смене состояния (событие `state-changed`).
+pub(crate) fn emit_state_changed<R: Runtime>(app: &AppHandle<R>, old: AppState, new: AppState) {
+    if let Err(e) = app.emit("state-changed", StateChange { old, new }) {
+        tracing::warn!(error = %e, "failed to emit state-changed event");
+    }
 }
 
 /// Handles pipeline side effects triggered by state transitions.
@@ -210,9 +698,18 @@ fn handle_transition_side_effects<R: Runtime>(app: &AppHandle<R>, old: AppState,
         (AppState::Idle, AppState::Recording) => {
             pipeline::start_recording(app);
         }
-        (AppState::Recording, AppState::Transcribing) => {
+        (AppState::Recording | AppState::Paused, AppState::Transcribing) => {
             pipeline::stop_recording_and_run_pipeline(app);
         }
+        (AppState::Recording, AppState::Paused) => {
+            pipeline::pause_recording(app);
+        }
+        (AppState::Paused, AppState::Recording) => {
+            pipeline::resume_recording(app);
+        }
+        (AppState::Paused, AppState::Idle) => {
+            pipeline::discard_recording(app);
+        }
         (AppState::Transcribing | AppState::Enhancing | AppState::Pasting, AppState::Idle) => {
             pipeline::cancel_pipeline(app);
         }
@@ -230,9 +727,12 @@ pub fn run() {
         tracing::error!(error = %e, "failed to load config, using defaults");
         AppConfig::default()
     });
+    logging::set_log_level(&app_config.log_level);
+    logging::cleanup_old_logs(app_config.log_retention_days);
 
     let recording_mode = app_config.recording_mode.clone();
-    let hotkey_str = app_config.hotkey.clone();
+    let hotkeys = app_config.hotkeys.clone();
+    let cancel_hotkey_str = app_config.cancel_hotkey.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -246,21 +746,48 @@ pub fn run() {
         .manage(Mutex::new(app_config))
         .manage(PipelineState::new())
         .manage(ResultText::new())
+        .manage(PendingPaste::new())
+        .manage(EnumerationCache::default())
+        .manage(stt::ModelCache::default())
+        .manage(IdleTimer::new())
+        .manage(hotkey::PttLatchState::new())
         .invoke_handler(tauri::generate_handler![
             get_config,
             save_config,
+            export_config,
+            import_config,
             reset_config,
             get_has_api_key,
             save_api_key,
+            delete_api_key,
             validate_api_key,
+            validate_setup,
+            validate_config_file,
+            get_last_metrics,
+            get_history,
+            clear_history,
+            dictate_once,
+            run_self_test,
+            get_current_state,
+            start_recording_cmd,
+            stop_recording_cmd,
+            start_mic_test,
+            stop_mic_test,
+            get_audio_devices,
+            list_models,
             update_hotkey,
             get_result_text,
+            has_pending_paste,
+            accept_pending_paste,
+            discard_pending_paste,
             copy_to_clipboard,
         ])
         .setup(move |app| {
             tray::create_tray(app)?;
+            idle::spawn_idle_watcher(app.handle().clone());
 
-            if let Err(e) = hotkey::register_hotkey(app.handle(), &hotkey_str) {
+            if let Err(e) = hotkey::register_hotkeys(app.handle(), &hotkeys, &cancel_hotkey_str)
+            {
                 tracing::error!(error = %e, "failed to register hotkey, tray menu is available as fallback");
                 let config_path = crate::config::storage::config_dir()
                     .map(|d| d.join("config.json").display().to_string())
@@ -268,20 +795,140 @@ pub fn run() {
                 notifications::notify_error(
                     app.handle(),
                     &format!(
-                        "Failed to register hotkey: {}. Use tray menu instead. \
+                        "{}. Use tray menu instead. \
                          Change hotkey in: {}",
                         e, config_path
                     ),
                 );
             }
 
-            // Onboarding: открыть настройки при первом запуске (нет API-ключа)
-            if !config::secrets::has_api_key() {
+            // Onboarding: открыть настройки при первом запуске (нет нужного API-ключа)
+            if api_key_missing(&app.state::<Mutex<AppConfig>>()) {
                 open_settings_onboarding(app.handle());
             }
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Выход мог прервать вставку: Ctrl/Cmd зажат, в clipboard наш текст
+                paste::cleanup_on_exit();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn models(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_model_ids_should_return_sorted_ids() {
+        // Given
+        let body = br#"{"data": [{"id": "whisper-1"}, {"id": "gpt-4o-mini"}]}"#;
+
+        // When
+        let ids = parse_model_ids(body).unwrap();
+
+        // Then
+        assert_eq!(ids, models(&["gpt-4o-mini", "whisper-1"]));
+    }
+
+    #[test]
+    fn parse_model_ids_should_reject_non_openai_body() {
+        assert!(parse_model_ids(br#"{"models": ["llama3"]}"#).is_err());
+        assert!(parse_model_ids(b"<html>Not Found</html>").is_err());
+    }
+
+    #[test]
+    fn unknown_models_should_report_missing_once() {
+        // Given
+        let available = models(&["gpt-4o-mini", "whisper-1"]);
+        let wanted = models(&["whisper-1", "gpt-5", "gpt-5"]);
+
+        // When / Then
+        assert_eq!(unknown_models(&available, &wanted), models(&["gpt-5"]));
+    }
+
+    #[test]
+    fn wanted_models_should_skip_stt_model_for_offline_recognition() {
+        // Given
+        let config = AppConfig {
+            stt_provider: SttProviderKind::Offline,
+            enhance_enabled: true,
+            ..AppConfig::default()
+        };
+
+        // When / Then
+        assert_eq!(wanted_models(&config), vec![config.enhance_model.clone()]);
+    }
+
+    #[tokio::test]
+    async fn check_setup_should_leave_models_unverified_on_404_and_405() {
+        for status in [404, 405] {
+            // Given: endpoint без GET /v1/models
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/v1/models"))
+                .respond_with(ResponseTemplate::new(status))
+                .mount(&server)
+                .await;
+
+            // When
+            let result = check_setup(&server.uri(), "key", &models(&["whisper-1"])).await;
+
+            // Then
+            assert!(result.connectivity_ok, "status {status}");
+            assert!(result.key_valid, "status {status}");
+            assert!(!result.models_verified, "status {status}");
+            assert_eq!(result.error, None, "status {status}");
+        }
+    }
+
+    #[tokio::test]
+    async fn check_setup_should_report_unknown_models() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": [{ "id": "whisper-1" }] })),
+            )
+            .mount(&server)
+            .await;
+
+        // When
+        let result = check_setup(&server.uri(), "key", &models(&["whisper-1", "gpt-5"])).await;
+
+        // Then
+        assert!(result.models_verified);
+        assert_eq!(result.unknown_models, models(&["gpt-5"]));
+        assert!(result.error.unwrap().contains("gpt-5"));
+    }
+
+    #[tokio::test]
+    async fn check_setup_should_report_rejected_key() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        // When
+        let result = check_setup(&server.uri(), "bad", &[]).await;
+
+        // Then
+        assert!(result.connectivity_ok);
+        assert!(!result.key_valid);
+    }
 }
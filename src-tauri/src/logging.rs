@@ -1,29 +1,53 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use tracing_appender::rolling;
 use tracing_subscriber::fmt;
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::config::schema::LOG_LEVELS;
+
+/// Префикс файлов лога; tracing-appender добавляет к нему дату `.YYYY-MM-DD`.
+const LOG_FILE_PREFIX: &str = "voicedictator.log";
+
+/// Уровень до загрузки конфига и для неизвестных значений `log_level`.
+const DEFAULT_LOG_LEVEL: &str = "info";
+
+/// Handle для замены фильтра на лету (`log_level` из настроек).
+/// `None`, если уровень задан через RUST_LOG - тогда он главнее конфига.
+static LOG_FILTER: OnceLock<Option<reload::Handle<EnvFilter, Registry>>> = OnceLock::new();
+
+/// Каталог с файлами лога.
+fn log_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("voicedictator")
+        .join("logs")
+}
 
 /// Инициализирует систему логирования.
 ///
 /// Настраивает tracing-subscriber с выводом в файл (ротация по дням)
 /// и в stdout (только в debug-сборке).
-/// Уровень по умолчанию: info, переопределяется через RUST_LOG.
+/// Уровень по умолчанию: info; после загрузки конфига меняется через
+/// [`set_log_level`]. RUST_LOG, если задан, главнее `log_level`.
 pub fn init_logging() {
-    let app_name = "voicedictator";
-
-    let log_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(app_name)
-        .join("logs");
-
-    let file_appender = rolling::daily(&log_dir, "voicedictator.log");
+    let file_appender = rolling::daily(log_dir(), LOG_FILE_PREFIX);
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
 
     // _guard must be kept alive for the lifetime of the application.
     // We leak it intentionally to avoid dropping the writer.
     std::mem::forget(_guard);
 
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let env_filter = EnvFilter::try_from_default_env().ok();
+    let from_env = env_filter.is_some();
+    let (filter_layer, reload_handle) =
+        reload::Layer::new(env_filter.unwrap_or_else(|| EnvFilter::new(DEFAULT_LOG_LEVEL)));
+    let _ = LOG_FILTER.set((!from_env).then_some(reload_handle));
 
     let file_layer = fmt::layer()
         .with_writer(non_blocking)
@@ -32,7 +56,7 @@ pub fn init_logging() {
         .with_thread_ids(false);
 
     let registry = tracing_subscriber::registry()
-        .with(env_filter)
+        .with(filter_layer)
         .with(file_layer);
 
     #[cfg(debug_assertions)]
@@ -46,3 +70,169 @@ pub fn init_logging() {
         registry.init();
     }
 }
+
+/// Применяет `log_level` из конфига без перезапуска.
+///
+/// Неизвестный уровень заменяется на info. Ничего не делает, если уровень
+/// задан через RUST_LOG или логирование еще не инициализировано.
+pub fn set_log_level(level: &str) {
+    let Some(Some(handle)) = LOG_FILTER.get() else {
+        return;
+    };
+    let level = effective_level(level);
+    match handle.reload(EnvFilter::new(level)) {
+        Ok(()) => tracing::info!(level, "log level applied"),
+        Err(e) => tracing::warn!(error = %e, "failed to change log level"),
+    }
+}
+
+/// Уровень для фильтра: `level`, если он допустим, иначе info.
+fn effective_level(level: &str) -> &str {
+    if LOG_LEVELS.contains(&level) {
+        level
+    } else {
+        DEFAULT_LOG_LEVEL
+    }
+}
+
+/// Удаляет файлы лога старше `retention_days` дней (`log_retention_days`).
+///
+/// Вызывается при старте после загрузки конфига. 0 - хранить логи бессрочно.
+/// Ошибки только логируются: очистка не должна мешать запуску.
+pub fn cleanup_old_logs(retention_days: u32) {
+    if retention_days == 0 {
+        return;
+    }
+    let today = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| (d.as_secs() / 86_400) as i64)
+        .unwrap_or_default();
+    match remove_stale_logs(&log_dir(), today, retention_days) {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!(removed, retention_days, "old log files removed"),
+        Err(e) => tracing::warn!(error = %e, "failed to clean up old log files"),
+    }
+}
+
+/// Удаляет `voicedictator.log.YYYY-MM-DD` старше `retention_days` дней от `today`.
+///
+/// `today` - номер дня от Unix-эпохи (UTC, как у ротации tracing-appender).
+/// Сегодняшний (активный) файл и файлы с нераспознанным именем не трогаются.
+/// Возвращает число удаленных файлов.
+fn remove_stale_logs(dir: &Path, today: i64, retention_days: u32) -> io::Result<usize> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        let Some(day) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(log_file_day)
+        else {
+            continue;
+        };
+        if day < today && today - day > i64::from(retention_days) {
+            match fs::remove_file(&path) {
+                Ok(()) => removed += 1,
+                Err(e) => tracing::warn!(error = %e, path = ?path, "failed to remove old log file"),
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// День (от Unix-эпохи) из имени файла `voicedictator.log.YYYY-MM-DD`.
+fn log_file_day(file_name: &str) -> Option<i64> {
+    let date = file_name.strip_prefix(LOG_FILE_PREFIX)?.strip_prefix('.')?;
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// Номер дня от 1970-01-01 для даты григорианского календаря.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = i64::from((month + 9) % 12);
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn effective_level_should_fall_back_to_info_for_unknown_level() {
+        assert_eq!(effective_level("debug"), "debug");
+        assert_eq!(effective_level("verbose"), "info");
+        assert_eq!(effective_level(""), "info");
+    }
+
+    #[test]
+    fn log_file_day_should_parse_dated_names_only() {
+        assert_eq!(log_file_day("voicedictator.log.1970-01-01"), Some(0));
+        assert_eq!(log_file_day("voicedictator.log.2024-03-01"), Some(19_783));
+        assert_eq!(log_file_day("voicedictator.log"), None);
+        assert_eq!(log_file_day("voicedictator.log.2024-13-01"), None);
+        assert_eq!(log_file_day("other.log.2024-03-01"), None);
+    }
+
+    #[test]
+    fn remove_stale_logs_should_delete_only_files_older_than_retention() {
+        // Given: сегодня 2024-03-15, хранить 14 дней
+        let tmp = TempDir::new().unwrap();
+        let today = days_from_civil(2024, 3, 15);
+        let names = [
+            "voicedictator.log.2024-03-15",
+            "voicedictator.log.2024-03-01",
+            "voicedictator.log.2024-02-29",
+            "voicedictator.log.2023-12-31",
+            "notes.txt",
+        ];
+        for name in names {
+            fs::write(tmp.path().join(name), "log").unwrap();
+        }
+
+        // When
+        let removed = remove_stale_logs(tmp.path(), today, 14).unwrap();
+
+        // Then
+        assert_eq!(removed, 2);
+        let mut left: Vec<String> = fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            vec![
+                "notes.txt",
+                "voicedictator.log.2024-03-01",
+                "voicedictator.log.2024-03-15",
+            ]
+        );
+    }
+
+    #[test]
+    fn remove_stale_logs_should_ignore_missing_dir() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(
+            remove_stale_logs(&tmp.path().join("logs"), 0, 14).unwrap(),
+            0
+        );
+    }
+}
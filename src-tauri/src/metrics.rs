@@ -0,0 +1,126 @@
+//! Тайминги сессий диктовки: где тратится время от остановки записи до вставки.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Сколько последних сессий хранится в памяти.
+const METRICS_HISTORY_SIZE: usize = 20;
+
+/// Тайминги одной сессии диктовки (миллисекунды).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SessionMetrics {
+    /// Длительность записанного аудио
+    pub audio_ms: u64,
+    /// Препроцессинг и обрезка тишины
+    pub preprocess_ms: u64,
+    /// Распознавание (STT)
+    pub stt_ms: u64,
+    /// Улучшение текста; `None`, если enhance выключен
+    pub enhance_ms: Option<u64>,
+    /// Вставка; `None`, если текст ждет подтверждения в окне результата
+    pub paste_ms: Option<u64>,
+    /// Вся обработка: от остановки записи до доставки текста
+    pub total_ms: u64,
+}
+
+impl SessionMetrics {
+    /// Пишет одну структурированную строку лога с таймингами сессии.
+    pub fn log_summary(&self) {
+        tracing::info!(
+            audio_ms = self.audio_ms,
+            preprocess_ms = self.preprocess_ms,
+            stt_ms = self.stt_ms,
+            enhance_ms = ?self.enhance_ms,
+            paste_ms = ?self.paste_ms,
+            total_ms = self.total_ms,
+            "session metrics"
+        );
+    }
+}
+
+/// Длительность в миллисекундах для метрик.
+pub fn as_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Кольцевой буфер метрик последних сессий.
+pub struct MetricsHistory {
+    sessions: Mutex<VecDeque<SessionMetrics>>,
+}
+
+impl MetricsHistory {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(VecDeque::with_capacity(METRICS_HISTORY_SIZE)),
+        }
+    }
+
+    /// Добавляет метрики сессии, вытесняя самую старую при переполнении.
+    pub fn record(&self, metrics: SessionMetrics) {
+        let mut sessions = self.sessions.lock().expect("metrics mutex poisoned");
+        if sessions.len() == METRICS_HISTORY_SIZE {
+            sessions.pop_front();
+        }
+        sessions.push_back(metrics);
+    }
+
+    /// Метрики последней сессии.
+    pub fn last(&self) -> Option<SessionMetrics> {
+        self.sessions
+            .lock()
+            .expect("metrics mutex poisoned")
+            .back()
+            .cloned()
+    }
+
+    /// Число сохраненных сессий.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.sessions.lock().expect("metrics mutex poisoned").len()
+    }
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_should_return_none_without_sessions() {
+        assert_eq!(MetricsHistory::new().last(), None);
+    }
+
+    #[test]
+    fn record_should_keep_only_last_sessions() {
+        // Given
+        let history = MetricsHistory::new();
+
+        // When
+        for i in 0..METRICS_HISTORY_SIZE as u64 + 5 {
+            history.record(SessionMetrics {
+                total_ms: i,
+                ..Default::default()
+            });
+        }
+
+        // Then
+        assert_eq!(history.len(), METRICS_HISTORY_SIZE);
+        assert_eq!(
+            history.last().map(|m| m.total_ms),
+            Some(METRICS_HISTORY_SIZE as u64 + 4)
+        );
+    }
+
+    #[test]
+    fn as_ms_should_convert_duration() {
+        assert_eq!(as_ms(Duration::from_millis(3200)), 3200);
+    }
+}
@@ -3,11 +3,20 @@ use tauri::{AppHandle, Manager, Runtime};
 use crate::config::schema::AppConfig;
 use crate::state::AppState;
 
+/// Сколько символов вставленного текста показывать в уведомлении.
+const TEXT_PREVIEW_CHARS: usize = 80;
+
 /// Отправляет OS-уведомление о смене состояния.
 ///
 /// Проверяет `show_notifications` в конфиге. Если выключено - не отправляет.
+/// `text` - результат сессии для превью в "Text inserted" (только из pipeline).
 /// При ошибке отправки логирует warning, не блокирует pipeline.
-pub fn notify_state_change<R: Runtime>(app: &AppHandle<R>, old: AppState, new: AppState) {
+pub fn notify_state_change<R: Runtime>(
+    app: &AppHandle<R>,
+    old: AppState,
+    new: AppState,
+    text: Option<&str>,
+) {
     let config = app.state::<std::sync::Mutex<AppConfig>>();
     let show = config
         .lock()
@@ -17,8 +26,8 @@ pub fn notify_state_change<R: Runtime>(app: &AppHandle<R>, old: AppState, new: A
         return;
     }
 
-    if let Some((title, body)) = notification_text(old, new) {
-        if let Err(e) = send_notification(app, title, body) {
+    if let Some((title, body)) = notification_text(old, new, text) {
+        if let Err(e) = send_notification(app, title, &body) {
             tracing::warn!(error = %e, "failed to send notification");
         }
     }
@@ -44,22 +53,56 @@ pub fn notify_info<R: Runtime>(app: &AppHandle<R>, message: &str) {
     }
 }
 
-/// Возвращает (title, body) для уведомления при переходе состояний.
 /// Возвращает (title, body) для уведомления при переходе состояний.
 ///
 /// Уведомляем только о ключевых моментах (ТЗ FR-3): Recording started,
 /// Text inserted, Processing cancelled, Error. Промежуточные состояния
 /// (Transcribing, Enhancing, Pasting) не уведомляют - иначе спам.
+/// Для Pasting -> Idle к телу добавляется превью `text`, если он передан.
 /// Возвращает `None` если уведомление не нужно.
-fn notification_text(old: AppState, new: AppState) -> Option<(&'static str, &'static str)> {
-    match new {
-        AppState::Recording => Some(("VoiceDictator", "Recording started")),
-        AppState::Idle if old == AppState::Pasting => Some(("VoiceDictator", "Text inserted")),
-        AppState::Idle if old == AppState::Error => Some(("VoiceDictator", "Error dismissed")),
-        AppState::Idle => Some(("VoiceDictator", "Processing cancelled")),
-        AppState::Error => Some(("VoiceDictator", "An error occurred")),
+fn notification_text(
+    old: AppState,
+    new: AppState,
+    text: Option<&str>,
+) -> Option<(&'static str, String)> {
+    let body = match new {
+        AppState::Recording if old == AppState::Paused => "Recording resumed",
+        AppState::Recording => "Recording started",
+        AppState::Paused => "Recording paused",
+        AppState::Idle if old == AppState::Pasting => {
+            return Some(("VoiceDictator", text_inserted_body(text)));
+        }
+        AppState::Idle if old == AppState::Error => "Error dismissed",
+        AppState::Idle if old == AppState::Paused => "Recording discarded",
+        AppState::Idle => "Processing cancelled",
+        AppState::Error => "An error occurred",
         // Промежуточные: Transcribing, Enhancing, Pasting - без уведомлений
-        _ => None,
+        _ => return None,
+    };
+    Some(("VoiceDictator", body.to_string()))
+}
+
+/// Тело уведомления "Text inserted" с превью текста (если он не пустой).
+fn text_inserted_body(text: Option<&str>) -> String {
+    match text.filter(|t| !t.trim().is_empty()) {
+        Some(text) => format!(
+            "Text inserted: \"{}\"",
+            text_preview(text, TEXT_PREVIEW_CHARS)
+        ),
+        None => "Text inserted".to_string(),
+    }
+}
+
+/// Превью текста в одну строку: не более `max_chars` символов и "..." при обрезке.
+///
+/// Режет по символам, а не байтам, поэтому кириллица и эмодзи не ломаются.
+pub fn text_preview(text: &str, max_chars: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let preview: String = line.chars().take(max_chars).collect();
+    if preview.len() < line.len() {
+        format!("{preview}...")
+    } else {
+        preview
     }
 }
 
@@ -85,7 +128,7 @@ mod tests {
     #[test]
     fn notification_text_should_report_recording_started() {
         // Given / When
-        let result = notification_text(AppState::Idle, AppState::Recording);
+        let result = notification_text(AppState::Idle, AppState::Recording, None);
 
         // Then
         let (title, body) = result.expect("should produce notification");
@@ -93,10 +136,26 @@ mod tests {
         assert_eq!(body, "Recording started");
     }
 
+    #[test]
+    fn notification_text_should_report_pause_and_resume() {
+        assert_eq!(
+            notification_text(AppState::Recording, AppState::Paused, None),
+            Some(("VoiceDictator", "Recording paused".to_string()))
+        );
+        assert_eq!(
+            notification_text(AppState::Paused, AppState::Recording, None),
+            Some(("VoiceDictator", "Recording resumed".to_string()))
+        );
+        assert_eq!(
+            notification_text(AppState::Paused, AppState::Idle, None),
+            Some(("VoiceDictator", "Recording discarded".to_string()))
+        );
+    }
+
     #[test]
     fn notification_text_should_report_text_inserted_after_pasting() {
         // Given / When
-        let result = notification_text(AppState::Pasting, AppState::Idle);
+        let result = notification_text(AppState::Pasting, AppState::Idle, None);
 
         // Then
         let (title, body) = result.expect("should produce notification");
@@ -104,10 +163,35 @@ mod tests {
         assert_eq!(body, "Text inserted");
     }
 
+    #[test]
+    fn notification_text_should_include_preview_of_inserted_text() {
+        // Given
+        let text = "Привет,\nмир";
+
+        // When
+        let result = notification_text(AppState::Pasting, AppState::Idle, Some(text));
+
+        // Then
+        let (_, body) = result.expect("should produce notification");
+        assert_eq!(body, "Text inserted: \"Привет, мир\"");
+    }
+
+    #[test]
+    fn text_preview_should_truncate_by_chars_not_bytes() {
+        // Given: многобайтовые символы
+        let text = "ё".repeat(TEXT_PREVIEW_CHARS + 5);
+
+        // When
+        let preview = text_preview(&text, TEXT_PREVIEW_CHARS);
+
+        // Then
+        assert_eq!(preview, format!("{}...", "ё".repeat(TEXT_PREVIEW_CHARS)));
+    }
+
     #[test]
     fn notification_text_should_report_cancelled_when_idle_from_non_pasting() {
         // Given / When
-        let result = notification_text(AppState::Transcribing, AppState::Idle);
+        let result = notification_text(AppState::Transcribing, AppState::Idle, None);
 
         // Then
         let (title, body) = result.expect("should produce notification");
@@ -118,7 +202,7 @@ mod tests {
     #[test]
     fn notification_text_should_report_error_dismissed_when_idle_from_error() {
         // Given / When
-        let result = notification_text(AppState::Error, AppState::Idle);
+        let result = notification_text(AppState::Error, AppState::Idle, None);
 
         // Then
         let (title, body) = result.expect("should produce notification");
@@ -129,7 +213,7 @@ mod tests {
     #[test]
     fn notification_text_should_report_error() {
         // Given / When
-        let result = notification_text(AppState::Recording, AppState::Error);
+        let result = notification_text(AppState::Recording, AppState::Error, None);
 
         // Then
         let (_, body) = result.expect("should produce notification");
@@ -139,9 +223,9 @@ mod tests {
     #[test]
     fn notification_text_should_skip_intermediate_states() {
         // Промежуточные состояния (Transcribing, Enhancing, Pasting) не уведомляют
-        assert!(notification_text(AppState::Recording, AppState::Transcribing).is_none());
-        assert!(notification_text(AppState::Transcribing, AppState::Enhancing).is_none());
-        assert!(notification_text(AppState::Enhancing, AppState::Pasting).is_none());
+        assert!(notification_text(AppState::Recording, AppState::Transcribing, None).is_none());
+        assert!(notification_text(AppState::Transcribing, AppState::Enhancing, None).is_none());
+        assert!(notification_text(AppState::Enhancing, AppState::Pasting, None).is_none());
     }
 
     #[test]
@@ -155,7 +239,7 @@ mod tests {
         ];
 
         for (old, new) in transitions {
-            let (title, body) = notification_text(old, new)
+            let (title, body) = notification_text(old, new, None)
                 .unwrap_or_else(|| panic!("expected text for {:?} -> {:?}", old, new));
             assert!(!title.is_empty(), "empty title for {:?} -> {:?}", old, new);
             assert!(!body.is_empty(), "empty body for {:?} -> {:?}", old, new);
@@ -1,10 +1,14 @@
-use arboard::Clipboard;
+use std::sync::Mutex;
+
+use arboard::{Clipboard, ImageData};
 
 /// Состояние сохраненного содержимого clipboard.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum SavedClipboard {
     /// Clipboard содержал текст, который был успешно сохранен.
     Text(String),
+    /// Clipboard содержал изображение (только при включенном сохранении изображений).
+    Image(ImageData<'static>),
     /// Clipboard был пуст или содержал нетекстовые данные (изображение, файлы).
     /// При restore не трогаем clipboard - не хотим потерять non-text содержимое.
     NonTextOrEmpty,
@@ -18,6 +22,12 @@ const CLIPBOARD_RETRY_COUNT: u32 = 3;
 /// Задержка между retry (мс).
 const CLIPBOARD_RETRY_DELAY_MS: u64 = 50;
 
+/// Сохраненный clipboard вставки, которая еще не дошла до restore.
+///
+/// Если приложение закрывают во время паузы перед restore, содержимое
+/// восстанавливается при выходе (`restore_in_flight`).
+static IN_FLIGHT: Mutex<Option<SavedClipboard>> = Mutex::new(None);
+
 /// Менеджер буфера обмена с поддержкой save/restore.
 ///
 /// Сохраняет текущее содержимое clipboard перед записью нового текста,
@@ -25,6 +35,9 @@ const CLIPBOARD_RETRY_DELAY_MS: u64 = 50;
 pub struct ClipboardManager {
     clipboard: Clipboard,
     saved: SavedClipboard,
+    preserve_images: bool,
+    /// Сохраненное содержимое зарегистрировано в `IN_FLIGHT`
+    in_flight: bool,
 }
 
 impl ClipboardManager {
@@ -35,12 +48,23 @@ impl ClipboardManager {
         Ok(Self {
             clipboard,
             saved: SavedClipboard::NotSaved,
+            preserve_images: false,
+            in_flight: false,
         })
     }
 
-    /// Сохраняет текущее текстовое содержимое clipboard.
+    /// Включает сохранение изображений при save/restore.
+    ///
+    /// Чтение и запись изображения заметно дороже текста, поэтому по умолчанию выключено.
+    pub fn with_image_preservation(mut self, enabled: bool) -> Self {
+        self.preserve_images = enabled;
+        self
+    }
+
+    /// Сохраняет текущее содержимое clipboard.
     ///
     /// - Текст -> сохраняется для последующего restore.
+    /// - Изображение (если включено `with_image_preservation`) -> сохраняется как `Image`.
     /// - Нет текста / пустой / non-text -> запоминает `NonTextOrEmpty` (restore будет no-op).
     /// - `ClipboardOccupied` -> retry с backoff (до `CLIPBOARD_RETRY_COUNT` попыток).
     /// - Прочие ошибки -> пробрасываются вверх.
@@ -51,17 +75,47 @@ impl ClipboardManager {
                 self.saved = SavedClipboard::Text(text);
             }
             Err(arboard::Error::ContentNotAvailable) => {
-                tracing::debug!("Clipboard has no text content, save as NonTextOrEmpty");
-                self.saved = SavedClipboard::NonTextOrEmpty;
+                self.saved = self.save_non_text();
             }
             Err(e) => {
                 tracing::warn!("Clipboard save failed: {e}");
                 return Err(super::PasteError::ClipboardUnavailable(e.to_string()));
             }
         }
+        if matches!(
+            self.saved,
+            SavedClipboard::Text(_) | SavedClipboard::Image(_)
+        ) {
+            *IN_FLIGHT.lock().expect("clipboard mutex poisoned") = Some(self.saved.clone());
+            self.in_flight = true;
+        }
         Ok(())
     }
 
+    /// Сохраняет изображение, если оно есть и сохранение изображений включено.
+    ///
+    /// Ошибка чтения изображения не прерывает вставку: restore просто будет no-op.
+    fn save_non_text(&mut self) -> SavedClipboard {
+        if !self.preserve_images {
+            tracing::debug!("Clipboard has no text content, save as NonTextOrEmpty");
+            return SavedClipboard::NonTextOrEmpty;
+        }
+        match self.clipboard.get_image() {
+            Ok(image) => {
+                tracing::debug!("Clipboard image saved ({}x{})", image.width, image.height);
+                SavedClipboard::Image(image)
+            }
+            Err(arboard::Error::ContentNotAvailable) => {
+                tracing::debug!("Clipboard has no text or image content, save as NonTextOrEmpty");
+                SavedClipboard::NonTextOrEmpty
+            }
+            Err(e) => {
+                tracing::warn!("Clipboard image save failed: {e}, save as NonTextOrEmpty");
+                SavedClipboard::NonTextOrEmpty
+            }
+        }
+    }
+
     /// Записывает текст в clipboard.
     pub fn write(&mut self, text: &str) -> super::Result<()> {
         self.clipboard
@@ -74,10 +128,24 @@ impl ClipboardManager {
     /// Восстанавливает ранее сохраненное содержимое clipboard.
     ///
     /// - `Text(s)` -> записывает сохраненный текст обратно.
+    /// - `Image(img)` -> записывает сохраненное изображение обратно.
     /// - `NonTextOrEmpty` -> no-op (не трогаем clipboard, чтобы не потерять non-text данные).
     /// - `NotSaved` -> no-op (save не вызывался).
     pub fn restore(&mut self) -> super::Result<()> {
+        self.clear_in_flight();
         let saved = std::mem::replace(&mut self.saved, SavedClipboard::NotSaved);
+        self.put(saved)
+    }
+
+    /// Снимает регистрацию в `IN_FLIGHT`: вставка завершена или restore уже идет.
+    fn clear_in_flight(&mut self) {
+        if std::mem::take(&mut self.in_flight) {
+            *IN_FLIGHT.lock().expect("clipboard mutex poisoned") = None;
+        }
+    }
+
+    /// Записывает сохраненное содержимое в clipboard.
+    fn put(&mut self, saved: SavedClipboard) -> super::Result<()> {
         match saved {
             SavedClipboard::Text(content) => {
                 self.clipboard
@@ -85,6 +153,13 @@ impl ClipboardManager {
                     .map_err(|e| super::PasteError::ClipboardWrite(e.to_string()))?;
                 tracing::debug!("Clipboard content restored ({} chars)", content.len());
             }
+            SavedClipboard::Image(image) => {
+                let (width, height) = (image.width, image.height);
+                self.clipboard
+                    .set_image(image)
+                    .map_err(|e| super::PasteError::ClipboardWrite(e.to_string()))?;
+                tracing::debug!("Clipboard image restored ({width}x{height})");
+            }
             SavedClipboard::NonTextOrEmpty => {
                 tracing::debug!("Clipboard had non-text/empty content, skipping restore");
             }
@@ -109,6 +184,19 @@ impl ClipboardManager {
         }
     }
 
+    /// Восстанавливает clipboard незавершенной вставки (выход во время паузы перед restore).
+    ///
+    /// Возвращает `true`, если было что восстанавливать.
+    pub fn restore_in_flight() -> super::Result<bool> {
+        let Some(saved) = IN_FLIGHT.lock().expect("clipboard mutex poisoned").take() else {
+            return Ok(false);
+        };
+        let mut manager = Self::new()?;
+        manager.put(saved)?;
+        tracing::info!("Clipboard restored on shutdown");
+        Ok(true)
+    }
+
     /// Читает текст из clipboard с retry при `ClipboardOccupied`.
     fn get_text_with_retry(&mut self) -> std::result::Result<String, arboard::Error> {
         let mut last_err = arboard::Error::ContentNotAvailable;
@@ -133,6 +221,14 @@ impl ClipboardManager {
     }
 }
 
+impl Drop for ClipboardManager {
+    /// Вставка закончилась (в том числе с текстом, оставленным в clipboard
+    /// для ручной вставки): при выходе восстанавливать нечего.
+    fn drop(&mut self) {
+        self.clear_in_flight();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
@@ -163,6 +259,16 @@ mod tests {
         }
     }
 
+    #[test]
+    #[serial]
+    fn restore_in_flight_should_do_nothing_without_pending_paste() {
+        // Given
+        *IN_FLIGHT.lock().unwrap() = None;
+
+        // When / Then
+        assert!(!ClipboardManager::restore_in_flight().unwrap());
+    }
+
     #[test]
     #[serial]
     fn new_should_create_clipboard_manager() {
@@ -341,4 +447,75 @@ mod tests {
         let final_content = manager.read().unwrap();
         assert_eq!(final_content, Some("user's important data".to_string()));
     }
+
+    /// Изображение 2x2 RGBA для тестов.
+    fn test_image() -> ImageData<'static> {
+        ImageData {
+            width: 2,
+            height: 2,
+            bytes: vec![255u8; 16].into(),
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn save_should_categorize_image_clipboard_as_image() {
+        // Given
+        let _guard = ClipboardTestGuard::new();
+        let mut manager = ClipboardManager::new()
+            .unwrap()
+            .with_image_preservation(true);
+        if manager.clipboard.set_image(test_image()).is_err() {
+            return; // окружение без поддержки изображений в clipboard
+        }
+
+        // When
+        manager.save().unwrap();
+
+        // Then
+        assert!(
+            matches!(manager.saved, SavedClipboard::Image(_)),
+            "expected Image, got {:?}",
+            manager.saved
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn save_should_skip_image_when_preservation_disabled() {
+        // Given
+        let _guard = ClipboardTestGuard::new();
+        let mut manager = ClipboardManager::new().unwrap();
+        if manager.clipboard.set_image(test_image()).is_err() {
+            return;
+        }
+
+        // When
+        manager.save().unwrap();
+
+        // Then
+        assert!(matches!(manager.saved, SavedClipboard::NonTextOrEmpty));
+    }
+
+    #[test]
+    #[serial]
+    fn save_and_restore_should_preserve_image() {
+        // Given
+        let _guard = ClipboardTestGuard::new();
+        let mut manager = ClipboardManager::new()
+            .unwrap()
+            .with_image_preservation(true);
+        if manager.clipboard.set_image(test_image()).is_err() {
+            return;
+        }
+
+        // When
+        manager.save().unwrap();
+        manager.write("dictated text").unwrap();
+        manager.restore().unwrap();
+
+        // Then
+        let image = manager.clipboard.get_image().unwrap();
+        assert_eq!((image.width, image.height), (2, 2));
+    }
 }
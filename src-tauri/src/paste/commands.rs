@@ -0,0 +1,349 @@
+//! Режим голосовых команд: фразы "new line", "scratch that", "comma" и т.п.
+//! превращаются в нажатия клавиш и правки вместо вставки буквального текста.
+//!
+//! Разбор (`parse_commands`) отделен от выполнения (`execute`): разбор -
+//! чистая функция, выполнение идет через [`Paster`].
+
+use std::sync::Mutex;
+
+use super::{paste_by_method, segment, PasteOptions, PasteStatus, Paster};
+use crate::config::schema::PasteSuffix;
+
+/// Сколько графем вставила последняя команда-режимная вставка
+/// (для "scratch that" в следующей диктовке).
+static LAST_INSERTED: Mutex<usize> = Mutex::new(0);
+
+/// Клавиша, которую нажимает команда.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKey {
+    Enter,
+    Backspace,
+}
+
+/// Действие, полученное из распознанного текста.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Обычный текст, вставляется выбранным способом
+    Text(String),
+    /// Enter
+    NewLine,
+    /// Два Enter
+    NewParagraph,
+    /// Удалить последний вставленный фрагмент (предыдущей диктовки)
+    DeleteLast,
+}
+
+/// Команда из таблицы фраз.
+#[derive(Debug, Clone, Copy)]
+enum Command {
+    NewLine,
+    NewParagraph,
+    DeleteLast,
+    Punctuation(char),
+}
+
+/// Фразы команд (слова в нижнем регистре, без пунктуации).
+const COMMANDS: &[(&[&str], Command)] = &[
+    (&["new", "line"], Command::NewLine),
+    (&["new", "paragraph"], Command::NewParagraph),
+    (&["scratch", "that"], Command::DeleteLast),
+    (&["delete", "that"], Command::DeleteLast),
+    (&["comma"], Command::Punctuation(',')),
+    (&["period"], Command::Punctuation('.')),
+    (&["full", "stop"], Command::Punctuation('.')),
+    (&["question", "mark"], Command::Punctuation('?')),
+    (&["exclamation", "mark"], Command::Punctuation('!')),
+    (&["colon"], Command::Punctuation(':')),
+    (&["новая", "строка"], Command::NewLine),
+    (&["новый", "абзац"], Command::NewParagraph),
+    (&["удали", "это"], Command::DeleteLast),
+    (&["запятая"], Command::Punctuation(',')),
+];
+
+/// Разбирает текст на действия.
+///
+/// Слова между командами склеиваются через пробел в `Action::Text`;
+/// пунктуация приклеивается к предыдущему слову. "scratch that" сразу после
+/// текста той же диктовки просто отбрасывает этот текст, иначе дает
+/// `Action::DeleteLast`. Регистр и пунктуация вокруг фразы команды
+/// (STT пишет "New line.") не мешают распознаванию.
+pub fn parse_commands(text: &str) -> Vec<Action> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|w| normalize_word(w)).collect();
+
+    let mut actions = Vec::new();
+    let mut buffer = String::new();
+    let mut i = 0;
+    while i < words.len() {
+        let Some((len, command)) = match_command(&normalized[i..]) else {
+            if !buffer.is_empty() {
+                buffer.push(' ');
+            }
+            buffer.push_str(words[i]);
+            i += 1;
+            continue;
+        };
+        i += len;
+
+        match command {
+            Command::Punctuation(mark) => {
+                let trimmed = buffer.trim_end_matches(|c: char| c.is_ascii_punctuation());
+                buffer.truncate(trimmed.len());
+                buffer.push(mark);
+            }
+            Command::DeleteLast if !buffer.is_empty() => buffer.clear(),
+            Command::DeleteLast => {
+                flush(&mut buffer, &mut actions);
+                actions.push(Action::DeleteLast);
+            }
+            Command::NewLine => {
+                flush(&mut buffer, &mut actions);
+                actions.push(Action::NewLine);
+            }
+            Command::NewParagraph => {
+                flush(&mut buffer, &mut actions);
+                actions.push(Action::NewParagraph);
+            }
+        }
+    }
+    flush(&mut buffer, &mut actions);
+    actions
+}
+
+/// Выполняет действия через `paster`.
+///
+/// Текст вставляется `options.method` (суффикс - только если диктовка
+/// закончилась текстом). `DeleteLast` стирает Backspace-ами последний
+/// вставленный фрагмент: из этой диктовки или `last_inserted` графем
+/// предыдущей. После выполнения `last_inserted` - размер последнего фрагмента.
+/// На первой неудачной вставке выполнение останавливается.
+pub fn execute(
+    paster: &mut impl Paster,
+    actions: &[Action],
+    options: &PasteOptions,
+    last_inserted: &mut usize,
+) -> PasteStatus {
+    for (index, action) in actions.iter().enumerate() {
+        let result = match action {
+            Action::Text(text) => {
+                let status = paste_by_method(paster, text, options);
+                if status != PasteStatus::Pasted {
+                    return status;
+                }
+                *last_inserted = segment::chunk_text(text, 1).len();
+                let is_last = index + 1 == actions.len();
+                if is_last && options.suffix != PasteSuffix::None {
+                    match paster.send_suffix(options.suffix) {
+                        Ok(()) => *last_inserted += 1,
+                        Err(e) => {
+                            tracing::warn!("Failed to send paste suffix {:?}: {e}", options.suffix)
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Action::NewLine => paster.press_key(EditKey::Enter, 1),
+            Action::NewParagraph => paster.press_key(EditKey::Enter, 2),
+            Action::DeleteLast => {
+                let count = std::mem::take(last_inserted);
+                paster.press_key(EditKey::Backspace, count)
+            }
+        };
+        if let Err(e) = result {
+            tracing::warn!("Dictation command {action:?} failed: {e}");
+            return PasteStatus::ClipboardOnly;
+        }
+    }
+    PasteStatus::Pasted
+}
+
+/// Разбирает и выполняет команды, запоминая размер вставки между диктовками.
+pub fn paste_with_commands(
+    paster: &mut impl Paster,
+    text: &str,
+    options: &PasteOptions,
+) -> PasteStatus {
+    let actions = parse_commands(text);
+    tracing::info!("Executing {} dictation command action(s)", actions.len());
+    let mut last_inserted = LAST_INSERTED.lock().unwrap_or_else(|e| e.into_inner());
+    execute(paster, &actions, options, &mut last_inserted)
+}
+
+fn match_command(words: &[String]) -> Option<(usize, Command)> {
+    COMMANDS
+        .iter()
+        .filter(|(phrase, _)| {
+            words.len() >= phrase.len() && phrase.iter().zip(words).all(|(p, w)| p == w)
+        })
+        .max_by_key(|(phrase, _)| phrase.len())
+        .map(|(phrase, command)| (phrase.len(), *command))
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn flush(buffer: &mut String, actions: &mut Vec<Action>) {
+    if !buffer.is_empty() {
+        actions.push(Action::Text(std::mem::take(buffer)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::PasteMethod;
+    use crate::paste::{Result, TypingOptions};
+    use std::time::Duration;
+
+    fn text(s: &str) -> Action {
+        Action::Text(s.to_string())
+    }
+
+    #[test]
+    fn parse_should_keep_plain_text_as_single_action() {
+        assert_eq!(
+            parse_commands("Hello there, how are you?"),
+            vec![text("Hello there, how are you?")]
+        );
+    }
+
+    #[test]
+    fn parse_should_split_text_on_new_line_and_paragraph() {
+        // When
+        let actions = parse_commands("First line. New line. Second line new paragraph Third");
+
+        // Then
+        assert_eq!(
+            actions,
+            vec![
+                text("First line."),
+                Action::NewLine,
+                text("Second line"),
+                Action::NewParagraph,
+                text("Third"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_should_attach_punctuation_to_previous_word() {
+        assert_eq!(
+            parse_commands("hello comma world question mark"),
+            vec![text("hello, world?")]
+        );
+        assert_eq!(
+            parse_commands("привет запятая мир"),
+            vec![text("привет, мир")]
+        );
+    }
+
+    #[test]
+    fn parse_should_drop_text_of_same_utterance_on_scratch_that() {
+        assert_eq!(
+            parse_commands("wrong words scratch that right words"),
+            vec![text("right words")]
+        );
+    }
+
+    #[test]
+    fn parse_should_emit_delete_last_when_scratch_that_leads() {
+        assert_eq!(
+            parse_commands("Scratch that. Better version"),
+            vec![Action::DeleteLast, text("Better version")]
+        );
+    }
+
+    /// Fake paster: вставка всегда успешна, журнал текстов и клавиш.
+    #[derive(Default)]
+    struct RecordingPaster {
+        log: Vec<String>,
+    }
+
+    impl Paster for RecordingPaster {
+        fn save_clipboard(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn write_clipboard(&mut self, text: &str) -> Result<()> {
+            self.log.push(format!("text:{text}"));
+            Ok(())
+        }
+
+        fn restore_clipboard(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn simulate_paste(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn simulate_paste_external(&mut self) -> Result<String> {
+            Ok("fake".to_string())
+        }
+
+        fn type_text(&mut self, _text: &str, _options: &TypingOptions) -> Result<()> {
+            Ok(())
+        }
+
+        fn send_suffix(&mut self, suffix: PasteSuffix) -> Result<()> {
+            self.log.push(format!("suffix:{suffix:?}"));
+            Ok(())
+        }
+
+        fn press_key(&mut self, key: EditKey, times: usize) -> Result<()> {
+            self.log.push(format!("{key:?}x{times}"));
+            Ok(())
+        }
+
+        fn pause(&mut self, _duration: Duration) {}
+    }
+
+    #[test]
+    fn execute_should_paste_text_and_press_keys_in_order() {
+        // Given
+        let mut paster = RecordingPaster::default();
+        let options = PasteOptions {
+            method: PasteMethod::Clipboard,
+            suffix: PasteSuffix::Space,
+            ..PasteOptions::default()
+        };
+        let mut last_inserted = 0;
+
+        // When
+        let status = execute(
+            &mut paster,
+            &parse_commands("one new paragraph two"),
+            &options,
+            &mut last_inserted,
+        );
+
+        // Then: суффикс только после последнего фрагмента
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(
+            paster.log,
+            vec!["text:one", "Enterx2", "text:two", "suffix:Space"]
+        );
+        assert_eq!(last_inserted, 4);
+    }
+
+    #[test]
+    fn execute_should_backspace_previous_dictation_on_delete_last() {
+        // Given: предыдущая диктовка вставила 6 графем
+        let mut paster = RecordingPaster::default();
+        let mut last_inserted = 6;
+
+        // When
+        execute(
+            &mut paster,
+            &[Action::DeleteLast, Action::DeleteLast],
+            &PasteOptions::default(),
+            &mut last_inserted,
+        );
+
+        // Then: второй раз стирать уже нечего
+        assert_eq!(paster.log, vec!["Backspacex6", "Backspacex0"]);
+        assert_eq!(last_inserted, 0);
+    }
+}
@@ -1,11 +1,55 @@
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "linux")]
+use std::process::Command;
 use std::thread;
 use std::time::Duration;
 
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
 
+use super::commands::EditKey;
+use super::segment;
+use crate::config::schema::{AppConfig, PasteSuffix};
+
 /// Задержка между нажатием модификатора и клавиши (мс).
 const KEY_DELAY_MS: u64 = 50;
 
+/// Размер части по умолчанию при посимвольном вводе (графемы).
+const DEFAULT_TYPE_CHUNK_SIZE: usize = 32;
+
+/// Пауза между частями по умолчанию при посимвольном вводе (мс).
+const DEFAULT_TYPE_CHUNK_DELAY_MS: u64 = 20;
+
+/// Параметры посимвольного ввода текста.
+///
+/// Некоторые приложения теряют символы при слишком быстром вводе,
+/// поэтому длинный текст вводится частями с паузами.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TypingOptions {
+    /// Максимум графем в одной части
+    pub chunk_size: usize,
+    /// Пауза между частями
+    pub chunk_delay: Duration,
+}
+
+impl Default for TypingOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_TYPE_CHUNK_SIZE,
+            chunk_delay: Duration::from_millis(DEFAULT_TYPE_CHUNK_DELAY_MS),
+        }
+    }
+}
+
+impl TypingOptions {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            chunk_size: config.type_chunk_size as usize,
+            chunk_delay: Duration::from_millis(u64::from(config.type_chunk_delay_ms)),
+        }
+    }
+}
+
 /// Guard, гарантирующий отпускание клавиши-модификатора при Drop.
 ///
 /// Если ошибка произойдет после Press, но до Release, guard отпустит
@@ -28,7 +72,7 @@ impl<'a> ModifierGuard<'a> {
     fn press(&mut self) -> super::Result<()> {
         self.enigo
             .key(self.key, Direction::Press)
-            .map_err(|e| super::PasteError::InputSimulation(e.to_string()))?;
+            .map_err(simulation_error)?;
         self.pressed = true;
         Ok(())
     }
@@ -36,7 +80,7 @@ impl<'a> ModifierGuard<'a> {
     fn release(&mut self) -> super::Result<()> {
         self.enigo
             .key(self.key, Direction::Release)
-            .map_err(|e| super::PasteError::InputSimulation(e.to_string()))?;
+            .map_err(simulation_error)?;
         self.pressed = false;
         Ok(())
     }
@@ -58,8 +102,7 @@ impl Drop for ModifierGuard<'_> {
 /// На macOS вместо Control используется Meta (Command).
 /// Модификатор гарантированно отпускается даже при ошибках (через guard).
 pub fn simulate_paste() -> super::Result<()> {
-    let mut enigo = Enigo::new(&Settings::default())
-        .map_err(|e| super::PasteError::InputSimulation(e.to_string()))?;
+    let mut enigo = Enigo::new(&Settings::default()).map_err(simulation_error)?;
 
     let modifier = paste_modifier_key();
     tracing::debug!("Simulating paste with modifier {:?}", modifier);
@@ -72,7 +115,7 @@ pub fn simulate_paste() -> super::Result<()> {
     guard
         .enigo
         .key(paste_v_key(), Direction::Click)
-        .map_err(|e| super::PasteError::InputSimulation(e.to_string()))?;
+        .map_err(simulation_error)?;
 
     thread::sleep(Duration::from_millis(KEY_DELAY_MS));
     guard.release()?;
@@ -81,6 +124,174 @@ pub fn simulate_paste() -> super::Result<()> {
     Ok(())
 }
 
+/// Отпускает модификатор вставки (Ctrl/Cmd) на случай, если выход
+/// прервал `simulate_paste` между Press и Release. Best-effort.
+pub fn release_paste_modifier() -> super::Result<()> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(simulation_error)?;
+    enigo
+        .key(paste_modifier_key(), Direction::Release)
+        .map_err(simulation_error)
+}
+
+/// Отправляет суффикс после вставки: Enter или пробел.
+///
+/// Вызывается после того, как `simulate_paste` отпустил модификатор, и
+/// использует отдельный экземпляр enigo без зажатых клавиш - ошибка здесь
+/// не может оставить Ctrl/Cmd нажатым.
+pub fn send_suffix(suffix: PasteSuffix) -> super::Result<()> {
+    if suffix == PasteSuffix::None {
+        return Ok(());
+    }
+    let mut enigo = Enigo::new(&Settings::default()).map_err(simulation_error)?;
+
+    tracing::debug!(?suffix, "Sending paste suffix");
+    match suffix {
+        PasteSuffix::Newline => enigo.key(Key::Return, Direction::Click),
+        PasteSuffix::Space => enigo.text(" "),
+        PasteSuffix::None => Ok(()),
+    }
+    .map_err(simulation_error)
+}
+
+/// Нажимает клавишу голосовой команды `times` раз (Enter, Backspace).
+pub fn press_key(key: EditKey, times: usize) -> super::Result<()> {
+    if times == 0 {
+        return Ok(());
+    }
+    let mut enigo = Enigo::new(&Settings::default()).map_err(simulation_error)?;
+
+    tracing::debug!(?key, times, "Pressing command key");
+    let key = match key {
+        EditKey::Enter => Key::Return,
+        EditKey::Backspace => Key::Backspace,
+    };
+    for _ in 0..times {
+        enigo.key(key, Direction::Click).map_err(simulation_error)?;
+        thread::sleep(Duration::from_millis(KEY_DELAY_MS));
+    }
+    Ok(())
+}
+
+/// Вводит текст напрямую (без clipboard) с параметрами по умолчанию.
+#[allow(dead_code)]
+pub fn type_text(text: &str) -> super::Result<()> {
+    type_text_with(text, &TypingOptions::default())
+}
+
+/// Вводит текст напрямую частями по `chunk_size` графем с паузой `chunk_delay`.
+///
+/// Графемы (эмодзи, буквы с диакритикой) не разрываются между частями.
+pub fn type_text_with(text: &str, options: &TypingOptions) -> super::Result<()> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(simulation_error)?;
+
+    let chunks = segment::chunk_text(text, options.chunk_size);
+    tracing::debug!(chunks = chunks.len(), "typing text directly");
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        if i > 0 && !options.chunk_delay.is_zero() {
+            thread::sleep(options.chunk_delay);
+        }
+        enigo.text(chunk).map_err(simulation_error)?;
+    }
+
+    Ok(())
+}
+
+/// Внешняя утилита симуляции Ctrl+V (Wayland, где enigo не справляется).
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExternalPasteTool {
+    Wtype,
+    Ydotool,
+}
+
+#[cfg(target_os = "linux")]
+impl ExternalPasteTool {
+    /// Определяет утилиту по имени файла программы.
+    fn from_program(program: &str) -> Option<Self> {
+        match Path::new(program).file_name()?.to_str()? {
+            "wtype" => Some(Self::Wtype),
+            "ydotool" => Some(Self::Ydotool),
+            _ => None,
+        }
+    }
+
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            Self::Wtype => &["-M", "ctrl", "v", "-m", "ctrl"],
+            // KEY_LEFTCTRL = 29, KEY_V = 47 (linux/input-event-codes.h)
+            Self::Ydotool => &["key", "29:1", "47:1", "47:0", "29:0"],
+        }
+    }
+}
+
+/// Утилиты, которые ищутся в PATH при пустом `wayland_paste_tool` (в порядке приоритета).
+#[cfg(target_os = "linux")]
+const AUTO_DETECT_PASTE_TOOLS: [&str; 2] = ["wtype", "ydotool"];
+
+/// Симулирует Ctrl+V внешней утилитой (wtype или ydotool).
+///
+/// `tool` - имя или путь к утилите; пустая строка - первая найденная в PATH.
+/// Возвращает программу, через которую выполнена вставка.
+#[cfg(target_os = "linux")]
+pub fn simulate_paste_external(tool: &str) -> super::Result<String> {
+    let program = resolve_paste_tool(tool).ok_or_else(|| {
+        super::PasteError::InputSimulation(
+            "no external paste tool available (wtype, ydotool)".to_string(),
+        )
+    })?;
+    let kind = ExternalPasteTool::from_program(&program).ok_or_else(|| {
+        super::PasteError::InputSimulation(format!("unsupported paste tool: {program}"))
+    })?;
+
+    tracing::debug!(program = %program, "Simulating paste with external tool");
+    let output = Command::new(&program)
+        .args(kind.args())
+        .output()
+        .map_err(|e| super::PasteError::InputSimulation(format!("{program}: {e}")))?;
+
+    if !output.status.success() {
+        return Err(super::PasteError::InputSimulation(format!(
+            "{program} exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(program)
+}
+
+/// Находит утилиту вставки: заданную в конфиге или первую доступную из известных.
+#[cfg(target_os = "linux")]
+fn resolve_paste_tool(tool: &str) -> Option<String> {
+    let tool = tool.trim();
+    if tool.is_empty() {
+        return AUTO_DETECT_PASTE_TOOLS
+            .iter()
+            .find(|name| find_in_path(name).is_some())
+            .map(|name| name.to_string());
+    }
+    let available = if tool.contains('/') {
+        Path::new(tool).is_file()
+    } else {
+        find_in_path(tool).is_some()
+    };
+    available.then(|| tool.to_string())
+}
+
+/// Ищет исполняемый файл `name` в каталогах PATH.
+#[cfg(target_os = "linux")]
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Преобразует ошибку enigo в `PasteError::InputSimulation`.
+fn simulation_error(e: impl std::fmt::Display) -> super::PasteError {
+    super::PasteError::InputSimulation(e.to_string())
+}
+
 /// Возвращает клавишу-модификатор для вставки в зависимости от ОС.
 fn paste_modifier_key() -> Key {
     if cfg!(target_os = "macos") {
@@ -110,6 +321,33 @@ fn paste_v_key() -> Key {
 mod tests {
     use super::*;
 
+    #[test]
+    fn typing_options_should_follow_config() {
+        // Given
+        let mut config = AppConfig::default();
+        config.type_chunk_size = 8;
+        config.type_chunk_delay_ms = 40;
+
+        // When
+        let options = TypingOptions::from_config(&config);
+
+        // Then
+        assert_eq!(options.chunk_size, 8);
+        assert_eq!(options.chunk_delay, Duration::from_millis(40));
+    }
+
+    #[test]
+    fn simulation_error_should_map_to_input_simulation() {
+        // Given / When
+        let err = simulation_error("no display");
+
+        // Then
+        assert!(
+            matches!(&err, super::super::PasteError::InputSimulation(msg) if msg == "no display")
+        );
+        assert_eq!(err.to_string(), "input simulation failed: no display");
+    }
+
     #[test]
     fn paste_modifier_key_should_return_control_on_windows() {
         // Given / When
@@ -134,4 +372,37 @@ mod tests {
         #[cfg(not(target_os = "windows"))]
         assert_eq!(key, Key::Unicode('v'));
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn external_paste_tool_should_be_detected_by_file_name() {
+        assert_eq!(
+            ExternalPasteTool::from_program("wtype"),
+            Some(ExternalPasteTool::Wtype)
+        );
+        assert_eq!(
+            ExternalPasteTool::from_program("/usr/local/bin/ydotool"),
+            Some(ExternalPasteTool::Ydotool)
+        );
+        assert_eq!(ExternalPasteTool::from_program("xdotool"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn resolve_paste_tool_should_reject_missing_path() {
+        assert_eq!(resolve_paste_tool("/nonexistent/dir/wtype"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn resolve_paste_tool_should_accept_existing_path() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("ydotool");
+        std::fs::write(&tool, "").unwrap();
+        let tool = tool.to_str().unwrap();
+
+        // When / Then
+        assert_eq!(resolve_paste_tool(tool).as_deref(), Some(tool));
+    }
 }
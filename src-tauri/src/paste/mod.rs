@@ -1,10 +1,16 @@
 pub mod clipboard;
+pub mod commands;
 pub mod input;
+pub mod segment;
 
+use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
 pub use self::clipboard::ClipboardManager;
+use self::commands::EditKey;
+pub use self::input::TypingOptions;
+use crate::config::schema::{AppConfig, PasteMethod, PasteSuffix};
 
 /// Задержка перед симуляцией Ctrl+V (мс).
 ///
@@ -12,11 +18,25 @@ pub use self::clipboard::ClipboardManager;
 /// и стабилизацию фокуса окна.
 const PRE_PASTE_DELAY_MS: u64 = 150;
 
+/// Сколько раз повторить Ctrl+V после неудачной симуляции.
+///
+/// Первое нажатие после смены фокуса иногда не срабатывает, второе - проходит.
+const PASTE_RETRY_COUNT: u32 = 1;
+
+/// Пауза перед повторной симуляцией Ctrl+V (мс).
+const PASTE_RETRY_DELAY_MS: u64 = 100;
+
+/// Пауза между вставками сегментов длинного текста (мс).
+const SEGMENT_DELAY_MS: u64 = 100;
+
 /// Задержка перед восстановлением clipboard (мс).
 ///
 /// Дает приложению-получателю время обработать Ctrl+V.
 const RESTORE_DELAY_MS: u64 = 500;
 
+/// Сколько ждать очистки при выходе, прежде чем закрыть приложение (мс).
+const SHUTDOWN_CLEANUP_TIMEOUT_MS: u64 = 500;
+
 /// Ошибки модуля вставки текста.
 #[derive(Debug, thiserror::Error)]
 pub enum PasteError {
@@ -35,7 +55,7 @@ pub type Result<T> = std::result::Result<T, PasteError>;
 /// Результат операции вставки.
 #[derive(Debug, Clone, PartialEq)]
 pub enum PasteStatus {
-    /// Текст вставлен через Ctrl+V/Cmd+V, clipboard восстановлен.
+    /// Текст вставлен (Ctrl+V/Cmd+V или посимвольным вводом), clipboard восстановлен.
     Pasted,
     /// Текст записан в clipboard, но симуляция клавиш не удалась.
     /// Пользователь должен вставить вручную (Ctrl+V).
@@ -44,7 +64,259 @@ pub enum PasteStatus {
     ResultWindow,
 }
 
-/// Вставляет текст в активное поле ввода.
+/// Итог вставки через clipboard с учетом сегментов.
+#[derive(Debug, Clone, PartialEq)]
+struct ClipboardPaste<'a> {
+    status: PasteStatus,
+    /// Сколько графем вставлено до сбоя (все - при `Pasted`).
+    pasted_graphemes: usize,
+    /// Не вставленный остаток текста (пусто при `Pasted`).
+    remaining: &'a str,
+}
+
+/// Параметры вставки текста.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PasteOptions {
+    pub method: PasteMethod,
+    pub typing: TypingOptions,
+    /// Внешняя утилита вставки (Linux/Wayland); пусто - автоопределение
+    pub paste_tool: String,
+    /// Сохранять и восстанавливать изображение в clipboard
+    pub preserve_images: bool,
+    /// Суффикс после успешной вставки
+    pub suffix: PasteSuffix,
+    /// Размер сегмента (графем) при вставке через clipboard; 0 - без деления
+    pub segment_size: usize,
+    /// Выполнять голосовые команды ("new line", "scratch that") вместо вставки их текста
+    pub command_mode: bool,
+}
+
+impl PasteOptions {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            method: config.paste_method,
+            typing: TypingOptions::from_config(config),
+            paste_tool: config.wayland_paste_tool.clone(),
+            preserve_images: config.preserve_clipboard_images,
+            suffix: config.paste_suffix,
+            segment_size: config.paste_segment_chars as usize,
+            command_mode: config.command_mode,
+        }
+    }
+}
+
+/// Платформенные операции вставки (seam для тестов).
+pub trait Paster {
+    fn save_clipboard(&mut self) -> Result<()>;
+    fn write_clipboard(&mut self, text: &str) -> Result<()>;
+    fn restore_clipboard(&mut self) -> Result<()>;
+    fn simulate_paste(&mut self) -> Result<()>;
+    /// Ctrl+V внешней утилитой; возвращает имя использованного backend.
+    fn simulate_paste_external(&mut self) -> Result<String>;
+    fn type_text(&mut self, text: &str, options: &TypingOptions) -> Result<()>;
+    fn send_suffix(&mut self, suffix: PasteSuffix) -> Result<()>;
+    /// Нажимает клавишу `times` раз (голосовые команды).
+    fn press_key(&mut self, key: EditKey, times: usize) -> Result<()>;
+    fn pause(&mut self, duration: Duration);
+}
+
+/// Реальная вставка через arboard + enigo.
+pub struct SystemPaster {
+    clipboard: Option<ClipboardManager>,
+    paste_tool: String,
+}
+
+impl SystemPaster {
+    /// Создает paster; недоступный clipboard не является ошибкой
+    /// (посимвольный ввод работает и без него).
+    pub fn new() -> Self {
+        let clipboard = ClipboardManager::new()
+            .inspect_err(|e| tracing::warn!("Clipboard unavailable: {e}"))
+            .ok();
+        Self {
+            clipboard,
+            paste_tool: String::new(),
+        }
+    }
+
+    /// Включает сохранение изображений в clipboard при вставке.
+    pub fn with_image_preservation(mut self, enabled: bool) -> Self {
+        self.clipboard = self.clipboard.map(|c| c.with_image_preservation(enabled));
+        self
+    }
+
+    /// Задает внешнюю утилиту вставки (Linux/Wayland); пусто - автоопределение.
+    pub fn with_paste_tool(mut self, tool: &str) -> Self {
+        self.paste_tool = tool.to_string();
+        self
+    }
+
+    fn clipboard(&mut self) -> Result<&mut ClipboardManager> {
+        self.clipboard
+            .as_mut()
+            .ok_or_else(|| PasteError::ClipboardUnavailable("not initialized".to_string()))
+    }
+}
+
+impl Paster for SystemPaster {
+    fn save_clipboard(&mut self) -> Result<()> {
+        self.clipboard()?.save()
+    }
+
+    fn write_clipboard(&mut self, text: &str) -> Result<()> {
+        self.clipboard()?.write(text)
+    }
+
+    fn restore_clipboard(&mut self) -> Result<()> {
+        self.clipboard()?.restore()
+    }
+
+    fn simulate_paste(&mut self) -> Result<()> {
+        input::simulate_paste()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn simulate_paste_external(&mut self) -> Result<String> {
+        input::simulate_paste_external(&self.paste_tool)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn simulate_paste_external(&mut self) -> Result<String> {
+        Err(PasteError::InputSimulation(
+            "external paste tools are supported only on Linux".to_string(),
+        ))
+    }
+
+    fn type_text(&mut self, text: &str, options: &TypingOptions) -> Result<()> {
+        input::type_text_with(text, options)
+    }
+
+    fn send_suffix(&mut self, suffix: PasteSuffix) -> Result<()> {
+        input::send_suffix(suffix)
+    }
+
+    fn press_key(&mut self, key: EditKey, times: usize) -> Result<()> {
+        input::press_key(key, times)
+    }
+
+    fn pause(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Очистка при выходе: отпускает модификатор и восстанавливает clipboard
+/// вставки, прерванной выходом.
+///
+/// Выполняется в отдельном потоке и ждет не дольше `SHUTDOWN_CLEANUP_TIMEOUT_MS`,
+/// чтобы зависший clipboard или ввод не задержали выход.
+pub fn cleanup_on_exit() {
+    let (done_tx, done_rx) = mpsc::channel();
+    thread::spawn(move || {
+        if let Err(e) = input::release_paste_modifier() {
+            tracing::warn!("Failed to release paste modifier on shutdown: {e}");
+        }
+        if let Err(e) = ClipboardManager::restore_in_flight() {
+            tracing::warn!("Failed to restore clipboard on shutdown: {e}");
+        }
+        let _ = done_tx.send(());
+    });
+    if done_rx
+        .recv_timeout(Duration::from_millis(SHUTDOWN_CLEANUP_TIMEOUT_MS))
+        .is_err()
+    {
+        tracing::warn!("Shutdown cleanup timed out, exiting anyway");
+    }
+}
+
+/// Вставляет текст в активное поле ввода выбранным способом.
+///
+/// См. [`paste_with`]; при `options.command_mode` - [`commands::paste_with_commands`].
+pub fn paste_text(text: &str, options: &PasteOptions) -> PasteStatus {
+    let mut paster = SystemPaster::new()
+        .with_paste_tool(&options.paste_tool)
+        .with_image_preservation(options.preserve_images);
+    if options.command_mode {
+        return commands::paste_with_commands(&mut paster, text, options);
+    }
+    paste_with(&mut paster, text, options)
+}
+
+/// Вставляет текст через `paster` согласно `options.method`.
+///
+/// - `Clipboard`: save -> write -> Ctrl+V/Cmd+V -> restore.
+/// - `Type`: посимвольный ввод; при ошибке текст кладется в clipboard.
+/// - `Auto`: как `Clipboard`, но если вставка не удалась (clipboard
+///   недоступен или симуляция клавиш вернула ошибку), посимвольно вводится
+///   еще не вставленный остаток (сегменты до сбоя уже в поле). Поглощение Ctrl+V целевым приложением надежно
+///   определить нельзя, поэтому сигналом служит ошибка симуляции.
+///
+/// После успешной вставки (`Pasted`) отправляется `options.suffix`; ошибка
+/// суффикса не меняет результат - текст уже вставлен.
+pub fn paste_with(paster: &mut impl Paster, text: &str, options: &PasteOptions) -> PasteStatus {
+    tracing::info!(
+        method = ?options.method,
+        "Starting paste pipeline ({} chars)",
+        text.len()
+    );
+
+    let status = paste_by_method(paster, text, options);
+    if status == PasteStatus::Pasted && options.suffix != PasteSuffix::None {
+        if let Err(e) = paster.send_suffix(options.suffix) {
+            tracing::warn!("Failed to send paste suffix {:?}: {e}", options.suffix);
+        }
+    }
+    status
+}
+
+/// Вставка выбранным способом без суффикса.
+fn paste_by_method(paster: &mut impl Paster, text: &str, options: &PasteOptions) -> PasteStatus {
+    match options.method {
+        PasteMethod::Clipboard => paste_via_clipboard(paster, text, options.segment_size).status,
+        PasteMethod::Type => match paster.type_text(text, &options.typing) {
+            Ok(()) => {
+                tracing::info!("Text typed successfully");
+                PasteStatus::Pasted
+            }
+            Err(e) => {
+                tracing::warn!("Typing failed: {e}, falling back to clipboard");
+                clipboard_only(paster, text)
+            }
+        },
+        PasteMethod::Auto => match paste_via_clipboard(paster, text, options.segment_size) {
+            ClipboardPaste {
+                status: PasteStatus::Pasted,
+                ..
+            } => PasteStatus::Pasted,
+            ClipboardPaste {
+                status: failed,
+                pasted_graphemes,
+                remaining,
+            } => {
+                tracing::info!(
+                    pasted_graphemes,
+                    "Clipboard paste failed ({failed:?}), escalating to typing the remainder"
+                );
+                match paster.type_text(remaining, &options.typing) {
+                    Ok(()) => {
+                        if failed == PasteStatus::ClipboardOnly {
+                            if let Err(e) = paster.restore_clipboard() {
+                                tracing::warn!("Failed to restore clipboard: {e}");
+                            }
+                        }
+                        tracing::info!("Text typed successfully after clipboard failure");
+                        PasteStatus::Pasted
+                    }
+                    Err(e) => {
+                        tracing::warn!("Typing fallback failed: {e}");
+                        failed
+                    }
+                }
+            }
+        },
+    }
+}
+
+/// Вставка через clipboard.
 ///
 /// Pipeline:
 /// 1. Сохранить текущее содержимое clipboard
@@ -53,52 +325,613 @@ pub enum PasteStatus {
 /// 4. Подождать пока приложение обработает вставку
 /// 5. Восстановить содержимое clipboard
 ///
-/// При ошибке симуляции клавиш (Wayland, отсутствие permissions):
-/// текст остается в clipboard, возвращается `ClipboardOnly`.
+/// Текст длиннее `segment_size` графем вставляется сегментами (шаги 2-3 для
+/// каждого с паузой `SEGMENT_DELAY_MS`), clipboard восстанавливается один раз.
+/// Если сегмент не вставился, в clipboard остается весь текст (`ClipboardOnly`);
+/// в результате - сколько графем уже вставлено и не вставленный остаток.
+///
+/// Неудачная симуляция Ctrl+V повторяется до `PASTE_RETRY_COUNT` раз
+/// (clipboard при этом не пересохраняется).
+///
+/// При ошибке симуляции клавиш (Wayland, отсутствие permissions) пробуется
+/// внешняя утилита (wtype/ydotool); если и она недоступна - текст остается
+/// в clipboard, возвращается `ClipboardOnly`.
 ///
 /// При ошибке clipboard: возвращается `ResultWindow`.
-pub fn paste_text(text: &str) -> PasteStatus {
-    tracing::info!("Starting paste pipeline ({} chars)", text.len());
+fn paste_via_clipboard<'a>(
+    paster: &mut impl Paster,
+    text: &'a str,
+    segment_size: usize,
+) -> ClipboardPaste<'a> {
+    if let Err(e) = paster.save_clipboard() {
+        tracing::warn!("Failed to save clipboard: {e}, continuing without restore");
+    }
 
-    let mut manager = match ClipboardManager::new() {
-        Ok(m) => m,
-        Err(e) => {
-            tracing::warn!("Clipboard unavailable: {e}, falling back to ResultWindow");
-            return PasteStatus::ResultWindow;
+    let segments = split_segments(text, segment_size);
+    if segments.len() > 1 {
+        tracing::info!(segments = segments.len(), "Pasting text in segments");
+    }
+
+    let mut pasted_bytes = 0;
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            paster.pause(Duration::from_millis(SEGMENT_DELAY_MS));
+        }
+        if let Err(status) = paste_segment(paster, segment) {
+            let status = if segments.len() == 1 {
+                status
+            } else {
+                tracing::warn!(
+                    "Segment {}/{} failed, leaving full text in clipboard",
+                    i + 1,
+                    segments.len()
+                );
+                clipboard_only(paster, text)
+            };
+            return ClipboardPaste {
+                status,
+                // Все сегменты до сбоя полные: по `segment_size` графем
+                pasted_graphemes: i * segment_size,
+                remaining: &text[pasted_bytes..],
+            };
         }
-    };
+        pasted_bytes += segment.len();
+    }
 
-    if let Err(e) = manager.save() {
-        tracing::warn!("Failed to save clipboard: {e}, continuing without restore");
+    paster.pause(Duration::from_millis(RESTORE_DELAY_MS));
+
+    if let Err(e) = paster.restore_clipboard() {
+        tracing::warn!("Failed to restore clipboard: {e} (text was pasted successfully)");
     }
 
-    if let Err(e) = manager.write(text) {
-        tracing::warn!("Failed to write to clipboard: {e}, falling back to ResultWindow");
-        return PasteStatus::ResultWindow;
+    tracing::info!("Paste completed successfully");
+    ClipboardPaste {
+        status: PasteStatus::Pasted,
+        pasted_graphemes: segments
+            .iter()
+            .map(|s| segment::chunk_text(s, 1).len())
+            .sum(),
+        remaining: "",
     }
+}
 
-    // Let clipboard changes propagate and window focus stabilize
-    thread::sleep(Duration::from_millis(PRE_PASTE_DELAY_MS));
+/// Делит текст на сегменты по `segment_size` графем; 0 - без деления.
+fn split_segments(text: &str, segment_size: usize) -> Vec<&str> {
+    if segment_size == 0 || text.is_empty() {
+        return vec![text];
+    }
+    segment::chunk_text(text, segment_size)
+}
 
-    if let Err(e) = input::simulate_paste() {
-        tracing::warn!("Key simulation failed: {e}, text is in clipboard (ClipboardOnly mode)");
-        return PasteStatus::ClipboardOnly;
+/// Записывает сегмент в clipboard и вставляет его (Ctrl+V с retry, затем внешняя утилита).
+///
+/// При ошибке возвращает итоговый статус: `ResultWindow` (clipboard недоступен)
+/// или `ClipboardOnly` (сегмент в clipboard, симуляция не удалась).
+fn paste_segment(paster: &mut impl Paster, segment: &str) -> std::result::Result<(), PasteStatus> {
+    if let Err(e) = paster.write_clipboard(segment) {
+        tracing::warn!("Failed to write to clipboard: {e}, falling back to ResultWindow");
+        return Err(PasteStatus::ResultWindow);
     }
 
-    thread::sleep(Duration::from_millis(RESTORE_DELAY_MS));
+    // Let clipboard changes propagate and window focus stabilize
+    paster.pause(Duration::from_millis(PRE_PASTE_DELAY_MS));
+
+    if let Err(e) = simulate_paste_with_retry(paster) {
+        tracing::warn!("Key simulation failed: {e}, trying external paste tool");
+        match paster.simulate_paste_external() {
+            Ok(backend) => tracing::info!(backend = %backend, "Pasted via external tool"),
+            Err(e) => {
+                tracing::warn!(
+                    "External paste failed: {e}, text is in clipboard (ClipboardOnly mode)"
+                );
+                return Err(PasteStatus::ClipboardOnly);
+            }
+        }
+    }
+    Ok(())
+}
 
-    if let Err(e) = manager.restore() {
-        tracing::warn!("Failed to restore clipboard: {e} (text was pasted successfully)");
+/// Симуляция Ctrl+V с `PASTE_RETRY_COUNT` повторами; возвращает последнюю ошибку.
+fn simulate_paste_with_retry(paster: &mut impl Paster) -> Result<()> {
+    let mut attempt = 0;
+    loop {
+        match paster.simulate_paste() {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < PASTE_RETRY_COUNT => {
+                attempt += 1;
+                tracing::warn!("Key simulation failed: {e}, retry {attempt}/{PASTE_RETRY_COUNT}");
+                paster.pause(Duration::from_millis(PASTE_RETRY_DELAY_MS));
+            }
+            Err(e) => return Err(e),
+        }
     }
+}
 
-    tracing::info!("Paste completed successfully");
-    PasteStatus::Pasted
+/// Оставляет текст в clipboard для ручной вставки.
+fn clipboard_only(paster: &mut impl Paster, text: &str) -> PasteStatus {
+    match paster.write_clipboard(text) {
+        Ok(()) => PasteStatus::ClipboardOnly,
+        Err(e) => {
+            tracing::warn!("Failed to write to clipboard: {e}, falling back to ResultWindow");
+            PasteStatus::ResultWindow
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Fake-реализация платформенных операций с журналом вызовов.
+    #[derive(Default)]
+    struct FakePaster {
+        clipboard_fails: bool,
+        simulate_fails: bool,
+        /// Сколько первых вызовов simulate_paste завершатся ошибкой
+        simulate_fail_times: u32,
+        /// После скольких вызовов simulate_paste все следующие завершатся ошибкой
+        fail_simulate_after: Option<usize>,
+        type_fails: bool,
+        suffix_fails: bool,
+        /// Имя внешней утилиты вставки; `None` - утилита недоступна
+        external_tool: Option<&'static str>,
+        calls: Vec<&'static str>,
+        typed: Option<String>,
+        written: Vec<String>,
+    }
+
+    impl Paster for FakePaster {
+        fn save_clipboard(&mut self) -> Result<()> {
+            self.calls.push("save");
+            self.fail_if(self.clipboard_fails)
+        }
+
+        fn write_clipboard(&mut self, text: &str) -> Result<()> {
+            self.calls.push("write");
+            self.fail_if(self.clipboard_fails)?;
+            self.written.push(text.to_string());
+            Ok(())
+        }
+
+        fn restore_clipboard(&mut self) -> Result<()> {
+            self.calls.push("restore");
+            self.fail_if(self.clipboard_fails)
+        }
+
+        fn simulate_paste(&mut self) -> Result<()> {
+            let previous = self.calls.iter().filter(|c| **c == "simulate").count();
+            self.calls.push("simulate");
+            if self.fail_simulate_after.is_some_and(|n| previous >= n) {
+                return self.fail_if(true);
+            }
+            if self.simulate_fail_times > 0 {
+                self.simulate_fail_times -= 1;
+                return self.fail_if(true);
+            }
+            self.fail_if(self.simulate_fails)
+        }
+
+        fn simulate_paste_external(&mut self) -> Result<String> {
+            self.calls.push("external");
+            self.external_tool
+                .map(str::to_string)
+                .ok_or_else(|| PasteError::InputSimulation("no tool".to_string()))
+        }
+
+        fn type_text(&mut self, text: &str, _options: &TypingOptions) -> Result<()> {
+            self.calls.push("type");
+            self.fail_if(self.type_fails)?;
+            self.typed = Some(text.to_string());
+            Ok(())
+        }
+
+        fn send_suffix(&mut self, _suffix: PasteSuffix) -> Result<()> {
+            self.calls.push("suffix");
+            self.fail_if(self.suffix_fails)
+        }
+
+        fn press_key(&mut self, _key: EditKey, _times: usize) -> Result<()> {
+            self.calls.push("key");
+            Ok(())
+        }
+
+        fn pause(&mut self, _duration: Duration) {}
+    }
+
+    impl FakePaster {
+        fn fail_if(&self, fail: bool) -> Result<()> {
+            if fail {
+                Err(PasteError::InputSimulation("fake failure".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn options(method: PasteMethod) -> PasteOptions {
+        PasteOptions {
+            method,
+            ..PasteOptions::default()
+        }
+    }
+
+    #[test]
+    fn auto_should_escalate_to_typing_when_simulate_fails() {
+        // Given
+        let mut paster = FakePaster {
+            simulate_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Auto));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(paster.typed.as_deref(), Some("hello"));
+        assert_eq!(
+            paster.calls,
+            vec!["save", "write", "simulate", "simulate", "external", "type", "restore"]
+        );
+    }
+
+    #[test]
+    fn auto_should_not_type_when_clipboard_paste_succeeds() {
+        // Given
+        let mut paster = FakePaster::default();
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Auto));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert!(!paster.calls.contains(&"type"));
+    }
+
+    #[test]
+    fn auto_should_type_when_clipboard_unavailable() {
+        // Given
+        let mut paster = FakePaster {
+            clipboard_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Auto));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(paster.typed.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn auto_should_keep_clipboard_only_when_typing_also_fails() {
+        // Given
+        let mut paster = FakePaster {
+            simulate_fails: true,
+            type_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Auto));
+
+        // Then
+        assert_eq!(status, PasteStatus::ClipboardOnly);
+        assert!(!paster.calls.contains(&"restore"));
+    }
+
+    #[test]
+    fn clipboard_method_should_not_fall_back_to_typing() {
+        // Given
+        let mut paster = FakePaster {
+            simulate_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Clipboard));
+
+        // Then
+        assert_eq!(status, PasteStatus::ClipboardOnly);
+        assert!(!paster.calls.contains(&"type"));
+    }
+
+    #[test]
+    fn clipboard_method_should_paste_via_external_tool_when_simulate_fails() {
+        // Given
+        let mut paster = FakePaster {
+            simulate_fails: true,
+            external_tool: Some("wtype"),
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Clipboard));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(
+            paster.calls,
+            vec!["save", "write", "simulate", "simulate", "external", "restore"]
+        );
+    }
+
+    #[test]
+    fn clipboard_method_should_not_use_external_tool_when_simulate_succeeds() {
+        // Given
+        let mut paster = FakePaster {
+            external_tool: Some("wtype"),
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Clipboard));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert!(!paster.calls.contains(&"external"));
+    }
+
+    #[test]
+    fn clipboard_method_should_retry_simulate_once_without_resaving() {
+        // Given: первый Ctrl+V не срабатывает, второй - проходит
+        let mut paster = FakePaster {
+            simulate_fail_times: 1,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Clipboard));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(
+            paster.calls,
+            vec!["save", "write", "simulate", "simulate", "restore"]
+        );
+    }
+
+    #[test]
+    fn clipboard_method_should_give_up_after_bounded_retries() {
+        // Given
+        let mut paster = FakePaster {
+            simulate_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Clipboard));
+
+        // Then
+        assert_eq!(status, PasteStatus::ClipboardOnly);
+        let attempts = paster.calls.iter().filter(|c| **c == "simulate").count();
+        assert_eq!(attempts, 1 + PASTE_RETRY_COUNT as usize);
+    }
+
+    fn segmented(segment_size: usize) -> PasteOptions {
+        PasteOptions {
+            method: PasteMethod::Clipboard,
+            segment_size,
+            ..PasteOptions::default()
+        }
+    }
+
+    #[test]
+    fn clipboard_method_should_paste_long_text_in_segments() {
+        // Given
+        let mut paster = FakePaster::default();
+
+        // When
+        let status = paste_with(&mut paster, "abcdefg", &segmented(3));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(paster.written, vec!["abc", "def", "g"]);
+        let restores = paster.calls.iter().filter(|c| **c == "restore").count();
+        assert_eq!(restores, 1);
+        assert_eq!(paster.calls.last(), Some(&"restore"));
+    }
+
+    #[test]
+    fn segments_should_not_split_graphemes() {
+        // Given: "е" + комбинируемое ударение - одна графема
+        let text = "ае\u{0301}б";
+
+        // When
+        let segments = split_segments(text, 2);
+
+        // Then
+        assert_eq!(segments, vec!["ае\u{0301}", "б"]);
+    }
+
+    #[test]
+    fn failed_segment_should_leave_full_text_in_clipboard() {
+        // Given: первый сегмент вставлен, второй - нет
+        let mut paster = FakePaster {
+            fail_simulate_after: Some(1),
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "abcdef", &segmented(3));
+
+        // Then
+        assert_eq!(status, PasteStatus::ClipboardOnly);
+        assert_eq!(paster.written.last().map(String::as_str), Some("abcdef"));
+        assert!(!paster.calls.contains(&"restore"));
+    }
+
+    #[test]
+    fn failed_segment_should_report_pasted_graphemes_and_remainder() {
+        // Given: второй сегмент не вставляется
+        let mut paster = FakePaster {
+            fail_simulate_after: Some(1),
+            ..FakePaster::default()
+        };
+
+        // When
+        let result = paste_via_clipboard(&mut paster, "абвгде", 3);
+
+        // Then
+        assert_eq!(
+            result,
+            ClipboardPaste {
+                status: PasteStatus::ClipboardOnly,
+                pasted_graphemes: 3,
+                remaining: "где",
+            }
+        );
+    }
+
+    #[test]
+    fn auto_should_type_only_remainder_when_segment_fails() {
+        // Given: первый сегмент вставлен, второй - нет
+        let mut paster = FakePaster {
+            fail_simulate_after: Some(1),
+            ..FakePaster::default()
+        };
+        let options = PasteOptions {
+            method: PasteMethod::Auto,
+            segment_size: 3,
+            ..PasteOptions::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "abcdef", &options);
+
+        // Then: уже вставленный сегмент не повторяется
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(paster.typed.as_deref(), Some("def"));
+        assert_eq!(paster.calls.last(), Some(&"restore"));
+    }
+
+    #[test]
+    fn zero_segment_size_should_paste_in_one_piece() {
+        // Given
+        let mut paster = FakePaster::default();
+
+        // When
+        let status = paste_with(&mut paster, "abcdef", &segmented(0));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(paster.written, vec!["abcdef"]);
+    }
+
+    fn options_with_suffix(method: PasteMethod, suffix: PasteSuffix) -> PasteOptions {
+        PasteOptions {
+            method,
+            suffix,
+            ..PasteOptions::default()
+        }
+    }
+
+    #[test]
+    fn clipboard_method_should_send_suffix_after_restore() {
+        // Given
+        let mut paster = FakePaster::default();
+
+        // When
+        let status = paste_with(
+            &mut paster,
+            "hello",
+            &options_with_suffix(PasteMethod::Clipboard, PasteSuffix::Newline),
+        );
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(
+            paster.calls,
+            vec!["save", "write", "simulate", "restore", "suffix"]
+        );
+    }
+
+    #[test]
+    fn type_method_should_send_suffix_after_typing() {
+        // Given
+        let mut paster = FakePaster::default();
+
+        // When
+        let status = paste_with(
+            &mut paster,
+            "hello",
+            &options_with_suffix(PasteMethod::Type, PasteSuffix::Space),
+        );
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(paster.calls, vec!["type", "suffix"]);
+    }
+
+    #[test]
+    fn suffix_should_not_be_sent_when_paste_failed() {
+        // Given
+        let mut paster = FakePaster {
+            simulate_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(
+            &mut paster,
+            "hello",
+            &options_with_suffix(PasteMethod::Clipboard, PasteSuffix::Newline),
+        );
+
+        // Then
+        assert_eq!(status, PasteStatus::ClipboardOnly);
+        assert!(!paster.calls.contains(&"suffix"));
+    }
+
+    #[test]
+    fn suffix_failure_should_keep_pasted_status() {
+        // Given
+        let mut paster = FakePaster {
+            suffix_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(
+            &mut paster,
+            "hello",
+            &options_with_suffix(PasteMethod::Type, PasteSuffix::Newline),
+        );
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+    }
+
+    #[test]
+    fn type_method_should_not_touch_clipboard_on_success() {
+        // Given
+        let mut paster = FakePaster::default();
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Type));
+
+        // Then
+        assert_eq!(status, PasteStatus::Pasted);
+        assert_eq!(paster.calls, vec!["type"]);
+    }
+
+    #[test]
+    fn type_method_should_leave_text_in_clipboard_when_typing_fails() {
+        // Given
+        let mut paster = FakePaster {
+            type_fails: true,
+            ..FakePaster::default()
+        };
+
+        // When
+        let status = paste_with(&mut paster, "hello", &options(PasteMethod::Type));
+
+        // Then
+        assert_eq!(status, PasteStatus::ClipboardOnly);
+        assert_eq!(paster.calls, vec!["type", "write"]);
+    }
+
     #[test]
     fn paste_error_should_display_clipboard_unavailable() {
         // Given
@@ -0,0 +1,149 @@
+//! Разбиение текста на части для посимвольного ввода без разрыва графем.
+//!
+//! Упрощенная сегментация графем (без внешних зависимостей): склеивает
+//! комбинируемые знаки, вариационные селекторы, модификаторы тона кожи,
+//! ZWJ-последовательности эмодзи, пары флагов и CRLF.
+
+/// Делит текст на части не более `chunk_size` графем.
+///
+/// `chunk_size == 0` трактуется как 1.
+pub fn chunk_text(text: &str, chunk_size: usize) -> Vec<&str> {
+    let chunk_size = chunk_size.max(1);
+    let boundaries = grapheme_boundaries(text);
+
+    boundaries
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let start = chunk[0].0;
+            let end = chunk[chunk.len() - 1].1;
+            &text[start..end]
+        })
+        .collect()
+}
+
+/// Возвращает границы графем (start, end) в байтах.
+fn grapheme_boundaries(text: &str) -> Vec<(usize, usize)> {
+    let mut clusters: Vec<(usize, usize)> = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut regional_run = 0;
+
+    for (idx, c) in text.char_indices() {
+        let end = idx + c.len_utf8();
+        let joins = match prev {
+            None => false,
+            Some('\u{200D}') => true,
+            Some('\r') => c == '\n',
+            Some(_) if is_regional_indicator(c) => regional_run % 2 == 1,
+            Some(_) => is_extending(c),
+        };
+
+        if is_regional_indicator(c) {
+            regional_run = if joins { regional_run + 1 } else { 1 };
+        } else {
+            regional_run = 0;
+        }
+
+        match clusters.last_mut() {
+            Some(last) if joins => last.1 = end,
+            _ => clusters.push((idx, end)),
+        }
+        prev = Some(c);
+    }
+
+    clusters
+}
+
+/// Символы, продолжающие предыдущую графему.
+fn is_extending(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'     // комбинируемые диакритики
+        | '\u{0483}'..='\u{0489}'   // кириллические комбинируемые знаки
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{200C}'..='\u{200D}'   // ZWNJ / ZWJ
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE00}'..='\u{FE0F}'   // вариационные селекторы
+        | '\u{FE20}'..='\u{FE2F}'
+        | '\u{1F3FB}'..='\u{1F3FF}' // модификаторы тона кожи
+        | '\u{E0020}'..='\u{E007F}' // теги (флаги регионов)
+        | '\u{E0100}'..='\u{E01EF}'
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_text_should_respect_chunk_size() {
+        // Given
+        let text = "abcdefghij";
+
+        // When
+        let chunks = chunk_text(text, 4);
+
+        // Then
+        assert_eq!(chunks, vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn chunk_text_should_not_split_combining_marks() {
+        // Given: "é" как e + U+0301, "й" как и + U+0306
+        let text = "e\u{301}e\u{301}и\u{306}";
+
+        // When
+        let chunks = chunk_text(text, 1);
+
+        // Then
+        assert_eq!(chunks, vec!["e\u{301}", "e\u{301}", "и\u{306}"]);
+    }
+
+    #[test]
+    fn chunk_text_should_keep_emoji_sequences_whole() {
+        // Given: семья (ZWJ), палец с тоном кожи, сердце с VS16, флаги
+        let family = "👨\u{200D}👩\u{200D}👧";
+        let thumb = "👍\u{1F3FD}";
+        let heart = "❤\u{FE0F}";
+        let flags = "🇷🇺🇺🇸";
+        let text = format!("{family}{thumb}{heart}{flags}");
+
+        // When
+        let chunks = chunk_text(&text, 1);
+
+        // Then
+        assert_eq!(chunks, vec![family, thumb, heart, "🇷🇺", "🇺🇸"]);
+    }
+
+    #[test]
+    fn chunk_text_should_cover_long_text_without_loss() {
+        // Given
+        let text = "Привет, мир! 👋🏻 ".repeat(50);
+
+        // When
+        let chunks = chunk_text(&text, 16);
+
+        // Then
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks.iter().all(|c| grapheme_boundaries(c).len() <= 16));
+        assert!(chunks.iter().all(|c| !c.starts_with('\u{1F3FB}')));
+    }
+
+    #[test]
+    fn chunk_text_should_keep_crlf_together() {
+        assert_eq!(chunk_text("a\r\nb", 1), vec!["a", "\r\n", "b"]);
+    }
+
+    #[test]
+    fn chunk_text_should_treat_zero_size_as_one() {
+        assert_eq!(chunk_text("ab", 0), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn chunk_text_should_return_empty_for_empty_input() {
+        assert!(chunk_text("", 8).is_empty());
+    }
+}
@@ -8,14 +8,20 @@ use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewUrl, WebviewWindowBuild
 
 use crate::audio::capture_cpal::AudioCapture;
 use crate::audio::preprocess::{self, TARGET_SAMPLE_RATE};
-use crate::audio::CaptureFormat;
-use crate::config::schema::AppConfig;
-use crate::enhance::{EnhanceProvider, OpenAiEnhancer};
+use crate::audio::{self, AudioError, AudioSource, CaptureFormat};
+use crate::config::schema::{AppConfig, SttProviderKind};
+use crate::config::secrets::ApiKeyScope;
+use crate::enhance::{self, numbers, protect, EnhanceProvider, OpenAiEnhancer};
+use crate::history;
+use crate::idle::IdleTimer;
+use crate::metrics::{self, SessionMetrics};
 use crate::notifications;
 use crate::paste::{self, PasteStatus};
-use crate::state::{AppEvent, SharedAppState};
-use crate::stt::{self, OpenAiSttClient};
+use crate::state::{AppEvent, AppState, SharedAppState};
+use crate::stt::{self, streaming::StreamingSession};
 use crate::tray;
+use crate::vad::AutoStopMonitor;
+use crate::webhook::{self, WebhookPayload};
 
 #[cfg(target_os = "macos")]
 const PASTE_SHORTCUT: &str = "Cmd+V";
@@ -30,7 +36,14 @@ pub struct PipelineState {
     capture: Mutex<Option<AudioCapture>>,
     cancel: Arc<AtomicBool>,
     timeout_handle: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    recording_clock: Mutex<RecordingClock>,
     pipeline_handle: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    level_handle: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    /// Потоковая транскрипция идущей записи (`stt_streaming`).
+    streaming: Mutex<Option<StreamingSession>>,
+    /// Захват проверки микрофона из настроек (вне state machine диктовки).
+    mic_test: Mutex<Option<AudioCapture>>,
+    mic_test_handle: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
 }
 
 impl PipelineState {
@@ -39,9 +52,114 @@ impl PipelineState {
             capture: Mutex::new(None),
             cancel: Arc::new(AtomicBool::new(false)),
             timeout_handle: Mutex::new(None),
+            recording_clock: Mutex::new(RecordingClock::default()),
             pipeline_handle: Mutex::new(None),
+            level_handle: Mutex::new(None),
+            streaming: Mutex::new(None),
+            mic_test: Mutex::new(None),
+            mic_test_handle: Mutex::new(None),
         }
     }
+
+    /// Забирает сессию потоковой транскрипции; drop сессии отменяет ее запросы.
+    fn take_streaming(&self) -> Option<StreamingSession> {
+        self.streaming
+            .lock()
+            .expect("streaming mutex poisoned")
+            .take()
+    }
+
+    /// Останавливает таймер `max_recording_duration_sec`, если он запущен.
+    fn cancel_duration_timeout(&self) {
+        if let Some(handle) = self
+            .timeout_handle
+            .lock()
+            .expect("timeout mutex poisoned")
+            .take()
+        {
+            handle.abort();
+        }
+    }
+}
+
+/// Время записи для таймаута безопасности: паузы не учитываются.
+#[derive(Debug, Default)]
+struct RecordingClock {
+    /// Лимит `max_recording_duration_sec`
+    limit: Duration,
+    /// Записано до последней паузы
+    recorded: Duration,
+    /// Начало текущего отрезка записи; `None` на паузе
+    resumed_at: Option<Instant>,
+}
+
+impl RecordingClock {
+    /// Новая запись с лимитом `limit`.
+    fn start(&mut self, limit: Duration, now: Instant) {
+        *self = Self {
+            limit,
+            recorded: Duration::ZERO,
+            resumed_at: Some(now),
+        };
+    }
+
+    /// Пауза: текущий отрезок добавляется к записанному времени.
+    fn pause(&mut self, now: Instant) {
+        if let Some(resumed_at) = self.resumed_at.take() {
+            self.recorded += now.saturating_duration_since(resumed_at);
+        }
+    }
+
+    /// Продолжение после паузы.
+    fn resume(&mut self, now: Instant) {
+        self.resumed_at.get_or_insert(now);
+    }
+
+    /// Сколько еще можно записывать до таймаута.
+    fn remaining(&self, now: Instant) -> Duration {
+        let running = self
+            .resumed_at
+            .map(|t| now.saturating_duration_since(t))
+            .unwrap_or_default();
+        self.limit.saturating_sub(self.recorded + running)
+    }
+}
+
+/// Запускает задачу, которая вызовет `on_timeout` через `after`.
+///
+/// Отмена - `abort()` у возвращенного handle.
+fn spawn_timeout(
+    after: Duration,
+    on_timeout: impl FnOnce() + Send + 'static,
+) -> tauri::async_runtime::JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(after).await;
+        on_timeout();
+    })
+}
+
+/// Запускает таймер безопасности на оставшееся время записи.
+///
+/// По истечении останавливает запись, если она еще идет.
+fn start_duration_timeout<R: Runtime>(app: &AppHandle<R>, remaining: Duration) {
+    let app_handle = app.clone();
+    let handle = spawn_timeout(remaining, move || {
+        if app_handle.state::<SharedAppState>().current_state() != AppState::Recording {
+            return;
+        }
+        tracing::info!(
+            remaining_ms = metrics::as_ms(remaining),
+            "safety timeout reached, auto-stopping"
+        );
+        crate::dispatch_and_update(&app_handle, AppEvent::MaxDurationTimeout);
+    });
+
+    let pipeline = app.state::<PipelineState>();
+    pipeline.cancel_duration_timeout();
+    *pipeline
+        .timeout_handle
+        .lock()
+        .expect("timeout mutex poisoned") = Some(handle);
 }
 
 /// Текст для окна результата (показывается когда буфер обмена недоступен).
@@ -53,6 +171,86 @@ impl ResultText {
     }
 }
 
+/// Текст, ожидающий подтверждения перед вставкой (`confirm_before_paste`).
+pub struct PendingPaste(Mutex<Option<PendingText>>);
+
+/// Результат диктовки, отложенный до решения пользователя.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingText {
+    pub text: String,
+    pub raw: String,
+    /// Язык текста для webhook (`None` - неизвестен).
+    pub language: Option<String>,
+}
+
+impl PendingPaste {
+    pub fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// Сохраняет текст, заменяя предыдущий неподтвержденный.
+    pub fn set(&self, pending: PendingText) {
+        *self.0.lock().expect("pending mutex poisoned") = Some(pending);
+    }
+
+    /// Забирает текст для вставки (Accept).
+    pub fn take(&self) -> Option<PendingText> {
+        self.0.lock().expect("pending mutex poisoned").take()
+    }
+
+    /// Отбрасывает текст (Discard). Clipboard не затрагивается.
+    pub fn discard(&self) -> bool {
+        self.take().is_some()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.0.lock().expect("pending mutex poisoned").is_some()
+    }
+}
+
+/// Что делать с готовым текстом.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DeliveryDecision {
+    /// Вставить сразу.
+    AutoPaste,
+    /// Показать окно результата с Accept/Discard.
+    Confirm,
+}
+
+pub(crate) fn delivery_decision(config: &AppConfig) -> DeliveryDecision {
+    if config.confirm_before_paste {
+        DeliveryDecision::Confirm
+    } else {
+        DeliveryDecision::AutoPaste
+    }
+}
+
+/// Интервал отправки события `audio-level` во время записи (мс).
+const LEVEL_EMIT_INTERVAL_MS: u64 = 100;
+
+/// Интервал отправки `audio-level` во время проверки микрофона (мс).
+const MIC_TEST_LEVEL_INTERVAL_MS: u64 = 50;
+
+/// Через сколько секунд проверка микрофона останавливается сама.
+const MIC_TEST_MAX_SEC: u64 = 30;
+
+/// Задержка перед вставкой после закрытия окна подтверждения (мс).
+///
+/// Дает ОС вернуть фокус в окно, где пользователь диктовал.
+const FOCUS_RETURN_DELAY_MS: u64 = 300;
+
+/// Нужен ли API-ключ: для облачного STT или улучшения текста через API.
+pub(crate) fn requires_api_key(config: &AppConfig) -> bool {
+    config.stt_provider == SttProviderKind::OpenAi || config.enhance_enabled
+}
+
+/// Не хватает ли API-ключа для записи: ключ нужен (`requires_api_key`), но не задан.
+///
+/// `has_key` вызывается только когда ключ нужен (офлайн-настройка не трогает keychain).
+pub(crate) fn api_key_missing(config: &AppConfig, has_key: impl FnOnce() -> bool) -> bool {
+    requires_api_key(config) && !has_key()
+}
+
 // --- Public API (called from dispatch_and_update) ---
 
 /// Запускает захват аудио и таймаут безопасности.
@@ -75,9 +273,27 @@ pub fn start_recording<R: Runtime>(app: &AppHandle<R>) {
     }
 
     pipeline.cancel.store(false, Ordering::SeqCst);
+    app.state::<IdleTimer>().touch();
+    stop_mic_test(app);
+
+    // Незакрытый захват прошлой записи не должен остаться живым потоком
+    if stop_stale_capture(&pipeline) {
+        tracing::warn!("stale audio capture found on recording start, stopped it");
+    }
+
+    let config = app
+        .state::<Mutex<AppConfig>>()
+        .lock()
+        .expect("config mutex poisoned")
+        .clone();
 
-    let mut capture = match AudioCapture::new() {
+    let mut capture = match AudioCapture::from_config(&config) {
         Ok(c) => c,
+        Err(AudioError::NoInputDevice) => {
+            tracing::error!("no input device found after re-probing");
+            handle_pipeline_error(app, "No microphone found, please connect one");
+            return;
+        }
         Err(e) => {
             tracing::error!(error = %e, "failed to create audio capture");
             handle_pipeline_error(app, &format!("Microphone not available: {e}"));
@@ -87,46 +303,406 @@ pub fn start_recording<R: Runtime>(app: &AppHandle<R>) {
 
     if let Err(e) = capture.start_recording() {
         tracing::error!(error = %e, "failed to start recording");
-        handle_pipeline_error(app, &format!("Failed to start recording: {e}"));
+        let message = match e {
+            AudioError::NoInputDevice => "No microphone found, please connect one".to_string(),
+            e => format!("Failed to start recording: {e}"),
+        };
+        handle_pipeline_error(app, &message);
         return;
     }
 
     *pipeline.capture.lock().expect("capture mutex poisoned") = Some(capture);
+    spawn_level_emitter(app, &config);
 
     // Таймаут безопасности: авто-остановка по истечении max_recording_duration_sec
-    let max_sec = app
-        .state::<Mutex<AppConfig>>()
+    let limit = Duration::from_secs(u64::from(config.max_recording_duration_sec));
+    pipeline
+        .recording_clock
         .lock()
-        .expect("config mutex poisoned")
-        .max_recording_duration_sec;
+        .expect("clock mutex poisoned")
+        .start(limit, Instant::now());
+    start_duration_timeout(app, limit);
+}
+
+/// Состояние захвата, проверяемое монитором записи.
+enum CaptureStatus {
+    /// Уровень сигнала, уровни каналов и новые сэмплы для авто-стопа
+    /// и потоковой транскрипции (если они включены).
+    Recording {
+        level: f32,
+        channel_levels: Vec<f32>,
+        auto_stop_samples: Option<(Vec<f32>, CaptureFormat)>,
+        streaming_samples: Option<(Vec<f32>, CaptureFormat)>,
+    },
+    /// Буфер записи заполнен, дальнейшие сэмплы отбрасываются.
+    BufferFull,
+    Paused,
+    /// Поток записи упал (микрофон отключен), указан текст ошибки.
+    Disconnected(String),
+    Stopped,
+}
 
+/// Следит за активной записью: отправляет уровень сигнала (`audio-level`) для VU-метра
+/// и уровни каналов (`audio-channel-levels`, если каналов больше одного), сообщает об отключении микрофона не позже чем через `LEVEL_EMIT_INTERVAL_MS`,
+/// останавливает запись по тишине (`vad_auto_stop`, только toggle) и при
+/// заполнении буфера записи, не дожидаясь таймера `max_recording_duration_sec`.
+/// При `stt_streaming` передает новые сэмплы в сессию потоковой транскрипции.
+///
+/// Задача завершается сама, когда запись остановлена.
+fn spawn_level_emitter<R: Runtime>(app: &AppHandle<R>, config: &AppConfig) {
     let app_handle = app.clone();
+    let config = config.clone();
     let handle = tauri::async_runtime::spawn(async move {
-        tokio::time::sleep(Duration::from_secs(max_sec as u64)).await;
-        tracing::info!(seconds = max_sec, "safety timeout reached, auto-stopping");
-        crate::dispatch_and_update(&app_handle, AppEvent::MaxDurationTimeout);
+        let mut auto_stop = AutoStopMonitor::from_config(&config);
+        *app_handle
+            .state::<PipelineState>()
+            .streaming
+            .lock()
+            .expect("streaming mutex poisoned") = start_streaming_session(&config);
+        let mut interval = tokio::time::interval(Duration::from_millis(LEVEL_EMIT_INTERVAL_MS));
+        loop {
+            interval.tick().await;
+            let pipeline = app_handle.state::<PipelineState>();
+            let streaming_offset = pipeline
+                .streaming
+                .lock()
+                .expect("streaming mutex poisoned")
+                .as_ref()
+                .map(StreamingSession::consumed);
+            let status = {
+                let mut capture = pipeline.capture.lock().expect("capture mutex poisoned");
+                match capture.as_mut() {
+                    Some(c) if c.has_stream_failed() => {
+                        let error = c.take_stream_error().unwrap_or_default();
+                        capture.take();
+                        CaptureStatus::Disconnected(error)
+                    }
+                    Some(c) if c.is_recording() && c.is_buffer_full() => CaptureStatus::BufferFull,
+                    Some(c) if c.is_recording() => CaptureStatus::Recording {
+                        level: c.current_level(),
+                        channel_levels: c.channel_levels(),
+                        auto_stop_samples: auto_stop
+                            .as_ref()
+                            .and_then(|monitor| c.samples_since(monitor.consumed())),
+                        streaming_samples: streaming_offset
+                            .and_then(|offset| c.samples_since(offset)),
+                    },
+                    Some(c) if c.is_paused() => CaptureStatus::Paused,
+                    _ => CaptureStatus::Stopped,
+                }
+            };
+
+            match status {
+                CaptureStatus::Recording {
+                    level,
+                    channel_levels,
+                    auto_stop_samples,
+                    streaming_samples,
+                } => {
+                    if let Err(e) = app_handle.emit("audio-level", level) {
+                        tracing::warn!(error = %e, "failed to emit audio-level event");
+                    }
+                    emit_channel_levels(&app_handle, channel_levels);
+                    if let Some((samples, format)) = streaming_samples {
+                        // Запись могла остановиться: тогда сессию уже забрал pipeline
+                        let mut streaming =
+                            pipeline.streaming.lock().expect("streaming mutex poisoned");
+                        if let Some(session) = streaming.as_mut() {
+                            session.feed(&samples, &format);
+                        }
+                    }
+                    if let (Some(monitor), Some((samples, format))) =
+                        (auto_stop.as_mut(), auto_stop_samples)
+                    {
+                        monitor.resume();
+                        match monitor.feed(&samples, &format) {
+                            Ok(true) => {
+                                tracing::info!("silence threshold reached, auto-stopping");
+                                monitor.finish();
+                                crate::dispatch_and_update(&app_handle, AppEvent::SilenceTimeout);
+                                auto_stop = None;
+                            }
+                            Ok(false) => {}
+                            Err(e) => {
+                                tracing::warn!(error = %e, "VAD failed, auto-stop disabled");
+                                auto_stop = None;
+                            }
+                        }
+                    }
+                }
+                CaptureStatus::Paused => {
+                    if let Err(e) = app_handle.emit("audio-level", 0.0f32) {
+                        tracing::warn!(error = %e, "failed to emit audio-level event");
+                    }
+                    if let Some(monitor) = auto_stop.as_mut() {
+                        monitor.pause();
+                    }
+                }
+                CaptureStatus::BufferFull => {
+                    tracing::warn!("recording buffer is full, stopping before the safety timeout");
+                    crate::dispatch_and_update(&app_handle, AppEvent::MaxDurationTimeout);
+                    break;
+                }
+                CaptureStatus::Disconnected(error) => {
+                    handle_capture_disconnect(&app_handle, &error);
+                    break;
+                }
+                CaptureStatus::Stopped => break,
+            }
+        }
+        if let Some(monitor) = auto_stop.as_mut() {
+            monitor.finish();
+        }
+        // Запись закончилась без передачи в pipeline (отключение, остановка до старта сессии)
+        app_handle.state::<PipelineState>().take_streaming();
     });
 
-    *pipeline
-        .timeout_handle
+    let pipeline = app.state::<PipelineState>();
+    let previous = pipeline
+        .level_handle
         .lock()
-        .expect("timeout mutex poisoned") = Some(handle);
+        .expect("level_handle mutex poisoned")
+        .replace(handle);
+    if let Some(old) = previous {
+        old.abort();
+    }
 }
 
-/// Останавливает захват аудио и запускает pipeline обработки.
+/// Запускает потоковую транскрипцию записи (`stt_streaming`, только OpenAI).
 ///
-/// Вызывается при переходе состояния Recording -> Transcribing.
-pub fn stop_recording_and_run_pipeline<R: Runtime>(app: &AppHandle<R>) {
+/// Вызывается внутри async runtime. Без API-ключа или при ошибке клиента
+/// запись распознается целиком после остановки.
+fn start_streaming_session(config: &AppConfig) -> Option<StreamingSession> {
+    if !config.stt_streaming || config.stt_provider != SttProviderKind::OpenAi {
+        return None;
+    }
+    let api_key = match crate::config::secrets::load_api_key(&ApiKeyScope::from_config(config)) {
+        Ok(Some(key)) => key,
+        Ok(None) => return None,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to load API key, streaming transcription disabled");
+            return None;
+        }
+    };
+    match stt::openai::OpenAiSttClient::from_config(config, &api_key) {
+        Ok(client) => {
+            tracing::info!("streaming transcription started");
+            Some(StreamingSession::start_from_config(
+                Arc::new(client),
+                config,
+            ))
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "STT client error, streaming transcription disabled");
+            None
+        }
+    }
+}
+
+/// Останавливает захват, оставшийся вне записи; `true`, если он был.
+fn stop_stale_capture(pipeline: &PipelineState) -> bool {
+    let Some(mut stale) = pipeline
+        .capture
+        .lock()
+        .expect("capture mutex poisoned")
+        .take()
+    else {
+        return false;
+    };
+    if let Err(e) = stale.stop_recording() {
+        tracing::warn!(error = %e, "failed to stop stale audio capture");
+    }
+    true
+}
+
+/// Закрывает потоки микрофона, открытые вне записи: проверку микрофона
+/// и незакрытый захват прошлой записи. Для освобождения ресурсов в Idle.
+pub fn release_audio_streams<R: Runtime>(app: &AppHandle<R>) {
+    stop_mic_test(app);
+    if stop_stale_capture(&app.state::<PipelineState>()) {
+        tracing::info!("stale audio capture released");
+    }
+}
+
+/// Отправляет RMS каждого канала (`audio-channel-levels`) для поиска "мертвого"
+/// канала стерео-микрофона; для mono событие не отправляется.
+fn emit_channel_levels<R: Runtime>(app: &AppHandle<R>, levels: Vec<f32>) {
+    if levels.len() <= 1 {
+        return;
+    }
+    if let Err(e) = app.emit("audio-channel-levels", levels) {
+        tracing::warn!(error = %e, "failed to emit audio-channel-levels event");
+    }
+}
+
+/// Запускает проверку микрофона: захват без накопления в pipeline и события
+/// `audio-level` и `audio-channel-levels` каждые `MIC_TEST_LEVEL_INTERVAL_MS`
+/// для VU-метра в настройках.
+///
+/// Не затрагивает state machine; останавливается `stop_mic_test`, началом
+/// диктовки или само через `MIC_TEST_MAX_SEC`.
+pub fn start_mic_test<R: Runtime>(app: &AppHandle<R>) -> audio::Result<()> {
+    stop_mic_test(app);
+
+    let config = app
+        .state::<Mutex<AppConfig>>()
+        .lock()
+        .expect("config mutex poisoned")
+        .clone();
+    let mut capture = AudioCapture::from_config(&config)?;
+    capture.start_recording()?;
+    tracing::info!("microphone test started");
+
     let pipeline = app.state::<PipelineState>();
+    *pipeline.mic_test.lock().expect("mic_test mutex poisoned") = Some(capture);
+
+    let app_handle = app.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        let mut interval = tokio::time::interval(Duration::from_millis(MIC_TEST_LEVEL_INTERVAL_MS));
+        loop {
+            interval.tick().await;
+            let (level, channel_levels) = {
+                let pipeline = app_handle.state::<PipelineState>();
+                let capture = pipeline.mic_test.lock().expect("mic_test mutex poisoned");
+                match capture.as_ref() {
+                    Some(c) if c.is_recording() && !c.has_stream_failed() => {
+                        (c.current_level(), c.channel_levels())
+                    }
+                    _ => break,
+                }
+            };
+            if let Err(e) = app_handle.emit("audio-level", level) {
+                tracing::warn!(error = %e, "failed to emit audio-level event");
+            }
+            emit_channel_levels(&app_handle, channel_levels);
+            if started.elapsed() >= Duration::from_secs(MIC_TEST_MAX_SEC) {
+                break;
+            }
+        }
+        stop_mic_test_capture(&app_handle);
+    });
+    pipeline
+        .mic_test_handle
+        .lock()
+        .expect("mic_test_handle mutex poisoned")
+        .replace(handle);
+    Ok(())
+}
 
+/// Останавливает проверку микрофона, если она запущена.
+pub fn stop_mic_test<R: Runtime>(app: &AppHandle<R>) {
+    let pipeline = app.state::<PipelineState>();
     if let Some(handle) = pipeline
-        .timeout_handle
+        .mic_test_handle
         .lock()
-        .expect("timeout mutex poisoned")
+        .expect("mic_test_handle mutex poisoned")
         .take()
     {
         handle.abort();
     }
+    stop_mic_test_capture(app);
+}
+
+/// Закрывает захват проверки микрофона и обнуляет VU-метр.
+fn stop_mic_test_capture<R: Runtime>(app: &AppHandle<R>) {
+    let pipeline = app.state::<PipelineState>();
+    let Some(mut capture) = pipeline
+        .mic_test
+        .lock()
+        .expect("mic_test mutex poisoned")
+        .take()
+    else {
+        return;
+    };
+    if let Err(e) = capture.stop_recording() {
+        tracing::warn!(error = %e, "failed to stop microphone test capture");
+    }
+    tracing::info!("microphone test stopped");
+    if let Err(e) = app.emit("audio-level", 0.0f32) {
+        tracing::warn!(error = %e, "failed to emit audio-level event");
+    }
+}
+
+/// Микрофон отключился во время записи: отменяет таймаут и переводит в Error.
+fn handle_capture_disconnect<R: Runtime>(app: &AppHandle<R>, error: &str) {
+    let pipeline = app.state::<PipelineState>();
+    pipeline.cancel_duration_timeout();
+    pipeline.take_streaming();
+
+    tracing::error!(error, "microphone disconnected during recording");
+    handle_pipeline_error(app, "Microphone disconnected during recording");
+}
+
+/// Приостанавливает запись без закрытия потока (Recording -> Paused).
+pub fn pause_recording<R: Runtime>(app: &AppHandle<R>) {
+    let pipeline = app.state::<PipelineState>();
+    // Время на паузе не считается в max_recording_duration_sec
+    pipeline.cancel_duration_timeout();
+    pipeline
+        .recording_clock
+        .lock()
+        .expect("clock mutex poisoned")
+        .pause(Instant::now());
+
+    let mut capture = pipeline.capture.lock().expect("capture mutex poisoned");
+    match capture.as_mut().map(|c| c.pause_recording()) {
+        Some(Ok(())) => {}
+        Some(Err(e)) => tracing::warn!(error = %e, "failed to pause recording"),
+        None => tracing::warn!("no active recording to pause"),
+    }
+}
+
+/// Продолжает приостановленную запись в тот же буфер (Paused -> Recording).
+pub fn resume_recording<R: Runtime>(app: &AppHandle<R>) {
+    let pipeline = app.state::<PipelineState>();
+    {
+        let mut capture = pipeline.capture.lock().expect("capture mutex poisoned");
+        match capture.as_mut().map(|c| c.resume_recording()) {
+            Some(Ok(())) => {}
+            Some(Err(e)) => tracing::warn!(error = %e, "failed to resume recording"),
+            None => tracing::warn!("no paused recording to resume"),
+        }
+    }
+
+    let remaining = {
+        let mut clock = pipeline
+            .recording_clock
+            .lock()
+            .expect("clock mutex poisoned");
+        let now = Instant::now();
+        clock.resume(now);
+        clock.remaining(now)
+    };
+    start_duration_timeout(app, remaining);
+}
+
+/// Останавливает захват и отбрасывает записанное (Paused -> Idle по отмене).
+pub fn discard_recording<R: Runtime>(app: &AppHandle<R>) {
+    let pipeline = app.state::<PipelineState>();
+    pipeline.cancel_duration_timeout();
+    pipeline.take_streaming();
+
+    let capture = pipeline
+        .capture
+        .lock()
+        .expect("capture mutex poisoned")
+        .take();
+    match capture.map(|mut c| c.stop_recording()) {
+        Some(Ok(_)) => tracing::info!("recording discarded"),
+        Some(Err(e)) => tracing::warn!(error = %e, "failed to stop discarded recording"),
+        None => tracing::warn!("no paused recording to discard"),
+    }
+}
+
+/// Останавливает захват аудио и запускает pipeline обработки.
+///
+/// Вызывается при переходе состояния Recording -> Transcribing.
+pub fn stop_recording_and_run_pipeline<R: Runtime>(app: &AppHandle<R>) {
+    let pipeline = app.state::<PipelineState>();
+
+    pipeline.cancel_duration_timeout();
+    let streaming = pipeline.take_streaming();
 
     let mut capture = match pipeline
         .capture
@@ -142,7 +718,7 @@ pub fn stop_recording_and_run_pipeline<R: Runtime>(app: &AppHandle<R>) {
         }
     };
 
-    let (audio, format) = match capture.stop_recording() {
+    let captured = match capture.stop_recording() {
         Ok(data) => data,
         Err(e) => {
             tracing::error!(error = %e, "failed to stop recording");
@@ -157,8 +733,37 @@ pub fn stop_recording_and_run_pipeline<R: Runtime>(app: &AppHandle<R>) {
         .expect("config mutex poisoned")
         .clone();
 
-    let api_key = match crate::config::secrets::load_api_key() {
+    // Случайное короткое нажатие: не тратим запрос к STT
+    let samples_per_sec = captured.format.sample_rate * u32::from(captured.format.channels.max(1));
+    if audio::is_too_short(
+        &captured.samples,
+        samples_per_sec,
+        config.min_recording_duration_ms,
+    ) {
+        tracing::info!(
+            samples = captured.samples.len(),
+            min_ms = config.min_recording_duration_ms,
+            "recording too short, skipping transcription"
+        );
+        notifications::notify_error(app, "Recording too short, try again");
+        abort_pipeline(app);
+        return;
+    }
+
+    if captured.truncated {
+        notifications::notify_info(
+            app,
+            &format!(
+                "Recording exceeded {} seconds, the rest was not captured",
+                config.max_recording_duration_sec
+            ),
+        );
+    }
+    let (audio, format) = (captured.samples, captured.format);
+
+    let api_key = match crate::config::secrets::load_api_key(&ApiKeyScope::from_config(&config)) {
         Ok(Some(key)) => key,
+        Ok(None) if !requires_api_key(&config) => String::new(),
         Ok(None) => {
             handle_pipeline_error(app, "API key not configured");
             return;
@@ -173,7 +778,10 @@ pub fn stop_recording_and_run_pipeline<R: Runtime>(app: &AppHandle<R>) {
     let app_handle = app.clone();
 
     let handle = tauri::async_runtime::spawn(async move {
-        run_pipeline(app_handle, audio, format, config, api_key, cancel).await;
+        run_pipeline(
+            app_handle, audio, format, config, api_key, streaming, cancel,
+        )
+        .await;
     });
 
     *pipeline
@@ -190,14 +798,7 @@ pub fn cancel_pipeline<R: Runtime>(app: &AppHandle<R>) {
     pipeline.cancel.store(true, Ordering::SeqCst);
     tracing::info!("pipeline cancellation requested");
 
-    let timeout = pipeline
-        .timeout_handle
-        .lock()
-        .expect("timeout mutex poisoned")
-        .take();
-    if let Some(handle) = timeout {
-        handle.abort();
-    }
+    pipeline.cancel_duration_timeout();
 
     // Прерываем задачу pipeline, чтобы гарантировать остановку даже если
     // новая запись стартует и сбросит флаг cancel.
@@ -218,7 +819,15 @@ pub fn cancel_pipeline<R: Runtime>(app: &AppHandle<R>) {
 #[derive(Debug)]
 pub(crate) enum ProcessingOutcome {
     /// Текст готов к вставке или показу в окне результата.
-    Text(String),
+    /// `raw` - исходный текст распознавания (до улучшения),
+    /// `language` - язык из настроек или определенный STT (`None` - неизвестен),
+    /// `timings` - тайминги этапов обработки (без вставки).
+    Text {
+        text: String,
+        raw: String,
+        language: Option<String>,
+        timings: SessionMetrics,
+    },
     /// Запись слишком короткая после обрезки тишины.
     TooShort { duration_ms: u32 },
     /// STT вернул пустой текст (речь не обнаружена).
@@ -233,16 +842,26 @@ pub(crate) enum ProcessingOutcome {
 ///
 /// Без зависимостей от Tauri. Вызывает `on_transcription_done` после STT,
 /// чтобы вызывающий код мог обновить UI-состояние (Transcribing -> Enhancing).
+/// С `streaming` текст берется из потоковой транскрипции, шедшей во время записи;
+/// при ее ошибке запись распознается целиком.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn process_audio(
     audio: &[f32],
     format: &CaptureFormat,
     config: &AppConfig,
     api_key: &str,
+    models: &stt::ModelCache,
+    streaming: Option<StreamingSession>,
     cancel: &AtomicBool,
     on_transcription_done: impl FnOnce() + Send,
 ) -> ProcessingOutcome {
     let pipeline_start = Instant::now();
     let is_cancelled = || cancel.load(Ordering::SeqCst);
+    let samples_per_sec = u64::from(format.sample_rate) * u64::from(format.channels.max(1));
+    let mut timings = SessionMetrics {
+        audio_ms: audio.len() as u64 * 1000 / samples_per_sec.max(1),
+        ..Default::default()
+    };
 
     tracing::info!(
         samples = audio.len(),
@@ -253,7 +872,13 @@ pub(crate) async fn process_audio(
 
     // Шаг 1: Препроцессинг (моно 16кГц)
     let step = Instant::now();
-    let processed = preprocess::preprocess(audio, format.channels, format.sample_rate);
+    let preprocess_start = step;
+    let processed = preprocess::preprocess_with(
+        audio,
+        format.channels,
+        format.sample_rate,
+        &preprocess::PreprocessOptions::from_config(config),
+    );
     tracing::info!(
         ms = step.elapsed().as_millis() as u64,
         samples = processed.len(),
@@ -274,10 +899,15 @@ pub(crate) async fn process_audio(
     } else {
         processed
     };
+    timings.preprocess_ms = metrics::as_ms(preprocess_start.elapsed());
 
     // Шаг 3: Проверка минимальной длительности
     let duration_ms = (trimmed.len() as u64 * 1000 / TARGET_SAMPLE_RATE as u64) as u32;
-    if duration_ms < config.min_recording_duration_ms {
+    if audio::is_too_short(
+        &trimmed,
+        TARGET_SAMPLE_RATE,
+        config.min_recording_duration_ms,
+    ) {
         tracing::info!(
             duration_ms,
             min_ms = config.min_recording_duration_ms,
@@ -291,39 +921,63 @@ pub(crate) async fn process_audio(
         return ProcessingOutcome::Cancelled;
     }
 
-    // Шаг 4: STT
+    // Шаг 4: STT (или текст потоковой транскрипции, шедшей во время записи)
     let step = Instant::now();
     let language = match config.language.as_str() {
         "auto" => None,
         lang => Some(lang),
     };
 
-    let stt_client = match OpenAiSttClient::from_config(config, api_key) {
-        Ok(c) => c,
-        Err(e) => {
-            return ProcessingOutcome::Error(format!("STT client error: {e}"));
-        }
+    let streamed = match streaming {
+        Some(session) => match session.finish(audio, format).await {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::warn!(error = %e, "streaming transcription failed, transcribing the whole recording");
+                None
+            }
+        },
+        None => None,
     };
 
-    let raw_text = match stt::transcribe_audio(
-        Arc::new(stt_client),
-        &trimmed,
-        TARGET_SAMPLE_RATE,
-        language,
-        None,
-        None,
-    )
-    .await
-    {
-        Ok(text) => text,
-        Err(e) => {
-            return ProcessingOutcome::Error(format!("Transcription failed: {e}"));
+    let (raw_text, detected_language) = if let Some(text) = streamed {
+        (text, None)
+    } else {
+        let stt_client = match stt::build_provider(config, api_key, models).await {
+            Ok(c) => c,
+            Err(e) => {
+                return ProcessingOutcome::Error(format!("STT client error: {e}"));
+            }
+        };
+
+        let prompt = Some(config.stt_prompt.trim()).filter(|p| !p.is_empty());
+        let max_concurrent = config.stt_chain_chunk_prompts.then_some(1);
+
+        match stt::transcribe_samples(
+            stt_client,
+            &trimmed,
+            TARGET_SAMPLE_RATE,
+            &stt::TranscribeOptions {
+                language,
+                prompt,
+                max_concurrent,
+                ..stt::TranscribeOptions::default()
+            },
+            &stt::ChunkingConfig::from_config(config),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                return ProcessingOutcome::Error(format!("Transcription failed: {e}"));
+            }
         }
     };
 
+    timings.stt_ms = metrics::as_ms(step.elapsed());
     tracing::info!(
-        ms = step.elapsed().as_millis() as u64,
+        ms = timings.stt_ms,
         chars = raw_text.len(),
+        detected_language = detected_language.as_deref().unwrap_or("-"),
         "STT complete"
     );
 
@@ -340,10 +994,14 @@ pub(crate) async fn process_audio(
     // Уведомляем вызывающий код о завершении STT (для перехода состояния UI)
     on_transcription_done();
 
-    // Шаг 5: Enhance (если включено)
-    let text = if config.enhance_enabled {
+    // Шаг 5: Enhance (если включено и подходит по длине и языку)
+    // При "auto" подсказываем enhance язык, который определила STT-модель
+    let text_language = language.or(detected_language.as_deref());
+    let text = if enhance::should_enhance(&raw_text, text_language, config) {
         let step = Instant::now();
-        match enhance_text(config, api_key, &raw_text, language).await {
+        let result = enhance_text(config, api_key, &raw_text, text_language).await;
+        timings.enhance_ms = Some(metrics::as_ms(step.elapsed()));
+        match result {
             Ok(enhanced) => {
                 tracing::info!(
                     ms = step.elapsed().as_millis() as u64,
@@ -355,14 +1013,18 @@ pub(crate) async fn process_audio(
             }
             Err(e) => {
                 tracing::warn!(error = %e, "enhance failed, using raw text");
-                raw_text
+                raw_text.clone()
             }
         }
     } else {
-        tracing::debug!("enhance disabled, using raw text");
-        raw_text
+        tracing::debug!("enhance disabled or skipped, using raw text");
+        raw_text.clone()
     };
 
+    // Шаг 6: замены по глоссарию и нормализация чисел (детерминированно, без API)
+    let text = enhance::apply_glossary(&text, &config.glossary);
+    let text = numbers::normalize_numbers(&text, config.number_format, &config.language);
+
     if is_cancelled() {
         tracing::info!("pipeline cancelled after enhance");
         return ProcessingOutcome::Cancelled;
@@ -374,64 +1036,280 @@ pub(crate) async fn process_audio(
         "pipeline processing completed"
     );
 
-    ProcessingOutcome::Text(text)
+    timings.total_ms = metrics::as_ms(pipeline_start.elapsed());
+    ProcessingOutcome::Text {
+        text,
+        raw: raw_text,
+        language: text_language.map(str::to_string),
+        timings,
+    }
 }
 
-/// Полный pipeline диктовки: препроцессинг -> STT -> enhance -> вставка.
+/// Записывает клип фиксированной длительности (блокирующе).
+pub(crate) fn record_clip(
+    source: &mut impl AudioSource,
+    duration: Duration,
+) -> Result<(Vec<f32>, CaptureFormat), AudioError> {
+    source.start_recording()?;
+    std::thread::sleep(duration);
+    source.stop_recording()
+}
+
+/// Обрабатывает записанный клип и возвращает текст (без вставки).
 ///
-/// Запускается асинхронно после остановки записи. Делегирует основную обработку
-/// в `process_audio`, сам занимается вставкой и переходами состояний Tauri.
-async fn run_pipeline<R: Runtime>(
-    app: AppHandle<R>,
-    audio: Vec<f32>,
-    format: CaptureFormat,
-    config: AppConfig,
-    api_key: String,
+/// Используется `dictate_once` для автоматизации и smoke-тестов.
+pub(crate) async fn transcribe_clip(
+    audio: &[f32],
+    format: &CaptureFormat,
+    config: &AppConfig,
+    api_key: &str,
+    models: &stt::ModelCache,
+) -> Result<String, String> {
+    let cancel = AtomicBool::new(false);
+    match process_audio(audio, format, config, api_key, models, None, &cancel, || {}).await {
+        ProcessingOutcome::Text { text, .. } => Ok(text),
+        ProcessingOutcome::TooShort { duration_ms } => {
+            Err(format!("Recording too short ({duration_ms}ms)"))
+        }
+        ProcessingOutcome::NoSpeech => Err("No speech detected".to_string()),
+        ProcessingOutcome::Cancelled => Err("Cancelled".to_string()),
+        ProcessingOutcome::Error(message) => Err(message),
+    }
+}
+
+/// Записывает клип `duration` с микрофона и распознает его без хоткея и вставки.
+///
+/// Длительность ограничивается `max_recording_duration_sec`.
+pub async fn dictate_once(
+    config: AppConfig,
+    duration: Duration,
+    models: &stt::ModelCache,
+) -> Result<String, String> {
+    let api_key = match crate::config::secrets::load_api_key(&ApiKeyScope::from_config(&config))
+        .map_err(|e| format!("Failed to load API key: {e}"))?
+    {
+        Some(key) => key,
+        None if !requires_api_key(&config) => String::new(),
+        None => return Err("API key not configured".to_string()),
+    };
+
+    let duration = duration.min(Duration::from_secs(u64::from(
+        config.max_recording_duration_sec,
+    )));
+    tracing::info!(ms = duration.as_millis() as u64, "dictate_once started");
+
+    // cpal::Stream не Send, поэтому запись целиком в блокирующем потоке
+    let capture_config = config.clone();
+    let (audio, format) = tauri::async_runtime::spawn_blocking(move || {
+        let mut capture = AudioCapture::from_config(&capture_config)?;
+        record_clip(&mut capture, duration)
+    })
+    .await
+    .map_err(|e| format!("Recording task failed: {e}"))?
+    .map_err(|e| format!("Recording failed: {e}"))?;
+
+    transcribe_clip(&audio, &format, &config, &api_key, models).await
+}
+
+/// Длительность записи для самопроверки микрофона и STT.
+const SELF_TEST_DURATION: Duration = Duration::from_secs(1);
+
+/// Результат самопроверки: какие этапы прошли и на каком сломалось.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct SelfTestResult {
+    /// Микрофон открылся и записал звук
+    pub mic_ok: bool,
+    /// Препроцессинг и кодирование в OGG/Opus прошли
+    pub encode_ok: bool,
+    /// STT-провайдер ответил
+    pub stt_ok: bool,
+    /// Распознанный текст (пустой, если в записи тишина)
+    pub detected_text: Option<String>,
+    /// Ошибка первого упавшего этапа
+    pub error: Option<String>,
+}
+
+/// Самопроверка: запись ~1с -> препроцессинг + кодирование -> STT.
+///
+/// Без вставки, enhance и clipboard. Каждая ошибка помечена этапом,
+/// чтобы онбординг мог показать, что сломалось: микрофон, ключ или сеть.
+pub async fn run_self_test(config: AppConfig, models: &stt::ModelCache) -> SelfTestResult {
+    tracing::info!("self-test started");
+
+    let capture_config = config.clone();
+    let recorded = tauri::async_runtime::spawn_blocking(move || {
+        let mut capture = AudioCapture::from_config(&capture_config)?;
+        record_clip(&mut capture, SELF_TEST_DURATION)
+    })
+    .await
+    .map_err(|e| format!("Recording task failed: {e}"))
+    .and_then(|r| r.map_err(|e| e.to_string()));
+
+    let (audio, format) = match recorded {
+        Ok(clip) if !clip.0.is_empty() => clip,
+        Ok(_) => return self_test_failure("Microphone: no audio captured".to_string()),
+        Err(e) => return self_test_failure(format!("Microphone: {e}")),
+    };
+
+    let api_key = if config.stt_provider == SttProviderKind::OpenAi {
+        match crate::config::secrets::load_api_key(&ApiKeyScope::from_config(&config)) {
+            Ok(key) => key,
+            Err(e) => {
+                tracing::warn!(error = %e, "self-test: failed to load API key");
+                None
+            }
+        }
+    } else {
+        Some(String::new())
+    };
+
+    let result = self_test_clip(&audio, &format, &config, api_key.as_deref(), models).await;
+    tracing::info!(
+        encode_ok = result.encode_ok,
+        stt_ok = result.stt_ok,
+        error = result.error.as_deref().unwrap_or("-"),
+        "self-test finished"
+    );
+    result
+}
+
+/// Ошибка на этапе записи: дальше проверять нечего.
+fn self_test_failure(error: String) -> SelfTestResult {
+    SelfTestResult {
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+/// Этапы самопроверки после записи: кодирование и STT.
+///
+/// `api_key` - `None`, если ключ нужен, но не настроен.
+async fn self_test_clip(
+    audio: &[f32],
+    format: &CaptureFormat,
+    config: &AppConfig,
+    api_key: Option<&str>,
+    models: &stt::ModelCache,
+) -> SelfTestResult {
+    let mut result = SelfTestResult {
+        mic_ok: true,
+        ..Default::default()
+    };
+
+    let processed = preprocess::preprocess_with(
+        audio,
+        format.channels,
+        format.sample_rate,
+        &preprocess::PreprocessOptions::from_config(config),
+    );
+    let encode = audio::encode::EncodeConfig::from_config(config);
+    if let Err(e) = audio::encode::encode_audio(&processed, TARGET_SAMPLE_RATE, &encode) {
+        result.error = Some(format!("Encoding: {e}"));
+        return result;
+    }
+    result.encode_ok = true;
+
+    let Some(api_key) = api_key else {
+        result.error = Some("Speech recognition: API key not configured".to_string());
+        return result;
+    };
+    let language = Some(config.language.as_str()).filter(|lang| *lang != "auto");
+    let transcription = match stt::build_provider(config, api_key, models).await {
+        Ok(client) => {
+            stt::transcribe_samples(
+                client,
+                &processed,
+                TARGET_SAMPLE_RATE,
+                &stt::TranscribeOptions {
+                    language,
+                    ..stt::TranscribeOptions::default()
+                },
+                &stt::ChunkingConfig::from_config(config),
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    match transcription {
+        Ok((text, _)) => {
+            result.stt_ok = true;
+            result.detected_text = Some(text.trim().to_string());
+        }
+        Err(e) => result.error = Some(format!("Speech recognition: {e}")),
+    }
+    result
+}
+
+/// Полный pipeline диктовки: препроцессинг -> STT -> enhance -> вставка.
+///
+/// Запускается асинхронно после остановки записи. Делегирует основную обработку
+/// в `process_audio`, сам занимается вставкой и переходами состояний Tauri.
+async fn run_pipeline<R: Runtime>(
+    app: AppHandle<R>,
+    audio: Vec<f32>,
+    format: CaptureFormat,
+    config: AppConfig,
+    api_key: String,
+    streaming: Option<StreamingSession>,
     cancel: Arc<AtomicBool>,
 ) {
     let pipeline_start = Instant::now();
 
+    if config.debug_save_audio {
+        let (audio, format, config) = (audio.clone(), format.clone(), config.clone());
+        tokio::task::spawn_blocking(move || {
+            crate::debug_audio::save_debug_recording(&audio, &format, &config)
+        });
+    }
+
     let app_for_transition = app.clone();
-    let outcome = process_audio(&audio, &format, &config, &api_key, &cancel, move || {
-        dispatch_pipeline_event(&app_for_transition, AppEvent::TranscriptionDone)
-    })
+    let models = app.state::<stt::ModelCache>();
+    let outcome = process_audio(
+        &audio,
+        &format,
+        &config,
+        &api_key,
+        &models,
+        streaming,
+        &cancel,
+        move || dispatch_pipeline_event(&app_for_transition, AppEvent::TranscriptionDone, None),
+    )
     .await;
 
     match outcome {
-        ProcessingOutcome::Text(text) => {
+        ProcessingOutcome::Text {
+            text,
+            raw,
+            language,
+            mut timings,
+        } => {
+            app.state::<SharedAppState>().set_last_result(text.clone());
+
             // Переход: Enhancing -> Pasting
-            dispatch_pipeline_event(&app, AppEvent::EnhancementDone);
-
-            // Вставка (в отдельном потоке для чистого Win32-состояния)
-            let step = Instant::now();
-            let text_for_paste = text.clone();
-            let status = tokio::task::spawn_blocking(move || paste::paste_text(&text_for_paste))
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::error!(error = %e, "paste thread panicked");
-                    PasteStatus::ResultWindow
+            dispatch_pipeline_event(&app, AppEvent::EnhancementDone, None);
+
+            if delivery_decision(&config) == DeliveryDecision::Confirm {
+                tracing::info!("awaiting user confirmation before paste");
+                app.state::<PendingPaste>().set(PendingText {
+                    text: text.clone(),
+                    raw,
+                    language,
                 });
-            tracing::info!(
-                ms = step.elapsed().as_millis() as u64,
-                status = ?status,
-                "paste complete"
-            );
-
-            match status {
-                PasteStatus::Pasted => {}
-                PasteStatus::ClipboardOnly => {
-                    notifications::notify_info(
-                        &app,
-                        &format!("Text copied to clipboard (paste with {PASTE_SHORTCUT})"),
-                    );
-                }
-                PasteStatus::ResultWindow => {
-                    show_result_window(&app, &text);
-                }
+                show_result_window(&app, &text);
+                // Pasting -> Idle без уведомления "Text inserted": текст еще не вставлен
+                finish_silently(&app, AppEvent::PasteDone);
+            } else {
+                let step = Instant::now();
+                deliver_text(&app, &config, &text, &raw, language.as_deref()).await;
+                timings.paste_ms = Some(metrics::as_ms(step.elapsed()));
+
+                // Переход: Pasting -> Idle (уведомление с превью текста)
+                dispatch_pipeline_event(&app, AppEvent::PasteDone, Some(&text));
             }
 
-            // Переход: Pasting -> Idle
-            dispatch_pipeline_event(&app, AppEvent::PasteDone);
+            timings.total_ms = metrics::as_ms(pipeline_start.elapsed());
+            timings.log_summary();
+            app.state::<SharedAppState>().record_metrics(timings);
         }
         ProcessingOutcome::TooShort { duration_ms } => {
             tracing::info!(duration_ms, "recording too short");
@@ -456,9 +1334,115 @@ async fn run_pipeline<R: Runtime>(
     );
 }
 
+/// Вставляет подтвержденный текст (Accept в окне результата).
+///
+/// Окно результата закрывается, после возврата фокуса текст вставляется
+/// тем же путем, что и при автоматической вставке.
+pub fn accept_pending_paste<R: Runtime>(app: &AppHandle<R>) -> std::result::Result<(), String> {
+    let pending = app
+        .state::<PendingPaste>()
+        .take()
+        .ok_or_else(|| "No text awaiting confirmation".to_string())?;
+
+    if let Some(window) = app.get_webview_window("result") {
+        if let Err(e) = window.close() {
+            tracing::warn!(error = %e, "failed to close result window");
+        }
+    }
+
+    let config = app
+        .state::<Mutex<AppConfig>>()
+        .lock()
+        .expect("config mutex poisoned")
+        .clone();
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(FOCUS_RETURN_DELAY_MS)).await;
+        deliver_text(
+            &app_handle,
+            &config,
+            &pending.text,
+            &pending.raw,
+            pending.language.as_deref(),
+        )
+        .await;
+    });
+
+    Ok(())
+}
+
+/// Отбрасывает неподтвержденный текст (Discard). Clipboard не меняется.
+pub fn discard_pending_paste<R: Runtime>(app: &AppHandle<R>) {
+    if app.state::<PendingPaste>().discard() {
+        tracing::info!("pending paste discarded by user");
+    }
+    *app.state::<ResultText>()
+        .0
+        .lock()
+        .expect("result mutex poisoned") = None;
+
+    if let Some(window) = app.get_webview_window("result") {
+        if let Err(e) = window.close() {
+            tracing::warn!(error = %e, "failed to close result window");
+        }
+    }
+}
+
 // --- Helpers ---
 
+/// Вставляет текст и отправляет его на webhook (если настроен).
+///
+/// `language` - язык текста (из настроек или определенный STT) для webhook.
+async fn deliver_text<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &AppConfig,
+    text: &str,
+    raw: &str,
+    language: Option<&str>,
+) {
+    // Вставка (в отдельном потоке для чистого Win32-состояния)
+    let step = Instant::now();
+    let text_for_paste = text.to_string();
+    let options = paste::PasteOptions::from_config(config);
+    let status = tokio::task::spawn_blocking(move || paste::paste_text(&text_for_paste, &options))
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!(error = %e, "paste thread panicked");
+            PasteStatus::ResultWindow
+        });
+    tracing::info!(
+        ms = step.elapsed().as_millis() as u64,
+        status = ?status,
+        "paste complete"
+    );
+
+    match status {
+        PasteStatus::Pasted => history::record(config, text),
+        PasteStatus::ClipboardOnly => {
+            notifications::notify_info(
+                app,
+                &format!("Text copied to clipboard (paste with {PASTE_SHORTCUT})"),
+            );
+        }
+        PasteStatus::ResultWindow => {
+            show_result_window(app, text);
+        }
+    }
+
+    if let Some(url) = config
+        .webhook_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())
+    {
+        let payload = WebhookPayload::new(text, raw, language);
+        webhook::spawn_delivery(url.trim().to_string(), payload);
+    }
+}
+
 /// Улучшает текст через OpenAI Responses API.
+///
+/// Фразы из `protected_phrases` маскируются до запроса и восстанавливаются после;
+/// если LLM потеряла плейсхолдер, возвращается сырой текст.
 async fn enhance_text(
     config: &AppConfig,
     api_key: &str,
@@ -467,19 +1451,31 @@ async fn enhance_text(
 ) -> std::result::Result<String, String> {
     let enhancer =
         OpenAiEnhancer::from_config(config, api_key).map_err(|e| format!("enhance init: {e}"))?;
-    enhancer
-        .enhance(raw_text, language)
+    let masked = protect::mask_phrases(raw_text, &config.protected_phrases);
+    let enhanced = enhancer
+        .enhance(&masked.text, language)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    match protect::unmask_phrases(&enhanced, &masked.originals) {
+        Some(text) => Ok(text),
+        None => {
+            tracing::warn!("protected phrases not preserved by enhance, using raw text");
+            Ok(raw_text.to_string())
+        }
+    }
 }
 
 /// Отправляет событие pipeline: переход состояния + трей + уведомление.
-fn dispatch_pipeline_event<R: Runtime>(app: &AppHandle<R>, event: AppEvent) {
+///
+/// `text` - результат сессии для превью в уведомлении о вставке.
+fn dispatch_pipeline_event<R: Runtime>(app: &AppHandle<R>, event: AppEvent, text: Option<&str>) {
     let shared = app.state::<SharedAppState>();
     let (old, new) = shared.dispatch_with_old(&event);
     if old != new {
         tray::update_tray(app, new);
-        notifications::notify_state_change(app, old, new);
+        crate::emit_state_changed(app, old, new);
+        notifications::notify_state_change(app, old, new, text);
     }
 }
 
@@ -490,20 +1486,28 @@ fn handle_pipeline_error<R: Runtime>(app: &AppHandle<R>, message: &str) {
 
     let shared = app.state::<SharedAppState>();
 
-    let (_, error_state) = shared.dispatch_with_old(&AppEvent::Failed(message.to_string()));
+    let (old, error_state) = shared.dispatch_with_old(&AppEvent::Failed(message.to_string()));
     tray::update_tray(app, error_state);
+    crate::emit_state_changed(app, old, error_state);
 
     // Авто-восстановление в Idle
     let (_, idle_state) = shared.dispatch_with_old(&AppEvent::ErrorAcknowledged);
     tray::update_tray(app, idle_state);
+    crate::emit_state_changed(app, error_state, idle_state);
 }
 
 /// Прерывает pipeline штатно -> Idle (слишком короткая запись, нет речи и т.д.).
 fn abort_pipeline<R: Runtime>(app: &AppHandle<R>) {
+    finish_silently(app, AppEvent::Cancel);
+}
+
+/// Применяет событие и обновляет трей без уведомления о смене состояния.
+fn finish_silently<R: Runtime>(app: &AppHandle<R>, event: AppEvent) {
     let shared = app.state::<SharedAppState>();
-    let (old, new) = shared.dispatch_with_old(&AppEvent::Cancel);
+    let (old, new) = shared.dispatch_with_old(&event);
     if old != new {
         tray::update_tray(app, new);
+        crate::emit_state_changed(app, old, new);
     }
 }
 
@@ -557,6 +1561,59 @@ mod tests {
         assert!(state.pipeline_handle.lock().unwrap().is_none());
     }
 
+    #[test]
+    fn recording_clock_should_not_count_paused_time() {
+        // Given
+        let start = Instant::now();
+        let mut clock = RecordingClock::default();
+        clock.start(Duration::from_secs(60), start);
+
+        // When: 10с записи, 100с паузы, еще 5с записи
+        clock.pause(start + Duration::from_secs(10));
+        clock.resume(start + Duration::from_secs(110));
+
+        // Then
+        let now = start + Duration::from_secs(115);
+        assert_eq!(clock.remaining(now), Duration::from_secs(45));
+        assert_eq!(
+            clock.remaining(start + Duration::from_secs(500)),
+            Duration::ZERO
+        );
+    }
+
+    #[tokio::test]
+    async fn aborted_timeout_should_not_fire_after_early_stop() {
+        // Given
+        let fired = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&fired);
+        let handle = spawn_timeout(Duration::from_millis(50), move || {
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        // When: запись остановлена раньше таймаута
+        handle.abort();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        // Then
+        assert!(!fired.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn timeout_should_fire_when_not_cancelled() {
+        // Given
+        let fired = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&fired);
+
+        // When
+        let handle = spawn_timeout(Duration::from_millis(10), move || {
+            flag.store(true, Ordering::SeqCst);
+        });
+        handle.await.unwrap();
+
+        // Then
+        assert!(fired.load(Ordering::SeqCst));
+    }
+
     #[test]
     fn cancel_flag_should_toggle() {
         // Given
@@ -567,87 +1624,513 @@ mod tests {
         state.cancel.store(true, Ordering::SeqCst);
         assert!(state.cancel.load(Ordering::SeqCst));
 
-        state.cancel.store(false, Ordering::SeqCst);
-        assert!(!state.cancel.load(Ordering::SeqCst));
-    }
+        state.cancel.store(false, Ordering::SeqCst);
+        assert!(!state.cancel.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn delivery_decision_should_auto_paste_by_default() {
+        // Given
+        let config = AppConfig::default();
+
+        // When / Then
+        assert_eq!(delivery_decision(&config), DeliveryDecision::AutoPaste);
+    }
+
+    #[test]
+    fn delivery_decision_should_confirm_when_enabled() {
+        // Given
+        let config = AppConfig {
+            confirm_before_paste: true,
+            ..Default::default()
+        };
+
+        // When / Then
+        assert_eq!(delivery_decision(&config), DeliveryDecision::Confirm);
+    }
+
+    #[test]
+    fn requires_api_key_should_skip_key_only_for_fully_offline_setup() {
+        // Given
+        let offline = AppConfig {
+            stt_provider: SttProviderKind::Offline,
+            enhance_enabled: false,
+            ..AppConfig::default()
+        };
+        let offline_with_enhance = AppConfig {
+            stt_provider: SttProviderKind::Offline,
+            enhance_enabled: true,
+            ..AppConfig::default()
+        };
+
+        // When / Then
+        assert!(requires_api_key(&AppConfig::default()));
+        assert!(requires_api_key(&offline_with_enhance));
+        assert!(!requires_api_key(&offline));
+    }
+
+    #[test]
+    fn api_key_missing_should_allow_offline_setup_without_key() {
+        // Given: офлайн STT без улучшения и без ключа
+        let offline = AppConfig {
+            stt_provider: SttProviderKind::Offline,
+            enhance_enabled: false,
+            ..AppConfig::default()
+        };
+        let mut key_checked = false;
+
+        // When
+        let missing = api_key_missing(&offline, || {
+            key_checked = true;
+            false
+        });
+
+        // Then: запись разрешена, keychain не опрашивается
+        assert!(!missing);
+        assert!(!key_checked);
+    }
+
+    #[test]
+    fn api_key_missing_should_block_cloud_setup_without_key() {
+        assert!(api_key_missing(&AppConfig::default(), || false));
+        assert!(!api_key_missing(&AppConfig::default(), || true));
+    }
+
+    #[test]
+    fn pending_paste_should_hand_out_text_once_on_accept() {
+        // Given
+        let pending = PendingPaste::new();
+        pending.set(PendingText {
+            text: "Hello.".to_string(),
+            raw: "hello".to_string(),
+            language: None,
+        });
+
+        // When
+        let first = pending.take();
+        let second = pending.take();
+
+        // Then
+        assert_eq!(first.map(|p| p.text), Some("Hello.".to_string()));
+        assert_eq!(second, None);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn discard_should_leave_clipboard_untouched() {
+        // Given: в clipboard пользовательские данные
+        let Ok(mut clipboard) = arboard::Clipboard::new() else {
+            return;
+        };
+        let original = clipboard.get_text().ok();
+        clipboard.set_text("user data").expect("set clipboard");
+        let pending = PendingPaste::new();
+        pending.set(PendingText {
+            text: "secret dictation".to_string(),
+            raw: "secret dictation".to_string(),
+            language: None,
+        });
+
+        // When
+        let discarded = pending.discard();
+
+        // Then
+        assert!(discarded);
+        assert!(!pending.is_pending());
+        assert_eq!(clipboard.get_text().ok().as_deref(), Some("user data"));
+
+        if let Some(text) = original {
+            let _ = clipboard.set_text(text);
+        }
+    }
+
+    #[test]
+    fn result_text_should_store_and_retrieve() {
+        // Given
+        let result = ResultText::new();
+        assert!(result.0.lock().unwrap().is_none());
+
+        // When
+        *result.0.lock().unwrap() = Some("Hello world".to_string());
+
+        // Then
+        assert_eq!(result.0.lock().unwrap().as_deref(), Some("Hello world"));
+    }
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use crate::audio::{AudioError, CaptureFormat};
+    use crate::config::schema::AppConfig;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Генерирует синусоидальный тон 440Гц с заданной частотой дискретизации.
+    fn generate_tone(sample_rate: u32, duration_ms: u32, amplitude: f32) -> Vec<f32> {
+        let num_samples = (sample_rate * duration_ms / 1000) as usize;
+        (0..num_samples)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                amplitude * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+            })
+            .collect()
+    }
+
+    fn make_test_config(base_url: &str) -> AppConfig {
+        AppConfig {
+            api_base_url: base_url.to_string(),
+            min_recording_duration_ms: 300,
+            vad_trim_silence: false,
+            enhance_enabled: true,
+            connect_timeout_sec: 5,
+            read_timeout_stt_sec: 10,
+            read_timeout_enhance_sec: 10,
+            retry_count: 0,
+            language: "auto".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn make_test_format() -> CaptureFormat {
+        CaptureFormat {
+            sample_rate: 16000,
+            channels: 1,
+        }
+    }
+
+    fn make_responses_json(text: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "resp_test",
+            "output": [{
+                "type": "message",
+                "content": [{
+                    "type": "output_text",
+                    "text": text
+                }]
+            }]
+        })
+    }
+
+    /// Заглушка микрофона: отдает заранее заданный буфер.
+    struct StubSource {
+        audio: Vec<f32>,
+        started: bool,
+    }
+
+    impl AudioSource for StubSource {
+        fn start_recording(&mut self) -> Result<(), AudioError> {
+            self.started = true;
+            Ok(())
+        }
+
+        fn stop_recording(&mut self) -> Result<(Vec<f32>, CaptureFormat), AudioError> {
+            if !self.started {
+                return Err(AudioError::NotRecording);
+            }
+            self.started = false;
+            Ok((std::mem::take(&mut self.audio), make_test_format()))
+        }
+    }
+
+    #[tokio::test]
+    async fn dictate_once_should_return_text_from_stub_capture_and_providers() {
+        // Given: заглушка микрофона + mock STT и enhance
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "text": "smoke test" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("Smoke test.")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut source = StubSource {
+            audio: generate_tone(16000, 1000, 0.3),
+            started: false,
+        };
+        let config = make_test_config(&server.uri());
+
+        // When
+        let (audio, format) =
+            record_clip(&mut source, Duration::from_millis(10)).expect("stub recording");
+        let result = transcribe_clip(&audio, &format, &config, "test-key").await;
+
+        // Then
+        assert_eq!(result, Ok("Smoke test.".to_string()));
+    }
+
+    #[tokio::test]
+    async fn transcribe_clip_should_report_too_short_recording() {
+        // Given: 100мс при минимуме 300мс, сеть не нужна
+        let audio = generate_tone(16000, 100, 0.3);
+        let config = make_test_config("http://127.0.0.1:9");
+
+        // When
+        let result = transcribe_clip(&audio, &make_test_format(), &config, "test-key").await;
+
+        // Then
+        assert!(result.unwrap_err().starts_with("Recording too short"));
+    }
+
+    #[tokio::test]
+    async fn self_test_clip_should_pass_all_stages_with_mock_stt() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": " hello " })),
+            )
+            .mount(&server)
+            .await;
+        let audio = generate_tone(16000, 1000, 0.3);
+        let config = make_test_config(&server.uri());
+
+        // When
+        let result = self_test_clip(
+            &audio,
+            &make_test_format(),
+            &config,
+            Some("test-key"),
+            &stt::ModelCache::default(),
+        )
+        .await;
+
+        // Then
+        assert_eq!(
+            result,
+            SelfTestResult {
+                mic_ok: true,
+                encode_ok: true,
+                stt_ok: true,
+                detected_text: Some("hello".to_string()),
+                error: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn self_test_clip_should_report_stt_stage_on_auth_failure() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        let audio = generate_tone(16000, 1000, 0.3);
+        let config = make_test_config(&server.uri());
+
+        // When
+        let result = self_test_clip(
+            &audio,
+            &make_test_format(),
+            &config,
+            Some("bad-key"),
+            &stt::ModelCache::default(),
+        )
+        .await;
+
+        // Then
+        assert!(result.mic_ok && result.encode_ok);
+        assert!(!result.stt_ok);
+        let error = result.error.unwrap();
+        assert!(error.starts_with("Speech recognition:"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn self_test_clip_should_report_missing_api_key() {
+        // Given
+        let audio = generate_tone(16000, 1000, 0.3);
+        let config = make_test_config("http://127.0.0.1:9");
+
+        // When
+        let result = self_test_clip(
+            &audio,
+            &make_test_format(),
+            &config,
+            None,
+            &stt::ModelCache::default(),
+        )
+        .await;
+
+        // Then
+        assert!(result.encode_ok);
+        assert!(!result.stt_ok);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Speech recognition: API key not configured")
+        );
+    }
+
+    #[tokio::test]
+    async fn pipeline_should_complete_happy_path_with_mock_stt_and_enhance() {
+        // Given: mock STT возвращает сырой текст, mock enhance - улучшенный.
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "text": "hello world" })),
+            )
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(make_responses_json("Hello, world!")),
+            )
+            .mount(&server)
+            .await;
 
-    #[test]
-    fn result_text_should_store_and_retrieve() {
-        // Given
-        let result = ResultText::new();
-        assert!(result.0.lock().unwrap().is_none());
+        let audio = generate_tone(16000, 1000, 0.3);
+        let config = make_test_config(&server.uri());
+        let cancel = AtomicBool::new(false);
 
         // When
-        *result.0.lock().unwrap() = Some("Hello world".to_string());
+        let outcome = process_audio(
+            &audio,
+            &make_test_format(),
+            &config,
+            "test-key",
+            &stt::ModelCache::default(),
+            None,
+            &cancel,
+            || {},
+        )
+        .await;
 
         // Then
-        assert_eq!(result.0.lock().unwrap().as_deref(), Some("Hello world"));
+        match outcome {
+            ProcessingOutcome::Text {
+                text,
+                raw,
+                language,
+                timings,
+            } => {
+                assert_eq!(text, "Hello, world!");
+                assert_eq!(raw, "hello world");
+                assert_eq!(language, None);
+                assert_eq!(timings.audio_ms, 1000);
+                assert!(timings.enhance_ms.is_some());
+                assert_eq!(timings.paste_ms, None);
+            }
+            other => panic!("ожидался Text, получено: {other:?}"),
+        }
     }
-}
 
-#[cfg(test)]
-mod integration_tests {
-    use super::*;
-    use crate::audio::CaptureFormat;
-    use crate::config::schema::AppConfig;
-    use wiremock::matchers::{method, path};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
+    #[tokio::test]
+    async fn pipeline_should_report_detected_language_when_auto() {
+        // Given: язык "auto", STT сообщает определенный язык
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!({ "text": "привет мир", "language": "Russian" }),
+                ),
+            )
+            .mount(&server)
+            .await;
 
-    /// Генерирует синусоидальный тон 440Гц с заданной частотой дискретизации.
-    fn generate_tone(sample_rate: u32, duration_ms: u32, amplitude: f32) -> Vec<f32> {
-        let num_samples = (sample_rate * duration_ms / 1000) as usize;
-        (0..num_samples)
-            .map(|i| {
-                let t = i as f32 / sample_rate as f32;
-                amplitude * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
-            })
-            .collect()
-    }
+        let audio = generate_tone(16000, 1000, 0.3);
+        let mut config = make_test_config(&server.uri());
+        config.enhance_enabled = false;
+        let cancel = AtomicBool::new(false);
 
-    fn make_test_config(base_url: &str) -> AppConfig {
-        AppConfig {
-            api_base_url: base_url.to_string(),
-            min_recording_duration_ms: 300,
-            vad_trim_silence: false,
-            enhance_enabled: true,
-            connect_timeout_sec: 5,
-            read_timeout_stt_sec: 10,
-            read_timeout_enhance_sec: 10,
-            retry_count: 0,
-            language: "auto".to_string(),
-            ..Default::default()
-        }
-    }
+        // When
+        let outcome = process_audio(
+            &audio,
+            &make_test_format(),
+            &config,
+            "test-key",
+            &stt::ModelCache::default(),
+            None,
+            &cancel,
+            || {},
+        )
+        .await;
 
-    fn make_test_format() -> CaptureFormat {
-        CaptureFormat {
-            sample_rate: 16000,
-            channels: 1,
+        // Then: для webhook передается язык распознавания, а не "auto"
+        match outcome {
+            ProcessingOutcome::Text { language, .. } => {
+                assert_eq!(language.as_deref(), Some("russian"));
+            }
+            other => panic!("ожидался Text, получено: {other:?}"),
         }
     }
 
-    fn make_responses_json(text: &str) -> serde_json::Value {
-        serde_json::json!({
-            "id": "resp_test",
-            "output": [{
-                "type": "message",
-                "content": [{
-                    "type": "output_text",
-                    "text": text
-                }]
-            }]
-        })
+    #[tokio::test]
+    async fn pipeline_should_preserve_protected_phrases_through_enhance() {
+        // Given: enhance получает только плейсхолдеры и чистит текст вокруг них
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "text": "um run kubectl get pods on AcmeCloud ok" }),
+            ))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/responses"))
+            .and(body_string_contains("run ⟦P0⟧ on ⟦P1⟧"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(make_responses_json("Run ⟦P0⟧ on ⟦P1⟧, ok.")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let audio = generate_tone(16000, 1000, 0.3);
+        let mut config = make_test_config(&server.uri());
+        config.protected_phrases = vec!["AcmeCloud".to_string(), "kubectl get pods".to_string()];
+        let cancel = AtomicBool::new(false);
+
+        // When
+        let outcome = process_audio(
+            &audio,
+            &make_test_format(),
+            &config,
+            "test-key",
+            &stt::ModelCache::default(),
+            None,
+            &cancel,
+            || {},
+        )
+        .await;
+
+        // Then
+        match outcome {
+            ProcessingOutcome::Text { text, .. } => {
+                assert_eq!(text, "Run kubectl get pods on AcmeCloud, ok.");
+            }
+            other => panic!("ожидался Text, получено: {other:?}"),
+        }
     }
 
     #[tokio::test]
-    async fn pipeline_should_complete_happy_path_with_mock_stt_and_enhance() {
-        // Given: mock STT возвращает сырой текст, mock enhance - улучшенный.
+    async fn pipeline_should_use_raw_text_when_enhance_drops_protected_phrase() {
+        // Given: LLM "исправила" плейсхолдер
         let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/audio/transcriptions"))
             .respond_with(
                 ResponseTemplate::new(200)
-                    .set_body_json(serde_json::json!({ "text": "hello world" })),
+                    .set_body_json(serde_json::json!({ "text": "ping AcmeCloud now" })),
             )
             .mount(&server)
             .await;
@@ -655,13 +2138,15 @@ mod integration_tests {
         Mock::given(method("POST"))
             .and(path("/v1/responses"))
             .respond_with(
-                ResponseTemplate::new(200).set_body_json(make_responses_json("Hello, world!")),
+                ResponseTemplate::new(200)
+                    .set_body_json(make_responses_json("Ping Acme Cloud now.")),
             )
             .mount(&server)
             .await;
 
         let audio = generate_tone(16000, 1000, 0.3);
-        let config = make_test_config(&server.uri());
+        let mut config = make_test_config(&server.uri());
+        config.protected_phrases = vec!["AcmeCloud".to_string()];
         let cancel = AtomicBool::new(false);
 
         // When
@@ -670,6 +2155,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             || {},
         )
@@ -677,9 +2164,7 @@ mod integration_tests {
 
         // Then
         match outcome {
-            ProcessingOutcome::Text(text) => {
-                assert_eq!(text, "Hello, world!");
-            }
+            ProcessingOutcome::Text { text, .. } => assert_eq!(text, "ping AcmeCloud now"),
             other => panic!("ожидался Text, получено: {other:?}"),
         }
     }
@@ -730,6 +2215,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             || {},
         )
@@ -737,7 +2224,7 @@ mod integration_tests {
 
         // Then: текст извлечен из message-элемента, reasoning-элемент пропущен
         match outcome {
-            ProcessingOutcome::Text(text) => {
+            ProcessingOutcome::Text { text, .. } => {
                 assert_eq!(
                     text, "Test transcription result.",
                     "текст должен быть извлечен из message-элемента enhance-ответа"
@@ -770,6 +2257,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             || {},
         )
@@ -804,6 +2293,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             || {},
         )
@@ -848,6 +2339,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             || {},
         )
@@ -855,13 +2348,55 @@ mod integration_tests {
 
         // Then
         match outcome {
-            ProcessingOutcome::Text(text) => {
+            ProcessingOutcome::Text { text, .. } => {
                 assert_eq!(text, "raw dictation text");
             }
             other => panic!("ожидался Text, получено: {other:?}"),
         }
     }
 
+    #[tokio::test]
+    async fn pipeline_should_apply_glossary_when_enhance_disabled() {
+        // Given: STT стабильно ошибается в названии компании
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "text": "deploy to acme cloud now" })),
+            )
+            .mount(&server)
+            .await;
+
+        let audio = generate_tone(16000, 1000, 0.3);
+        let mut config = make_test_config(&server.uri());
+        config.enhance_enabled = false;
+        config.glossary = vec![("acme cloud".to_string(), "AcmeCloud".to_string())];
+        let cancel = AtomicBool::new(false);
+
+        // When
+        let outcome = process_audio(
+            &audio,
+            &make_test_format(),
+            &config,
+            "test-key",
+            &stt::ModelCache::default(),
+            None,
+            &cancel,
+            || {},
+        )
+        .await;
+
+        // Then: raw остается исходным, итоговый текст - с заменой
+        match outcome {
+            ProcessingOutcome::Text { text, raw, .. } => {
+                assert_eq!(text, "deploy to AcmeCloud now");
+                assert_eq!(raw, "deploy to acme cloud now");
+            }
+            other => panic!("ожидался Text, получено: {other:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn pipeline_should_call_on_transcription_done_after_stt() {
         // Given
@@ -894,6 +2429,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             move || {
                 callback_flag.store(true, Ordering::SeqCst);
@@ -931,6 +2468,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             || {},
         )
@@ -964,6 +2503,8 @@ mod integration_tests {
             &make_test_format(),
             &config,
             "test-key",
+            &stt::ModelCache::default(),
+            None,
             &cancel,
             || {},
         )
@@ -980,4 +2521,101 @@ mod integration_tests {
             other => panic!("expected Error, got: {other:?}"),
         }
     }
+
+    /// Провайдер потоковой транскрипции: фиксированный текст или `None` - ошибка.
+    struct StreamingStub(Option<&'static str>);
+
+    impl stt::SttProvider for StreamingStub {
+        async fn transcribe(
+            &self,
+            _audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> stt::Result<String> {
+            self.0.map(str::to_string).ok_or(stt::SttError::AuthFailed)
+        }
+    }
+
+    fn streaming_session(stub: StreamingStub) -> StreamingSession {
+        StreamingSession::start(
+            Arc::new(stub),
+            stt::ChunkingConfig::default(),
+            stt::streaming::StreamingOptions::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn pipeline_should_use_streamed_text_without_uploading_recording() {
+        // Given: запись уже распознана потоковой сессией
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let audio = generate_tone(16000, 1000, 0.3);
+        let mut config = make_test_config(&server.uri());
+        config.enhance_enabled = false;
+        let cancel = AtomicBool::new(false);
+
+        // When
+        let outcome = process_audio(
+            &audio,
+            &make_test_format(),
+            &config,
+            "test-key",
+            &stt::ModelCache::default(),
+            Some(streaming_session(StreamingStub(Some("streamed text")))),
+            &cancel,
+            || {},
+        )
+        .await;
+
+        // Then
+        match outcome {
+            ProcessingOutcome::Text { raw, .. } => assert_eq!(raw, "streamed text"),
+            other => panic!("ожидался Text, получено: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn pipeline_should_transcribe_whole_recording_when_streaming_fails() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "text": "full recording" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let audio = generate_tone(16000, 1000, 0.3);
+        let mut config = make_test_config(&server.uri());
+        config.enhance_enabled = false;
+        let cancel = AtomicBool::new(false);
+
+        // When
+        let outcome = process_audio(
+            &audio,
+            &make_test_format(),
+            &config,
+            "test-key",
+            &stt::ModelCache::default(),
+            Some(streaming_session(StreamingStub(None))),
+            &cancel,
+            || {},
+        )
+        .await;
+
+        // Then
+        match outcome {
+            ProcessingOutcome::Text { raw, .. } => assert_eq!(raw, "full recording"),
+            other => panic!("ожидался Text, получено: {other:?}"),
+        }
+    }
 }
@@ -0,0 +1,137 @@
+//! Скрытие API-ключей в логах и текстах ошибок.
+//!
+//! Тела ответов API попадают в `SttError::ApiError` / `EnhanceError::ApiError`,
+//! а оттуда - в лог и уведомления. Если сервер эхом вернул ключ или заголовок
+//! Authorization, он не должен оказаться в rolling-логе.
+
+/// Замена для скрытого секрета.
+const REDACTED: &str = "[REDACTED]";
+
+/// Максимальная длина тела ответа в тексте ошибки (символов).
+const MAX_ERROR_BODY_CHARS: usize = 500;
+
+/// Минимальная длина токена `sk-...`, который считается ключом.
+const MIN_KEY_TOKEN_LEN: usize = 20;
+
+/// Минимальная длина ключа для точной замены (короткие строки дают ложные срабатывания).
+const MIN_EXACT_SECRET_LEN: usize = 8;
+
+/// Скрывает секреты в тексте: сам `api_key`, токен после `Bearer`
+/// и похожие на ключ токены `sk-...`.
+pub fn redact_secrets(text: &str, api_key: &str) -> String {
+    let text = if api_key.len() >= MIN_EXACT_SECRET_LEN {
+        text.replace(api_key, REDACTED)
+    } else {
+        text.to_string()
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut after_bearer = false;
+    for piece in text.split_inclusive(|c: char| !is_token_char(c)) {
+        let token = piece.trim_end_matches(|c: char| !is_token_char(c));
+        let separator = &piece[token.len()..];
+        if token.is_empty() {
+            out.push_str(separator);
+            continue;
+        }
+        if after_bearer || looks_like_api_key(token) {
+            out.push_str(REDACTED);
+        } else {
+            out.push_str(token);
+        }
+        out.push_str(separator);
+        after_bearer = token.eq_ignore_ascii_case("bearer");
+    }
+    out
+}
+
+/// Текст ошибки API из тела ответа: без секретов и не длиннее
+/// `MAX_ERROR_BODY_CHARS` символов.
+pub fn api_error_message(body: &str, api_key: &str) -> String {
+    let redacted = redact_secrets(body, api_key);
+    if redacted.chars().count() <= MAX_ERROR_BODY_CHARS {
+        return redacted;
+    }
+    let truncated: String = redacted.chars().take(MAX_ERROR_BODY_CHARS).collect();
+    format!("{truncated}...")
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+fn looks_like_api_key(token: &str) -> bool {
+    token.starts_with("sk-") && token.len() >= MIN_KEY_TOKEN_LEN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stt::SttError;
+
+    const SAMPLE_KEY: &str = "sk-proj-abcdefghijklmnopqrstuvwxyz012345";
+
+    #[test]
+    fn redact_secrets_should_hide_bearer_token_and_key() {
+        // Given
+        let line = format!("POST /v1/responses Authorization: Bearer {SAMPLE_KEY}");
+
+        // When
+        let redacted = redact_secrets(&line, "");
+
+        // Then
+        assert!(!redacted.contains(SAMPLE_KEY), "{redacted}");
+        assert_eq!(
+            redacted,
+            "POST /v1/responses Authorization: Bearer [REDACTED]"
+        );
+    }
+
+    #[test]
+    fn redact_secrets_should_hide_exact_key_without_prefix() {
+        // Given: ключ стороннего провайдера не похож на sk-...
+        let key = "gsk_live_7f3a9c1e";
+
+        // When
+        let redacted = redact_secrets(&format!("invalid key {key}, try again"), key);
+
+        // Then
+        assert_eq!(redacted, "invalid key [REDACTED], try again");
+    }
+
+    #[test]
+    fn redact_secrets_should_keep_ordinary_text() {
+        let text = "Model gpt-4o-mini not found (code: model_not_found)";
+        assert_eq!(redact_secrets(text, SAMPLE_KEY), text);
+    }
+
+    #[test]
+    fn api_error_should_not_contain_echoed_key() {
+        // Given: сервер вернул ключ в теле ответа
+        let body =
+            format!(r#"{{"error": {{"message": "Incorrect API key provided: {SAMPLE_KEY}"}}}}"#);
+
+        // When
+        let err = SttError::ApiError {
+            status: 401,
+            message: api_error_message(&body, SAMPLE_KEY),
+        };
+
+        // Then
+        let formatted = format!("{err} / {err:?}");
+        assert!(!formatted.contains(SAMPLE_KEY), "{formatted}");
+        assert!(formatted.contains("[REDACTED]"), "{formatted}");
+    }
+
+    #[test]
+    fn api_error_message_should_truncate_long_body() {
+        // Given
+        let body = "я".repeat(MAX_ERROR_BODY_CHARS + 100);
+
+        // When
+        let message = api_error_message(&body, "");
+
+        // Then
+        assert_eq!(message, format!("{}...", "я".repeat(MAX_ERROR_BODY_CHARS)));
+    }
+}
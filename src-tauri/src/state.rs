@@ -1,22 +1,35 @@
 use std::sync::Mutex;
 
+use serde::Serialize;
+
 use crate::config::schema::RecordingMode;
+use crate::metrics::{MetricsHistory, SessionMetrics};
 
 /// Состояния конечного автомата приложения.
 ///
 /// Определяет жизненный цикл диктовки: от ожидания до вставки текста.
 /// Переходы управляются функцией `transition`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 #[allow(dead_code)]
 pub enum AppState {
     Idle,
     Recording,
+    /// Запись приостановлена: поток открыт, данные не накапливаются
+    Paused,
     Transcribing,
     Enhancing,
     Pasting,
     Error,
 }
 
+/// Payload события `state-changed`: переход состояния для фронтенда.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct StateChange {
+    pub old: AppState,
+    pub new: AppState,
+}
+
 /// События, вызывающие переходы между состояниями.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -31,6 +44,10 @@ pub enum AppEvent {
     SilenceTimeout,
     /// Достигнут лимит длительности записи
     MaxDurationTimeout,
+    /// Пауза записи (только из Recording)
+    Pause,
+    /// Продолжение записи (только из Paused)
+    Resume,
     /// STT вернул результат
     TranscriptionDone,
     /// Улучшение текста завершено
@@ -45,6 +62,22 @@ pub enum AppEvent {
     ErrorAcknowledged,
 }
 
+/// Событие начала записи в режиме `mode` (трей, кнопки UI).
+pub fn start_event(mode: &RecordingMode) -> AppEvent {
+    match mode {
+        RecordingMode::Toggle => AppEvent::HotkeyPressed,
+        RecordingMode::PushToTalk => AppEvent::HotkeyDown,
+    }
+}
+
+/// Событие остановки записи в режиме `mode` (трей, кнопки UI).
+pub fn stop_event(mode: &RecordingMode) -> AppEvent {
+    match mode {
+        RecordingMode::Toggle => AppEvent::HotkeyPressed,
+        RecordingMode::PushToTalk => AppEvent::HotkeyUp,
+    }
+}
+
 /// Чистая функция перехода состояний.
 ///
 /// Возвращает новое состояние. При невалидном переходе логирует
@@ -80,7 +113,23 @@ pub fn transition(state: AppState, event: &AppEvent, mode: &RecordingMode) -> Ap
             AppState::Transcribing
         }
         // Safety timeout (оба режима)
-        (AppState::Recording, AppEvent::MaxDurationTimeout) => AppState::Transcribing,
+        (AppState::Recording | AppState::Paused, AppEvent::MaxDurationTimeout) => {
+            AppState::Transcribing
+        }
+
+        // Пауза и продолжение записи (только toggle: в PTT запись идет, пока зажата клавиша)
+        (AppState::Recording, AppEvent::Pause) if *mode == RecordingMode::Toggle => {
+            AppState::Paused
+        }
+        (AppState::Paused, AppEvent::Resume) if *mode == RecordingMode::Toggle => {
+            AppState::Recording
+        }
+        // Отмена из паузы: запись отбрасывается
+        (AppState::Paused, AppEvent::Cancel) => AppState::Idle,
+        // Остановка из паузы: запись уходит в обработку
+        (AppState::Paused, AppEvent::HotkeyPressed) if *mode == RecordingMode::Toggle => {
+            AppState::Transcribing
+        }
 
         // Pipeline: последовательная обработка
         (AppState::Transcribing, AppEvent::TranscriptionDone) => AppState::Enhancing,
@@ -122,6 +171,11 @@ pub fn transition(state: AppState, event: &AppEvent, mode: &RecordingMode) -> Ap
 pub struct SharedAppState {
     state: Mutex<AppState>,
     recording_mode: Mutex<RecordingMode>,
+    metrics: MetricsHistory,
+    /// Последний успешно распознанный текст (только в памяти)
+    last_result: Mutex<Option<String>>,
+    /// Сериализует переходы вместе с побочными эффектами (`dispatch_serialized`)
+    transition_lock: Mutex<()>,
 }
 
 #[allow(dead_code)]
@@ -131,6 +185,9 @@ impl SharedAppState {
         Self {
             state: Mutex::new(AppState::Idle),
             recording_mode: Mutex::new(mode),
+            metrics: MetricsHistory::new(),
+            last_result: Mutex::new(None),
+            transition_lock: Mutex::new(()),
         }
     }
 
@@ -171,6 +228,29 @@ impl SharedAppState {
         (old, new)
     }
 
+    /// Применяет событие и выполняет `on_transition(old, new)` под общей блокировкой.
+    ///
+    /// Переход атомарен и без нее, но побочные эффекты (старт/стоп захвата аудио)
+    /// - нет: при одновременных нажатиях хоткея и клика в трее второй старт мог
+    /// начаться до завершения первой остановки. Здесь эффекты одного перехода
+    /// завершаются до начала следующего. `on_transition` вызывается только при
+    /// смене состояния и не должен сам вызывать `dispatch_serialized` (deadlock).
+    pub fn dispatch_serialized(
+        &self,
+        event: &AppEvent,
+        on_transition: impl FnOnce(AppState, AppState),
+    ) -> (AppState, AppState) {
+        let _guard = self
+            .transition_lock
+            .lock()
+            .expect("transition mutex poisoned");
+        let (old, new) = self.dispatch_with_old(event);
+        if old != new {
+            on_transition(old, new);
+        }
+        (old, new)
+    }
+
     /// Возвращает текущий режим записи.
     pub fn recording_mode(&self) -> RecordingMode {
         self.recording_mode
@@ -183,6 +263,29 @@ impl SharedAppState {
     pub fn set_recording_mode(&self, mode: RecordingMode) {
         *self.recording_mode.lock().expect("mode mutex poisoned") = mode;
     }
+
+    /// Сохраняет тайминги завершенной сессии (хранятся последние N).
+    pub fn record_metrics(&self, metrics: SessionMetrics) {
+        self.metrics.record(metrics);
+    }
+
+    /// Тайминги последней сессии.
+    pub fn last_metrics(&self) -> Option<SessionMetrics> {
+        self.metrics.last()
+    }
+
+    /// Запоминает последний распознанный текст для "Copy last result" в трее.
+    pub fn set_last_result(&self, text: String) {
+        *self.last_result.lock().expect("last_result mutex poisoned") = Some(text);
+    }
+
+    /// Последний распознанный текст, если он есть.
+    pub fn last_result(&self) -> Option<String> {
+        self.last_result
+            .lock()
+            .expect("last_result mutex poisoned")
+            .clone()
+    }
 }
 
 impl Default for SharedAppState {
@@ -249,6 +352,151 @@ mod tests {
         assert_eq!(new, AppState::Transcribing);
     }
 
+    // --- Pause ---
+
+    #[test]
+    fn recording_should_pause_and_resume() {
+        // Given
+        let mode = RecordingMode::Toggle;
+
+        // When
+        let paused = transition(AppState::Recording, &AppEvent::Pause, &mode);
+        let resumed = transition(paused, &AppEvent::Resume, &mode);
+
+        // Then
+        assert_eq!(paused, AppState::Paused);
+        assert_eq!(resumed, AppState::Recording);
+    }
+
+    #[test]
+    fn pause_and_resume_should_only_apply_to_matching_state() {
+        let mode = RecordingMode::Toggle;
+        assert_eq!(
+            transition(AppState::Recording, &AppEvent::Resume, &mode),
+            AppState::Recording
+        );
+        assert_eq!(
+            transition(AppState::Paused, &AppEvent::Pause, &mode),
+            AppState::Paused
+        );
+    }
+
+    #[test]
+    fn paused_should_start_transcribing_when_stopped() {
+        assert_eq!(
+            transition(
+                AppState::Paused,
+                &AppEvent::HotkeyPressed,
+                &RecordingMode::Toggle
+            ),
+            AppState::Transcribing
+        );
+        assert_eq!(
+            transition(
+                AppState::Paused,
+                &AppEvent::MaxDurationTimeout,
+                &RecordingMode::Toggle
+            ),
+            AppState::Transcribing
+        );
+    }
+
+    #[test]
+    fn pause_should_be_ignored_in_ptt_mode() {
+        let mode = RecordingMode::PushToTalk;
+        assert_eq!(
+            transition(AppState::Recording, &AppEvent::Pause, &mode),
+            AppState::Recording
+        );
+        assert_eq!(
+            transition(AppState::Paused, &AppEvent::Resume, &mode),
+            AppState::Paused
+        );
+    }
+
+    #[test]
+    fn paused_should_go_idle_when_cancelled() {
+        // Given
+        let state = AppState::Paused;
+
+        // When
+        let new = transition(state, &AppEvent::Cancel, &RecordingMode::Toggle);
+
+        // Then
+        assert_eq!(new, AppState::Idle);
+    }
+
+    #[test]
+    fn paused_should_ignore_hotkey_up_in_toggle_mode() {
+        // Given
+        let state = AppState::Paused;
+
+        // When
+        let new = transition(state, &AppEvent::HotkeyUp, &RecordingMode::Toggle);
+
+        // Then
+        assert_eq!(new, AppState::Paused);
+    }
+
+    #[test]
+    fn paused_should_ignore_pipeline_events() {
+        let mode = RecordingMode::Toggle;
+        for event in [
+            AppEvent::HotkeyDown,
+            AppEvent::TranscriptionDone,
+            AppEvent::EnhancementDone,
+            AppEvent::PasteDone,
+            AppEvent::ErrorAcknowledged,
+        ] {
+            assert_eq!(
+                transition(AppState::Paused, &event, &mode),
+                AppState::Paused,
+                "event {event:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn paused_should_go_to_error_when_failed() {
+        assert_eq!(
+            transition(
+                AppState::Paused,
+                &AppEvent::Failed("mic".to_string()),
+                &RecordingMode::Toggle
+            ),
+            AppState::Error
+        );
+    }
+
+    #[test]
+    fn paused_should_ignore_silence_timeout() {
+        // Given
+        let state = AppState::Paused;
+
+        // When
+        let new = transition(state, &AppEvent::SilenceTimeout, &RecordingMode::Toggle);
+
+        // Then
+        assert_eq!(new, AppState::Paused);
+    }
+
+    #[test]
+    fn pause_and_resume_should_be_ignored_outside_recording() {
+        let mode = RecordingMode::Toggle;
+        for event in [AppEvent::Pause, AppEvent::Resume] {
+            assert_eq!(
+                transition(AppState::Idle, &event, &mode),
+                AppState::Idle,
+                "event {event:?}"
+            );
+            assert_eq!(
+                transition(AppState::Transcribing, &event, &mode),
+                AppState::Transcribing,
+                "event {event:?}"
+            );
+        }
+    }
+
     // --- PTT mode ---
 
     #[test]
@@ -624,4 +872,88 @@ mod tests {
         // Then
         assert_eq!(new, AppState::Idle);
     }
+
+    #[test]
+    fn dispatch_serialized_should_keep_single_capture_under_concurrent_presses() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // Given: имитация захвата - не больше одного "потока" записи одновременно
+        let shared = Arc::new(SharedAppState::new(RecordingMode::Toggle));
+        let live = Arc::new(AtomicUsize::new(0));
+        let starts = Arc::new(AtomicUsize::new(0));
+        let stops = Arc::new(AtomicUsize::new(0));
+
+        // When: 8 потоков по 50 нажатий Toggle-хоткея
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let (shared, live) = (Arc::clone(&shared), Arc::clone(&live));
+                let (starts, stops) = (Arc::clone(&starts), Arc::clone(&stops));
+                std::thread::spawn(move || {
+                    for _ in 0..50 {
+                        shared.dispatch_serialized(&AppEvent::HotkeyPressed, |old, new| {
+                            match (old, new) {
+                                (AppState::Idle, AppState::Recording) => {
+                                    assert_eq!(live.fetch_add(1, Ordering::SeqCst), 0);
+                                    starts.fetch_add(1, Ordering::SeqCst);
+                                    std::thread::yield_now();
+                                }
+                                (AppState::Recording, AppState::Transcribing) => {
+                                    assert_eq!(live.fetch_sub(1, Ordering::SeqCst), 1);
+                                    stops.fetch_add(1, Ordering::SeqCst);
+                                    // Pipeline завершился: снова можно записывать
+                                    shared.dispatch_with_old(&AppEvent::Cancel);
+                                }
+                                _ => {}
+                            }
+                        });
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        // Then: каждая запись остановлена не больше одного раза, утечек нет
+        let (starts, stops) = (starts.load(Ordering::SeqCst), stops.load(Ordering::SeqCst));
+        assert!(starts > 0);
+        assert_eq!(starts - stops, live.load(Ordering::SeqCst));
+        assert!(live.load(Ordering::SeqCst) <= 1);
+    }
+
+    #[test]
+    fn start_and_stop_events_should_drive_recording_in_both_modes() {
+        for mode in [RecordingMode::Toggle, RecordingMode::PushToTalk] {
+            // When
+            let recording = transition(AppState::Idle, &start_event(&mode), &mode);
+            let stopped = transition(recording, &stop_event(&mode), &mode);
+
+            // Then
+            assert_eq!(recording, AppState::Recording, "{mode:?}");
+            assert_eq!(stopped, AppState::Transcribing, "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn state_change_should_serialize_to_snake_case() {
+        // Given
+        let change = StateChange {
+            old: AppState::Recording,
+            new: AppState::Transcribing,
+        };
+
+        // When
+        let json = serde_json::to_value(change).unwrap();
+
+        // Then
+        assert_eq!(
+            json,
+            serde_json::json!({"old": "recording", "new": "transcribing"})
+        );
+        assert_eq!(
+            serde_json::to_value(AppState::Idle).unwrap(),
+            serde_json::json!("idle")
+        );
+    }
 }
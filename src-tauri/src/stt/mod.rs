@@ -1,11 +1,18 @@
+#[cfg(feature = "offline-whisper")]
 pub mod offline_whisper;
 pub mod openai;
+pub mod streaming;
 
 use std::sync::Arc;
 
 use tokio::sync::Semaphore;
+
+use crate::audio::encode::{encode_audio, EncodeConfig};
+use crate::config::schema::{AppConfig, SttProviderKind};
 use tokio::task::JoinSet;
 
+#[cfg(feature = "offline-whisper")]
+pub use self::offline_whisper::OfflineWhisperProvider;
 pub use self::openai::OpenAiSttClient;
 
 /// Ошибки STT-модуля.
@@ -31,6 +38,16 @@ pub enum SttError {
 
     #[error("encoding failed: {0}")]
     EncodingFailed(String),
+
+    #[error("audio payload too large for STT endpoint")]
+    PayloadTooLarge,
+
+    #[error("failed to load STT model: {0}")]
+    ModelLoadFailed(String),
+
+    #[error("STT inference failed: {0}")]
+    #[cfg_attr(not(feature = "offline-whisper"), allow(dead_code))]
+    InferenceFailed(String),
 }
 
 pub type Result<T> = std::result::Result<T, SttError>;
@@ -39,11 +56,148 @@ pub type Result<T> = std::result::Result<T, SttError>;
 ///
 /// Позволяет подменять реализацию (online/offline) через generics.
 pub trait SttProvider: Send + Sync {
+    /// `prompt` - подсказка со словарем/контекстом (имена, термины, написание).
     fn transcribe(
         &self,
         audio: &[u8],
         language: Option<&str>,
+        prompt: Option<&str>,
     ) -> impl std::future::Future<Output = Result<String>> + Send;
+
+    /// Как `transcribe`, но дополнительно возвращает язык, определенный моделью.
+    ///
+    /// По умолчанию провайдер язык не сообщает (`None`).
+    fn transcribe_detect_language(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> impl std::future::Future<Output = Result<(String, Option<String>)>> + Send {
+        async move { Ok((self.transcribe(audio, language, prompt).await?, None)) }
+    }
+}
+
+/// Провайдер STT, выбранный по `stt_provider` в конфиге.
+///
+/// Enum вместо `Box<dyn SttProvider>`: трейт использует `impl Future` в возвращаемом типе.
+pub enum AnySttProvider {
+    OpenAi(OpenAiSttClient),
+    #[cfg(feature = "offline-whisper")]
+    Offline(OfflineWhisperProvider),
+}
+
+impl SttProvider for AnySttProvider {
+    async fn transcribe(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        match self {
+            Self::OpenAi(client) => client.transcribe(audio, language, prompt).await,
+            #[cfg(feature = "offline-whisper")]
+            Self::Offline(provider) => provider.transcribe(audio, language, prompt).await,
+        }
+    }
+
+    async fn transcribe_detect_language(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<(String, Option<String>)> {
+        match self {
+            Self::OpenAi(client) => {
+                client
+                    .transcribe_detect_language(audio, language, prompt)
+                    .await
+            }
+            #[cfg(feature = "offline-whisper")]
+            Self::Offline(provider) => {
+                provider
+                    .transcribe_detect_language(audio, language, prompt)
+                    .await
+            }
+        }
+    }
+}
+
+/// Загруженные локальные модели STT, общие для всех диктовок (managed state).
+///
+/// Модель Whisper загружается при первой офлайн-диктовке и переиспользуется,
+/// пока не изменится `whisper_model_path` или `release` не освободит ее
+/// после простоя (`idle_release_after_min`).
+#[derive(Default)]
+pub struct ModelCache {
+    #[cfg(feature = "offline-whisper")]
+    whisper: std::sync::Mutex<Option<(std::path::PathBuf, OfflineWhisperProvider)>>,
+}
+
+impl ModelCache {
+    /// Провайдер для модели из конфига; при промахе модель загружается в `spawn_blocking`.
+    #[cfg(feature = "offline-whisper")]
+    async fn whisper(&self, config: &AppConfig) -> Result<OfflineWhisperProvider> {
+        let path = offline_whisper::model_path(config)?;
+        if let Some((cached, provider)) = self
+            .whisper
+            .lock()
+            .expect("model cache mutex poisoned")
+            .as_ref()
+        {
+            if *cached == path {
+                return Ok(provider.clone());
+            }
+        }
+
+        let load_path = path.clone();
+        let provider = tokio::task::spawn_blocking(move || OfflineWhisperProvider::new(&load_path))
+            .await
+            .map_err(|e| SttError::ModelLoadFailed(format!("task failed: {e}")))??;
+        *self.whisper.lock().expect("model cache mutex poisoned") = Some((path, provider.clone()));
+        Ok(provider)
+    }
+
+    /// Освобождает загруженные модели; `true`, если было что освобождать.
+    ///
+    /// Идущее распознавание держит свою ссылку на модель и не прерывается.
+    #[cfg(feature = "offline-whisper")]
+    pub fn release(&self) -> bool {
+        self.whisper
+            .lock()
+            .expect("model cache mutex poisoned")
+            .take()
+            .is_some()
+    }
+
+    /// Без feature `offline-whisper` локальных моделей нет.
+    #[cfg(not(feature = "offline-whisper"))]
+    pub fn release(&self) -> bool {
+        false
+    }
+}
+
+/// Создает провайдер STT по конфигу.
+///
+/// Модель офлайн-провайдера (`whisper_model_path`) берется из `models` или
+/// загружается туда; если сборка без feature `offline-whisper`, возвращается
+/// `ModelLoadFailed`.
+#[cfg_attr(not(feature = "offline-whisper"), allow(unused_variables))]
+pub async fn build_provider(
+    config: &AppConfig,
+    api_key: &str,
+    models: &ModelCache,
+) -> Result<AnySttProvider> {
+    match config.stt_provider {
+        SttProviderKind::OpenAi => Ok(AnySttProvider::OpenAi(OpenAiSttClient::from_config(
+            config, api_key,
+        )?)),
+        #[cfg(feature = "offline-whisper")]
+        SttProviderKind::Offline => Ok(AnySttProvider::Offline(models.whisper(config).await?)),
+        #[cfg(not(feature = "offline-whisper"))]
+        SttProviderKind::Offline => Err(SttError::ModelLoadFailed(
+            "offline STT is not available in this build (offline-whisper feature)".to_string(),
+        )),
+    }
 }
 
 /// Один фрагмент аудио для отправки в STT.
@@ -52,52 +206,192 @@ pub struct AudioChunk {
     pub samples: Vec<f32>,
 }
 
-/// Overlap между чанками (секунды).
-const CHUNK_OVERLAP_SEC: f32 = 1.5;
+/// Параметры нарезки длинной записи на чанки и кодирования чанков.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkingConfig {
+    /// Overlap между чанками (секунды).
+    pub overlap_sec: f32,
+    /// Минимальная длительность чанка (секунды), не делим дальше.
+    pub min_chunk_sec: f32,
+    /// Начало зоны поиска тихого места для разреза (проценты от длины чанка).
+    /// Ищем тишину в последних (100 - quiet_search_start_percent)% чанка.
+    pub quiet_search_start_percent: usize,
+    /// Параметры кодера Opus для чанков.
+    pub encode: EncodeConfig,
+}
 
-/// Минимальная длительность чанка (секунды), не делим дальше.
-const MIN_CHUNK_SEC: f32 = 5.0;
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            overlap_sec: 1.5,
+            min_chunk_sec: 5.0,
+            quiet_search_start_percent: 70,
+            encode: EncodeConfig::default(),
+        }
+    }
+}
+
+impl ChunkingConfig {
+    /// Параметры из конфига; некорректные значения заменяются значениями по умолчанию.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let defaults = Self::default();
+        let non_negative = |value: f32, default: f32| {
+            if value.is_finite() && value >= 0.0 {
+                value
+            } else {
+                default
+            }
+        };
+        Self {
+            overlap_sec: non_negative(config.stt_chunk_overlap_sec, defaults.overlap_sec),
+            min_chunk_sec: non_negative(config.stt_min_chunk_sec, defaults.min_chunk_sec),
+            quiet_search_start_percent: config.stt_quiet_search_start_percent.clamp(1, 99) as usize,
+            encode: EncodeConfig::from_config(config),
+        }
+    }
+}
 
 /// Максимальная длительность одного чанка по умолчанию (секунды).
 const DEFAULT_MAX_CHUNK_SEC: u32 = 30;
 
+/// Нижняя граница длительности чанка при повторной нарезке после 413 (секунды).
+const MIN_RECHUNK_SEC: u32 = 5;
+
 /// Максимальное количество параллельных STT-запросов по умолчанию.
 const DEFAULT_MAX_CONCURRENT_STT_CHUNKS: u32 = 3;
 
-/// Начало зоны поиска тихого места для разреза (проценты от длины чанка).
-/// Ищем тишину в последних (100 - QUIET_SEARCH_START_PERCENT)% чанка.
-const QUIET_SEARCH_START_PERCENT: usize = 70;
+/// Сколько символов текста предыдущего чанка передается в prompt следующего.
+const PROMPT_TAIL_CHARS: usize = 200;
 
 /// Размер окна RMS-анализа энергии (миллисекунды).
 const RMS_WINDOW_MS: u32 = 20;
 
+/// Параметры запроса транскрипции; `None` - значение по умолчанию.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TranscribeOptions<'a> {
+    /// Язык записи (ISO-639-1); `None` - автоопределение.
+    pub language: Option<&'a str>,
+    /// Подсказка для STT (имена, термины).
+    pub prompt: Option<&'a str>,
+    /// Максимальная длительность чанка (секунды), по умолчанию `DEFAULT_MAX_CHUNK_SEC`.
+    pub max_chunk_sec: Option<u32>,
+    /// Сколько чанков распознается параллельно,
+    /// по умолчанию `DEFAULT_MAX_CONCURRENT_STT_CHUNKS`.
+    pub max_concurrent: Option<u32>,
+}
+
 /// Высокоуровневая функция: кодирует PCM в OGG/Opus и транскрибирует.
 ///
 /// Если аудио укладывается в один чанк, кодирует и отправляет как есть.
 /// Для длинных записей: разбивает на чанки, кодирует каждый последовательно,
 /// транскрибирует параллельно через JoinSet + Semaphore, склеивает текст.
+/// При `options.max_concurrent == Some(1)` чанки идут по очереди, и каждый следующий
+/// получает хвост текста предыдущего в `prompt` - меньше расхождений на стыках.
+///
+/// Если endpoint отвечает `PayloadTooLarge` (413), запись нарезается заново
+/// на вдвое более короткие чанки, пока не будет достигнут `MIN_RECHUNK_SEC`.
 pub async fn transcribe_audio<P: SttProvider + 'static>(
     provider: Arc<P>,
     samples: &[f32],
     sample_rate: u32,
-    language: Option<&str>,
-    max_chunk_sec: Option<u32>,
-    max_concurrent: Option<u32>,
+    options: &TranscribeOptions<'_>,
+    chunking: &ChunkingConfig,
 ) -> Result<String> {
+    let (text, _) =
+        transcribe_audio_detect_language(provider, samples, sample_rate, options, chunking).await?;
+    Ok(text)
+}
+
+/// Как `transcribe_audio`, но возвращает `(text, detected_language)`.
+///
+/// Для нескольких чанков берется язык первого чанка, для которого провайдер его сообщил.
+pub async fn transcribe_audio_detect_language<P: SttProvider + 'static>(
+    provider: Arc<P>,
+    samples: &[f32],
+    sample_rate: u32,
+    options: &TranscribeOptions<'_>,
+    chunking: &ChunkingConfig,
+) -> Result<(String, Option<String>)> {
     if sample_rate == 0 {
         return Err(SttError::EncodingFailed(
             "sample_rate must be > 0".to_string(),
         ));
     }
 
-    let max_sec = max_chunk_sec.unwrap_or(DEFAULT_MAX_CHUNK_SEC).max(1);
+    let mut max_sec = options
+        .max_chunk_sec
+        .unwrap_or(DEFAULT_MAX_CHUNK_SEC)
+        .max(1);
+    loop {
+        let result = transcribe_with_chunk_sec(
+            Arc::clone(&provider),
+            samples,
+            sample_rate,
+            options,
+            max_sec,
+            chunking,
+        )
+        .await;
+
+        match result {
+            Err(SttError::PayloadTooLarge) if max_sec > MIN_RECHUNK_SEC => {
+                let smaller = (max_sec / 2).max(MIN_RECHUNK_SEC);
+                tracing::warn!(
+                    "STT payload too large with {max_sec}s chunks, retrying with {smaller}s"
+                );
+                max_sec = smaller;
+            }
+            other => return other,
+        }
+    }
+}
+
+/// Распознает PCM провайдером из `build_provider`.
+///
+/// Облачный провайдер получает закодированные чанки (`transcribe_audio_detect_language`),
+/// офлайн-модель - PCM напрямую, без кодирования в Opus и обратно.
+pub async fn transcribe_samples(
+    provider: AnySttProvider,
+    samples: &[f32],
+    sample_rate: u32,
+    options: &TranscribeOptions<'_>,
+    chunking: &ChunkingConfig,
+) -> Result<(String, Option<String>)> {
+    #[cfg(feature = "offline-whisper")]
+    if let AnySttProvider::Offline(offline) = &provider {
+        return offline
+            .transcribe_pcm(samples, sample_rate, options.language, options.prompt)
+            .await
+            .map(|text| (text, None));
+    }
+    transcribe_audio_detect_language(Arc::new(provider), samples, sample_rate, options, chunking)
+        .await
+}
+
+/// Одна попытка транскрипции с заданной максимальной длительностью чанка.
+async fn transcribe_with_chunk_sec<P: SttProvider + 'static>(
+    provider: Arc<P>,
+    samples: &[f32],
+    sample_rate: u32,
+    options: &TranscribeOptions<'_>,
+    max_sec: u32,
+    chunking: &ChunkingConfig,
+) -> Result<(String, Option<String>)> {
+    let TranscribeOptions {
+        language,
+        prompt,
+        max_concurrent,
+        ..
+    } = *options;
     let max_chunk_samples = max_sec as usize * sample_rate as usize;
 
     // Короткое аудио: один чанк, без параллелизма
     if samples.len() <= max_chunk_samples {
-        let encoded = crate::audio::encode::encode_ogg_opus(samples, sample_rate)
+        let encoded = encode_audio(samples, sample_rate, &chunking.encode)
             .map_err(|e| SttError::EncodingFailed(e.to_string()))?;
-        return provider.transcribe(&encoded, language).await;
+        return provider
+            .transcribe_detect_language(&encoded, language, prompt)
+            .await;
     }
 
     tracing::info!(
@@ -106,14 +400,14 @@ pub async fn transcribe_audio<P: SttProvider + 'static>(
         max_sec
     );
 
-    let chunks = chunk_audio(samples, sample_rate, max_sec);
+    let chunks = chunk_audio(samples, sample_rate, max_sec, chunking);
     let chunk_count = chunks.len();
     tracing::info!("Split into {chunk_count} chunks");
 
     // Кодирование чанков последовательно (CPU-bound, быстрое)
     let mut encoded_chunks = Vec::with_capacity(chunk_count);
     for (i, chunk) in chunks.iter().enumerate() {
-        let encoded = crate::audio::encode::encode_ogg_opus(&chunk.samples, sample_rate)
+        let encoded = encode_audio(&chunk.samples, sample_rate, &chunking.encode)
             .map_err(|e| SttError::EncodingFailed(e.to_string()))?;
         tracing::debug!(
             "Encoded chunk {}/{} ({:.1}s, {} bytes OGG)",
@@ -125,18 +419,52 @@ pub async fn transcribe_audio<P: SttProvider + 'static>(
         encoded_chunks.push((i, encoded));
     }
 
-    // Параллельная транскрипция через JoinSet + Semaphore
     let concurrent = max_concurrent
         .unwrap_or(DEFAULT_MAX_CONCURRENT_STT_CHUNKS)
         .max(1) as usize;
+    let ordered = if concurrent == 1 {
+        transcribe_chained(provider.as_ref(), encoded_chunks, language, prompt).await?
+    } else {
+        transcribe_parallel(provider, encoded_chunks, language, prompt, concurrent).await?
+    };
+
+    let detected_language = ordered.iter().find_map(|(_, lang)| lang.clone());
+    let texts: Vec<String> = ordered
+        .into_iter()
+        .map(|(text, _)| text)
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    tracing::info!(
+        "all {chunk_count} chunks complete, {} non-empty texts",
+        texts.len()
+    );
+
+    Ok((deduplicate_overlap_texts(&texts), detected_language))
+}
+
+/// Параллельная транскрипция через JoinSet + Semaphore (fail-fast).
+///
+/// Возвращает тексты чанков (и определенный язык) в порядке индексов,
+/// независимо от порядка завершения.
+async fn transcribe_parallel<P: SttProvider + 'static>(
+    provider: Arc<P>,
+    encoded_chunks: Vec<(usize, Vec<u8>)>,
+    language: Option<&str>,
+    prompt: Option<&str>,
+    concurrent: usize,
+) -> Result<Vec<(String, Option<String>)>> {
+    let chunk_count = encoded_chunks.len();
     let semaphore = Arc::new(Semaphore::new(concurrent));
     let language_owned: Option<String> = language.map(|s| s.to_string());
+    let prompt_owned: Option<String> = prompt.map(|s| s.to_string());
 
     let mut join_set = JoinSet::new();
     for (idx, encoded) in encoded_chunks {
         let provider = Arc::clone(&provider);
         let sem = Arc::clone(&semaphore);
         let lang = language_owned.clone();
+        let prompt = prompt_owned.clone();
 
         join_set.spawn(async move {
             let _permit = sem
@@ -145,17 +473,22 @@ pub async fn transcribe_audio<P: SttProvider + 'static>(
                 .map_err(|_| SttError::Network("semaphore closed".to_string()))?;
             tracing::debug!("chunk {idx} started upload");
             let start = std::time::Instant::now();
-            let text = provider.transcribe(&encoded, lang.as_deref()).await?;
+            let (text, detected) = provider
+                .transcribe_detect_language(&encoded, lang.as_deref(), prompt.as_deref())
+                .await?;
             tracing::debug!(
                 "chunk {idx} transcribed in {}ms",
                 start.elapsed().as_millis()
             );
-            Ok::<(usize, String), SttError>((idx, text.trim().to_string()))
+            Ok::<(usize, (String, Option<String>)), SttError>((
+                idx,
+                (text.trim().to_string(), detected),
+            ))
         });
     }
 
     // Сборка результатов с fail-fast
-    let mut results: Vec<(usize, String)> = Vec::with_capacity(chunk_count);
+    let mut results: Vec<(usize, (String, Option<String>))> = Vec::with_capacity(chunk_count);
     while let Some(join_result) = join_set.join_next().await {
         match join_result {
             Ok(Ok(item)) => results.push(item),
@@ -175,25 +508,80 @@ pub async fn transcribe_audio<P: SttProvider + 'static>(
 
     // Сортировка по индексу чанка для правильного порядка текста
     results.sort_by_key(|(idx, _)| *idx);
-    let texts: Vec<String> = results
-        .into_iter()
-        .map(|(_, text)| text)
-        .filter(|t| !t.is_empty())
-        .collect();
+    Ok(results.into_iter().map(|(_, item)| item).collect())
+}
 
-    tracing::info!(
-        "all {chunk_count} chunks complete, {} non-empty texts",
-        texts.len()
-    );
+/// Последовательная транскрипция: каждый чанк получает хвост текста
+/// предыдущего (вместе с `prompt`) для связности на стыках.
+async fn transcribe_chained<P: SttProvider>(
+    provider: &P,
+    encoded_chunks: Vec<(usize, Vec<u8>)>,
+    language: Option<&str>,
+    prompt: Option<&str>,
+) -> Result<Vec<(String, Option<String>)>> {
+    let mut results: Vec<(String, Option<String>)> = Vec::with_capacity(encoded_chunks.len());
+    for (idx, encoded) in encoded_chunks {
+        let chunk_prompt = match results.iter().rev().find(|(t, _)| !t.is_empty()) {
+            Some((previous, _)) => chained_prompt(prompt, previous),
+            None => prompt.map(str::to_string),
+        };
+        let start = std::time::Instant::now();
+        let (text, detected) = provider
+            .transcribe_detect_language(&encoded, language, chunk_prompt.as_deref())
+            .await?;
+        tracing::debug!(
+            "chunk {idx} transcribed in {}ms",
+            start.elapsed().as_millis()
+        );
+        results.push((text.trim().to_string(), detected));
+    }
+    Ok(results)
+}
 
-    Ok(deduplicate_overlap_texts(&texts))
+/// Prompt для очередного чанка: базовый prompt и хвост текста предыдущего чанка.
+fn chained_prompt(base: Option<&str>, previous_text: &str) -> Option<String> {
+    let parts: Vec<&str> = [
+        base.unwrap_or("").trim(),
+        text_tail(previous_text, PROMPT_TAIL_CHARS),
+    ]
+    .into_iter()
+    .filter(|p| !p.is_empty())
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Последние `max_chars` символов текста, начиная с целого слова.
+fn text_tail(text: &str, max_chars: usize) -> &str {
+    let text = text.trim();
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+
+    let start = text
+        .char_indices()
+        .nth(count - max_chars)
+        .map_or(0, |(i, _)| i);
+    if text[..start].ends_with(char::is_whitespace) {
+        return &text[start..];
+    }
+    let tail = &text[start..];
+    match tail.find(char::is_whitespace) {
+        Some(pos) => tail[pos..].trim_start(),
+        None => tail,
+    }
 }
 
 /// Разбивает аудио на чанки подходящего размера.
 ///
 /// Пытается резать по тихим местам (минимум энергии).
 /// Если тихих мест нет, режет по таймеру с overlap.
-pub fn chunk_audio(samples: &[f32], sample_rate: u32, max_chunk_sec: u32) -> Vec<AudioChunk> {
+pub fn chunk_audio(
+    samples: &[f32],
+    sample_rate: u32,
+    max_chunk_sec: u32,
+    chunking: &ChunkingConfig,
+) -> Vec<AudioChunk> {
     if sample_rate == 0 || max_chunk_sec == 0 {
         tracing::warn!(
             "Invalid chunking params (sample_rate={sample_rate}, max_chunk_sec={max_chunk_sec}), \
@@ -212,8 +600,9 @@ pub fn chunk_audio(samples: &[f32], sample_rate: u32, max_chunk_sec: u32) -> Vec
         }];
     }
 
-    let overlap_samples = (CHUNK_OVERLAP_SEC * sample_rate as f32) as usize;
-    let min_chunk_samples = (MIN_CHUNK_SEC * sample_rate as f32) as usize;
+    let overlap_samples = (chunking.overlap_sec * sample_rate as f32) as usize;
+    let min_chunk_samples = (chunking.min_chunk_sec * sample_rate as f32) as usize;
+    let search_percent = chunking.quiet_search_start_percent.clamp(1, 99);
     let mut chunks = Vec::new();
     let mut offset = 0;
 
@@ -227,8 +616,8 @@ pub fn chunk_audio(samples: &[f32], sample_rate: u32, max_chunk_sec: u32) -> Vec
             break;
         }
 
-        // Ищем тихое место в последних 30% окна чанка
-        let search_start = offset + max_chunk_samples * QUIET_SEARCH_START_PERCENT / 100;
+        // Ищем тихое место в конце окна чанка (по умолчанию - в последних 30%)
+        let search_start = offset + max_chunk_samples * search_percent / 100;
         let search_end = offset + max_chunk_samples;
 
         let split_point = find_quiet_split_point(&samples[search_start..search_end], sample_rate)
@@ -250,7 +639,13 @@ pub fn chunk_audio(samples: &[f32], sample_rate: u32, max_chunk_sec: u32) -> Vec
             break;
         }
 
-        offset = actual_end.saturating_sub(overlap_samples);
+        // Overlap не длиннее чанка: иначе нарезка не продвинется
+        let next_offset = actual_end.saturating_sub(overlap_samples);
+        offset = if next_offset > offset {
+            next_offset
+        } else {
+            actual_end
+        };
     }
 
     chunks
@@ -285,7 +680,8 @@ fn find_quiet_split_point(segment: &[f32], sample_rate: u32) -> Option<usize> {
 /// Склеивает тексты из overlapping чанков с простой дедупликацией.
 ///
 /// Если последние N слов предыдущего текста совпадают с первыми N слов следующего,
-/// дубликат удаляется (проверяет 2-5 слов, case-insensitive).
+/// дубликат удаляется (проверяет 2-5 слов, case-insensitive, без учета
+/// завершающей пунктуации; см. `find_text_overlap`).
 fn deduplicate_overlap_texts(texts: &[String]) -> String {
     if texts.is_empty() {
         return String::new();
@@ -319,6 +715,12 @@ fn deduplicate_overlap_texts(texts: &[String]) -> String {
 
 /// Ищет overlap между концом `prev` и началом `next` (2-5 слов).
 ///
+/// Слова сравниваются без регистра и без завершающей пунктуации ("fox." == "fox").
+/// Короткое совпадение (2 слова) засчитывается только на границе фразы:
+/// совпавший хвост `prev` начинается с начала текста или после слова
+/// с разделителем, либо сам заканчивается разделителем. Так настоящие
+/// повторы вроде "very very" на стыке не схлопываются.
+///
 /// Возвращает количество совпавших слов (0 если overlap не найден).
 fn find_text_overlap(prev: &str, next: &str) -> usize {
     let prev_words: Vec<&str> = prev.split_whitespace().collect();
@@ -327,14 +729,22 @@ fn find_text_overlap(prev: &str, next: &str) -> usize {
     let max_check = prev_words.len().min(next_words.len()).min(5);
 
     for n in (2..=max_check).rev() {
-        let prev_tail = &prev_words[prev_words.len() - n..];
+        let tail_start = prev_words.len() - n;
+        let prev_tail = &prev_words[tail_start..];
         let next_head = &next_words[..n];
 
-        if prev_tail
+        let matches = prev_tail
             .iter()
             .zip(next_head.iter())
-            .all(|(a, b)| a.to_lowercase() == b.to_lowercase())
-        {
+            .all(|(a, b)| normalize_overlap_token(a) == normalize_overlap_token(b));
+        if !matches {
+            continue;
+        }
+
+        let at_boundary = tail_start == 0
+            || ends_with_delimiter(prev_words[tail_start - 1])
+            || prev_tail.last().is_some_and(|w| ends_with_delimiter(w));
+        if n >= 3 || at_boundary {
             return n;
         }
     }
@@ -342,6 +752,17 @@ fn find_text_overlap(prev: &str, next: &str) -> usize {
     0
 }
 
+/// Слово для сравнения при поиске overlap: нижний регистр, без завершающей пунктуации.
+fn normalize_overlap_token(word: &str) -> String {
+    word.trim_end_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+/// Заканчивается ли слово разделителем фразы (точка, запятая и т.п.).
+fn ends_with_delimiter(word: &str) -> bool {
+    word.ends_with(['.', ',', '!', '?', ';', ':', '…'])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -368,7 +789,12 @@ mod tests {
     }
 
     impl SttProvider for StubSttProvider {
-        async fn transcribe(&self, _audio: &[u8], _language: Option<&str>) -> Result<String> {
+        async fn transcribe(
+            &self,
+            _audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> Result<String> {
             let idx = self.call_count.fetch_add(1, Ordering::SeqCst);
             if idx < self.responses.len() {
                 self.responses[idx].clone()
@@ -378,6 +804,220 @@ mod tests {
         }
     }
 
+    // -- build_provider --
+
+    #[tokio::test]
+    async fn build_provider_should_create_openai_client_by_default() {
+        // Given / When
+        let provider =
+            build_provider(&AppConfig::default(), "sk-test", &ModelCache::default()).await;
+
+        // Then
+        assert!(matches!(provider, Ok(AnySttProvider::OpenAi(_))));
+    }
+
+    #[cfg(not(feature = "offline-whisper"))]
+    #[tokio::test]
+    async fn build_provider_should_report_offline_unavailable_without_feature() {
+        // Given
+        let config = AppConfig {
+            stt_provider: SttProviderKind::Offline,
+            ..AppConfig::default()
+        };
+
+        // When
+        let result = build_provider(&config, "", &ModelCache::default()).await;
+
+        // Then
+        assert!(matches!(result, Err(SttError::ModelLoadFailed(_))));
+    }
+
+    #[cfg(feature = "offline-whisper")]
+    #[tokio::test]
+    async fn build_provider_should_not_cache_failed_model_load() {
+        // Given
+        let models = ModelCache::default();
+        let config = AppConfig {
+            stt_provider: SttProviderKind::Offline,
+            whisper_model_path: Some("/nonexistent/ggml-base.bin".to_string()),
+            ..AppConfig::default()
+        };
+
+        // When
+        let result = build_provider(&config, "", &models).await;
+
+        // Then
+        assert!(matches!(result, Err(SttError::ModelLoadFailed(_))));
+        assert!(!models.release());
+    }
+
+    // -- prompt chaining --
+
+    /// Провайдер, запоминающий полученные prompt и отвечающий текстом по номеру вызова.
+    struct PromptRecordingStub {
+        prompts: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl SttProvider for PromptRecordingStub {
+        async fn transcribe(
+            &self,
+            _audio: &[u8],
+            _language: Option<&str>,
+            prompt: Option<&str>,
+        ) -> Result<String> {
+            let mut prompts = self.prompts.lock().unwrap();
+            prompts.push(prompt.map(str::to_string));
+            Ok(format!("text of chunk {}", prompts.len()))
+        }
+    }
+
+    #[tokio::test]
+    async fn transcribe_audio_should_chain_previous_text_into_prompt_when_sequential() {
+        // Given
+        let provider = Arc::new(PromptRecordingStub {
+            prompts: std::sync::Mutex::new(Vec::new()),
+        });
+        let samples = vec![0.1f32; 16_000 * 60];
+
+        // When
+        transcribe_audio(
+            Arc::clone(&provider),
+            &samples,
+            16_000,
+            &TranscribeOptions {
+                prompt: Some("Kubernetes"),
+                max_chunk_sec: Some(25),
+                max_concurrent: Some(1),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        // Then
+        let prompts = provider.prompts.lock().unwrap();
+        assert!(prompts.len() >= 2);
+        assert_eq!(prompts[0].as_deref(), Some("Kubernetes"));
+        assert_eq!(prompts[1].as_deref(), Some("Kubernetes text of chunk 1"));
+    }
+
+    #[tokio::test]
+    async fn transcribe_audio_should_pass_prompt_for_single_chunk() {
+        // Given
+        let provider = Arc::new(PromptRecordingStub {
+            prompts: std::sync::Mutex::new(Vec::new()),
+        });
+        let samples = vec![0.1f32; 16_000 * 5];
+
+        // When
+        transcribe_audio(
+            Arc::clone(&provider),
+            &samples,
+            16_000,
+            &TranscribeOptions {
+                prompt: Some("AcmeCloud"),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        // Then
+        assert_eq!(
+            *provider.prompts.lock().unwrap(),
+            vec![Some("AcmeCloud".to_string())]
+        );
+    }
+
+    /// Провайдер, сообщающий язык по номеру вызова: первый чанк - без языка.
+    struct LanguageReportingStub {
+        calls: AtomicUsize,
+    }
+
+    impl SttProvider for LanguageReportingStub {
+        async fn transcribe(
+            &self,
+            _audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> Result<String> {
+            Ok("text".to_string())
+        }
+
+        async fn transcribe_detect_language(
+            &self,
+            audio: &[u8],
+            language: Option<&str>,
+            prompt: Option<&str>,
+        ) -> Result<(String, Option<String>)> {
+            let idx = self.calls.fetch_add(1, Ordering::SeqCst);
+            let text = self.transcribe(audio, language, prompt).await?;
+            let detected = match idx {
+                0 => None,
+                1 => Some("russian".to_string()),
+                _ => Some("english".to_string()),
+            };
+            Ok((text, detected))
+        }
+    }
+
+    #[tokio::test]
+    async fn transcribe_audio_detect_language_should_return_first_reported_language() {
+        // Given
+        let provider = Arc::new(LanguageReportingStub {
+            calls: AtomicUsize::new(0),
+        });
+        let samples = vec![0.1f32; 16_000 * 80];
+
+        // When
+        let (_, detected) = transcribe_audio_detect_language(
+            Arc::clone(&provider),
+            &samples,
+            16_000,
+            &TranscribeOptions {
+                max_chunk_sec: Some(25),
+                max_concurrent: Some(1),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        // Then
+        assert_eq!(detected.as_deref(), Some("russian"));
+    }
+
+    #[tokio::test]
+    async fn transcribe_detect_language_default_should_report_no_language() {
+        // Given
+        let provider = StubSttProvider::with_responses(vec![Ok("hello".to_string())]);
+
+        // When
+        let result = provider
+            .transcribe_detect_language(&[0u8; 4], None, None)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(result, ("hello".to_string(), None));
+    }
+
+    #[test]
+    fn text_tail_should_start_at_word_boundary() {
+        assert_eq!(text_tail("alpha beta gamma", 8), "gamma");
+        assert_eq!(text_tail("alpha beta gamma", 10), "beta gamma");
+        assert_eq!(text_tail("short", 200), "short");
+    }
+
+    #[test]
+    fn chained_prompt_should_skip_empty_parts() {
+        assert_eq!(chained_prompt(None, "hello"), Some("hello".to_string()));
+        assert_eq!(chained_prompt(Some("  "), "  "), None);
+    }
+
     // -- chunk_audio --
 
     #[test]
@@ -387,7 +1027,7 @@ mod tests {
         let samples: Vec<f32> = vec![0.1; sample_rate as usize * 10]; // 10s
 
         // When
-        let chunks = chunk_audio(&samples, sample_rate, 25);
+        let chunks = chunk_audio(&samples, sample_rate, 25, &ChunkingConfig::default());
 
         // Then
         assert_eq!(chunks.len(), 1);
@@ -401,7 +1041,7 @@ mod tests {
         let samples: Vec<f32> = vec![0.1; sample_rate as usize * 60]; // 60s
 
         // When
-        let chunks = chunk_audio(&samples, sample_rate, 25);
+        let chunks = chunk_audio(&samples, sample_rate, 25, &ChunkingConfig::default());
 
         // Then
         assert!(
@@ -414,27 +1054,98 @@ mod tests {
     #[test]
     fn chunk_audio_should_return_single_chunk_for_empty_audio() {
         // Given / When
-        let chunks = chunk_audio(&[], 16_000, 25);
+        let chunks = chunk_audio(&[], 16_000, 25, &ChunkingConfig::default());
 
         // Then
         assert_eq!(chunks.len(), 1);
         assert!(chunks[0].samples.is_empty());
     }
 
+    #[test]
+    fn chunk_audio_should_use_configured_overlap() {
+        // Given: тишина - разрез по таймеру в конце окна, overlap 0.5s
+        let sample_rate = 16_000u32;
+        let samples: Vec<f32> = vec![0.0; sample_rate as usize * 60];
+        let chunking = ChunkingConfig {
+            overlap_sec: 0.5,
+            quiet_search_start_percent: 99,
+            ..ChunkingConfig::default()
+        };
+
+        // When
+        let chunks = chunk_audio(&samples, sample_rate, 25, &chunking);
+
+        // Then: сумма длин превышает запись ровно на overlap каждого стыка
+        let total: usize = chunks.iter().map(|c| c.samples.len()).sum();
+        let seams = chunks.len() - 1;
+        assert!(seams >= 1);
+        assert_eq!(total - samples.len(), seams * sample_rate as usize / 2);
+    }
+
+    #[test]
+    fn chunk_audio_should_terminate_when_overlap_exceeds_chunk() {
+        // Given: overlap длиннее самого чанка
+        let sample_rate = 16_000u32;
+        let samples: Vec<f32> = vec![0.0; sample_rate as usize * 30];
+        let chunking = ChunkingConfig {
+            overlap_sec: 20.0,
+            min_chunk_sec: 0.0,
+            ..ChunkingConfig::default()
+        };
+
+        // When
+        let chunks = chunk_audio(&samples, sample_rate, 5, &chunking);
+
+        // Then
+        assert!(!chunks.is_empty());
+        assert_eq!(
+            chunks.last().unwrap().samples.last(),
+            samples.last(),
+            "last chunk must reach the end of the recording"
+        );
+    }
+
+    #[test]
+    fn chunking_config_from_config_should_sanitize_values() {
+        // Given
+        let config = AppConfig {
+            stt_chunk_overlap_sec: -1.0,
+            stt_min_chunk_sec: f32::NAN,
+            stt_quiet_search_start_percent: 150,
+            ..AppConfig::default()
+        };
+
+        // When
+        let chunking = ChunkingConfig::from_config(&config);
+
+        // Then
+        assert_eq!(chunking.overlap_sec, 1.5);
+        assert_eq!(chunking.min_chunk_sec, 5.0);
+        assert_eq!(chunking.quiet_search_start_percent, 99);
+    }
+
+    #[test]
+    fn chunking_config_from_default_app_config_should_match_default() {
+        assert_eq!(
+            ChunkingConfig::from_config(&AppConfig::default()),
+            ChunkingConfig::default()
+        );
+    }
+
     #[test]
     fn chunk_audio_should_not_create_tiny_trailing_chunk() {
-        // Given: 27s, max_chunk=25, remainder=2s < MIN_CHUNK_SEC
+        // Given: 27s, max_chunk=25, remainder=2s < min_chunk_sec
         let sample_rate = 16_000u32;
         let samples: Vec<f32> = vec![0.0; sample_rate as usize * 27];
 
         // When
-        let chunks = chunk_audio(&samples, sample_rate, 25);
+        let chunks = chunk_audio(&samples, sample_rate, 25, &ChunkingConfig::default());
 
-        // Then: последний чанк >= MIN_CHUNK_SEC (или это единственный чанк)
+        // Then: последний чанк >= min_chunk_sec (или это единственный чанк)
         if chunks.len() > 1 {
             let last_sec = chunks.last().unwrap().samples.len() as f32 / sample_rate as f32;
             assert!(
-                last_sec >= MIN_CHUNK_SEC,
+                last_sec >= ChunkingConfig::default().min_chunk_sec,
                 "trailing chunk too short: {last_sec}s"
             );
         }
@@ -448,7 +1159,7 @@ mod tests {
         let samples: Vec<f32> = (0..total).map(|i| (i as f32) / total as f32).collect();
 
         // When
-        let chunks = chunk_audio(&samples, sample_rate, 25);
+        let chunks = chunk_audio(&samples, sample_rate, 25, &ChunkingConfig::default());
 
         // Then: первый и последний семпл присутствуют
         assert_eq!(chunks.first().unwrap().samples[0], samples[0]);
@@ -517,7 +1228,7 @@ mod tests {
         // Given
         let texts = vec![
             "the quick brown fox".to_string(),
-            "brown fox jumps over".to_string(),
+            "quick brown fox jumps over".to_string(),
         ];
 
         // When / Then
@@ -527,6 +1238,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deduplicate_should_match_words_with_trailing_punctuation() {
+        // Given: STT поставил точку на обрезанном конце чанка
+        let texts = vec![
+            "the quick brown fox.".to_string(),
+            "brown fox jumps over".to_string(),
+        ];
+
+        // When / Then
+        assert_eq!(
+            deduplicate_overlap_texts(&texts),
+            "the quick brown fox. jumps over"
+        );
+    }
+
+    #[test]
+    fn deduplicate_should_preserve_repeated_words_inside_sentence() {
+        // Given: "very very" - настоящий повтор, а не дубликат стыка
+        let texts = vec![
+            "this is very very".to_string(),
+            "very very important".to_string(),
+        ];
+
+        // When / Then
+        assert_eq!(
+            deduplicate_overlap_texts(&texts),
+            "this is very very very very important"
+        );
+    }
+
     #[test]
     fn deduplicate_should_be_case_insensitive() {
         let texts = vec!["Hello World".to_string(), "hello world again".to_string()];
@@ -537,8 +1278,8 @@ mod tests {
     fn deduplicate_should_handle_three_chunks() {
         let texts = vec![
             "aaa bbb ccc ddd".to_string(),
-            "ccc ddd eee fff".to_string(),
-            "eee fff ggg hhh".to_string(),
+            "bbb ccc ddd eee fff".to_string(),
+            "ddd eee fff ggg hhh".to_string(),
         ];
         assert_eq!(
             deduplicate_overlap_texts(&texts),
@@ -554,8 +1295,23 @@ mod tests {
     }
 
     #[test]
-    fn find_overlap_should_detect_two_word_overlap() {
-        assert_eq!(find_text_overlap("a b c d", "c d e f"), 2);
+    fn find_overlap_should_detect_two_word_overlap_at_sentence_boundary() {
+        assert_eq!(find_text_overlap("a b. c d", "c d e f"), 2);
+        assert_eq!(find_text_overlap("a b c d.", "c d e f"), 2);
+    }
+
+    #[test]
+    fn find_overlap_should_ignore_two_word_overlap_inside_sentence() {
+        assert_eq!(find_text_overlap("a b c d", "c d e f"), 0);
+    }
+
+    #[test]
+    fn find_overlap_should_ignore_trailing_punctuation() {
+        assert_eq!(
+            find_text_overlap("the quick brown fox.", "brown fox jumps"),
+            2
+        );
+        assert_eq!(find_text_overlap("x a, b c", "a b c! y"), 3);
     }
 
     #[test]
@@ -588,9 +1344,11 @@ mod tests {
             Arc::clone(&provider),
             &samples,
             16_000,
-            None,
-            Some(25),
-            None,
+            &TranscribeOptions {
+                max_chunk_sec: Some(25),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
         )
         .await;
 
@@ -616,9 +1374,11 @@ mod tests {
             Arc::clone(&provider),
             &samples,
             16_000,
-            None,
-            Some(25),
-            None,
+            &TranscribeOptions {
+                max_chunk_sec: Some(25),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
         )
         .await;
 
@@ -642,9 +1402,11 @@ mod tests {
             Arc::clone(&provider),
             &samples,
             16_000,
-            None,
-            Some(25),
-            None,
+            &TranscribeOptions {
+                max_chunk_sec: Some(25),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
         )
         .await;
 
@@ -668,9 +1430,11 @@ mod tests {
             Arc::clone(&provider),
             &samples,
             16_000,
-            None,
-            Some(25),
-            None,
+            &TranscribeOptions {
+                max_chunk_sec: Some(25),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
         )
         .await;
 
@@ -717,7 +1481,7 @@ mod tests {
         let samples = vec![0.1f32; 16_000 * 10];
 
         // When
-        let chunks = chunk_audio(&samples, 16_000, 0);
+        let chunks = chunk_audio(&samples, 16_000, 0, &ChunkingConfig::default());
 
         // Then: returns all audio as one chunk (fallback, no infinite loop)
         assert_eq!(chunks.len(), 1);
@@ -730,7 +1494,7 @@ mod tests {
         let samples = vec![0.1f32; 1000];
 
         // When
-        let chunks = chunk_audio(&samples, 0, 25);
+        let chunks = chunk_audio(&samples, 0, 25, &ChunkingConfig::default());
 
         // Then: returns all audio as one chunk (fallback, no infinite loop)
         assert_eq!(chunks.len(), 1);
@@ -746,7 +1510,14 @@ mod tests {
         let samples = vec![0.1f32; 1000];
 
         // When
-        let result = transcribe_audio(Arc::clone(&provider), &samples, 0, None, None, None).await;
+        let result = transcribe_audio(
+            Arc::clone(&provider),
+            &samples,
+            0,
+            &TranscribeOptions::default(),
+            &ChunkingConfig::default(),
+        )
+        .await;
 
         // Then
         assert!(matches!(result.unwrap_err(), SttError::EncodingFailed(_)));
@@ -766,8 +1537,17 @@ mod tests {
         let samples = vec![0.1f32; 16_000 * 3]; // 3 seconds
 
         // When
-        let result =
-            transcribe_audio(Arc::clone(&provider), &samples, 16_000, None, Some(0), None).await;
+        let result = transcribe_audio(
+            Arc::clone(&provider),
+            &samples,
+            16_000,
+            &TranscribeOptions {
+                max_chunk_sec: Some(0),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
+        )
+        .await;
 
         // Then: should complete without hanging
         assert!(result.is_ok());
@@ -775,12 +1555,97 @@ mod tests {
 
     // -- parallel transcribe --
 
+    /// Провайдер, отвечающий 413 на аудио больше `limit` байт.
+    struct PayloadLimitStub {
+        limit: usize,
+        calls: AtomicUsize,
+    }
+
+    impl SttProvider for PayloadLimitStub {
+        async fn transcribe(
+            &self,
+            audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if audio.len() > self.limit {
+                return Err(SttError::PayloadTooLarge);
+            }
+            Ok("ok".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn transcribe_audio_should_rechunk_smaller_when_payload_too_large() {
+        // Given: endpoint принимает не больше ~12s закодированного аудио
+        let limit = crate::audio::encode::encode_ogg_opus(&vec![0.1f32; 16_000 * 12], 16_000)
+            .unwrap()
+            .len();
+        let provider = Arc::new(PayloadLimitStub {
+            limit,
+            calls: AtomicUsize::new(0),
+        });
+        let samples = vec![0.1f32; 16_000 * 60];
+
+        // When: 30s -> 15s -> 7s
+        let result = transcribe_audio(
+            Arc::clone(&provider),
+            &samples,
+            16_000,
+            &TranscribeOptions {
+                max_chunk_sec: Some(30),
+                max_concurrent: Some(1),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
+        )
+        .await;
+
+        // Then
+        let text = result.unwrap();
+        assert!(text.split_whitespace().all(|t| t == "ok"));
+        assert!(provider.calls.load(Ordering::SeqCst) > 2);
+    }
+
+    #[tokio::test]
+    async fn transcribe_audio_should_fail_when_payload_too_large_at_min_chunk() {
+        // Given: endpoint отклоняет любые данные
+        let provider = Arc::new(PayloadLimitStub {
+            limit: 0,
+            calls: AtomicUsize::new(0),
+        });
+        let samples = vec![0.1f32; 16_000 * 20];
+
+        // When
+        let result = transcribe_audio(
+            Arc::clone(&provider),
+            &samples,
+            16_000,
+            &TranscribeOptions {
+                max_chunk_sec: Some(20),
+                max_concurrent: Some(1),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
+        )
+        .await;
+
+        // Then
+        assert!(matches!(result, Err(SttError::PayloadTooLarge)));
+    }
+
     /// Провайдер, возвращающий размер полученных OGG-данных.
     /// Позволяет проверить порядок чанков без зависимости от порядка вызовов.
     struct SizeReportingStub;
 
     impl SttProvider for SizeReportingStub {
-        async fn transcribe(&self, audio: &[u8], _language: Option<&str>) -> Result<String> {
+        async fn transcribe(
+            &self,
+            audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> Result<String> {
             Ok(format!("bytes:{}", audio.len()))
         }
     }
@@ -797,9 +1662,12 @@ mod tests {
             Arc::clone(&provider),
             &samples,
             16_000,
-            None,
-            Some(25),
-            Some(3),
+            &TranscribeOptions {
+                max_chunk_sec: Some(25),
+                max_concurrent: Some(3),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
         )
         .await;
 
@@ -855,9 +1723,12 @@ mod tests {
             Arc::clone(&provider),
             &samples,
             16_000,
-            None,
-            Some(25),
-            Some(3),
+            &TranscribeOptions {
+                max_chunk_sec: Some(25),
+                max_concurrent: Some(3),
+                ..TranscribeOptions::default()
+            },
+            &ChunkingConfig::default(),
         )
         .await;
 
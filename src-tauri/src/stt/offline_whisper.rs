@@ -1 +1,174 @@
-// whisper-rs bindings (offline STT) - planned for v1.1
+//! Офлайн-распознавание через whisper.cpp (`whisper-rs`).
+//!
+//! Pipeline передает модели PCM 16kHz mono напрямую (`transcribe_pcm`), без
+//! кодирования в Opus. Через `SttProvider` принимаются те же OGG/Opus байты,
+//! что и у облачного провайдера: они декодируются в PCM перед инференсом.
+//! Аудио не покидает машину.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+use super::{Result, SttError, SttProvider};
+use crate::audio::decode::decode_audio;
+use crate::audio::preprocess::TARGET_SAMPLE_RATE;
+use crate::config::schema::AppConfig;
+
+/// Провайдер STT на локальной модели Whisper (ggml).
+///
+/// Клон разделяет загруженную модель (`Arc<WhisperContext>`): ее держит
+/// `ModelCache`, провайдер для диктовки - только на время распознавания.
+#[derive(Clone)]
+pub struct OfflineWhisperProvider {
+    context: Arc<WhisperContext>,
+}
+
+impl OfflineWhisperProvider {
+    /// Загружает модель whisper.cpp из файла.
+    ///
+    /// Блокирующая операция (сотни мегабайт с диска): из async-кода вызывать
+    /// в `spawn_blocking`. Ошибки загрузки возвращаются как `SttError::ModelLoadFailed`.
+    pub fn new(model_path: &Path) -> Result<Self> {
+        if !model_path.is_file() {
+            return Err(SttError::ModelLoadFailed(format!(
+                "model file not found: {}",
+                model_path.display()
+            )));
+        }
+        let path = model_path.to_str().ok_or_else(|| {
+            SttError::ModelLoadFailed("model path is not valid UTF-8".to_string())
+        })?;
+
+        let context = WhisperContext::new_with_params(path, WhisperContextParameters::default())
+            .map_err(|e| SttError::ModelLoadFailed(e.to_string()))?;
+
+        tracing::info!(model = %model_path.display(), "offline Whisper model loaded");
+        Ok(Self {
+            context: Arc::new(context),
+        })
+    }
+
+    /// Распознает PCM mono 16kHz без промежуточного кодирования.
+    ///
+    /// Длинные записи модель обрабатывает окнами сама, нарезка не нужна.
+    pub async fn transcribe_pcm(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        if sample_rate != TARGET_SAMPLE_RATE {
+            return Err(SttError::EncodingFailed(format!(
+                "expected {TARGET_SAMPLE_RATE} Hz audio, got {sample_rate} Hz"
+            )));
+        }
+        if samples.is_empty() {
+            return Ok(String::new());
+        }
+
+        let context = Arc::clone(&self.context);
+        let samples = samples.to_vec();
+        let language = language.map(str::to_string);
+        let prompt = prompt.map(str::to_string);
+        let start = std::time::Instant::now();
+        let text = tokio::task::spawn_blocking(move || {
+            run_inference(&context, &samples, language.as_deref(), prompt.as_deref())
+        })
+        .await
+        .map_err(|e| SttError::InferenceFailed(format!("task failed: {e}")))??;
+
+        tracing::info!(
+            ms = start.elapsed().as_millis() as u64,
+            chars = text.len(),
+            "offline Whisper transcription complete"
+        );
+        Ok(text)
+    }
+}
+
+/// Путь к модели из `whisper_model_path`; не задан - `ModelLoadFailed`.
+pub fn model_path(config: &AppConfig) -> Result<PathBuf> {
+    config
+        .whisper_model_path
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| SttError::ModelLoadFailed("whisper_model_path is not set".to_string()))
+}
+
+/// Синхронный инференс: вызывается в `spawn_blocking`, т.к. занимает CPU на секунды.
+fn run_inference(
+    context: &WhisperContext,
+    samples: &[f32],
+    language: Option<&str>,
+    prompt: Option<&str>,
+) -> Result<String> {
+    let mut state = context
+        .create_state()
+        .map_err(|e| SttError::InferenceFailed(e.to_string()))?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some(language.unwrap_or("auto")));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    if let Some(prompt) = prompt.filter(|p| !p.trim().is_empty()) {
+        params.set_initial_prompt(prompt);
+    }
+
+    state
+        .full(params, samples)
+        .map_err(|e| SttError::InferenceFailed(e.to_string()))?;
+
+    let segments = state
+        .full_n_segments()
+        .map_err(|e| SttError::InferenceFailed(e.to_string()))?;
+    let mut text = String::new();
+    for i in 0..segments {
+        let segment = state
+            .full_get_segment_text(i)
+            .map_err(|e| SttError::InferenceFailed(e.to_string()))?;
+        text.push_str(&segment);
+    }
+
+    Ok(text.trim().to_string())
+}
+
+impl SttProvider for OfflineWhisperProvider {
+    async fn transcribe(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        let samples = decode_audio(audio).map_err(|e| SttError::EncodingFailed(e.to_string()))?;
+        self.transcribe_pcm(&samples, TARGET_SAMPLE_RATE, language, prompt)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_should_fail_with_model_load_error_when_file_missing() {
+        // Given
+        let path = Path::new("/nonexistent/ggml-base.bin");
+
+        // When
+        let result = OfflineWhisperProvider::new(path);
+
+        // Then
+        assert!(matches!(result, Err(SttError::ModelLoadFailed(_))));
+    }
+
+    #[test]
+    fn model_path_should_fail_when_not_set() {
+        let result = model_path(&AppConfig::default());
+        assert!(matches!(result, Err(SttError::ModelLoadFailed(_))));
+    }
+}
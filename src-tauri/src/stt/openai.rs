@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use bytes::Bytes;
 use reqwest::header;
@@ -6,6 +6,9 @@ use reqwest::StatusCode;
 use serde::Deserialize;
 
 use super::{Result, SttError, SttProvider};
+use crate::backoff::Jitter;
+use crate::config::schema::UploadFormat;
+use crate::redact;
 
 const USER_AGENT: &str = "VoiceDictator/0.1.0";
 
@@ -15,22 +18,92 @@ const MAX_RATE_LIMIT_RETRIES: u32 = 5;
 /// Верхняя граница задержки backoff (секунды).
 const MAX_BACKOFF_SEC: u64 = 16;
 
+/// Задержка по умолчанию, если Retry-After отсутствует или не распознан (секунды).
+const DEFAULT_RETRY_AFTER_SEC: u64 = 5;
+
+/// Разбирает Retry-After: целое число секунд или HTTP-date (RFC 9110, IMF-fixdate).
+///
+/// Для даты возвращает число секунд от `now` (0, если дата уже в прошлом).
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(sec) = value.parse::<u64>() {
+        return Some(sec);
+    }
+
+    let target = parse_http_date(value)?;
+    let now = now.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(target.saturating_sub(now))
+}
+
+/// Разбирает IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`) в секунды Unix-времени.
+fn parse_http_date(value: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [_weekday, day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|m| m == month)? as u64 + 1;
+    let year: u64 = year.parse().ok()?;
+
+    let mut hms = time.split(':').map(|p| p.parse::<u64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    if hms.next().is_some() || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day)? * 86_400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Количество дней от 1970-01-01 до даты по григорианскому календарю.
+fn days_from_civil(year: u64, month: u64, day: u64) -> Option<u64> {
+    let y = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    (era * 146_097 + doe).checked_sub(719_468)
+}
+
+/// Ограничивает температуру декодирования диапазоном 0.0..1.0 (`NaN` -> 0.0).
+fn clamp_temperature(temperature: f32) -> f32 {
+    if temperature.is_nan() {
+        0.0
+    } else {
+        temperature.clamp(0.0, 1.0)
+    }
+}
+
 /// Клиент для OpenAI STT API.
 ///
 /// Выполняет `POST /v1/audio/transcriptions` с multipart-данными.
-/// Поддерживает retry с exponential backoff и обработку rate limiting (429).
+/// Поддерживает retry с exponential backoff (с jitter ±25%) и обработку rate limiting (429).
 pub struct OpenAiSttClient {
     client: reqwest::Client,
     base_url: String,
     api_key: String,
     model: String,
+    temperature: f32,
     retry_count: u32,
     read_timeout: Duration,
+    jitter: Jitter,
+    upload_format: UploadFormat,
 }
 
 #[derive(Deserialize)]
 struct TranscriptionResponse {
     text: String,
+    /// Определенный язык (только в `verbose_json`, например "russian").
+    #[serde(default)]
+    language: Option<String>,
 }
 
 impl OpenAiSttClient {
@@ -39,6 +112,7 @@ impl OpenAiSttClient {
     /// - `base_url` - базовый URL (например "https://api.openai.com"), слеш в конце убирается
     /// - `api_key` - Bearer-токен
     /// - `model` - модель STT из конфига
+    /// - `temperature` - температура декодирования (0.0..1.0, вне диапазона ограничивается)
     /// - `connect_timeout` - таймаут установки соединения
     /// - `read_timeout` - таймаут ожидания ответа
     /// - `retry_count` - количество повторных попыток (0 = без retry)
@@ -46,6 +120,7 @@ impl OpenAiSttClient {
         base_url: &str,
         api_key: &str,
         model: &str,
+        temperature: f32,
         connect_timeout: Duration,
         read_timeout: Duration,
         retry_count: u32,
@@ -61,33 +136,92 @@ impl OpenAiSttClient {
             base_url: base_url.trim_end_matches('/').to_string(),
             api_key: api_key.to_string(),
             model: model.to_string(),
+            temperature: clamp_temperature(temperature),
             retry_count,
             read_timeout,
+            jitter: Jitter::from_time(),
+            upload_format: UploadFormat::default(),
         })
     }
 
+    /// Задает формат загружаемого аудио (имя файла и MIME-тип в запросе).
+    pub fn with_upload_format(mut self, format: UploadFormat) -> Self {
+        self.upload_format = format;
+        self
+    }
+
     /// Создает клиент из AppConfig и API-ключа.
+    ///
+    /// Endpoint и модель берутся из активного профиля провайдера,
+    /// формат аудио - из `audio_upload_format`.
     pub fn from_config(config: &crate::config::schema::AppConfig, api_key: &str) -> Result<Self> {
-        Self::new(
-            &config.api_base_url,
+        let profile = config.active_provider();
+        Ok(Self::new(
+            &profile.api_base_url,
             api_key,
-            &config.stt_model,
+            &profile.stt_model,
+            config.stt_temperature,
             Duration::from_secs(config.connect_timeout_sec as u64),
             Duration::from_secs(config.read_timeout_stt_sec as u64),
             config.retry_count,
-        )
+        )?
+        .with_upload_format(config.audio_upload_format))
     }
 
     /// Транскрипция с retry и rate limiting.
-    async fn do_transcribe(&self, audio: &[u8], language: Option<&str>) -> Result<String> {
+    async fn do_transcribe(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        let response = self.do_request(audio, language, prompt, false).await?;
+        Ok(response.text)
+    }
+
+    /// Транскрипция с определением языка через `verbose_json`.
+    ///
+    /// Язык запрашивается, только если он не задан явно и модель поддерживает
+    /// `verbose_json` (модели `gpt-4o*` принимают лишь `json`/`text`).
+    async fn do_transcribe_detect_language(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<(String, Option<String>)> {
+        let verbose = matches!(language, None | Some("auto")) && self.supports_verbose_json();
+        let response = self.do_request(audio, language, prompt, verbose).await?;
+        let detected = response
+            .language
+            .map(|lang| lang.trim().to_lowercase())
+            .filter(|lang| !lang.is_empty());
+        Ok((response.text, detected))
+    }
+
+    /// Поддерживает ли модель `response_format=verbose_json`.
+    fn supports_verbose_json(&self) -> bool {
+        !self.model.starts_with("gpt-4o")
+    }
+
+    /// Запрос транскрипции с retry и rate limiting.
+    async fn do_request(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+        verbose: bool,
+    ) -> Result<TranscriptionResponse> {
         let url = format!("{}/v1/audio/transcriptions", self.base_url);
         let audio_bytes = Bytes::copy_from_slice(audio);
         let mut retries_left = self.retry_count;
         let mut rate_limit_retries: u32 = 0;
 
         loop {
-            match self.send_request(&url, audio_bytes.clone(), language).await {
-                Ok(text) => return Ok(text),
+            match self
+                .send_request(&url, audio_bytes.clone(), language, prompt, verbose)
+                .await
+            {
+                Ok(response) => return Ok(response),
                 Err(SttError::RateLimited { retry_after_sec }) => {
                     rate_limit_retries += 1;
                     if rate_limit_retries > MAX_RATE_LIMIT_RETRIES {
@@ -106,16 +240,14 @@ impl OpenAiSttClient {
                         return Err(e);
                     }
                     let attempt = self.retry_count - retries_left;
-                    let backoff_sec = 1u64
-                        .checked_shl(attempt)
-                        .unwrap_or(MAX_BACKOFF_SEC)
-                        .min(MAX_BACKOFF_SEC);
+                    let backoff = self.jitter.backoff(attempt, MAX_BACKOFF_SEC);
                     tracing::warn!(
-                        "STT request failed (retry {}/{}), backoff {backoff_sec}s: {e}",
+                        "STT request failed (retry {}/{}), backoff {:.2}s: {e}",
                         attempt + 1,
-                        self.retry_count
+                        self.retry_count,
+                        backoff.as_secs_f32()
                     );
-                    tokio::time::sleep(Duration::from_secs(backoff_sec)).await;
+                    tokio::time::sleep(backoff).await;
                     retries_left -= 1;
                 }
             }
@@ -137,15 +269,21 @@ impl OpenAiSttClient {
         url: &str,
         audio: Bytes,
         language: Option<&str>,
-    ) -> Result<String> {
+        prompt: Option<&str>,
+        verbose: bool,
+    ) -> Result<TranscriptionResponse> {
         let file_part = reqwest::multipart::Part::stream(audio)
-            .file_name("audio.ogg")
-            .mime_str("audio/ogg")
+            .file_name(self.upload_format.file_name())
+            .mime_str(self.upload_format.mime_type())
             .map_err(|e| SttError::Network(e.to_string()))?;
 
         let mut form = reqwest::multipart::Form::new()
             .text("model", self.model.clone())
-            .text("response_format", "json")
+            .text(
+                "response_format",
+                if verbose { "verbose_json" } else { "json" },
+            )
+            .text("temperature", self.temperature.to_string())
             .part("file", file_part);
 
         if let Some(lang) = language {
@@ -154,6 +292,10 @@ impl OpenAiSttClient {
             }
         }
 
+        if let Some(prompt) = prompt.map(str::trim).filter(|p| !p.is_empty()) {
+            form = form.text("prompt", prompt.to_string());
+        }
+
         let response = self
             .client
             .post(url)
@@ -178,13 +320,17 @@ impl OpenAiSttClient {
             return Err(SttError::AuthFailed);
         }
 
+        if status == StatusCode::PAYLOAD_TOO_LARGE {
+            return Err(SttError::PayloadTooLarge);
+        }
+
         if status == StatusCode::TOO_MANY_REQUESTS {
             let retry_after = response
                 .headers()
                 .get(header::RETRY_AFTER)
                 .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse::<u64>().ok())
-                .unwrap_or(5)
+                .and_then(|v| parse_retry_after(v, SystemTime::now()))
+                .unwrap_or(DEFAULT_RETRY_AFTER_SEC)
                 .clamp(1, 60);
             return Err(SttError::RateLimited {
                 retry_after_sec: retry_after,
@@ -195,7 +341,7 @@ impl OpenAiSttClient {
             let body = response.text().await.unwrap_or_default();
             return Err(SttError::ApiError {
                 status: status.as_u16(),
-                message: body,
+                message: redact::api_error_message(&body, &self.api_key),
             });
         }
 
@@ -204,13 +350,28 @@ impl OpenAiSttClient {
             .await
             .map_err(|e| SttError::InvalidResponse(e.to_string()))?;
 
-        Ok(body.text)
+        Ok(body)
     }
 }
 
 impl SttProvider for OpenAiSttClient {
-    async fn transcribe(&self, audio: &[u8], language: Option<&str>) -> Result<String> {
-        self.do_transcribe(audio, language).await
+    async fn transcribe(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String> {
+        self.do_transcribe(audio, language, prompt).await
+    }
+
+    async fn transcribe_detect_language(
+        &self,
+        audio: &[u8],
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<(String, Option<String>)> {
+        self.do_transcribe_detect_language(audio, language, prompt)
+            .await
     }
 }
 
@@ -224,6 +385,7 @@ mod tests {
             "https://api.openai.com",
             "test-key",
             "gpt-4o-mini-transcribe",
+            0.0,
             Duration::from_secs(5),
             Duration::from_secs(30),
             3,
@@ -238,6 +400,7 @@ mod tests {
             "https://api.openai.com/",
             "test-key",
             "gpt-4o-mini-transcribe",
+            0.0,
             Duration::from_secs(5),
             Duration::from_secs(30),
             3,
@@ -255,6 +418,7 @@ mod tests {
             "https://api.openai.com",
             "key",
             "my-custom-model",
+            0.0,
             Duration::from_secs(5),
             Duration::from_secs(30),
             2,
@@ -265,6 +429,28 @@ mod tests {
         assert_eq!(client.model, "my-custom-model");
     }
 
+    #[test]
+    fn from_config_should_use_active_profile() {
+        // Given
+        let config = crate::config::schema::AppConfig {
+            profiles: vec![crate::config::schema::ProviderProfile {
+                name: "groq".to_string(),
+                api_base_url: "https://api.groq.com/openai".to_string(),
+                stt_model: "whisper-large-v3".to_string(),
+                enhance_model: "llama-3.3-70b".to_string(),
+            }],
+            active_profile: "groq".to_string(),
+            ..Default::default()
+        };
+
+        // When
+        let client = OpenAiSttClient::from_config(&config, "api-key-123").unwrap();
+
+        // Then
+        assert_eq!(client.base_url, "https://api.groq.com/openai");
+        assert_eq!(client.model, "whisper-large-v3");
+    }
+
     #[test]
     fn from_config_should_use_config_values() {
         // Given
@@ -274,6 +460,7 @@ mod tests {
             connect_timeout_sec: 10,
             read_timeout_stt_sec: 60,
             retry_count: 5,
+            stt_temperature: 0.3,
             ..Default::default()
         };
 
@@ -283,9 +470,54 @@ mod tests {
         // Then
         assert_eq!(client.base_url, "https://custom.api.com");
         assert_eq!(client.model, "custom-stt");
+        assert_eq!(client.temperature, 0.3);
         assert_eq!(client.retry_count, 5);
     }
 
+    #[test]
+    fn parse_retry_after_should_accept_seconds() {
+        assert_eq!(parse_retry_after("7", SystemTime::now()), Some(7));
+        assert_eq!(parse_retry_after(" 12 ", SystemTime::now()), Some(12));
+    }
+
+    #[test]
+    fn parse_retry_after_should_accept_http_date() {
+        // Given: 1994-11-06 08:49:37 GMT = 784111777
+        let now = UNIX_EPOCH + Duration::from_secs(784_111_767);
+
+        // When
+        let delay = parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT", now);
+
+        // Then
+        assert_eq!(delay, Some(10));
+    }
+
+    #[test]
+    fn parse_retry_after_should_return_zero_for_past_date() {
+        let now = UNIX_EPOCH + Duration::from_secs(800_000_000);
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT", now),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn parse_retry_after_should_reject_garbage() {
+        assert_eq!(parse_retry_after("soon", SystemTime::now()), None);
+        assert_eq!(
+            parse_retry_after("Sun, 06 Foo 1994 08:49:37 GMT", SystemTime::now()),
+            None
+        );
+    }
+
+    #[test]
+    fn clamp_temperature_should_limit_out_of_range_values() {
+        assert_eq!(clamp_temperature(-0.5), 0.0);
+        assert_eq!(clamp_temperature(1.7), 1.0);
+        assert_eq!(clamp_temperature(f32::NAN), 0.0);
+        assert_eq!(clamp_temperature(0.2), 0.2);
+    }
+
     #[test]
     fn is_retryable_should_return_true_for_network_error() {
         assert!(OpenAiSttClient::is_retryable(&SttError::Network(
@@ -353,16 +585,40 @@ mod integration_tests {
         }
     }
 
+    /// Matches if the raw request body is longer than the given number of bytes.
+    struct BodyLargerThan(usize);
+
+    impl Match for BodyLargerThan {
+        fn matches(&self, request: &Request) -> bool {
+            request.body.len() > self.0
+        }
+    }
+
+    /// Matches if the raw request body contains the given bytes.
+    struct BodyContainsBytes(&'static [u8]);
+
+    impl Match for BodyContainsBytes {
+        fn matches(&self, request: &Request) -> bool {
+            request
+                .body
+                .windows(self.0.len())
+                .any(|window| window == self.0)
+        }
+    }
+
     async fn create_test_client(base_url: &str) -> OpenAiSttClient {
-        OpenAiSttClient::new(
+        let mut client = OpenAiSttClient::new(
             base_url,
             "test-api-key",
             "gpt-4o-mini-transcribe",
+            0.0,
             Duration::from_secs(5),
             Duration::from_secs(10),
             2,
         )
-        .unwrap()
+        .unwrap();
+        client.jitter = Jitter::with_seed(1);
+        client
     }
 
     #[tokio::test]
@@ -381,12 +637,39 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert_eq!(result.unwrap(), "hello world");
     }
 
+    #[tokio::test]
+    async fn transcribe_should_upload_wav_with_matching_file_name_and_mime() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(BodyContainsBytes(b"filename=\"audio.wav\""))
+            .and(BodyContainsBytes(b"audio/wav"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": "wav" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri())
+            .await
+            .with_upload_format(UploadFormat::Wav);
+        let audio = crate::audio::encode::encode_wav(&[0.0; 1600], 16_000);
+
+        // When
+        let result = client.do_transcribe(&audio, None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "wav");
+    }
+
     #[tokio::test]
     async fn transcribe_should_fail_on_401() {
         // Given
@@ -400,12 +683,111 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert!(matches!(result.unwrap_err(), SttError::AuthFailed));
     }
 
+    #[tokio::test]
+    async fn transcribe_should_fail_on_413_without_retry() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(ResponseTemplate::new(413))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
+
+        // Then
+        assert!(matches!(result.unwrap_err(), SttError::PayloadTooLarge));
+    }
+
+    #[tokio::test]
+    async fn transcribe_audio_should_rechunk_after_413() {
+        // Given: endpoint accepts at most ~12s of encoded audio per request
+        let limit = crate::audio::encode::encode_ogg_opus(&vec![0.1f32; 16_000 * 12], 16_000)
+            .unwrap()
+            .len()
+            + 1024;
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(BodyLargerThan(limit))
+            .respond_with(ResponseTemplate::new(413))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": "part" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+        let samples = vec![0.1f32; 16_000 * 60];
+
+        // When
+        let result = crate::stt::transcribe_audio(
+            std::sync::Arc::new(client),
+            &samples,
+            16_000,
+            &crate::stt::TranscribeOptions {
+                max_chunk_sec: Some(30),
+                max_concurrent: Some(1),
+                ..crate::stt::TranscribeOptions::default()
+            },
+            &crate::stt::ChunkingConfig::default(),
+        )
+        .await;
+
+        // Then
+        let text = result.unwrap();
+        assert!(text.starts_with("part"));
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.iter().any(|r| r.body.len() > limit));
+        assert!(requests.iter().any(|r| r.body.len() <= limit));
+    }
+
+    #[tokio::test]
+    async fn transcribe_should_accept_http_date_retry_after() {
+        // Given: Retry-After as HTTP-date in the past -> minimal wait
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .append_header("Retry-After", "Sun, 06 Nov 1994 08:49:37 GMT"),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": "ok" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let started = std::time::Instant::now();
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
+
+        // Then: past date clamps to 1s instead of the 5s default
+        assert_eq!(result.unwrap(), "ok");
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
     #[tokio::test]
     async fn transcribe_should_handle_rate_limiting() {
         // Given: first request -> 429, second -> 200
@@ -429,7 +811,7 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert_eq!(result.unwrap(), "after rate limit");
@@ -458,7 +840,7 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert_eq!(result.unwrap(), "recovered");
@@ -477,7 +859,7 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert!(matches!(
@@ -500,7 +882,7 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert!(matches!(
@@ -525,7 +907,7 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert_eq!(result.unwrap(), "   ");
@@ -544,7 +926,7 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert!(matches!(result.unwrap_err(), SttError::InvalidResponse(_)));
@@ -568,12 +950,157 @@ mod integration_tests {
         let client = create_test_client(&server.uri()).await;
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), Some("ru")).await;
+        let result = client
+            .do_transcribe(&make_test_audio(), Some("ru"), None)
+            .await;
 
         // Then
         assert_eq!(result.unwrap(), "test");
     }
 
+    #[tokio::test]
+    async fn detect_language_should_parse_verbose_json() {
+        // Given: whisper model supports verbose_json and reports language
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(BodyContains("\r\n\r\nverbose_json\r\n".to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "text": "привет world",
+                "language": "Russian",
+                "duration": 1.5
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = OpenAiSttClient::new(
+            &server.uri(),
+            "test-api-key",
+            "whisper-large-v3",
+            0.0,
+            Duration::from_secs(5),
+            Duration::from_secs(10),
+            0,
+        )
+        .unwrap();
+
+        // When
+        let result = client
+            .do_transcribe_detect_language(&make_test_audio(), Some("auto"), None)
+            .await;
+
+        // Then
+        assert_eq!(
+            result.unwrap(),
+            ("привет world".to_string(), Some("russian".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn detect_language_should_use_json_for_gpt4o_models() {
+        // Given: gpt-4o-mini-transcribe does not support verbose_json
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(BodyContains("\r\n\r\njson\r\n".to_string()))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": "hello" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client
+            .do_transcribe_detect_language(&make_test_audio(), None, None)
+            .await;
+
+        // Then
+        assert_eq!(result.unwrap(), ("hello".to_string(), None));
+    }
+
+    #[tokio::test]
+    async fn transcribe_should_pass_temperature_param() {
+        // Given: mock expects "temperature" field with default value 0
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(BodyContains("name=\"temperature\"".to_string()))
+            .and(BodyContains("\r\n\r\n0\r\n".to_string()))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": "test" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
+
+        // Then
+        assert_eq!(result.unwrap(), "test");
+    }
+
+    #[tokio::test]
+    async fn transcribe_should_pass_prompt_param() {
+        // Given: mock expects "prompt" field with vocabulary text
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(BodyContains("name=\"prompt\"".to_string()))
+            .and(BodyContains("\r\n\r\nAcmeCloud, kubectl\r\n".to_string()))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": "test" })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client
+            .do_transcribe(&make_test_audio(), None, Some("AcmeCloud, kubectl"))
+            .await;
+
+        // Then
+        assert_eq!(result.unwrap(), "test");
+    }
+
+    #[tokio::test]
+    async fn transcribe_should_omit_empty_prompt() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(BodyContains("name=\"prompt\"".to_string()))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "text": "ok" })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_test_client(&server.uri()).await;
+
+        // When
+        let result = client
+            .do_transcribe(&make_test_audio(), None, Some("  "))
+            .await;
+
+        // Then
+        assert_eq!(result.unwrap(), "ok");
+    }
+
     #[tokio::test]
     async fn transcribe_should_timeout_on_slow_response() {
         // Given: server delays response longer than read_timeout
@@ -593,6 +1120,7 @@ mod integration_tests {
             &server.uri(),
             "test-api-key",
             "gpt-4o-mini-transcribe",
+            0.0,
             Duration::from_secs(5),
             Duration::from_millis(200), // very short read timeout
             0,                          // no retries
@@ -600,7 +1128,7 @@ mod integration_tests {
         .unwrap();
 
         // When
-        let result = client.do_transcribe(&make_test_audio(), None).await;
+        let result = client.do_transcribe(&make_test_audio(), None, None).await;
 
         // Then
         assert!(matches!(result.unwrap_err(), SttError::Timeout));
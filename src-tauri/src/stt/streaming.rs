@@ -0,0 +1,579 @@
+//! Потоковая транскрипция: чанки отправляются в STT по мере записи.
+//!
+//! Границы чанков совпадают с `chunk_audio`, поэтому результат склейки
+//! тот же, что и при транскрипции всего буфера после остановки.
+//! `StreamingSession` связывает чанкер с буфером идущей записи.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+use super::{
+    chunk_audio, deduplicate_overlap_texts, AudioChunk, ChunkingConfig, Result, SttError,
+    SttProvider, DEFAULT_MAX_CHUNK_SEC, DEFAULT_MAX_CONCURRENT_STT_CHUNKS,
+};
+use crate::audio::encode::{encode_audio, EncodeConfig};
+use crate::audio::preprocess::{resample, to_mono, TARGET_SAMPLE_RATE};
+use crate::audio::CaptureFormat;
+use crate::config::schema::AppConfig;
+
+/// Накопитель PCM, выдающий готовые чанки во время записи.
+pub struct StreamingChunker {
+    sample_rate: u32,
+    max_chunk_sec: u32,
+    chunking: ChunkingConfig,
+    pending: Vec<f32>,
+}
+
+impl StreamingChunker {
+    pub fn new(sample_rate: u32, max_chunk_sec: u32) -> Self {
+        Self {
+            sample_rate,
+            max_chunk_sec: max_chunk_sec.max(1),
+            chunking: ChunkingConfig::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Задает параметры нарезки (те же, что передаются в `transcribe_audio`).
+    pub fn with_chunking(mut self, chunking: ChunkingConfig) -> Self {
+        self.chunking = chunking;
+        self
+    }
+
+    /// Добавляет сэмплы и возвращает чанки, граница которых уже определена.
+    ///
+    /// Чанк выдается, только когда после окна разреза накоплено не меньше
+    /// `min_chunk_sec` - тогда `chunk_audio` не склеит его с хвостом.
+    pub fn push(&mut self, samples: &[f32]) -> Vec<AudioChunk> {
+        self.pending.extend_from_slice(samples);
+
+        let rate = self.sample_rate as usize;
+        let max_chunk_samples = self.max_chunk_sec as usize * rate;
+        let min_chunk_samples = (self.chunking.min_chunk_sec * self.sample_rate as f32) as usize;
+        let overlap_samples = (self.chunking.overlap_sec * self.sample_rate as f32) as usize;
+
+        let mut ready = Vec::new();
+        while rate > 0 && self.pending.len() > max_chunk_samples + min_chunk_samples {
+            let window = &self.pending[..max_chunk_samples + min_chunk_samples + 1];
+            let Some(first) =
+                chunk_audio(window, self.sample_rate, self.max_chunk_sec, &self.chunking)
+                    .into_iter()
+                    .next()
+            else {
+                break;
+            };
+
+            let next_offset = first.samples.len().saturating_sub(overlap_samples);
+            self.pending.drain(..next_offset);
+            ready.push(first);
+        }
+        ready
+    }
+
+    /// Завершает запись: возвращает оставшиеся чанки (с учетом правила хвоста).
+    pub fn finish(self) -> Vec<AudioChunk> {
+        if self.pending.is_empty() {
+            return Vec::new();
+        }
+        chunk_audio(
+            &self.pending,
+            self.sample_rate,
+            self.max_chunk_sec,
+            &self.chunking,
+        )
+    }
+}
+
+/// Параметры потоковой транскрипции; `None` - значение по умолчанию.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamingOptions {
+    /// Язык записи (ISO-639-1); `None` - автоопределение.
+    pub language: Option<String>,
+    /// Подсказка для STT (имена, термины).
+    pub prompt: Option<String>,
+    /// Сколько чанков распознается параллельно,
+    /// по умолчанию `DEFAULT_MAX_CONCURRENT_STT_CHUNKS`.
+    pub max_concurrent: Option<u32>,
+    /// Параметры кодирования чанков.
+    pub encode: EncodeConfig,
+}
+
+/// Запускает транскрипцию чанков по мере их поступления из `chunks`.
+///
+/// Чанки кодируются и отправляются сразу (до `max_concurrent` параллельно).
+/// В возвращаемый канал приходит склеенный текст всех готовых по порядку чанков
+/// после каждого продвижения; последнее сообщение - полный текст.
+/// При ошибке отправляется `Err` и транскрипция прекращается.
+/// Если получатель результатов закрыт, незавершенные запросы отменяются.
+pub fn start_streaming_transcription<P: SttProvider + 'static>(
+    provider: Arc<P>,
+    mut chunks: mpsc::UnboundedReceiver<AudioChunk>,
+    sample_rate: u32,
+    options: StreamingOptions,
+) -> mpsc::UnboundedReceiver<Result<String>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let StreamingOptions {
+            language,
+            prompt,
+            max_concurrent,
+            encode,
+        } = options;
+        let concurrent = max_concurrent
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_STT_CHUNKS)
+            .max(1) as usize;
+        let semaphore = Arc::new(Semaphore::new(concurrent));
+        let mut join_set: JoinSet<Result<(usize, String)>> = JoinSet::new();
+        let mut done: BTreeMap<usize, String> = BTreeMap::new();
+        let mut texts: Vec<String> = Vec::new();
+        let mut next_index = 0;
+        let mut input_open = true;
+
+        loop {
+            tokio::select! {
+                _ = tx.closed(), if input_open || !join_set.is_empty() => {
+                    tracing::info!("streaming transcription dropped, aborting");
+                    join_set.abort_all();
+                    return;
+                }
+                chunk = chunks.recv(), if input_open => match chunk {
+                    Some(chunk) => {
+                        let idx = next_index;
+                        next_index += 1;
+                        let encoded =
+                            match encode_audio(&chunk.samples, sample_rate, &encode) {
+                                Ok(encoded) => encoded,
+                                Err(e) => {
+                                    let _ = tx.send(Err(SttError::EncodingFailed(e.to_string())));
+                                    join_set.abort_all();
+                                    return;
+                                }
+                            };
+                        let provider = Arc::clone(&provider);
+                        let sem = Arc::clone(&semaphore);
+                        let lang = language.clone();
+                        let prompt = prompt.clone();
+                        join_set.spawn(async move {
+                            let _permit = sem
+                                .acquire()
+                                .await
+                                .map_err(|_| SttError::Network("semaphore closed".to_string()))?;
+                            tracing::debug!("streaming chunk {idx} started upload");
+                            let text = provider
+                                .transcribe(&encoded, lang.as_deref(), prompt.as_deref())
+                                .await?;
+                            Ok((idx, text.trim().to_string()))
+                        });
+                    }
+                    None => input_open = false,
+                },
+                Some(joined) = join_set.join_next(), if !join_set.is_empty() => {
+                    let (idx, text) = match joined {
+                        Ok(Ok(item)) => item,
+                        Ok(Err(e)) => {
+                            let _ = tx.send(Err(e));
+                            join_set.abort_all();
+                            return;
+                        }
+                        Err(join_err) => {
+                            let _ = tx.send(Err(SttError::Network(format!(
+                                "task failed: {join_err}"
+                            ))));
+                            join_set.abort_all();
+                            return;
+                        }
+                    };
+                    done.insert(idx, text);
+
+                    // Продвигаемся только по непрерывному префиксу чанков
+                    let before = texts.len();
+                    while let Some(text) = done.remove(&texts.len()) {
+                        texts.push(text);
+                    }
+                    if texts.len() > before {
+                        let non_empty: Vec<String> =
+                            texts.iter().filter(|t| !t.is_empty()).cloned().collect();
+                        if tx.send(Ok(deduplicate_overlap_texts(&non_empty))).is_err() {
+                            join_set.abort_all();
+                            return;
+                        }
+                    }
+                }
+                else => break,
+            }
+        }
+
+        tracing::info!(chunks = next_index, "streaming transcription complete");
+    });
+
+    rx
+}
+
+/// Потоковая транскрипция идущей записи.
+///
+/// Получает interleaved-сэмплы из буфера захвата порциями (как `AutoStopMonitor`),
+/// приводит их к 16 kHz mono и отправляет готовые чанки в STT, не дожидаясь
+/// остановки. Drop сессии отменяет незавершенные запросы.
+pub struct StreamingSession {
+    chunker: StreamingChunker,
+    /// Сколько сэмплов буфера записи уже передано в чанкер.
+    consumed: usize,
+    chunks: mpsc::UnboundedSender<AudioChunk>,
+    results: mpsc::UnboundedReceiver<Result<String>>,
+}
+
+impl StreamingSession {
+    /// Запускает сессию; вызывать внутри tokio runtime.
+    pub fn start<P: SttProvider + 'static>(
+        provider: Arc<P>,
+        chunking: ChunkingConfig,
+        options: StreamingOptions,
+    ) -> Self {
+        let (chunks, chunk_rx) = mpsc::unbounded_channel();
+        let results =
+            start_streaming_transcription(provider, chunk_rx, TARGET_SAMPLE_RATE, options);
+        Self {
+            chunker: StreamingChunker::new(TARGET_SAMPLE_RATE, DEFAULT_MAX_CHUNK_SEC)
+                .with_chunking(chunking),
+            consumed: 0,
+            chunks,
+            results,
+        }
+    }
+
+    /// Параметры потоковой транскрипции из конфига (язык, prompt, нарезка, кодирование).
+    pub fn start_from_config<P: SttProvider + 'static>(
+        provider: Arc<P>,
+        config: &AppConfig,
+    ) -> Self {
+        let chunking = ChunkingConfig::from_config(config);
+        let options = StreamingOptions {
+            language: Some(config.language.clone()).filter(|lang| lang != "auto"),
+            prompt: Some(config.stt_prompt.trim().to_string()).filter(|p| !p.is_empty()),
+            max_concurrent: config.stt_chain_chunk_prompts.then_some(1),
+            encode: chunking.encode,
+        };
+        Self::start(provider, chunking, options)
+    }
+
+    /// Сколько сэмплов буфера записи уже обработано (смещение для следующей порции).
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Обрабатывает новую порцию interleaved-сэмплов записи.
+    pub fn feed(&mut self, samples: &[f32], format: &CaptureFormat) {
+        let channels = format.channels.max(1) as usize;
+        let whole = samples.len() - samples.len() % channels;
+        self.consumed += whole;
+
+        let mono = to_mono(&samples[..whole], format.channels);
+        let resampled = resample(&mono, format.sample_rate, TARGET_SAMPLE_RATE);
+        for chunk in self.chunker.push(&resampled) {
+            // Задача транскрипции завершилась с ошибкой - ее вернет `finish`
+            let _ = self.chunks.send(chunk);
+        }
+    }
+
+    /// Запись остановлена: дописывает необработанный хвост `recording`
+    /// и возвращает полный склеенный текст.
+    pub async fn finish(mut self, recording: &[f32], format: &CaptureFormat) -> Result<String> {
+        if let Some(rest) = recording.get(self.consumed..) {
+            self.feed(rest, format);
+        }
+
+        let Self {
+            chunker,
+            chunks,
+            mut results,
+            ..
+        } = self;
+        for chunk in chunker.finish() {
+            let _ = chunks.send(chunk);
+        }
+        drop(chunks);
+
+        let mut text = String::new();
+        while let Some(update) = results.recv().await {
+            text = update?;
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::UploadFormat;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    const RATE: u32 = 1000;
+
+    /// Сигнал с тихими участками: чанкер найдет разные точки разреза.
+    fn speech_like(seconds: usize) -> Vec<f32> {
+        (0..seconds * RATE as usize)
+            .map(|i| {
+                let t = i as f32 / RATE as f32;
+                let envelope = if (t as usize) % 7 == 6 { 0.01 } else { 0.5 };
+                envelope * (t * 40.0).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chunker_should_match_chunk_audio_boundaries() {
+        // Given
+        let samples = speech_like(95);
+        let expected = chunk_audio(&samples, RATE, 30, &ChunkingConfig::default());
+
+        // When: подаем порциями по 250мс, как из callback записи
+        let mut chunker = StreamingChunker::new(RATE, 30);
+        let mut chunks: Vec<AudioChunk> = samples
+            .chunks(250)
+            .flat_map(|part| chunker.push(part))
+            .collect();
+        chunks.extend(chunker.finish());
+
+        // Then
+        assert_eq!(chunks.len(), expected.len());
+        for (got, want) in chunks.iter().zip(&expected) {
+            assert_eq!(got.samples, want.samples);
+        }
+    }
+
+    #[test]
+    fn chunker_should_emit_first_chunk_before_recording_ends() {
+        // Given
+        let mut chunker = StreamingChunker::new(RATE, 30);
+
+        // When: 36 секунд - больше окна и минимального хвоста
+        let ready = chunker.push(&speech_like(36));
+
+        // Then
+        assert_eq!(ready.len(), 1);
+    }
+
+    #[test]
+    fn chunker_should_return_nothing_for_empty_recording() {
+        assert!(StreamingChunker::new(RATE, 30).finish().is_empty());
+    }
+
+    /// Провайдер, отвечающий текстом по размеру аудио: короткий чанк - первый текст.
+    struct SizedStub {
+        split_bytes: usize,
+    }
+
+    impl SttProvider for SizedStub {
+        async fn transcribe(
+            &self,
+            audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> Result<String> {
+            if audio.len() < self.split_bytes {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                Ok("hello big world".to_string())
+            } else {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok("big world again".to_string())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn streaming_should_report_progressive_stitched_text() {
+        // Given: первый чанк 1с, второй 2с
+        let split_bytes = crate::audio::encode::encode_ogg_opus(&[0.0; 24_000], 16_000)
+            .unwrap()
+            .len();
+        let provider = Arc::new(SizedStub { split_bytes });
+        let (tx, rx) = mpsc::unbounded_channel();
+        let options = StreamingOptions {
+            max_concurrent: Some(2),
+            ..StreamingOptions::default()
+        };
+        let mut results = start_streaming_transcription(provider, rx, 16_000, options);
+
+        // When
+        for seconds in [1, 2] {
+            tx.send(AudioChunk {
+                samples: vec![0.0; 16_000 * seconds],
+            })
+            .unwrap();
+        }
+        drop(tx);
+
+        let mut updates = Vec::new();
+        while let Some(update) = results.recv().await {
+            updates.push(update.unwrap());
+        }
+
+        // Then: сначала текст первого чанка, затем склейка без дубля overlap
+        assert_eq!(updates, vec!["hello big world", "hello big world again"]);
+    }
+
+    struct FailingStub;
+
+    impl SttProvider for FailingStub {
+        async fn transcribe(
+            &self,
+            _audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> Result<String> {
+            Err(SttError::AuthFailed)
+        }
+    }
+
+    #[tokio::test]
+    async fn streaming_should_report_error_and_stop() {
+        // Given
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut results = start_streaming_transcription(
+            Arc::new(FailingStub),
+            rx,
+            16_000,
+            StreamingOptions::default(),
+        );
+
+        // When
+        tx.send(AudioChunk {
+            samples: vec![0.0; 16_000],
+        })
+        .unwrap();
+
+        // Then
+        assert!(matches!(
+            results.recv().await,
+            Some(Err(SttError::AuthFailed))
+        ));
+        assert!(results.recv().await.is_none());
+    }
+
+    /// Провайдер для WAV-чанков: отвечает по длительности и считает запросы.
+    struct CountingStub {
+        calls: AtomicUsize,
+    }
+
+    impl SttProvider for CountingStub {
+        async fn transcribe(
+            &self,
+            audio: &[u8],
+            _language: Option<&str>,
+            _prompt: Option<&str>,
+        ) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            // 16 kHz, 16 бит: больше 20 секунд - полный первый чанк
+            if audio.len() > 20 * 16_000 * 2 {
+                Ok("first part".to_string())
+            } else {
+                Ok("second part".to_string())
+            }
+        }
+    }
+
+    fn wav_session<P: SttProvider + 'static>(provider: Arc<P>) -> StreamingSession {
+        let encode = EncodeConfig {
+            format: UploadFormat::Wav,
+            ..EncodeConfig::default()
+        };
+        StreamingSession::start(
+            provider,
+            ChunkingConfig {
+                encode,
+                ..ChunkingConfig::default()
+            },
+            StreamingOptions {
+                encode,
+                ..StreamingOptions::default()
+            },
+        )
+    }
+
+    /// Стерео 48 kHz с единственной паузой на 25-й секунде - там пройдет разрез.
+    fn stereo_tone(seconds: usize) -> Vec<f32> {
+        let pause = 24_900 * 48..25_100 * 48;
+        (0..seconds * 48_000)
+            .flat_map(|i| {
+                let s = if pause.contains(&i) {
+                    0.0
+                } else {
+                    0.3 * (i as f32 * 0.05).sin()
+                };
+                [s, s]
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn session_should_upload_chunks_while_recording() {
+        // Given
+        let provider = Arc::new(CountingStub {
+            calls: AtomicUsize::new(0),
+        });
+        let mut session = wav_session(Arc::clone(&provider));
+        let format = CaptureFormat {
+            sample_rate: 48_000,
+            channels: 2,
+        };
+        let recording = stereo_tone(40);
+
+        // When: 36 секунд записи читаются из буфера порциями по ~100мс
+        // (нечетная длина - неполный кадр дочитывается со следующей порцией)
+        let recorded = 36 * 48_000 * 2;
+        while session.consumed() < recorded {
+            let end = (session.consumed() + 9_601).min(recorded);
+            session.feed(&recording[session.consumed()..end], &format);
+        }
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while provider.calls.load(Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("first chunk should be uploaded before stop");
+
+        let text = session.finish(&recording, &format).await.unwrap();
+
+        // Then
+        assert_eq!(text, "first part second part");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn session_should_return_provider_error_on_finish() {
+        // Given
+        let session = wav_session(Arc::new(FailingStub));
+        let format = CaptureFormat {
+            sample_rate: 16_000,
+            channels: 1,
+        };
+
+        // When
+        let result = session.finish(&[0.1; 16_000], &format).await;
+
+        // Then
+        assert!(matches!(result, Err(SttError::AuthFailed)));
+    }
+
+    #[tokio::test]
+    async fn session_should_return_empty_text_for_empty_recording() {
+        // Given
+        let session = wav_session(Arc::new(FailingStub));
+        let format = CaptureFormat {
+            sample_rate: 16_000,
+            channels: 1,
+        };
+
+        // When
+        let text = session.finish(&[], &format).await.unwrap();
+
+        // Then
+        assert!(text.is_empty());
+    }
+}
@@ -6,16 +6,22 @@ use tauri::tray::TrayIconBuilder;
 use tauri::{AppHandle, Manager, Runtime};
 
 use crate::config::schema::RecordingMode;
+use crate::notifications;
+use crate::paste::ClipboardManager;
 use crate::state::{AppEvent, AppState, SharedAppState};
 
 const TRAY_ID: &str = "main";
 const ICON_SIZE: u32 = 32;
 
+/// Длина превью последнего результата в пункте меню (символов).
+const LAST_RESULT_PREVIEW_CHARS: usize = 30;
+
 // Кэшированные RGBA-данные иконок (генерируются один раз при первом доступе)
 static ICON_IDLE: LazyLock<Vec<u8>> = LazyLock::new(|| generate_circle_rgba(128, 128, 128));
 static ICON_RECORDING: LazyLock<Vec<u8>> = LazyLock::new(|| generate_circle_rgba(220, 50, 50));
 static ICON_PROCESSING: LazyLock<Vec<u8>> = LazyLock::new(|| generate_circle_rgba(50, 120, 220));
 static ICON_ERROR: LazyLock<Vec<u8>> = LazyLock::new(|| generate_circle_rgba(255, 140, 0));
+static ICON_PAUSED: LazyLock<Vec<u8>> = LazyLock::new(|| generate_circle_rgba(230, 190, 40));
 
 /// Создает tray-иконку с начальным меню для состояния Idle.
 pub fn create_tray<R: Runtime>(
@@ -77,11 +83,45 @@ fn build_menu<R: Runtime>(
                 None::<&str>,
             )?;
             builder = builder.item(&start);
+            if let Some(text) = app.state::<SharedAppState>().last_result() {
+                let copy = MenuItem::with_id(
+                    app,
+                    "copy_last_result",
+                    last_result_label(&text),
+                    true,
+                    None::<&str>,
+                )?;
+                builder = builder.item(&copy);
+            }
         }
         AppState::Recording => {
+            // Пауза доступна только в toggle-режиме
+            let toggle_mode =
+                app.state::<SharedAppState>().recording_mode() == RecordingMode::Toggle;
+            let pause = MenuItem::with_id(
+                app,
+                "pause_recording",
+                "Pause Recording",
+                toggle_mode,
+                None::<&str>,
+            )?;
             let stop =
                 MenuItem::with_id(app, "stop_recording", "Stop Recording", true, None::<&str>)?;
-            builder = builder.item(&stop);
+            builder = builder.item(&pause).item(&stop);
+        }
+        AppState::Paused => {
+            let resume = MenuItem::with_id(
+                app,
+                "resume_recording",
+                "Resume Recording",
+                true,
+                None::<&str>,
+            )?;
+            let stop =
+                MenuItem::with_id(app, "stop_recording", "Stop Recording", true, None::<&str>)?;
+            let discard =
+                MenuItem::with_id(app, "cancel", "Discard Recording", true, None::<&str>)?;
+            builder = builder.item(&resume).item(&stop).item(&discard);
         }
         AppState::Transcribing | AppState::Enhancing | AppState::Pasting => {
             let cancel = MenuItem::with_id(app, "cancel", "Cancel Processing", true, None::<&str>)?;
@@ -115,21 +155,14 @@ fn build_menu<R: Runtime>(
 fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, menu_id: &str) {
     match menu_id {
         "start_recording" => {
-            let shared = app.state::<SharedAppState>();
-            let event = match shared.recording_mode() {
-                RecordingMode::Toggle => AppEvent::HotkeyPressed,
-                RecordingMode::PushToTalk => AppEvent::HotkeyDown,
-            };
-            crate::dispatch_and_update(app, event);
+            crate::start_dictation(app);
         }
         "stop_recording" => {
-            let shared = app.state::<SharedAppState>();
-            let event = match shared.recording_mode() {
-                RecordingMode::Toggle => AppEvent::HotkeyPressed,
-                RecordingMode::PushToTalk => AppEvent::HotkeyUp,
-            };
-            crate::dispatch_and_update(app, event);
+            crate::stop_dictation(app);
         }
+        "copy_last_result" => copy_last_result(app),
+        "pause_recording" => crate::dispatch_and_update(app, AppEvent::Pause),
+        "resume_recording" => crate::dispatch_and_update(app, AppEvent::Resume),
         "cancel" => crate::dispatch_and_update(app, AppEvent::Cancel),
         "dismiss_error" => crate::dispatch_and_update(app, AppEvent::ErrorAcknowledged),
         "settings" => {
@@ -145,22 +178,60 @@ fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, menu_id: &str) {
     }
 }
 
+/// Копирует последний распознанный текст в clipboard.
+fn copy_last_result<R: Runtime>(app: &AppHandle<R>) {
+    let Some(text) = app.state::<SharedAppState>().last_result() else {
+        tracing::warn!("no last result to copy");
+        return;
+    };
+    match ClipboardManager::new().and_then(|mut clipboard| clipboard.write(&text)) {
+        Ok(()) => notifications::notify_info(app, "Last result copied to clipboard"),
+        Err(e) => {
+            tracing::error!(error = %e, "failed to copy last result");
+            notifications::notify_error(app, &format!("Failed to copy last result: {e}"));
+        }
+    }
+}
+
+/// Подпись пункта меню с коротким превью текста в одну строку.
+fn last_result_label(text: &str) -> String {
+    let preview = notifications::text_preview(text, LAST_RESULT_PREVIEW_CHARS);
+    format!("Copy last result: \"{preview}\"")
+}
+
 /// Возвращает иконку для указанного состояния.
 fn icon_for_state(state: AppState) -> Image<'static> {
     let data: &[u8] = match state {
         AppState::Idle => &ICON_IDLE,
         AppState::Recording => &ICON_RECORDING,
+        AppState::Paused => &ICON_PAUSED,
         AppState::Transcribing | AppState::Enhancing | AppState::Pasting => &ICON_PROCESSING,
         AppState::Error => &ICON_ERROR,
     };
     Image::new(data, ICON_SIZE, ICON_SIZE)
 }
 
+/// Текст tooltip для зафиксированной PTT-записи.
+const TOOLTIP_RECORDING_LOCKED: &str = "VoiceDictator - Recording (locked)";
+
+/// Показывает в tooltip, что PTT-запись зафиксирована (tap-to-lock).
+///
+/// Сбрасывается обычным `update_tray` при следующей смене состояния.
+pub fn show_recording_locked<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Err(e) = tray.set_tooltip(Some(TOOLTIP_RECORDING_LOCKED)) {
+        tracing::error!(error = %e, "failed to update tray tooltip");
+    }
+}
+
 /// Возвращает текст tooltip для указанного состояния.
 fn tooltip_for_state(state: AppState) -> &'static str {
     match state {
         AppState::Idle => "VoiceDictator - Idle",
         AppState::Recording => "VoiceDictator - Recording",
+        AppState::Paused => "VoiceDictator - Paused",
         AppState::Transcribing => "VoiceDictator - Transcribing",
         AppState::Enhancing => "VoiceDictator - Enhancing",
         AppState::Pasting => "VoiceDictator - Pasting",
@@ -199,6 +270,30 @@ fn generate_circle_rgba(r: u8, g: u8, b: u8) -> Vec<u8> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn last_result_label_should_show_short_text_fully() {
+        assert_eq!(
+            last_result_label("Hello,\nworld"),
+            "Copy last result: \"Hello, world\""
+        );
+    }
+
+    #[test]
+    fn last_result_label_should_truncate_long_text() {
+        // Given
+        let text = "а".repeat(LAST_RESULT_PREVIEW_CHARS + 10);
+
+        // When
+        let label = last_result_label(&text);
+
+        // Then
+        let expected = format!(
+            "Copy last result: \"{}...\"",
+            "а".repeat(LAST_RESULT_PREVIEW_CHARS)
+        );
+        assert_eq!(label, expected);
+    }
+
     #[test]
     fn tooltip_should_include_app_name_and_state() {
         assert_eq!(tooltip_for_state(AppState::Idle), "VoiceDictator - Idle");
@@ -309,6 +404,7 @@ mod tests {
         let states = [
             AppState::Idle,
             AppState::Recording,
+            AppState::Paused,
             AppState::Transcribing,
             AppState::Enhancing,
             AppState::Pasting,
@@ -0,0 +1,193 @@
+//! Авто-стоп записи по тишине в toggle-режиме.
+//!
+//! Монитор получает новые сэмплы записи порциями, приводит их к 16 kHz mono,
+//! режет на кадры VAD и прогоняет через `SilenceDetector`.
+
+use super::{
+    frames_for_ms, AnyVad, Result, SilenceClock, SilenceDetector, SilenceStatus, VoiceDetector,
+    VAD_FRAME_DURATION, VAD_FRAME_SIZE,
+};
+use crate::audio::preprocess::{resample, to_mono, TARGET_SAMPLE_RATE};
+use crate::audio::CaptureFormat;
+use crate::config::schema::{AppConfig, RecordingMode, SilenceClockMode};
+
+/// Следит за тишиной в идущей записи.
+pub struct AutoStopMonitor<V: VoiceDetector> {
+    detector: SilenceDetector<V>,
+    /// Сколько сэмплов буфера записи уже обработано.
+    consumed: usize,
+    /// Сэмплы 16 kHz, не набравшие полный кадр.
+    pending: Vec<f32>,
+}
+
+impl AutoStopMonitor<AnyVad> {
+    /// Создает монитор по конфигу; `None`, если авто-стоп выключен или режим PTT.
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        if !config.vad_auto_stop || config.recording_mode != RecordingMode::Toggle {
+            return None;
+        }
+
+        let clock = match config.vad_silence_clock {
+            SilenceClockMode::WallClock => SilenceClock::WallClock,
+            SilenceClockMode::AudioDuration => SilenceClock::AudioDuration(VAD_FRAME_DURATION),
+        };
+        let detector =
+            SilenceDetector::new(super::create_vad(config), config.vad_silence_threshold_sec)
+                .with_clock(clock)
+                .with_smoothing(
+                    frames_for_ms(config.vad_speech_onset_ms),
+                    frames_for_ms(config.vad_hangover_ms),
+                );
+        Some(Self::new(detector))
+    }
+}
+
+impl<V: VoiceDetector> AutoStopMonitor<V> {
+    pub fn new(detector: SilenceDetector<V>) -> Self {
+        Self {
+            detector,
+            consumed: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Сколько сэмплов буфера записи уже обработано (смещение для следующей порции).
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Обрабатывает новую порцию interleaved-сэмплов записи.
+    ///
+    /// Возвращает `true`, если тишина превысила порог и запись нужно остановить.
+    pub fn feed(&mut self, samples: &[f32], format: &CaptureFormat) -> Result<bool> {
+        let channels = format.channels.max(1) as usize;
+        let whole = samples.len() - samples.len() % channels;
+        self.consumed += whole;
+
+        let mono = to_mono(&samples[..whole], format.channels);
+        self.pending
+            .extend(resample(&mono, format.sample_rate, TARGET_SAMPLE_RATE));
+
+        let mut timed_out = false;
+        let full = self.pending.len() - self.pending.len() % VAD_FRAME_SIZE;
+        for frame in self.pending[..full].chunks(VAD_FRAME_SIZE) {
+            if self.detector.process_frame(frame)? == SilenceStatus::SilenceTimeout {
+                timed_out = true;
+                break;
+            }
+        }
+        self.pending.drain(..full);
+        Ok(timed_out)
+    }
+
+    /// Запись на паузе: таймер тишины не идет.
+    pub fn pause(&mut self) {
+        self.detector.pause();
+    }
+
+    /// Запись продолжена.
+    pub fn resume(&mut self) {
+        self.detector.resume();
+    }
+
+    /// Запись закончилась: сбрасывает детектор и недобранный кадр.
+    ///
+    /// Сброс VAD пишет в лог сводку вероятностей за запись
+    /// (`vad_debug_probabilities`, silero).
+    pub fn finish(&mut self) {
+        self.detector.reset();
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vad::EnergyVad;
+
+    fn format(sample_rate: u32, channels: u16) -> CaptureFormat {
+        CaptureFormat {
+            sample_rate,
+            channels,
+        }
+    }
+
+    fn monitor(threshold_sec: f32) -> AutoStopMonitor<EnergyVad> {
+        AutoStopMonitor::new(
+            SilenceDetector::new(EnergyVad::new(0.01), threshold_sec)
+                .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION)),
+        )
+    }
+
+    #[test]
+    fn monitor_should_stop_after_silence_threshold() {
+        // Given: порог 0.3с, стерео 48 kHz
+        let mut monitor = monitor(0.3);
+        let speech = vec![0.5; 48_000 * 2 / 10];
+        let silence = vec![0.0; 48_000 * 2 / 10];
+
+        // When
+        let after_speech = monitor.feed(&speech, &format(48_000, 2)).unwrap();
+        let first_silence = monitor.feed(&silence, &format(48_000, 2)).unwrap();
+        let second_silence = monitor.feed(&silence, &format(48_000, 2)).unwrap();
+
+        // Then
+        assert!(!after_speech);
+        assert!(!first_silence);
+        assert!(second_silence);
+    }
+
+    #[test]
+    fn monitor_should_count_only_whole_frames_of_samples() {
+        // Given
+        let mut monitor = monitor(10.0);
+
+        // When: последний неполный стерео-фрейм не учитывается
+        monitor.feed(&[0.0; 101], &format(16_000, 2)).unwrap();
+
+        // Then
+        assert_eq!(monitor.consumed(), 100);
+    }
+
+    #[test]
+    fn monitor_should_not_stop_while_paused() {
+        // Given
+        let mut monitor = monitor(0.0);
+
+        // When
+        monitor.pause();
+        let stopped = monitor.feed(&[0.0; 16_000], &format(16_000, 1)).unwrap();
+
+        // Then
+        assert!(!stopped);
+    }
+
+    #[test]
+    fn finish_should_reset_silence_timer_and_pending_samples() {
+        // Given: тишина чуть меньше порога и неполный кадр
+        let mut monitor = monitor(0.3);
+        monitor.feed(&[0.0; 4_500], &format(16_000, 1)).unwrap();
+        assert!(!monitor.pending.is_empty());
+
+        // When
+        monitor.finish();
+        let stopped = monitor.feed(&[0.0; 1_600], &format(16_000, 1)).unwrap();
+
+        // Then: отсчет тишины начался заново
+        assert!(!stopped);
+    }
+
+    #[test]
+    fn from_config_should_skip_push_to_talk_and_disabled_auto_stop() {
+        let ptt = AppConfig {
+            recording_mode: RecordingMode::PushToTalk,
+            ..AppConfig::default()
+        };
+        let disabled = AppConfig {
+            vad_auto_stop: false,
+            ..AppConfig::default()
+        };
+        assert!(AutoStopMonitor::from_config(&ptt).is_none());
+        assert!(AutoStopMonitor::from_config(&disabled).is_none());
+    }
+}
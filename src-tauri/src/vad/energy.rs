@@ -0,0 +1,56 @@
+use super::{Result, VoiceDetector};
+use crate::audio::preprocess::calculate_energy;
+
+/// Простой VAD по RMS энергии кадра.
+///
+/// Используется как запасной вариант, если модель Silero не загрузилась
+/// (нет ONNX Runtime): хуже отличает речь от шума, но auto-stop продолжает работать.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnergyVad {
+    threshold: f32,
+}
+
+impl EnergyVad {
+    /// Создает VAD с порогом RMS: кадры с энергией не ниже порога считаются речью.
+    pub fn new(threshold: f32) -> Self {
+        Self { threshold }
+    }
+}
+
+impl VoiceDetector for EnergyVad {
+    fn is_speech(&mut self, frame: &[f32]) -> Result<bool> {
+        Ok(calculate_energy(frame) >= self.threshold)
+    }
+
+    fn reset(&mut self) {
+        // Состояния между кадрами нет
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vad::VAD_FRAME_SIZE;
+
+    #[test]
+    fn energy_vad_should_detect_loud_frame_as_speech() {
+        // Given
+        let mut vad = EnergyVad::new(0.01);
+        let frame: Vec<f32> = (0..VAD_FRAME_SIZE)
+            .map(|i| 0.3 * (i as f32 * 0.1).sin())
+            .collect();
+
+        // When / Then
+        assert!(vad.is_speech(&frame).unwrap());
+    }
+
+    #[test]
+    fn energy_vad_should_treat_quiet_frame_as_silence() {
+        // Given
+        let mut vad = EnergyVad::new(0.01);
+        let frame = vec![0.001; VAD_FRAME_SIZE];
+
+        // When / Then
+        assert!(!vad.is_speech(&frame).unwrap());
+    }
+}
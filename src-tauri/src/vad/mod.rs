@@ -1,12 +1,40 @@
+pub mod auto_stop;
+pub mod energy;
 pub mod silero;
 
 use std::time::{Duration, Instant};
 
+use crate::config::schema::AppConfig;
+
+pub use self::auto_stop::AutoStopMonitor;
+pub use self::energy::EnergyVad;
 pub use self::silero::SileroVad;
 
 /// Размер кадра для Silero VAD v5 при 16kHz (32ms).
 pub const VAD_FRAME_SIZE: usize = 512;
 
+/// Длительность одного кадра VAD (512 samples при 16kHz).
+pub const VAD_FRAME_DURATION: Duration = Duration::from_millis(32);
+
+/// Порог вероятности речи по умолчанию (стандарт Silero).
+pub const DEFAULT_VAD_THRESHOLD: f32 = 0.5;
+
+/// Переводит длительность в миллисекундах в число кадров VAD (с округлением вверх).
+pub fn frames_for_ms(ms: u32) -> usize {
+    ms.div_ceil(VAD_FRAME_DURATION.as_millis() as u32) as usize
+}
+
+/// Приводит порог вероятности речи к диапазону 0.0..1.0.
+///
+/// `NaN` и бесконечности заменяются дефолтом, как порог тишины в `SilenceDetector`.
+pub fn clamp_vad_threshold(threshold: f32) -> f32 {
+    if threshold.is_finite() {
+        threshold.clamp(0.0, 1.0)
+    } else {
+        DEFAULT_VAD_THRESHOLD
+    }
+}
+
 /// Ошибки VAD-модуля.
 #[derive(Debug, thiserror::Error)]
 pub enum VadError {
@@ -29,10 +57,152 @@ pub trait VoiceDetector {
     /// Определяет, содержит ли кадр аудио речь.
     fn is_speech(&mut self, frame: &[f32]) -> Result<bool>;
 
+    /// Возвращает вероятность речи в кадре (0.0..1.0).
+    ///
+    /// По умолчанию - 1.0 или 0.0 по результату `is_speech`; детекторы
+    /// с настоящей вероятностью (Silero) переопределяют метод.
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32> {
+        Ok(if self.is_speech(frame)? { 1.0 } else { 0.0 })
+    }
+
     /// Сбрасывает внутреннее состояние для нового аудио.
     fn reset(&mut self);
 }
 
+/// VAD, выбранный при запуске: Silero или запасной энергетический.
+pub enum AnyVad {
+    Silero(Box<SileroVad>),
+    Energy(EnergyVad),
+}
+
+impl VoiceDetector for AnyVad {
+    fn is_speech(&mut self, frame: &[f32]) -> Result<bool> {
+        match self {
+            Self::Silero(vad) => vad.is_speech(frame),
+            Self::Energy(vad) => vad.is_speech(frame),
+        }
+    }
+
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32> {
+        match self {
+            Self::Silero(vad) => vad.speech_probability(frame),
+            Self::Energy(vad) => vad.speech_probability(frame),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Self::Silero(vad) => vad.reset(),
+            Self::Energy(vad) => vad.reset(),
+        }
+    }
+}
+
+/// Создает VAD для авто-стопа: Silero, а если модель не загрузилась -
+/// `EnergyVad` с порогом `vad_energy_threshold`.
+pub fn create_vad(config: &AppConfig) -> AnyVad {
+    match SileroVad::from_config(config) {
+        Ok(vad) => AnyVad::Silero(Box::new(vad)),
+        Err(e) => {
+            tracing::warn!(error = %e, "Silero VAD unavailable, falling back to energy VAD");
+            AnyVad::Energy(EnergyVad::new(config.vad_energy_threshold))
+        }
+    }
+}
+
+/// Сводка вероятностей речи за запись (для отладки порога VAD).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbabilitySummary {
+    pub frames: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+/// Накопитель статистики вероятностей речи по кадрам.
+///
+/// Хранит только агрегаты, без истории кадров.
+#[derive(Debug, Default, Clone)]
+pub struct ProbabilityStats {
+    frames: usize,
+    min: f32,
+    max: f32,
+    sum: f64,
+}
+
+impl ProbabilityStats {
+    /// Добавляет вероятность очередного кадра.
+    pub fn push(&mut self, probability: f32) {
+        if self.frames == 0 {
+            self.min = probability;
+            self.max = probability;
+        } else {
+            self.min = self.min.min(probability);
+            self.max = self.max.max(probability);
+        }
+        self.sum += f64::from(probability);
+        self.frames += 1;
+    }
+
+    /// Возвращает сводку или `None`, если кадров не было.
+    pub fn summary(&self) -> Option<ProbabilitySummary> {
+        if self.frames == 0 {
+            return None;
+        }
+        Some(ProbabilitySummary {
+            frames: self.frames,
+            min: self.min,
+            max: self.max,
+            mean: (self.sum / self.frames as f64) as f32,
+        })
+    }
+
+    /// Сбрасывает накопленную статистику.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Размечает буфер 16 kHz на участки речи.
+///
+/// Скользит окнами по `VAD_FRAME_SIZE` сэмплов (последний неполный кадр
+/// дополняется нулями) и возвращает пары `(start, end)` индексов сэмплов
+/// непрерывной речи, `end` не включается. Паузы короче `min_gap_ms`
+/// склеиваются с соседними участками. Перед разметкой состояние VAD сбрасывается.
+pub fn segment_speech(
+    vad: &mut impl VoiceDetector,
+    samples: &[f32],
+    min_gap_ms: u32,
+) -> Result<Vec<(usize, usize)>> {
+    vad.reset();
+
+    let min_gap_samples = frames_for_ms(min_gap_ms) * VAD_FRAME_SIZE;
+    let mut regions: Vec<(usize, usize)> = Vec::new();
+    let mut padded = [0.0f32; VAD_FRAME_SIZE];
+
+    for (index, chunk) in samples.chunks(VAD_FRAME_SIZE).enumerate() {
+        let frame = if chunk.len() == VAD_FRAME_SIZE {
+            chunk
+        } else {
+            padded[..chunk.len()].copy_from_slice(chunk);
+            padded[chunk.len()..].fill(0.0);
+            &padded[..]
+        };
+        if !vad.is_speech(frame)? {
+            continue;
+        }
+
+        let start = index * VAD_FRAME_SIZE;
+        let end = start + chunk.len();
+        match regions.last_mut() {
+            Some(last) if start - last.1 < min_gap_samples || start == last.1 => last.1 = end,
+            _ => regions.push((start, end)),
+        }
+    }
+
+    Ok(regions)
+}
+
 /// Результат обработки кадра детектором тишины.
 #[derive(Debug, Clone, PartialEq)]
 pub enum SilenceStatus {
@@ -44,6 +214,26 @@ pub enum SilenceStatus {
     SilenceTimeout,
 }
 
+/// Способ измерения длительности тишины.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SilenceClock {
+    /// Реальное время между кадрами (`Instant::now()`).
+    WallClock,
+    /// Накопленная длительность аудио: каждый тихий кадр добавляет
+    /// указанную длительность. Не зависит от задержек доставки кадров.
+    AudioDuration(Duration),
+}
+
+/// Пороги гистерезиса по вероятности речи.
+///
+/// Речь начинается при вероятности `>= speech_on`, заканчивается при `< speech_off`;
+/// между порогами сохраняется предыдущее решение.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hysteresis {
+    pub speech_on: f32,
+    pub speech_off: f32,
+}
+
 /// Детектор тишины для auto-stop в toggle-режиме.
 ///
 /// Оборачивает `VoiceDetector` и отслеживает длительность тишины.
@@ -51,7 +241,23 @@ pub enum SilenceStatus {
 pub struct SilenceDetector<V: VoiceDetector> {
     vad: V,
     silence_start: Option<Instant>,
+    silent_audio: Duration,
     threshold: Duration,
+    clock: SilenceClock,
+    /// Момент постановки на паузу (таймер тишины не идет).
+    paused_at: Option<Instant>,
+    /// Гистерезис по вероятности; `None` - решение `is_speech`.
+    hysteresis: Option<Hysteresis>,
+    /// Предыдущее решение для гистерезиса.
+    in_speech: bool,
+    /// Сколько кадров речи подряд нужно, чтобы объявить `Speech`.
+    onset_frames: usize,
+    /// Сколько кадров тишины подряд после речи не запускают таймер тишины.
+    hangover_frames: usize,
+    /// Объявлена ли речь (с учетом onset/hangover).
+    speaking: bool,
+    speech_run: usize,
+    silence_run: usize,
 }
 
 impl<V: VoiceDetector> SilenceDetector<V> {
@@ -69,22 +275,95 @@ impl<V: VoiceDetector> SilenceDetector<V> {
         Self {
             vad,
             silence_start: None,
+            silent_audio: Duration::ZERO,
             threshold: Duration::from_secs_f32(safe_threshold),
+            clock: SilenceClock::WallClock,
+            paused_at: None,
+            hysteresis: None,
+            in_speech: false,
+            onset_frames: 1,
+            hangover_frames: 0,
+            speaking: false,
+            speech_run: 0,
+            silence_run: 0,
+        }
+    }
+
+    /// Задает способ измерения тишины (по умолчанию - реальное время).
+    pub fn with_clock(mut self, clock: SilenceClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Включает гистерезис по вероятности речи вместо бинарного `is_speech`.
+    ///
+    /// Пороги ограничиваются диапазоном 0.0..1.0; если `speech_off > speech_on`,
+    /// он приравнивается к `speech_on`.
+    pub fn with_hysteresis(mut self, speech_on: f32, speech_off: f32) -> Self {
+        let speech_on = speech_on.clamp(0.0, 1.0);
+        self.hysteresis = Some(Hysteresis {
+            speech_on,
+            speech_off: speech_off.clamp(0.0, speech_on),
+        });
+        self
+    }
+
+    /// Включает сглаживание решений VAD.
+    ///
+    /// `Speech` объявляется после `onset_frames` кадров речи подряд (минимум 1),
+    /// а после речи таймер тишины запускается только когда тишина длится
+    /// больше `hangover_frames` кадров. Одиночные кадры не сбрасывают
+    /// и не запускают таймер. По умолчанию сглаживания нет (1 и 0).
+    pub fn with_smoothing(mut self, onset_frames: usize, hangover_frames: usize) -> Self {
+        self.onset_frames = onset_frames.max(1);
+        self.hangover_frames = hangover_frames;
+        self
+    }
+
+    /// Останавливает таймер тишины на время паузы записи.
+    pub fn pause(&mut self) {
+        self.paused_at.get_or_insert_with(Instant::now);
+    }
+
+    /// Продолжает анализ после паузы; отсчет тишины начинается заново.
+    ///
+    /// Пауза обычно означает, что пользователь обдумывает текст: тишина до
+    /// нее не должна приближать авто-стоп после продолжения.
+    pub fn resume(&mut self) {
+        if self.paused_at.take().is_some() {
+            self.silence_start = None;
+            self.silent_audio = Duration::ZERO;
+            self.silence_run = 0;
         }
     }
 
     /// Обрабатывает один кадр аудио и возвращает статус.
+    ///
+    /// Во время паузы кадры не анализируются, таймер тишины не продвигается.
     pub fn process_frame(&mut self, frame: &[f32]) -> Result<SilenceStatus> {
-        let is_speech = self.vad.is_speech(frame)?;
+        if self.paused_at.is_some() {
+            return Ok(SilenceStatus::Silence(self.current_silence()));
+        }
 
-        if is_speech {
+        let is_speech = self.detect_speech(frame)?;
+
+        if self.smooth(is_speech) {
             self.silence_start = None;
+            self.silent_audio = Duration::ZERO;
             return Ok(SilenceStatus::Speech);
         }
 
-        let now = Instant::now();
-        let silence_start = *self.silence_start.get_or_insert(now);
-        let silence_duration = now.duration_since(silence_start);
+        let silence_duration = match self.clock {
+            SilenceClock::WallClock => {
+                let now = Instant::now();
+                let silence_start = *self.silence_start.get_or_insert(now);
+                now.duration_since(silence_start)
+            }
+            SilenceClock::AudioDuration(frame_duration) => {
+                self.silent_audio += frame_duration;
+                self.silent_audio
+            }
+        };
 
         if silence_duration >= self.threshold {
             Ok(SilenceStatus::SilenceTimeout)
@@ -93,9 +372,60 @@ impl<V: VoiceDetector> SilenceDetector<V> {
         }
     }
 
+    /// Решение речь/тишина для кадра с учетом гистерезиса.
+    fn detect_speech(&mut self, frame: &[f32]) -> Result<bool> {
+        let Some(hysteresis) = self.hysteresis else {
+            return self.vad.is_speech(frame);
+        };
+
+        let probability = self.vad.speech_probability(frame)?;
+        if probability >= hysteresis.speech_on {
+            self.in_speech = true;
+        } else if probability < hysteresis.speech_off {
+            self.in_speech = false;
+        }
+        Ok(self.in_speech)
+    }
+
+    /// Применяет onset/hangover к решению по кадру и возвращает,
+    /// считается ли кадр речью.
+    fn smooth(&mut self, is_speech: bool) -> bool {
+        if is_speech {
+            self.speech_run += 1;
+            self.silence_run = 0;
+            if self.speech_run >= self.onset_frames {
+                self.speaking = true;
+            }
+        } else {
+            self.silence_run += 1;
+            self.speech_run = 0;
+            if self.silence_run > self.hangover_frames {
+                self.speaking = false;
+            }
+        }
+        self.speaking
+    }
+
+    /// Длительность тишины на момент паузы.
+    fn current_silence(&self) -> Duration {
+        match self.clock {
+            SilenceClock::WallClock => match (self.silence_start, self.paused_at) {
+                (Some(start), Some(paused_at)) => paused_at.duration_since(start),
+                _ => Duration::ZERO,
+            },
+            SilenceClock::AudioDuration(_) => self.silent_audio,
+        }
+    }
+
     /// Сбрасывает состояние детектора и внутреннего VAD.
     pub fn reset(&mut self) {
         self.silence_start = None;
+        self.silent_audio = Duration::ZERO;
+        self.paused_at = None;
+        self.in_speech = false;
+        self.speaking = false;
+        self.speech_run = 0;
+        self.silence_run = 0;
         self.vad.reset();
     }
 }
@@ -104,23 +434,37 @@ impl<V: VoiceDetector> SilenceDetector<V> {
 mod tests {
     use super::*;
 
+    /// Стаб VAD: по очереди возвращает заданные вероятности речи.
     struct StubVad {
-        responses: Vec<bool>,
+        probabilities: Vec<f32>,
         index: usize,
     }
 
     impl StubVad {
         fn new(responses: Vec<bool>) -> Self {
+            Self::with_probabilities(
+                responses
+                    .into_iter()
+                    .map(|speech| if speech { 1.0 } else { 0.0 })
+                    .collect(),
+            )
+        }
+
+        fn with_probabilities(probabilities: Vec<f32>) -> Self {
             Self {
-                responses,
+                probabilities,
                 index: 0,
             }
         }
     }
 
     impl VoiceDetector for StubVad {
-        fn is_speech(&mut self, _frame: &[f32]) -> Result<bool> {
-            let result = self.responses.get(self.index).copied().unwrap_or(false);
+        fn is_speech(&mut self, frame: &[f32]) -> Result<bool> {
+            Ok(self.speech_probability(frame)? >= 0.5)
+        }
+
+        fn speech_probability(&mut self, _frame: &[f32]) -> Result<f32> {
+            let result = self.probabilities.get(self.index).copied().unwrap_or(0.0);
             self.index += 1;
             Ok(result)
         }
@@ -208,6 +552,54 @@ mod tests {
         assert_eq!(status, SilenceStatus::Speech);
     }
 
+    #[test]
+    fn silence_detector_should_not_advance_while_paused() {
+        // Given: порог 0 сек - любой тихий кадр вне паузы дал бы таймаут
+        let vad = StubVad::new(vec![false; 4]);
+        let mut detector = SilenceDetector::new(vad, 0.0)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION));
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        detector.pause();
+        let paused: Vec<_> = (0..3)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+        detector.resume();
+        let resumed = detector.process_frame(&frame).unwrap();
+
+        // Then
+        assert!(paused
+            .iter()
+            .all(|s| *s == SilenceStatus::Silence(Duration::ZERO)));
+        assert_eq!(resumed, SilenceStatus::SilenceTimeout);
+    }
+
+    #[test]
+    fn silence_detector_should_restart_silence_timer_on_resume() {
+        // Given: порог 100ms = 4 кадра по 32ms
+        let vad = StubVad::new(vec![false; 6]);
+        let mut detector = SilenceDetector::new(vad, 0.1)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION));
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+        for _ in 0..3 {
+            detector.process_frame(&frame).unwrap();
+        }
+
+        // When: пауза и еще 3 тихих кадра после продолжения
+        detector.pause();
+        detector.resume();
+        let statuses: Vec<_> = (0..3)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+
+        // Then: 6 тихих кадров всего, но таймер начат заново
+        assert_eq!(
+            statuses.last(),
+            Some(&SilenceStatus::Silence(VAD_FRAME_DURATION * 3))
+        );
+    }
+
     #[test]
     fn silence_detector_should_not_timeout_before_threshold() {
         // Given
@@ -223,6 +615,346 @@ mod tests {
         assert_ne!(status, SilenceStatus::SilenceTimeout);
     }
 
+    #[test]
+    fn audio_clock_should_timeout_after_expected_number_of_silent_frames() {
+        // Given: порог 100мс, кадр 32мс -> таймаут на 4-м тихом кадре (128мс)
+        let vad = StubVad::new(vec![false; 4]);
+        let mut detector = SilenceDetector::new(vad, 0.1)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION));
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        let statuses: Vec<_> = (0..4)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+
+        // Then
+        assert_eq!(
+            statuses,
+            vec![
+                SilenceStatus::Silence(Duration::from_millis(32)),
+                SilenceStatus::Silence(Duration::from_millis(64)),
+                SilenceStatus::Silence(Duration::from_millis(96)),
+                SilenceStatus::SilenceTimeout,
+            ]
+        );
+    }
+
+    #[test]
+    fn audio_clock_should_restart_count_after_speech() {
+        // Given
+        let vad = StubVad::new(vec![false, false, true, false]);
+        let mut detector = SilenceDetector::new(vad, 1.0)
+            .with_clock(SilenceClock::AudioDuration(Duration::from_millis(100)));
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        let _ = detector.process_frame(&frame).unwrap();
+        let _ = detector.process_frame(&frame).unwrap();
+        let speech = detector.process_frame(&frame).unwrap();
+        let after_speech = detector.process_frame(&frame).unwrap();
+
+        // Then
+        assert_eq!(speech, SilenceStatus::Speech);
+        assert_eq!(
+            after_speech,
+            SilenceStatus::Silence(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn audio_clock_should_reset_accumulated_silence() {
+        // Given
+        let vad = StubVad::new(vec![false, false]);
+        let mut detector = SilenceDetector::new(vad, 1.0)
+            .with_clock(SilenceClock::AudioDuration(Duration::from_millis(100)));
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+        let _ = detector.process_frame(&frame).unwrap();
+
+        // When
+        detector.reset();
+        let status = detector.process_frame(&frame).unwrap();
+
+        // Then
+        assert_eq!(status, SilenceStatus::Silence(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn hangover_should_ignore_lone_silent_frame_between_speech() {
+        // Given: речь - один тихий кадр - речь
+        let vad = StubVad::new(vec![true, true, false, true]);
+        let mut detector = SilenceDetector::new(vad, 5.0)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION))
+            .with_smoothing(1, 2);
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        let statuses: Vec<_> = (0..4)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+
+        // Then
+        assert!(statuses.iter().all(|s| *s == SilenceStatus::Speech));
+    }
+
+    #[test]
+    fn hangover_should_start_silence_clock_after_window() {
+        // Given: hangover 2 кадра, затем тишина
+        let vad = StubVad::new(vec![true, false, false, false, false]);
+        let mut detector = SilenceDetector::new(vad, 5.0)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION))
+            .with_smoothing(1, 2);
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        let statuses: Vec<_> = (0..5)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+
+        // Then
+        assert_eq!(
+            statuses,
+            vec![
+                SilenceStatus::Speech,
+                SilenceStatus::Speech,
+                SilenceStatus::Speech,
+                SilenceStatus::Silence(Duration::from_millis(32)),
+                SilenceStatus::Silence(Duration::from_millis(64)),
+            ]
+        );
+    }
+
+    #[test]
+    fn onset_should_not_reset_silence_on_lone_speech_frame() {
+        // Given: тишина - одиночный кадр речи - тишина
+        let vad = StubVad::new(vec![false, true, false]);
+        let mut detector = SilenceDetector::new(vad, 5.0)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION))
+            .with_smoothing(2, 0);
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        let statuses: Vec<_> = (0..3)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+
+        // Then: таймер тишины продолжает идти
+        assert_eq!(
+            statuses,
+            vec![
+                SilenceStatus::Silence(Duration::from_millis(32)),
+                SilenceStatus::Silence(Duration::from_millis(64)),
+                SilenceStatus::Silence(Duration::from_millis(96)),
+            ]
+        );
+    }
+
+    #[test]
+    fn onset_should_declare_speech_after_consecutive_frames() {
+        // Given
+        let vad = StubVad::new(vec![false, true, true]);
+        let mut detector = SilenceDetector::new(vad, 5.0)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION))
+            .with_smoothing(2, 0);
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        let statuses: Vec<_> = (0..3)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+
+        // Then
+        assert_eq!(statuses[2], SilenceStatus::Speech);
+    }
+
+    /// VAD по амплитуде: речь - кадр с пиком выше 0.1.
+    struct AmplitudeVad;
+
+    impl VoiceDetector for AmplitudeVad {
+        fn is_speech(&mut self, frame: &[f32]) -> Result<bool> {
+            Ok(frame.iter().any(|s| s.abs() > 0.1))
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn speech_silence_speech(speech: usize, silence: usize, tail: usize) -> Vec<f32> {
+        [vec![0.5; speech], vec![0.0; silence], vec![0.5; tail]].concat()
+    }
+
+    #[test]
+    fn segment_speech_should_find_regions_around_silence() {
+        // Given: 10 кадров речи, 20 кадров тишины (640мс), 5 кадров речи
+        let samples =
+            speech_silence_speech(10 * VAD_FRAME_SIZE, 20 * VAD_FRAME_SIZE, 5 * VAD_FRAME_SIZE);
+
+        // When
+        let regions = segment_speech(&mut AmplitudeVad, &samples, 300).unwrap();
+
+        // Then
+        assert_eq!(
+            regions,
+            vec![
+                (0, 10 * VAD_FRAME_SIZE),
+                (30 * VAD_FRAME_SIZE, 35 * VAD_FRAME_SIZE)
+            ]
+        );
+    }
+
+    #[test]
+    fn segment_speech_should_merge_short_gaps() {
+        // Given: пауза 3 кадра (96мс) короче min_gap
+        let samples =
+            speech_silence_speech(4 * VAD_FRAME_SIZE, 3 * VAD_FRAME_SIZE, 4 * VAD_FRAME_SIZE);
+
+        // When
+        let regions = segment_speech(&mut AmplitudeVad, &samples, 300).unwrap();
+
+        // Then
+        assert_eq!(regions, vec![(0, 11 * VAD_FRAME_SIZE)]);
+    }
+
+    #[test]
+    fn segment_speech_should_handle_trailing_partial_frame() {
+        // Given: речь заканчивается неполным кадром
+        let samples = vec![0.5; VAD_FRAME_SIZE + 100];
+
+        // When
+        let regions = segment_speech(&mut AmplitudeVad, &samples, 0).unwrap();
+
+        // Then
+        assert_eq!(regions, vec![(0, VAD_FRAME_SIZE + 100)]);
+    }
+
+    #[test]
+    fn segment_speech_should_return_empty_for_silence() {
+        let samples = vec![0.0; 4 * VAD_FRAME_SIZE];
+        assert!(segment_speech(&mut AmplitudeVad, &samples, 300)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn frames_for_ms_should_round_up() {
+        assert_eq!(frames_for_ms(0), 0);
+        assert_eq!(frames_for_ms(32), 1);
+        assert_eq!(frames_for_ms(200), 7);
+    }
+
+    #[test]
+    fn default_speech_probability_should_follow_is_speech() {
+        // Given: детектор без собственной вероятности
+        struct BinaryVad(bool);
+        impl VoiceDetector for BinaryVad {
+            fn is_speech(&mut self, _frame: &[f32]) -> Result<bool> {
+                Ok(self.0)
+            }
+            fn reset(&mut self) {}
+        }
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When / Then
+        assert_eq!(BinaryVad(true).speech_probability(&frame).unwrap(), 1.0);
+        assert_eq!(BinaryVad(false).speech_probability(&frame).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn hysteresis_should_keep_speech_between_thresholds() {
+        // Given: речь, затем колебания между порогами, затем явная тишина
+        let vad = StubVad::with_probabilities(vec![0.8, 0.45, 0.4, 0.2]);
+        let mut detector = SilenceDetector::new(vad, 5.0)
+            .with_clock(SilenceClock::AudioDuration(VAD_FRAME_DURATION))
+            .with_hysteresis(0.5, 0.35);
+        let frame = vec![0.0; VAD_FRAME_SIZE];
+
+        // When
+        let statuses: Vec<_> = (0..4)
+            .map(|_| detector.process_frame(&frame).unwrap())
+            .collect();
+
+        // Then
+        assert_eq!(
+            statuses,
+            vec![
+                SilenceStatus::Speech,
+                SilenceStatus::Speech,
+                SilenceStatus::Speech,
+                SilenceStatus::Silence(VAD_FRAME_DURATION),
+            ]
+        );
+    }
+
+    #[test]
+    fn hysteresis_should_not_start_speech_below_on_threshold() {
+        // Given: вероятность между порогами без предшествующей речи
+        let vad = StubVad::with_probabilities(vec![0.45]);
+        let mut detector = SilenceDetector::new(vad, 5.0).with_hysteresis(0.5, 0.35);
+
+        // When
+        let status = detector.process_frame(&vec![0.0; VAD_FRAME_SIZE]).unwrap();
+
+        // Then
+        assert!(matches!(status, SilenceStatus::Silence(_)));
+    }
+
+    #[test]
+    fn hysteresis_should_clamp_off_threshold_to_on_threshold() {
+        // Given / When
+        let detector = SilenceDetector::new(StubVad::new(vec![]), 5.0).with_hysteresis(0.4, 0.9);
+
+        // Then
+        assert_eq!(
+            detector.hysteresis,
+            Some(Hysteresis {
+                speech_on: 0.4,
+                speech_off: 0.4,
+            })
+        );
+    }
+
+    #[test]
+    fn probability_stats_should_aggregate_min_max_mean() {
+        // Given
+        let mut stats = ProbabilityStats::default();
+
+        // When
+        for p in [0.1, 0.9, 0.5, 0.3] {
+            stats.push(p);
+        }
+        let summary = stats.summary().expect("summary");
+
+        // Then
+        assert_eq!(summary.frames, 4);
+        assert!((summary.min - 0.1).abs() < 1e-6);
+        assert!((summary.max - 0.9).abs() < 1e-6);
+        assert!((summary.mean - 0.45).abs() < 1e-6);
+    }
+
+    #[test]
+    fn probability_stats_should_return_none_without_frames() {
+        // Given
+        let stats = ProbabilityStats::default();
+
+        // When / Then
+        assert_eq!(stats.summary(), None);
+    }
+
+    #[test]
+    fn probability_stats_should_start_over_after_clear() {
+        // Given
+        let mut stats = ProbabilityStats::default();
+        stats.push(0.9);
+
+        // When
+        stats.clear();
+        stats.push(0.2);
+
+        // Then
+        let summary = stats.summary().expect("summary");
+        assert_eq!(summary.frames, 1);
+        assert!((summary.max - 0.2).abs() < 1e-6);
+    }
+
     #[test]
     fn vad_error_should_display_model_load_message() {
         // Given
@@ -298,6 +1030,25 @@ mod tests {
         assert_eq!(detector.threshold, Duration::from_secs(10));
     }
 
+    #[test]
+    fn clamp_vad_threshold_should_keep_valid_values() {
+        assert_eq!(clamp_vad_threshold(0.3), 0.3);
+        assert_eq!(clamp_vad_threshold(0.0), 0.0);
+        assert_eq!(clamp_vad_threshold(1.0), 1.0);
+    }
+
+    #[test]
+    fn clamp_vad_threshold_should_clamp_out_of_range_values() {
+        assert_eq!(clamp_vad_threshold(-0.2), 0.0);
+        assert_eq!(clamp_vad_threshold(1.7), 1.0);
+    }
+
+    #[test]
+    fn clamp_vad_threshold_should_use_default_for_nan_and_infinity() {
+        assert_eq!(clamp_vad_threshold(f32::NAN), DEFAULT_VAD_THRESHOLD);
+        assert_eq!(clamp_vad_threshold(f32::INFINITY), DEFAULT_VAD_THRESHOLD);
+    }
+
     #[test]
     fn silence_detector_should_accept_zero_threshold() {
         // Given
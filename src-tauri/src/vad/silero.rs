@@ -2,7 +2,11 @@ use ndarray::Array3;
 use ort::session::Session;
 use ort::value::{Tensor, TensorRef};
 
-use super::{VadError, VoiceDetector, VAD_FRAME_SIZE};
+use super::{
+    clamp_vad_threshold, ProbabilityStats, ProbabilitySummary, VadError, VoiceDetector,
+    VAD_FRAME_SIZE,
+};
+use crate::config::schema::AppConfig;
 
 /// Размер LSTM-состояния в Silero VAD v5.
 const STATE_DIM: usize = 128;
@@ -21,6 +25,8 @@ pub struct SileroVad {
     session: Session,
     state: Array3<f32>,
     threshold: f32,
+    debug_probabilities: bool,
+    stats: ProbabilityStats,
 }
 
 impl SileroVad {
@@ -28,23 +34,54 @@ impl SileroVad {
     ///
     /// Модель вшита в бинарник — внешние файлы не нужны.
     /// `threshold` - порог вероятности (0.0..1.0), стандарт: 0.5.
+    /// Значения вне диапазона ограничиваются, `NaN` заменяется стандартом.
     pub fn new(threshold: f32) -> super::Result<Self> {
+        Self::from_bytes(MODEL_BYTES, threshold)
+    }
+
+    /// Инициализирует VAD из байтов ONNX-модели (например, из `include_bytes!`).
+    ///
+    /// Ошибки загрузки модели возвращаются как `VadError::ModelLoadFailed`.
+    pub fn from_bytes(model: &[u8], threshold: f32) -> super::Result<Self> {
         let session = Session::builder()
             .map_err(|e| VadError::ModelLoadFailed(e.to_string()))?
             .with_intra_threads(1)
             .map_err(|e| VadError::ModelLoadFailed(e.to_string()))?
-            .commit_from_memory(MODEL_BYTES)
+            .commit_from_memory(model)
             .map_err(|e| VadError::ModelLoadFailed(e.to_string()))?;
 
         Ok(Self {
             session,
             state: Array3::<f32>::zeros((2, 1, STATE_DIM)),
-            threshold,
+            threshold: clamp_vad_threshold(threshold),
+            debug_probabilities: false,
+            stats: ProbabilityStats::default(),
         })
     }
 
-    /// Возвращает вероятность речи для кадра (0.0..1.0).
-    pub fn speech_probability(&mut self, frame: &[f32]) -> super::Result<f32> {
+    /// Создает VAD с порогом `vad_threshold` и отладкой `vad_debug_probabilities`.
+    pub fn from_config(config: &AppConfig) -> super::Result<Self> {
+        Ok(Self::new(config.vad_threshold)?.with_debug_logging(config.vad_debug_probabilities))
+    }
+
+    /// Включает отладочное логирование вероятностей (`vad_debug_probabilities`).
+    ///
+    /// Каждый кадр логируется на уровне trace, в конце записи (`reset`) -
+    /// сводка min/max/mean на уровне debug. По умолчанию выключено.
+    pub fn with_debug_logging(mut self, enabled: bool) -> Self {
+        self.debug_probabilities = enabled;
+        self
+    }
+
+    /// Возвращает и сбрасывает сводку вероятностей за текущую запись.
+    pub fn take_probability_summary(&mut self) -> Option<ProbabilitySummary> {
+        let summary = self.stats.summary();
+        self.stats.clear();
+        summary
+    }
+
+    /// Выполняет инференс модели и возвращает вероятность речи для кадра.
+    fn infer(&mut self, frame: &[f32]) -> super::Result<f32> {
         if frame.len() != VAD_FRAME_SIZE {
             return Err(VadError::InvalidFrameSize {
                 expected: VAD_FRAME_SIZE,
@@ -83,6 +120,11 @@ impl SileroVad {
             .map_err(|e| VadError::InferenceFailed(e.to_string()))?
             .to_owned();
 
+        if self.debug_probabilities {
+            tracing::trace!(probability = speech_prob, "VAD frame");
+            self.stats.push(speech_prob);
+        }
+
         Ok(speech_prob)
     }
 }
@@ -93,7 +135,21 @@ impl VoiceDetector for SileroVad {
         Ok(prob >= self.threshold)
     }
 
+    fn speech_probability(&mut self, frame: &[f32]) -> super::Result<f32> {
+        self.infer(frame)
+    }
+
     fn reset(&mut self) {
+        if let Some(summary) = self.take_probability_summary() {
+            tracing::debug!(
+                frames = summary.frames,
+                min = summary.min,
+                max = summary.max,
+                mean = summary.mean,
+                threshold = self.threshold,
+                "VAD probability summary"
+            );
+        }
         self.state = Array3::<f32>::zeros((2, 1, STATE_DIM));
     }
 }
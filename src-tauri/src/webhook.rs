@@ -0,0 +1,231 @@
+//! Best-effort доставка результатов диктовки на HTTP webhook.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// Таймаут одного запроса к webhook (секунды).
+const WEBHOOK_TIMEOUT_SEC: u64 = 5;
+
+/// Количество повторных попыток после первой неудачной.
+const WEBHOOK_RETRIES: u32 = 1;
+
+/// Пауза перед повторной попыткой (миллисекунды).
+const WEBHOOK_RETRY_DELAY_MS: u64 = 500;
+
+/// Тело запроса к webhook.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookPayload {
+    /// Итоговый текст (после улучшения).
+    pub text: String,
+    /// Сырой текст распознавания.
+    pub raw: String,
+    /// Язык текста: из конфига или определенный STT (`None` - неизвестен, не отправляется).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Unix timestamp (секунды).
+    pub timestamp: u64,
+}
+
+impl WebhookPayload {
+    /// `language` - язык текста; `None`, если он неизвестен (поле не отправляется).
+    pub fn new(text: &str, raw: &str, language: Option<&str>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            text: text.to_string(),
+            raw: raw.to_string(),
+            language: language.map(str::to_string),
+            timestamp,
+        }
+    }
+}
+
+/// Отправляет payload на webhook с одним повтором при ошибке.
+///
+/// Успех - любой 2xx. Разрешены только http:// и https:// URL.
+pub async fn send_webhook(url: &str, payload: &WebhookPayload) -> Result<(), String> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(format!("invalid webhook URL scheme: {url}"));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SEC))
+        .build()
+        .map_err(|e| format!("failed to build HTTP client: {e}"))?;
+
+    let mut last_error = String::new();
+    for attempt in 0..=WEBHOOK_RETRIES {
+        if attempt > 0 {
+            tokio::time::sleep(Duration::from_millis(WEBHOOK_RETRY_DELAY_MS)).await;
+        }
+
+        match client.post(url).json(payload).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                last_error = format!("webhook returned status {}", response.status().as_u16());
+            }
+            Err(e) => {
+                last_error = format!("webhook request failed: {e}");
+            }
+        }
+        tracing::warn!(attempt, error = %last_error, "webhook delivery attempt failed");
+    }
+
+    Err(last_error)
+}
+
+/// Доставляет payload и только логирует ошибку (она не пробрасывается).
+///
+/// Возвращает, был ли payload доставлен.
+pub async fn deliver(url: &str, payload: &WebhookPayload) -> bool {
+    match send_webhook(url, payload).await {
+        Ok(()) => {
+            tracing::info!("webhook delivered");
+            true
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "webhook delivery failed");
+            false
+        }
+    }
+}
+
+/// Запускает доставку в фоне, не блокируя pipeline.
+pub fn spawn_delivery(url: String, payload: WebhookPayload) {
+    tauri::async_runtime::spawn(async move {
+        deliver(&url, &payload).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_should_omit_unknown_language() {
+        // Given / When
+        let payload = WebhookPayload::new("Hello.", "hello", None);
+
+        // Then: ключа нет в JSON (не "language": null)
+        let body = serde_json::to_value(&payload).unwrap();
+        assert!(body.get("language").is_none(), "body: {body}");
+        assert!(payload.timestamp > 0);
+    }
+
+    #[test]
+    fn payload_should_keep_explicit_language() {
+        // Given / When
+        let payload = WebhookPayload::new("Привет.", "привет", Some("ru"));
+
+        // Then
+        assert_eq!(payload.language.as_deref(), Some("ru"));
+    }
+
+    #[tokio::test]
+    async fn send_webhook_should_reject_non_http_url() {
+        // Given
+        let payload = WebhookPayload::new("a", "a", Some("en"));
+
+        // When
+        let result = send_webhook("file:///etc/passwd", &payload).await;
+
+        // Then
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn webhook_should_post_expected_payload_shape() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header("content-type", "application/json"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let payload = WebhookPayload::new("Hello, world!", "hello world", Some("en"));
+
+        // When
+        let result = send_webhook(&format!("{}/hook", server.uri()), &payload).await;
+
+        // Then
+        assert!(result.is_ok());
+        let requests = server.received_requests().await.expect("requests recorded");
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).expect("json body");
+        assert_eq!(body["text"], "Hello, world!");
+        assert_eq!(body["raw"], "hello world");
+        assert_eq!(body["language"], "en");
+        assert!(body["timestamp"].as_u64().is_some());
+        assert_eq!(body.as_object().map(|o| o.len()), Some(4));
+    }
+
+    #[tokio::test]
+    async fn webhook_should_retry_once_then_report_error() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let payload = WebhookPayload::new("text", "text", None);
+
+        // When
+        let result = send_webhook(&format!("{}/hook", server.uri()), &payload).await;
+
+        // Then
+        assert_eq!(result, Err("webhook returned status 500".to_string()));
+    }
+
+    #[tokio::test]
+    async fn deliver_should_swallow_failures_after_retry() {
+        // Given: webhook всегда отвечает 500
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1 + u64::from(WEBHOOK_RETRIES))
+            .mount(&server)
+            .await;
+        let payload = WebhookPayload::new("text", "text", None);
+
+        // When
+        let delivered = tokio::time::timeout(
+            Duration::from_secs(5),
+            deliver(&format!("{}/hook", server.uri()), &payload),
+        )
+        .await;
+
+        // Then: ошибка не пробрасывается, повтор сделан, вызов завершился
+        assert_eq!(delivered, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn deliver_should_report_success() {
+        // Given
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let payload = WebhookPayload::new("text", "text", None);
+
+        // When / Then
+        assert!(deliver(&format!("{}/hook", server.uri()), &payload).await);
+    }
+}